@@ -0,0 +1,76 @@
+// Build time facts for the About dialog: the git commit being built, and the licenses of
+// every crate in the dependency tree. Neither is allowed to fail the build, a source
+// tarball without .git or a registry we can't read just means less to show.
+
+use std::path::{Path, PathBuf};
+
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+
+    let commit = std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=INSTALLER_GIT_COMMIT={commit}");
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("third_party_licenses.txt"), third_party_licenses()).unwrap();
+}
+
+// "name version: license", one crate per line, from Cargo.lock. The license comes from
+// the crate's own manifest in the local registry.
+fn third_party_licenses() -> String {
+    let Ok(lock) = std::fs::read_to_string("Cargo.lock") else {
+        return String::new();
+    };
+    let registries = registry_sources();
+
+    let mut lines = Vec::new();
+    for package in lock.split("[[package]]").skip(1) {
+        let field = |name: &str| {
+            package
+                .lines()
+                .find_map(|l| l.strip_prefix(&format!("{name} = ")))
+                .map(|v| v.trim_matches('"').to_string())
+        };
+        let (Some(name), Some(version)) = (field("name"), field("version")) else {
+            continue;
+        };
+        // Only published crates, which leaves out this one.
+        if field("source").is_none() {
+            continue;
+        }
+        let license = registries
+            .iter()
+            .find_map(|dir| manifest_license(&dir.join(format!("{name}-{version}")).join("Cargo.toml")))
+            .unwrap_or_else(|| "see the crate's repository".to_string());
+        lines.push(format!("{name} {version}: {license}"));
+    }
+    lines.join("\n")
+}
+
+// Every unpacked registry under $CARGO_HOME/registry/src.
+fn registry_sources() -> Vec<PathBuf> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(|h| Path::new(&h).join(".cargo")));
+    let Some(src) = cargo_home.map(|h| h.join("registry").join("src")) else {
+        return Vec::new();
+    };
+    std::fs::read_dir(src)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+fn manifest_license(manifest: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(manifest).ok()?;
+    contents
+        .lines()
+        .find_map(|l| l.strip_prefix("license = "))
+        .map(|v| v.trim().trim_matches('"').to_string())
+}
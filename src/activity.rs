@@ -0,0 +1,152 @@
+// A running log of what the installer did this session, shown under the action zone.
+// The status line only ever says what's happening now, this keeps what led up to it.
+
+use dioxus::logger::tracing;
+use dioxus::prelude::*;
+
+// Older entries are dropped past this, a session never needs more.
+const MAX_ENTRIES: usize = 1000;
+
+// How many of the newest entries the panel shows.
+pub const SHOWN_ENTRIES: usize = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn class(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "INFO",
+            Severity::Warning => "WARN",
+            Severity::Error => "ERROR",
+        }
+    }
+}
+
+// An install step as the log knows it: the English name for copied text and the log
+// file, the label's i18n key for the panel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LogStep {
+    pub name: &'static str,
+    pub label_key: &'static str,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogEntry {
+    // Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub severity: Severity,
+    pub message: String,
+    // The install step it was logged from, entries from one step are grouped together.
+    pub step: Option<LogStep>,
+}
+
+// A run of entries from the same step, or from none.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogGroup {
+    pub step: Option<LogStep>,
+    pub entries: Vec<LogEntry>,
+}
+
+// `entries` in order, with each step's consecutive entries in a group of their own.
+pub fn groups(entries: &[LogEntry]) -> Vec<LogGroup> {
+    let mut groups: Vec<LogGroup> = Vec::new();
+    for entry in entries {
+        match groups.last_mut() {
+            Some(group) if group.step == entry.step => group.entries.push(entry.clone()),
+            _ => groups.push(LogGroup { step: entry.step, entries: vec![entry.clone()] }),
+        }
+    }
+    groups
+}
+
+impl LogEntry {
+    // Just the time of day, the log only covers this session.
+    pub fn time(&self) -> String {
+        let format = time::macros::format_description!("[hour]:[minute]:[second]");
+        time::OffsetDateTime::from_unix_timestamp(self.timestamp as i64)
+            .ok()
+            .and_then(|t| t.format(format).ok())
+            .unwrap_or_default()
+    }
+}
+
+// The whole session as plain text for pasting into a help channel, with enough about the
+// machine and target to make sense of it.
+pub fn to_text(entries: &[LogEntry], target: &std::path::Path) -> String {
+    let mut text = format!(
+        "Cobalt installer {} on {} ({})\nTarget: {}\nCopied: {}\n\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        crate::mods_report::redact(target),
+        crate::history::format_timestamp(crate::history::now()),
+    );
+    for entry in entries {
+        let step = entry.step.map(|s| format!("[{}] ", s.name)).unwrap_or_default();
+        text.push_str(&format!("{} {:5} {step}{}\n", entry.time(), entry.severity.label(), entry.message));
+    }
+    text
+}
+
+// Add an entry, and send it to the log file too.
+pub fn push(log: Signal<Vec<LogEntry>>, severity: Severity, message: impl Into<String>) {
+    push_in(log, None, severity, message);
+}
+
+// The same, for an entry logged from install step `step`.
+pub fn push_in(mut log: Signal<Vec<LogEntry>>, step: Option<LogStep>, severity: Severity, message: impl Into<String>) {
+    let message = message.into();
+    match severity {
+        Severity::Info => tracing::info!("{message}"),
+        Severity::Warning => tracing::warn!("{message}"),
+        Severity::Error => tracing::error!("{message}"),
+    }
+    let mut entries = log.write();
+    entries.push(LogEntry { timestamp: crate::history::now(), severity, message, step });
+    if entries.len() > MAX_ENTRIES {
+        let excess = entries.len() - MAX_ENTRIES;
+        entries.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(step: Option<&'static str>, message: &str) -> LogEntry {
+        let step = step.map(|name| LogStep { name, label_key: "" });
+        LogEntry { timestamp: 0, severity: Severity::Info, message: message.to_string(), step }
+    }
+
+    #[test]
+    fn entries_are_grouped_by_step() {
+        let entries = [
+            entry(None, "Starting an install"),
+            entry(Some("Download"), "Downloading"),
+            entry(Some("Download"), "Downloaded 5 MB"),
+            entry(Some("Extraction"), "Extracting"),
+            entry(None, "Copied the log"),
+            entry(Some("Extraction"), "Rolled back"),
+        ];
+        let groups = groups(&entries);
+        let steps: Vec<(Option<&str>, usize)> = groups.iter().map(|g| (g.step.map(|s| s.name), g.entries.len())).collect();
+        assert_eq!(steps, [(None, 1), (Some("Download"), 2), (Some("Extraction"), 1), (None, 1), (Some("Extraction"), 1)]);
+
+        let text = to_text(&entries, std::path::Path::new("/sd"));
+        assert!(text.contains("INFO  [Download] Downloaded 5 MB\n"), "{text}");
+        assert!(text.contains("INFO  Starting an install\n"), "{text}");
+    }
+}
@@ -0,0 +1,255 @@
+// Turning an archive entry's name into a path under the target, or refusing to. Zips
+// come from GitHub, a custom URL or a backup, and any of them can carry names made to
+// land somewhere else: `..`, absolute paths, drive letters, Windows device names. The
+// answer has to be the same on every OS, so a name Windows would read differently (a
+// backslash, a trailing dot it would strip) is treated the Windows way everywhere.
+
+use std::path::{Component, Path, PathBuf};
+
+// The longest file or folder name most filesystems take, FAT and exFAT count it in UTF-16
+// units and the rest in bytes.
+const MAX_COMPONENT: usize = 255;
+
+// Names Windows opens as a device rather than a file, with or without an extension.
+const DEVICE_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8", "com9", "lpt1", "lpt2",
+    "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+// Not allowed in a file name on Windows. ':' also makes drive letters and NTFS streams.
+const RESERVED_CHARS: &[char] = &['<', '>', ':', '"', '|', '?', '*'];
+
+// Why a name can't be used, for the log.
+fn check_component(part: &str) -> Result<(), &'static str> {
+    if part == ".." {
+        return Err("it points outside the install folder");
+    }
+    if part.chars().any(|c| c.is_control() || RESERVED_CHARS.contains(&c)) {
+        return Err("it has characters Windows doesn't allow in a name");
+    }
+    // Windows drops these, so "..." or ".. " would be ".." there.
+    if part.ends_with('.') || part.ends_with(' ') {
+        return Err("it ends in a dot or a space");
+    }
+    let stem = part.split('.').next().unwrap_or(part).trim_end().to_ascii_lowercase();
+    if DEVICE_NAMES.contains(&stem.as_str()) {
+        return Err("it's a reserved device name on Windows");
+    }
+    if part.len() > MAX_COMPONENT || part.encode_utf16().count() > MAX_COMPONENT {
+        return Err("a part of it is too long");
+    }
+    Ok(())
+}
+
+// Where the entry `name` goes, relative to the target, with `/` and `\` both taken as
+// separators and `.` parts dropped. An error (the reason, for the log) for anything that
+// could end up outside the target, or anywhere but the same file on every OS.
+pub fn sanitize(name: &str) -> Result<PathBuf, &'static str> {
+    if name.starts_with(['/', '\\']) {
+        return Err("it's an absolute path");
+    }
+    let mut path = PathBuf::new();
+    for part in name.split(['/', '\\']).filter(|p| !p.is_empty() && *p != ".") {
+        check_component(part)?;
+        path.push(part);
+    }
+    // Belt and braces: whatever got through is plain names only.
+    if path.as_os_str().is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err("it has no usable name");
+    }
+    Ok(path)
+}
+
+// `path` as the `/` separated name the manifest and the marker store.
+pub fn to_name(path: &Path) -> String {
+    path.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scratch;
+
+    // Names from real malformed and malicious zips: zip slip proof of concepts, archives
+    // made on Windows with backslashes, and names that only mean something odd once
+    // Windows has had its way with them.
+    const KNOWN_BAD: &[&str] = &[
+        "../../../../../../../../tmp/evil.sh",
+        "..\\..\\..\\..\\..\\Windows\\Temp\\evil.exe",
+        "good.txt/../../../../../../tmp/evil.sh",
+        "atmosphere/../../evil",
+        "atmosphere\\..\\..\\evil",
+        "/etc/passwd",
+        "\\Windows\\System32\\evil.dll",
+        "C:\\Windows\\System32\\evil.dll",
+        "C:evil.exe",
+        "c:/evil",
+        "\\\\server\\share\\evil",
+        "\\\\?\\C:\\evil",
+        "//server/share/evil",
+        "..",
+        "../",
+        ".",
+        "",
+        "./",
+        "evil\0.txt",
+        "atmosphere/evil\0/../../x",
+        "evil\u{1}",
+        "CON",
+        "aux.txt",
+        "engage/mods/nul.json",
+        "LPT1",
+        "com9.dll",
+        "con .txt",
+        "...",
+        ".. ",
+        ". .",
+        "atmosphere/.../x",
+        "atmosphere. /x",
+        "evil ",
+        " ..",
+        "file.txt:hidden",
+        "atmosphere/contents/x::$DATA",
+        "a<b",
+        "what?",
+        "star*",
+    ];
+
+    // From actual releases, which have to keep working.
+    const KNOWN_GOOD: &[(&str, &str)] = &[
+        ("atmosphere/contents/0100A6301214E000/exefs/subsdk9", "atmosphere/contents/0100A6301214E000/exefs/subsdk9"),
+        ("atmosphere/contents/0100A6301214E000/romfs/Data/", "atmosphere/contents/0100A6301214E000/romfs/Data"),
+        ("engage/config/cobalt.toml", "engage/config/cobalt.toml"),
+        ("./engage/mods/.keep", "engage/mods/.keep"),
+        ("engage\\mods\\readme.txt", "engage/mods/readme.txt"),
+        ("engage//mods/x.bin", "engage/mods/x.bin"),
+        (".hidden", ".hidden"),
+        ("console.txt", "console.txt"),
+        ("com10", "com10"),
+        ("..foo", "..foo"),
+        ("mods/café.txt", "mods/café.txt"),
+        ("mods/cafe\u{301}.txt", "mods/cafe\u{301}.txt"),
+        ("mods/ファイル", "mods/ファイル"),
+    ];
+
+    #[test]
+    fn known_bad_names_are_rejected() {
+        for name in KNOWN_BAD {
+            assert!(sanitize(name).is_err(), "{name:?} was let through as {:?}", sanitize(name));
+        }
+    }
+
+    #[test]
+    fn release_names_are_kept() {
+        for (name, expected) in KNOWN_GOOD {
+            assert_eq!(sanitize(name).map(|p| to_name(&p)).as_deref(), Ok(*expected), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn long_names_are_rejected() {
+        assert!(sanitize(&"a".repeat(MAX_COMPONENT)).is_ok());
+        assert!(sanitize(&"a".repeat(MAX_COMPONENT + 1)).is_err());
+        // Short in UTF-16, long in bytes.
+        assert!(sanitize(&"é".repeat(200)).is_err());
+        assert!(sanitize(&format!("{}/x", "b".repeat(300))).is_err());
+    }
+
+    // xorshift64*, so the generated names are the same on every run and the case number
+    // in a failure points at the same name next time.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+    }
+
+    // What generated names are made of: separators, dots and spaces in every arrangement
+    // that has meant trouble, drive letters, device names, characters Windows refuses,
+    // both Unicode normalization forms of the same name, lookalike dots and plain parts.
+    const PIECES: &[&str] = &[
+        "/", "\\", "/", "\\", ".", "..", "...", " ", ". ", ".. ", "C:", "c:", ":", "\\\\?\\", "\\\\", "\0", "\u{7f}",
+        "\u{202e}", "CON", "nul", "Aux.", "lpt1", "*", "?", "\"", "<", "|", "atmosphere", "engage", "mods", "x", ".txt",
+        "café", "cafe\u{301}", "ＣＯＮ", "．．", "\u{2024}\u{2024}", "\u{ff0f}", "\u{2215}", "Ω", "ß", "İ", "ǅ", "🐉",
+        "%2e%2e", "~1", "$DATA", "::",
+    ];
+
+    fn generated_name(rng: &mut Rng) -> String {
+        let mut name: String = (0..1 + rng.below(12)).map(|_| PIECES[rng.below(PIECES.len())]).collect();
+        // Now and then, one part too long for any filesystem.
+        if rng.below(50) == 0 {
+            name.push_str(&"long".repeat(70));
+        }
+        name
+    }
+
+    // Whatever the name, the result is plain names only, no part of it reads as anything
+    // else on Windows, and joined onto a folder it stays inside that folder.
+    #[test]
+    fn generated_names_stay_inside_the_target() {
+        let dest = Path::new("/sd");
+        let mut rng = Rng(0x5EED_C0BA_17F0_0D42);
+        let mut accepted = 0;
+        for case in 0..50_000 {
+            let name = generated_name(&mut rng);
+            let Ok(path) = sanitize(&name) else {
+                continue;
+            };
+            accepted += 1;
+            assert!(path.is_relative(), "case {case}: {name:?} gave {path:?}");
+            let joined = dest.join(&path);
+            assert!(joined.starts_with(dest) && joined != dest, "case {case}: {name:?} gave {joined:?}");
+            for part in path.components() {
+                let Component::Normal(part) = part else {
+                    panic!("case {case}: {name:?} gave {path:?}");
+                };
+                let part = part.to_str().unwrap();
+                assert!(!part.contains(['/', '\\', ':', '\0']), "case {case}: {name:?} gave {path:?}");
+                let windows = part.trim_end_matches(['.', ' ']);
+                assert!(!windows.is_empty() && windows != "..", "case {case}: {name:?} gave {path:?}");
+            }
+            // The same answer every time, and for the name it stands for.
+            assert_eq!(sanitize(&to_name(&path)), Ok(path.clone()), "case {case}: {name:?}");
+        }
+        // The generator isn't just making names that all get thrown out.
+        assert!(accepted > 1000, "only {accepted} names were accepted");
+    }
+
+    // The same, but the files are really created and looked for afterwards, in case the
+    // filesystem reads a name differently than the checks above assume.
+    #[test]
+    fn generated_names_are_written_inside_the_target() {
+        let scratch = Scratch::new("archive_path_written");
+        let dest = scratch.0.join("sd");
+        std::fs::create_dir_all(&dest).unwrap();
+        let dest = dest.canonicalize().unwrap();
+        let mut rng = Rng(0xD1A5_0000_0001);
+        let mut written = 0;
+        while written < 300 {
+            let name = generated_name(&mut rng);
+            let Ok(path) = sanitize(&name) else {
+                continue;
+            };
+            let file = dest.join(&path);
+            // A part can be a file for one name and a folder for another, that's not what's
+            // being tested.
+            if std::fs::create_dir_all(file.parent().unwrap()).and_then(|_| std::fs::write(&file, name.as_bytes())).is_err() {
+                continue;
+            }
+            written += 1;
+            let landed = file.canonicalize().unwrap();
+            assert!(landed.starts_with(&dest), "{name:?} was written to {landed:?}");
+        }
+        // Nothing at all went next to the target.
+        let beside: Vec<_> = std::fs::read_dir(&scratch.0).unwrap().flatten().map(|e| e.file_name()).collect();
+        assert_eq!(beside, ["sd"]);
+    }
+}
@@ -0,0 +1,264 @@
+// Zipping up the current install before an update overwrites it.
+//
+// A backup holds every file the target's manifest lists, plus the manifest itself under
+// MANIFEST_ENTRY, so a restore can put both the files and the installed version back.
+
+use std::fs::File;
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::cancel::CancelToken;
+use crate::paths::data_dir;
+use crate::extract::{extract_archive, ExtractOptions};
+use crate::manifest::Manifest;
+use crate::uninstall::{remove_empty_dirs, remove_stale_files};
+
+pub const MANIFEST_ENTRY: &str = ".cobalt_manifest.json";
+
+pub fn backups_dir() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("backups"))
+}
+
+// A backup found in the backups folder, as shown on the restore screen.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    pub version: String,
+    pub date: String,
+}
+
+// cobalt_backup_<version>_<date>.zip, with anything odd in the tag flattened to '_'.
+pub fn backup_file_name(version: Option<&str>) -> String {
+    let version: String = version
+        .unwrap_or("unknown")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect();
+    let date = time::OffsetDateTime::now_utc().date();
+    format!("cobalt_backup_{version}_{date}.zip")
+}
+
+// What the backup will need on disk at most. Files are stored compressed, so the real
+// size is usually smaller, but this is the number we check free space against.
+pub fn required_space(manifest: &Manifest) -> u64 {
+    manifest
+        .files
+        .iter()
+        .filter_map(|f| std::fs::metadata(manifest.target.join(f)).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+// Write the backup, calling `progress(done, total)` after each file. Files the manifest
+// lists but that are gone from the target are skipped, there's nothing to keep. Checks
+// `cancel` between files and stops with an `Interrupted` error.
+pub fn create(manifest: &Manifest, cancel: &CancelToken, progress: impl FnMut(usize, usize)) -> std::io::Result<PathBuf> {
+    let dir = backups_dir()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "No data directory"))?;
+    create_in(&dir, manifest, cancel, progress)
+}
+
+// `name` in `dir`, or with .2, .3... before the extension if a backup already has it, so
+// a second backup of the same version on the same day doesn't replace the first.
+fn free_path(dir: &Path, name: &str) -> PathBuf {
+    let stem = name.strip_suffix(".zip").unwrap_or(name);
+    std::iter::once(dir.join(name))
+        .chain((2..).map(|n| dir.join(format!("{stem}.{n}.zip"))))
+        .find(|path| !path.exists())
+        .expect("there is always a free number")
+}
+
+fn create_in(
+    dir: &Path,
+    manifest: &Manifest,
+    cancel: &CancelToken,
+    mut progress: impl FnMut(usize, usize),
+) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let name = backup_file_name(manifest.version.as_deref());
+    // Written under a name of its own and only given the real one once it's finished, so
+    // a failed backup never replaces or looks like a good one. If this process dies first
+    // the sweep clears it up.
+    let partial = dir.join(format!("unfinished_{}_{name}", std::process::id()));
+
+    let result = write_backup(File::create(&partial)?, manifest, cancel, &mut progress).and_then(|_| {
+        let path = free_path(dir, &name);
+        std::fs::rename(&partial, &path).map(|_| path)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    result
+}
+
+fn write_backup<W: Write + Seek>(
+    out: W,
+    manifest: &Manifest,
+    cancel: &CancelToken,
+    progress: &mut impl FnMut(usize, usize),
+) -> std::io::Result<()> {
+    let mut zip = ZipWriter::new(out);
+    let options = SimpleFileOptions::default();
+    let total = manifest.files.len();
+
+    for (i, name) in manifest.files.iter().enumerate() {
+        cancel.check()?;
+        let source = manifest.target.join(name);
+        if source.is_file() {
+            zip.start_file(name.as_str(), options)?;
+            std::io::copy(&mut File::open(&source)?, &mut zip)?;
+        }
+        progress(i + 1, total);
+    }
+
+    zip.start_file(MANIFEST_ENTRY, options)?;
+    zip.write_all(serde_json::to_string_pretty(manifest)?.as_bytes())?;
+    zip.finish()?;
+    Ok(())
+}
+
+// The version and date out of a backup's file name. The date never contains '_' and the
+// version had them flattened, so splitting on the last one is enough. A second backup on
+// the same day has a number after the date, which isn't part of it.
+fn parse_file_name(name: &str) -> Option<(String, String)> {
+    let stem = name.strip_prefix("cobalt_backup_")?.strip_suffix(".zip")?;
+    let (version, date) = stem.rsplit_once('_')?;
+    let date = date.split('.').next().unwrap_or(date);
+    Some((version.to_string(), date.to_string()))
+}
+
+// The manifest a backup was made from, which says what it holds and where from.
+fn backed_up_manifest(backup: &Path) -> std::io::Result<Manifest> {
+    let mut archive = ZipArchive::new(File::open(backup)?)?;
+    let mut contents = String::new();
+    archive.by_name(MANIFEST_ENTRY)?.read_to_string(&mut contents)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+// Every backup of `target` in the backups folder, newest first. Backups of other targets
+// share the folder, they're left out.
+pub fn list(target: &Path) -> Vec<BackupInfo> {
+    backups_dir().map(|dir| list_in(&dir, target)).unwrap_or_default()
+}
+
+fn list_in(dir: &Path, target: &Path) -> Vec<BackupInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<(std::time::SystemTime, BackupInfo)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let (version, date) = parse_file_name(&e.file_name().to_string_lossy())?;
+            let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, BackupInfo { path: e.path(), version, date }))
+        })
+        .filter(|(_, info)| backed_up_manifest(&info.path).is_ok_and(|m| m.target == target))
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.0));
+    backups.into_iter().map(|(_, info)| info).collect()
+}
+
+// Put a backup back onto `target`, which has to be the target it was made of. Returns
+// the manifest describing the result and the number of bytes written.
+//
+// Files the `current` install wrote that the backup doesn't have (new in the version
+// being rolled back from) are deleted, so the target really matches the restored version.
+pub fn restore(
+    backup: &Path,
+    target: &Path,
+    current: Option<&Manifest>,
+    progress: impl FnMut(usize, usize),
+) -> std::io::Result<(Manifest, u64)> {
+    let backed_up = backed_up_manifest(backup)?;
+    if backed_up.target != target {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("this backup is of {}, not {}", backed_up.target.display(), target.display()),
+        ));
+    }
+
+    let options = ExtractOptions { exclude: &[MANIFEST_ENTRY], ..Default::default() };
+    let extracted = extract_archive(File::open(backup)?, target, options, progress)?;
+
+    if let Some(current) = current {
+        let removed = remove_stale_files(target, &current.files, &extracted.files)?;
+        remove_empty_dirs(target, &removed);
+    }
+
+    let manifest = Manifest { checksums: extracted.checksums, ..Manifest::new(target.to_path_buf(), backed_up.version, extracted.files) };
+    Ok((manifest, extracted.bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scratch;
+
+    #[test]
+    fn a_cancelled_backup_leaves_no_zip() {
+        let install = Scratch::new("backup_cancel_install");
+        let backups = Scratch::new("backup_cancel_backups");
+        let files: Vec<String> = (0..4).map(|n| format!("engage/mods/file_{n}.txt")).collect();
+        for file in &files {
+            install.file(file, b"contents");
+        }
+        let manifest = Manifest::new(install.0.clone(), Some("v1.0.0".to_string()), files);
+        let cancel = CancelToken::new();
+        let result = create_in(&backups.0, &manifest, &cancel, |done, _| {
+            if done == 2 {
+                cancel.cancel();
+            }
+        });
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::Interrupted);
+        assert_eq!(std::fs::read_dir(&backups.0).unwrap().count(), 0);
+
+        let path = create_in(&backups.0, &manifest, &CancelToken::new(), |_, _| {}).unwrap();
+        assert_eq!(ZipArchive::new(File::open(path).unwrap()).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn backups_of_the_same_version_on_the_same_day_are_all_kept() {
+        let install = Scratch::new("backup_same_day_install");
+        let backups = Scratch::new("backup_same_day_backups");
+        install.file("engage/config/cobalt.toml", b"config");
+        let manifest = Manifest::new(install.0.clone(), Some("v1.0.0".to_string()), vec!["engage/config/cobalt.toml".to_string()]);
+        let first = create_in(&backups.0, &manifest, &CancelToken::new(), |_, _| {}).unwrap();
+        let second = create_in(&backups.0, &manifest, &CancelToken::new(), |_, _| {}).unwrap();
+        assert_ne!(first, second);
+
+        // And a failed third one leaves both alone.
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        assert!(create_in(&backups.0, &manifest, &cancel, |_, _| {}).is_err());
+        let mut names: Vec<String> =
+            std::fs::read_dir(&backups.0).unwrap().flatten().map(|e| e.file_name().to_string_lossy().into_owned()).collect();
+        names.sort();
+        assert_eq!(names.len(), 2);
+        for name in &names {
+            let (version, date) = parse_file_name(name).unwrap();
+            assert_eq!((version.as_str(), date), ("v1.0.0", time::OffsetDateTime::now_utc().date().to_string()));
+        }
+    }
+
+    #[test]
+    fn only_backups_of_the_target_are_listed_and_restored() {
+        let ryujinx = Scratch::new("backup_target_ryujinx");
+        let sd_card = Scratch::new("backup_target_sd_card");
+        let backups = Scratch::new("backup_target_backups");
+        for install in [&ryujinx, &sd_card] {
+            install.file("engage/config/cobalt.toml", b"config");
+            let manifest = Manifest::new(install.0.clone(), Some("v1.0.0".to_string()), vec!["engage/config/cobalt.toml".to_string()]);
+            create_in(&backups.0, &manifest, &CancelToken::new(), |_, _| {}).unwrap();
+        }
+        let listed = list_in(&backups.0, &ryujinx.0);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(backed_up_manifest(&listed[0].path).unwrap().target, ryujinx.0);
+
+        let error = restore(&listed[0].path, &sd_card.0, None, |_, _| {}).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(restore(&listed[0].path, &ryujinx.0, None, |_, _| {}).is_ok());
+    }
+}
@@ -0,0 +1,124 @@
+// A new GitHub issue filled in from a failed run, so a report arrives with the facts we'd
+// otherwise have to ask for: installer version, OS, installation type, the error and
+// the last few log lines.
+//
+// Everything goes in the URL. Browsers (and GitHub) stop somewhere past 8000 characters,
+// so the log is cut down first and the error details after that if it still won't fit.
+
+use crate::activity::LogEntry;
+use crate::crash::CrashRecord;
+use crate::installation_type::InstallationType;
+use crate::mods_report::redact;
+use crate::{version_info, INSTALLER_REPO_URL};
+
+// How many of the newest log lines go in.
+const LOG_LINES: usize = 20;
+
+// Leaves room under what browsers accept.
+const MAX_URL_LEN: usize = 6000;
+
+// Everything but the URL-safe characters as %XX, UTF-8 bytes one at a time.
+fn encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn body(summary: &str, details: &str, installation_type: InstallationType, log: &[String]) -> String {
+    let mut body = format!(
+        "**What happened**\n\n<!-- What were you doing when this went wrong? -->\n\n\
+         **Error**\n\n{summary}\n\n```\n{details}\n```\n\n\
+         **Installer**: {}\n**OS**: {} ({})\n**Installation type**: {}\n",
+        version_info(),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        installation_type.name(),
+    );
+    if !log.is_empty() {
+        body.push_str(&format!("\n**Last log lines**\n\n```\n{}\n```\n", log.join("\n")));
+    }
+    body
+}
+
+fn url(title: &str, body: &str) -> String {
+    format!("{INSTALLER_REPO_URL}/issues/new?title={}&body={}", encode(title), encode(body))
+}
+
+// Log messages name the target, which would put the user name in a public issue.
+fn redact_home(lines: &mut [String]) {
+    if let Some(home) = dirs::home_dir() {
+        let shown = redact(&home);
+        let home = home.display().to_string();
+        for line in lines {
+            *line = line.replace(&home, &shown);
+        }
+    }
+}
+
+pub fn issue_url(summary: &str, details: &str, installation_type: InstallationType, entries: &[LogEntry]) -> String {
+    let title = format!("Installer error: {summary}");
+    let mut log: Vec<String> = entries[entries.len().saturating_sub(LOG_LINES)..]
+        .iter()
+        .map(|e| format!("{} {}", e.time(), e.message))
+        .collect();
+    redact_home(&mut log);
+    fit(&title, summary, details, installation_type, log)
+}
+
+// A crash from an earlier session. The backtrace goes in the details, which are the
+// first to be cut short if the whole thing is too long.
+pub fn crash_issue_url(record: &CrashRecord, installation_type: InstallationType) -> String {
+    let summary = format!("The installer crashed: {}", record.message);
+    let title = format!("Installer crash: {}", record.message);
+    let mut details = vec![format!(
+        "Crashed in {} on {} ({}), thread {}
+At: {}
+Install state: {}
+
+{}",
+        record.version,
+        record.os,
+        record.arch,
+        record.thread,
+        record.location,
+        if record.install_state.is_empty() { "none" } else { &record.install_state },
+        record.backtrace,
+    )];
+    redact_home(&mut details);
+    let mut log = record.log[record.log.len().saturating_sub(LOG_LINES)..].to_vec();
+    redact_home(&mut log);
+    fit(&title, &summary, &details[0], installation_type, log)
+}
+
+// The issue URL, dropping the oldest log lines and then cutting the details until it's
+// short enough.
+fn fit(title: &str, summary: &str, details: &str, installation_type: InstallationType, mut log: Vec<String>) -> String {
+    loop {
+        let candidate = url(title, &body(summary, details, installation_type, &log));
+        if candidate.len() <= MAX_URL_LEN || log.is_empty() {
+            break;
+        }
+        log.remove(0);
+    }
+
+    // Still too long without any log, so the details have to give.
+    let chars: Vec<char> = details.chars().collect();
+    let mut keep = chars.len();
+    loop {
+        let shown = if keep == chars.len() {
+            details.to_string()
+        } else {
+            format!("{}\n(cut short)", chars[..keep].iter().collect::<String>())
+        };
+        let candidate = url(title, &body(summary, &shown, installation_type, &log));
+        if candidate.len() <= MAX_URL_LEN || keep == 0 {
+            return candidate;
+        }
+        keep = keep * 3 / 4;
+    }
+}
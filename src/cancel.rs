@@ -0,0 +1,112 @@
+// Stopping an install partway. The UI makes one token per install and cancels it from the
+// Cancel button or the close prompt, and every stage gets a clone: the blocking ones
+// check it between files or chunks, the async ones (the download) also wake up for it
+// while they wait on the network. A stage that sees it cleans up what it had half done
+// and returns InstallerError::Cancelled.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::Poll;
+
+use tokio::sync::Notify;
+
+use crate::error::InstallerError;
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<Inner>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Relaxed);
+        self.0.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Relaxed)
+    }
+
+    // The flag itself, for extract::ExtractOptions.
+    pub fn flag(&self) -> &AtomicBool {
+        &self.0.cancelled
+    }
+
+    // For the blocking stages, which return io errors: `Interrupted` once cancelled, the
+    // kind InstallerError::from_extract turns into Cancelled.
+    pub fn check(&self) -> std::io::Result<()> {
+        if self.is_cancelled() {
+            Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "cancelled"))
+        } else {
+            Ok(())
+        }
+    }
+
+    // Resolves once the token is cancelled, straight away if it already is.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.0.notify.notified();
+            let mut notified = std::pin::pin!(notified);
+            // Registered before the check, so a cancel in between still wakes us.
+            notified.as_mut().enable();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    // Run `work` until it finishes or the token is cancelled, whichever comes first.
+    // Cancelling drops `work` where it stands.
+    pub async fn run<T>(&self, work: impl Future<Output = T>) -> Result<T, InstallerError> {
+        let mut work = std::pin::pin!(work);
+        let mut cancelled = std::pin::pin!(self.cancelled());
+        std::future::poll_fn(|cx| {
+            if cancelled.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Err(InstallerError::Cancelled));
+            }
+            work.as_mut().poll(cx).map(Ok)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_on<T>(future: impl Future<Output = T>) -> T {
+        tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+    }
+
+    #[test]
+    fn waiting_work_is_dropped_when_cancelled() {
+        let cancel = CancelToken::new();
+        let other = cancel.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            other.cancel();
+        });
+        let result = block_on(cancel.run(std::future::pending::<()>()));
+        assert!(matches!(result, Err(InstallerError::Cancelled)));
+        assert!(cancel.check().is_err_and(|e| e.kind() == std::io::ErrorKind::Interrupted));
+    }
+
+    #[test]
+    fn finished_work_is_kept() {
+        let cancel = CancelToken::new();
+        assert!(matches!(block_on(cancel.run(async { 7 })), Ok(7)));
+        assert!(cancel.check().is_ok());
+        cancel.cancel();
+        assert!(matches!(block_on(cancel.run(async { 7 })), Err(InstallerError::Cancelled)));
+    }
+}
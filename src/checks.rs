@@ -0,0 +1,233 @@
+// The troubleshooting checklist: what helpers ask someone to look at one step at a time
+// when Cobalt doesn't load, run in one go. Each check says pass, warn or fail with a line
+// on why, and the ones with an obvious fix carry it so the panel can offer a button.
+//
+// Everything but the GitHub check only reads the disk, and runs off the UI thread, so the
+// text is looked up in a language passed in rather than through t!.
+
+use std::path::{Path, PathBuf};
+
+use crate::i18n::{tr_args_in, tr_in, Language};
+use crate::installation_type::InstallationType;
+use crate::manifest::Manifest;
+use crate::cleanup::bad_subsdk9_path;
+use crate::paths::{Emulator, TargetError, EMULATORS};
+use crate::{disk, layout, mods};
+
+// Comfortably more than a Cobalt install, with room for a backup of the previous one.
+const LOW_SPACE: u64 = 100 * 1024 * 1024;
+
+// Folders emulators create for a game the first time it's started, relative to their
+// data folder: Ryujinx's per-game folder and the shader cache of the yuzu family.
+const GAME_TRACES: &[&str] = &["games", "shader"];
+
+// Created and removed again to see whether the mods folder takes writes.
+const WRITE_PROBE: &str = ".cobalt_installer_write_check";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    pub fn class(self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Warn => "warn",
+            Status::Fail => "fail",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Status::Pass => "PASS",
+            Status::Warn => "WARN",
+            Status::Fail => "FAIL",
+        }
+    }
+}
+
+// What the panel can do about a failed check.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fix {
+    // Delete a file an old install left behind.
+    CleanUp(PathBuf),
+    // Reinstall Cobalt's files over the current install.
+    Repair,
+    // Pick the SD card folder again.
+    SelectPath,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Check {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+    pub fix: Option<Fix>,
+}
+
+impl Check {
+    fn new(name: String, status: Status, detail: String) -> Self {
+        Self { name, status, detail, fix: None }
+    }
+
+    fn with_fix(self, fix: Fix) -> Self {
+        Self { fix: Some(fix), ..self }
+    }
+}
+
+fn shown(path: &Path) -> String {
+    disk::display_path(path)
+}
+
+fn detection(language: Language, installation_type: InstallationType, emulator: Option<&Emulator>) -> Check {
+    let name = tr_in(language, "check.detection");
+    let Some(emulator) = emulator else {
+        let path = crate::settings::SD_CARD_PATH.get();
+        return if path.is_empty() {
+            Check::new(name, Status::Fail, tr_in(language, "check.sd_none")).with_fix(Fix::SelectPath)
+        } else if !Path::new(&path).is_dir() {
+            Check::new(name, Status::Fail, tr_args_in(language, "check.sd_missing", &[("path", path)])).with_fix(Fix::SelectPath)
+        } else {
+            Check::new(name, Status::Pass, tr_args_in(language, "check.sd_picked", &[("path", path)]))
+        };
+    };
+    let Some(data) = emulator.data_path() else {
+        return Check::new(name, Status::Fail, tr_args_in(language, "check.detection_no_path", &[("emulator", emulator.name.to_string())]));
+    };
+    if !emulator.is_installed() {
+        let args = [("emulator", emulator.name.to_string()), ("path", shown(&data))];
+        return Check::new(name, Status::Fail, tr_args_in(language, "check.detection_missing", &args)).with_fix(Fix::SelectPath);
+    }
+    let mut detail = tr_args_in(
+        language,
+        "check.detection_found",
+        &[("emulator", emulator.name.to_string()), ("path", shown(&data))],
+    );
+    let others: Vec<&str> = EMULATORS
+        .iter()
+        .filter(|e| e.name != installation_type.name() && e.is_installed())
+        .map(|e| e.name)
+        .collect();
+    if !others.is_empty() {
+        detail.push(' ');
+        detail.push_str(&tr_args_in(language, "check.detection_also", &[("others", others.join(", "))]));
+    }
+    Check::new(name, Status::Pass, detail)
+}
+
+// Only for emulators, there's no telling from an SD card what's on the Switch.
+fn game(language: Language, emulator: &Emulator) -> Check {
+    let name = tr_in(language, "check.game");
+    let args = [("emulator", emulator.name.to_string())];
+    let started = emulator.data_path().is_some_and(|data| {
+        GAME_TRACES
+            .iter()
+            .any(|dir| disk::resolve_case_insensitive(&data, &format!("{dir}/{}", layout::ENGAGE_TITLE_ID)).is_dir())
+    });
+    if started {
+        Check::new(name, Status::Pass, tr_args_in(language, "check.game_found", &args))
+    } else {
+        Check::new(name, Status::Warn, tr_args_in(language, "check.game_missing", &args))
+    }
+}
+
+fn leftovers(language: Language, emulator: &Emulator) -> Check {
+    let name = tr_in(language, "check.leftovers");
+    match bad_subsdk9_path(emulator).filter(|p| p.exists()) {
+        Some(path) => Check::new(name, Status::Fail, tr_args_in(language, "check.leftovers_found", &[("path", shown(&path))]))
+            .with_fix(Fix::CleanUp(path)),
+        None => Check::new(name, Status::Pass, tr_in(language, "check.leftovers_none")),
+    }
+}
+
+fn mods_folder(language: Language, target: &Path) -> Check {
+    let name = tr_in(language, "check.mods_folder");
+    let dir = disk::resolve_case_insensitive(target, mods::MODS_DIR);
+    let path = shown(&dir);
+    if !dir.is_dir() {
+        return Check::new(name, Status::Warn, tr_args_in(language, "check.mods_missing", &[("path", path)]));
+    }
+    let probe = dir.join(WRITE_PROBE);
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            Check::new(name, Status::Pass, tr_args_in(language, "check.mods_ok", &[("path", path)]))
+        }
+        Err(e) => Check::new(
+            name,
+            Status::Fail,
+            tr_args_in(language, "check.mods_read_only", &[("path", path), ("error", e.to_string())]),
+        ),
+    }
+}
+
+fn free_space(language: Language, target: &Path) -> Check {
+    let name = tr_in(language, "check.free_space");
+    match disk::existing_ancestor(target).and_then(disk::free_space) {
+        Some(free) if free < LOW_SPACE => {
+            let args = [("free", disk::format_bytes(free)), ("needed", disk::format_bytes(LOW_SPACE))];
+            Check::new(name, Status::Warn, tr_args_in(language, "check.space_low", &args))
+        }
+        Some(free) => Check::new(name, Status::Pass, tr_args_in(language, "check.space_ok", &[("free", disk::format_bytes(free))])),
+        None => Check::new(name, Status::Warn, tr_in(language, "check.space_unknown")),
+    }
+}
+
+fn cobalt_files(language: Language, target: &Path) -> Check {
+    let name = tr_in(language, "check.cobalt_files");
+    let manifest = Manifest::load(target);
+    if !layout::has_cobalt(target) {
+        // Missing files are only a problem if we put them there in the first place.
+        return match manifest {
+            Some(_) => Check::new(name, Status::Fail, tr_in(language, "check.cobalt_incomplete")).with_fix(Fix::Repair),
+            None => Check::new(name, Status::Warn, tr_in(language, "check.cobalt_missing")),
+        };
+    }
+    match manifest.as_ref().and_then(|m| layout::damaged_files(target, m)) {
+        Some(damaged) if !damaged.is_empty() => {
+            let args = [("count", damaged.len().to_string()), ("files", damaged.join(", "))];
+            Check::new(name, Status::Fail, tr_args_in(language, "check.cobalt_damaged", &args)).with_fix(Fix::Repair)
+        }
+        Some(_) => Check::new(name, Status::Pass, tr_in(language, "check.cobalt_ok")),
+        None => Check::new(name, Status::Pass, tr_in(language, "check.cobalt_unverified")),
+    }
+}
+
+// Every check that only needs the disk. `target` is the resolved install target, or why
+// there isn't one. Reads a fair few files, so keep it off the UI thread.
+pub fn run_local(language: Language, installation_type: InstallationType, target: Result<PathBuf, TargetError>) -> Vec<Check> {
+    let emulator = installation_type.emulator();
+    let mut checks = vec![detection(language, installation_type, emulator)];
+    if let Some(emulator) = emulator.filter(|e| e.is_installed()) {
+        checks.push(game(language, emulator));
+        checks.push(leftovers(language, emulator));
+    }
+    // Without a target detection has already failed and says why, the rest need one.
+    if let Ok(target) = target {
+        checks.push(mods_folder(language, &target));
+        checks.push(free_space(language, &target));
+        checks.push(cobalt_files(language, &target));
+    }
+    checks
+}
+
+// Whether GitHub, where every download comes from, can be reached.
+pub async fn github(language: Language) -> Check {
+    let name = tr_in(language, "check.github");
+    match crate::release::latest(false).await {
+        Ok(Some(release)) => Check::new(name, Status::Pass, tr_args_in(language, "check.github_ok", &[("version", release.version().to_string())])),
+        Ok(None) => Check::new(name, Status::Warn, tr_in(language, "check.github_no_release")),
+        Err(e) => Check::new(name, Status::Fail, format!("{} ({e})", crate::failure::Failure::from_download(&e).summary())),
+    }
+}
+
+// The results as plain text, for pasting into a help channel.
+pub fn to_text(checks: &[Check]) -> String {
+    checks
+        .iter()
+        .map(|c| format!("[{}] {}: {}\n", c.status.label(), c.name, c.detail))
+        .collect()
+}
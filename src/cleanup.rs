@@ -0,0 +1,66 @@
+// Files old installs left where they break things, and removing them.
+
+use std::path::PathBuf;
+
+use dioxus::logger::tracing;
+
+use crate::error::InstallerError;
+use crate::paths::Emulator;
+use crate::system::{RealSystem, System};
+
+// An early installer put a subsdk9 in the emulator's own mods folder, where it's loaded
+// on top of Cobalt's and stops the game from starting.
+pub fn bad_subsdk9_path(emulator: &Emulator) -> Option<PathBuf> {
+    bad_subsdk9_path_in(&RealSystem, emulator)
+}
+
+pub fn bad_subsdk9_path_in(system: &dyn System, emulator: &Emulator) -> Option<PathBuf> {
+    emulator.data_path_in(system).map(|base| base.join("mods/contents/0100a6301214e000/skyline/exefs/subsdk9"))
+}
+
+// Returns whether there was a bad subsdk9 to delete.
+pub fn delete_bad_subsdk9(emulator: &Emulator) -> Result<bool, InstallerError> {
+    delete_bad_subsdk9_in(&RealSystem, emulator)
+}
+
+pub fn delete_bad_subsdk9_in(system: &dyn System, emulator: &Emulator) -> Result<bool, InstallerError> {
+    let Some(path) = bad_subsdk9_path_in(system, emulator) else {
+        return Err(InstallerError::Environment { problem: format!("Couldn't find {}'s folder on this system", emulator.name) });
+    };
+    if !system.exists(&path) {
+        tracing::info!("No bad subsdk9 found");
+        return Ok(false);
+    }
+    tracing::info!("Deleting bad subsdk9");
+    std::fs::remove_file(&path).map_err(|e| InstallerError::filesystem(&path, e))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::get_emulator;
+    use crate::system::FakeSystem;
+    use crate::Scratch;
+
+    #[test]
+    fn bad_subsdk9_is_deleted_once() {
+        let scratch = Scratch::new("bad_subsdk9");
+        let system = FakeSystem::new("linux", Some(&scratch.0));
+        let ryujinx = get_emulator("Ryujinx").unwrap();
+        assert!(!delete_bad_subsdk9_in(&system, ryujinx).unwrap());
+
+        let path = scratch.file(".config/Ryujinx/mods/contents/0100a6301214e000/skyline/exefs/subsdk9", b"old");
+        assert_eq!(bad_subsdk9_path_in(&system, ryujinx), Some(path.clone()));
+        assert!(delete_bad_subsdk9_in(&system, ryujinx).unwrap());
+        assert!(!path.exists());
+        assert!(!delete_bad_subsdk9_in(&system, ryujinx).unwrap());
+    }
+
+    #[test]
+    fn no_data_folder_is_an_environment_error() {
+        let system = FakeSystem::new("windows", None);
+        let result = delete_bad_subsdk9_in(&system, get_emulator("Eden").unwrap());
+        assert!(matches!(result, Err(InstallerError::Environment { .. })), "{result:?}");
+    }
+}
@@ -0,0 +1,388 @@
+// Installing without the window, for scripts and for setting up several SD cards in a
+// row:
+//
+//     CobaltInstaller install --target sdcard --path /run/media/deck/SWITCH
+//     CobaltInstaller install --target ryujinx
+//
+// It runs the same steps the window does (see installer.rs), prints each one as it goes
+// and exits with a code saying what, if anything, went wrong. The window's settings live
+// in its own storage and aren't read, anything that matters has a flag instead.
+
+use std::path::PathBuf;
+use std::time::Instant;
+
+use dioxus::logger::tracing;
+
+use crate::activity::Severity;
+use crate::error::InstallerError;
+use crate::i18n::{self, Language};
+use crate::install::{self, WriteOptions};
+use crate::installation_type::InstallationType;
+use crate::installer::{Install, Installer, InstallerEvent, StepError, StepEvent, StepStatus};
+use crate::manifest::Manifest;
+use crate::paths::{resolve_target, TargetError};
+use crate::progress::Phase;
+use crate::{disk, history, release, target_lock, RELEASE_URL};
+
+pub const EXIT_OK: i32 = 0;
+// Anything without a code of its own, a cancelled install included.
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_DOWNLOAD: i32 = 3;
+pub const EXIT_EXTRACTION: i32 = 4;
+// The files were written, but not where Cobalt looks for them.
+pub const EXIT_VALIDATION: i32 = 5;
+// Another installer holds the target's lock.
+pub const EXIT_TARGET_IN_USE: i32 = 6;
+
+const USAGE: &str = "Usage: CobaltInstaller install --target <ryujinx|citron|eden|sdcard> [options]
+
+Installs the latest Cobalt release without opening the window.
+
+Options:
+  --target <type>    Where to install: ryujinx, citron, eden or sdcard
+  --path <folder>    The SD card's root, needed for sdcard. For an emulator, installs
+                     here instead of the emulator's own sdcard folder
+  --version <tag>    Install this release (like v1.4.0) instead of the latest
+  --prereleases      Count prereleases as the latest release
+  --no-backup        Don't back up the current install before updating it
+  --reset-config     Replace Cobalt's config with the release's instead of keeping it
+  --help             Show this
+
+Exit codes: 0 installed, 1 failed, 2 bad arguments, 3 download failed, 4 extraction
+failed, 5 the install doesn't look right, 6 another installer is using the target.";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstallArgs {
+    pub target: InstallationType,
+    pub path: Option<PathBuf>,
+    pub version: Option<String>,
+    pub prereleases: bool,
+    pub backup: bool,
+    pub preserve_config: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Install(InstallArgs),
+    Help,
+}
+
+fn installation_type(name: &str) -> Option<InstallationType> {
+    match name.to_ascii_lowercase().as_str() {
+        "ryujinx" => Some(InstallationType::Ryujinx),
+        "citron" => Some(InstallationType::Citron),
+        "eden" => Some(InstallationType::Eden),
+        "sdcard" | "sd" => Some(InstallationType::SdCard),
+        _ => None,
+    }
+}
+
+// The command in `args` (without the program's own name), None without any so the
+// window opens as usual. macOS hands apps opened from Finder a -psn_ argument, that
+// doesn't count.
+pub fn parse(args: &[String]) -> Option<Result<Command, String>> {
+    let mut args = args.iter().filter(|a| !a.starts_with("-psn_")).peekable();
+    let command = args.next()?;
+    Some(match command.as_str() {
+        "--help" | "-h" | "help" => Ok(Command::Help),
+        "install" => parse_install(args),
+        other => Err(format!("Unknown command {other:?}")),
+    })
+}
+
+fn parse_install<'a>(mut args: impl Iterator<Item = &'a String>) -> Result<Command, String> {
+    let mut target = None;
+    let mut parsed = InstallArgs {
+        target: InstallationType::default(),
+        path: None,
+        version: None,
+        prereleases: false,
+        backup: true,
+        preserve_config: true,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().ok_or_else(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--target" => {
+                let name = value()?;
+                target = Some(installation_type(&name).ok_or_else(|| format!("Unknown target {name:?}"))?);
+            }
+            "--path" => parsed.path = Some(PathBuf::from(value()?)),
+            "--version" => parsed.version = Some(value()?),
+            "--prereleases" => parsed.prereleases = true,
+            "--no-backup" => parsed.backup = false,
+            "--reset-config" => parsed.preserve_config = false,
+            "--help" | "-h" => return Ok(Command::Help),
+            other => return Err(format!("Unknown option {other:?}")),
+        }
+    }
+    parsed.target = target.ok_or("--target is required")?;
+    if parsed.target.is_sd_card() && parsed.path.is_none() {
+        return Err("--target sdcard needs --path".to_string());
+    }
+    Ok(Command::Install(parsed))
+}
+
+// The window build has no console of its own. Run from a terminal, it borrows that one so
+// the output shows up.
+pub fn attach_console() {
+    #[cfg(windows)]
+    unsafe {
+        use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+// Run a parsed command, returning the exit code.
+pub fn run(command: Result<Command, String>) -> i32 {
+    match command {
+        Err(problem) => {
+            eprintln!("{problem}\n\n{USAGE}");
+            EXIT_USAGE
+        }
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            EXIT_OK
+        }
+        Ok(Command::Install(args)) => match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime.block_on(run_install(args)),
+            Err(e) => {
+                eprintln!("Couldn't start: {e}");
+                EXIT_FAILED
+            }
+        },
+    }
+}
+
+// TargetError's own text is translated, which needs the window.
+fn describe(error: TargetError) -> String {
+    let (key, args) = match error {
+        TargetError::EmulatorNotFound(emulator) => ("emulator.not_found", vec![("emulator", emulator.to_string())]),
+        TargetError::NoDataPath(emulator) => ("target.no_data_path", vec![("emulator", emulator.to_string())]),
+        TargetError::NoSdPath => ("target.no_sd_path", Vec::new()),
+    };
+    i18n::tr_args_in(Language::English, key, &args)
+}
+
+// What a failed step exits with.
+fn exit_code(error: &StepError, phase: Phase) -> i32 {
+    match (&error.error, phase) {
+        (InstallerError::Validation { .. }, _) => EXIT_VALIDATION,
+        (InstallerError::Cancelled, _) => EXIT_FAILED,
+        (InstallerError::Network { .. }, _) | (_, Phase::Download) => EXIT_DOWNLOAD,
+        (InstallerError::Archive { .. }, _) | (_, Phase::Extract) => EXIT_EXTRACTION,
+        _ => EXIT_FAILED,
+    }
+}
+
+async fn run_install(args: InstallArgs) -> i32 {
+    let started = Instant::now();
+    let target = match (&args.path, args.target.is_sd_card()) {
+        (Some(path), _) => path.clone(),
+        (None, sd_card) => match resolve_target(args.target, "") {
+            Ok(target) => target,
+            Err(e) => {
+                eprintln!("{}", describe(e));
+                return if sd_card { EXIT_USAGE } else { EXIT_FAILED };
+            }
+        },
+    };
+
+    let release = match &args.version {
+        Some(tag) => match release::list(args.prereleases).await {
+            Ok(list) => match list.into_iter().find(|r| &r.tag_name == tag) {
+                Some(release) => Some(release),
+                None => {
+                    eprintln!("Couldn't find Cobalt {tag} on GitHub");
+                    return EXIT_DOWNLOAD;
+                }
+            },
+            Err(e) => {
+                eprintln!("Couldn't list Cobalt's releases: {e}");
+                return EXIT_DOWNLOAD;
+            }
+        },
+        // Without GitHub's API the plain download link still works, the install just
+        // doesn't know its version.
+        None => release::latest(args.prereleases).await.ok().flatten(),
+    };
+    let url = match release.as_ref().map(|r| (r, r.zip_asset())) {
+        Some((_, Some(asset))) => asset.browser_download_url.clone(),
+        // The plain link is always the latest release, not the one asked for.
+        Some((release, None)) if args.version.is_some() => {
+            eprintln!("Cobalt {} has no zip to install", release.tag_name);
+            return EXIT_DOWNLOAD;
+        }
+        _ => RELEASE_URL.to_string(),
+    };
+
+    // Held until this returns, whichever way the install ends.
+    let _lock = match target_lock::acquire(&target) {
+        Ok(lock) => lock,
+        Err(holder) => {
+            eprintln!("Not installing, {holder} is already installing into {}", target.display());
+            return EXIT_TARGET_IN_USE;
+        }
+    };
+
+    let previous = Manifest::load(&target);
+    let action = if previous.is_some() { history::Action::Update } else { history::Action::Install };
+    let mut install = Install {
+        version: release.map(|r| r.tag_name),
+        backup: args.backup,
+        update: previous.is_some(),
+        previous,
+        emulator: args.target.emulator(),
+        write: WriteOptions::for_target(&target, args.preserve_config, 0, 0),
+        flush: install::needs_flush(&target, args.target.is_sd_card()),
+        ..Install::new(target.clone(), url)
+    };
+    println!(
+        "Installing Cobalt {} into {}",
+        install.version.as_deref().unwrap_or("(latest)"),
+        target.display()
+    );
+
+    let mut installer = Installer::release();
+    let steps: Vec<(&'static str, Phase)> = installer.steps().iter().map(|s| (s.name, s.phase)).collect();
+    // In tens of percent, so a step prints at most eleven progress lines.
+    let mut shown = None;
+    let result = installer
+        .run(&mut install, |event| match event {
+            InstallerEvent::Status(index, StepStatus::Running) => {
+                shown = None;
+                println!("[{}/{}] {}", index + 1, steps.len(), steps[index].0);
+            }
+            InstallerEvent::Step(_, StepEvent::Progress(fraction)) => {
+                let tens = (fraction.clamp(0.0, 1.0) * 10.0) as u32;
+                if shown != Some(tens) {
+                    shown = Some(tens);
+                    println!("      {}%", tens * 10);
+                }
+            }
+            InstallerEvent::Step(_, StepEvent::Note(severity, message)) => match severity {
+                Severity::Info => {
+                    tracing::info!("{message}");
+                    println!("      {message}");
+                }
+                Severity::Warning => {
+                    tracing::warn!("{message}");
+                    eprintln!("      Warning: {message}");
+                }
+                Severity::Error => {
+                    tracing::error!("{message}");
+                    eprintln!("      Error: {message}");
+                }
+            },
+            InstallerEvent::RollingBack => eprintln!("Undoing what the install wrote"),
+            _ => {}
+        })
+        .await;
+
+    let bytes_written = install.extracted.as_ref().map_or(0, |e| e.bytes);
+    let record = |outcome| {
+        let entry = history::Entry {
+            timestamp: history::now(),
+            action,
+            version: install.version.clone(),
+            target_type: args.target.name().to_string(),
+            target: target.clone(),
+            outcome,
+            duration_secs: started.elapsed().as_secs_f64(),
+            bytes_written,
+            phases: Vec::new(),
+        };
+        if let Err(e) = history::append(entry) {
+            tracing::error!("Couldn't write the install history: {e}");
+        }
+    };
+    match result {
+        Ok(()) => {
+            record(history::Outcome::Success);
+            install.finish().await;
+            println!(
+                "Installed in {:.1}s, {} written",
+                started.elapsed().as_secs_f64(),
+                disk::format_bytes(bytes_written)
+            );
+            for warning in &install.warnings {
+                eprintln!("Note: {warning}");
+            }
+            EXIT_OK
+        }
+        Err(error) => {
+            let message = match &error.rollback {
+                Some(e) => format!("Couldn't clean up after cancelling: {e}"),
+                None => format!("{} failed: {}", error.name, error.error),
+            };
+            tracing::error!("{message}");
+            eprintln!("{message}");
+            record(history::Outcome::Failed(message));
+            exit_code(&error, steps[error.step].1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Option<Result<Command, String>> {
+        parse(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn no_arguments_opens_the_window() {
+        assert_eq!(parse_args(&[]), None);
+        assert_eq!(parse_args(&["-psn_0_12345"]), None);
+    }
+
+    #[test]
+    fn install_arguments_are_read() {
+        let parsed = parse_args(&["install", "--target", "sdcard", "--path", "/run/media/deck/SWITCH", "--no-backup"]);
+        assert_eq!(
+            parsed,
+            Some(Ok(Command::Install(InstallArgs {
+                target: InstallationType::SdCard,
+                path: Some(PathBuf::from("/run/media/deck/SWITCH")),
+                version: None,
+                prereleases: false,
+                backup: false,
+                preserve_config: true,
+            })))
+        );
+        let Some(Ok(Command::Install(args))) = parse_args(&["install", "--version", "v1.4.0", "--target", "Ryujinx"]) else {
+            panic!("not an install");
+        };
+        assert_eq!((args.target, args.version.as_deref(), args.path), (InstallationType::Ryujinx, Some("v1.4.0"), None));
+        assert_eq!(parse_args(&["install", "--help"]), Some(Ok(Command::Help)));
+    }
+
+    #[test]
+    fn bad_arguments_are_refused() {
+        for args in [
+            &["uninstall"][..],
+            &["install"],
+            &["install", "--target"],
+            &["install", "--target", "yuzu"],
+            &["install", "--target", "sdcard"],
+            &["install", "--target", "eden", "--force"],
+        ] {
+            assert!(matches!(parse_args(args), Some(Err(_))), "{args:?}");
+        }
+        assert_eq!(run(Err("bad".to_string())), EXIT_USAGE);
+    }
+
+    #[test]
+    fn failures_exit_with_their_own_codes() {
+        let failed = |error| StepError { step: 0, name: "", error, rollback: None };
+        let environment = || InstallerError::Environment { problem: String::new() };
+        assert_eq!(exit_code(&failed(environment()), Phase::Download), EXIT_DOWNLOAD);
+        assert_eq!(exit_code(&failed(environment()), Phase::Extract), EXIT_EXTRACTION);
+        assert_eq!(exit_code(&failed(environment()), Phase::Flush), EXIT_FAILED);
+        let invalid = InstallerError::Validation { path: PathBuf::new(), problem: String::new() };
+        assert_eq!(exit_code(&failed(invalid), Phase::Finalize), EXIT_VALIDATION);
+        assert_eq!(exit_code(&failed(InstallerError::Cancelled), Phase::Extract), EXIT_FAILED);
+    }
+}
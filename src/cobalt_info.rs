@@ -0,0 +1,171 @@
+// The "What is Cobalt?" panel: the start of Cobalt's README, for people who arrive from a
+// video link without knowing what they're installing.
+//
+// The README is fetched in the background and cached for a day. Until it arrives, or if
+// it never does, the panel shows the short description bundled with the installer.
+// Only the bit of markdown a README intro uses is understood: headings, paragraphs and
+// bullet lists, with links, emphasis and code turned into plain text.
+
+use dioxus::logger::tracing;
+use serde::{Deserialize, Serialize};
+
+use crate::paths::cache_dir;
+
+const README_URL: &str = "https://raw.githubusercontent.com/Raytwo/Cobalt/main/README.md";
+
+const REFRESH_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+// Enough for the description and the feature list, the rest is for developers.
+const MAX_BLOCKS: usize = 8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Block {
+    Heading(String),
+    Paragraph(String),
+    List(Vec<String>),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct CachedReadme {
+    // Seconds since the Unix epoch.
+    fetched_at: u64,
+    markdown: String,
+}
+
+fn cache_path() -> Option<std::path::PathBuf> {
+    cache_dir().map(|d| d.join("cobalt_readme.json"))
+}
+
+fn load_cached() -> Option<CachedReadme> {
+    let contents = std::fs::read_to_string(cache_path()?).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn save_cached(cached: &CachedReadme) {
+    let Some(path) = cache_path() else {
+        return;
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, serde_json::to_string(cached)?));
+    if let Err(e) = result {
+        tracing::info!("Couldn't cache Cobalt's README: {e}");
+    }
+}
+
+// Whatever was cached last time, however old, so the panel has something right away.
+pub fn cached() -> Option<String> {
+    load_cached().map(|c| c.markdown)
+}
+
+async fn fetch() -> reqwest::Result<String> {
+    crate::http::get(README_URL).await?.error_for_status()?.text().await
+}
+
+// A fresh copy when the cached one is over a day old, None when there's nothing newer.
+pub async fn refresh() -> Option<String> {
+    let cached = load_cached();
+    if cached.as_ref().is_some_and(|c| crate::history::now().saturating_sub(c.fetched_at) < REFRESH_INTERVAL_SECS) {
+        return None;
+    }
+    match fetch().await {
+        Ok(markdown) => {
+            save_cached(&CachedReadme { fetched_at: crate::history::now(), markdown: markdown.clone() });
+            Some(markdown)
+        }
+        Err(e) => {
+            tracing::info!("Couldn't fetch Cobalt's README: {e}");
+            None
+        }
+    }
+}
+
+// `[text](url)` as text, images dropped, and the emphasis and code markers removed.
+fn plain(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        let image = rest[..start].ends_with('!');
+        out.push_str(&rest[..if image { start - 1 } else { start }]);
+        let after = &rest[start + 1..];
+        match after.find("](").and_then(|close| after[close..].find(')').map(|end| (close, close + end))) {
+            Some((close, end)) => {
+                if !image {
+                    out.push_str(&after[..close]);
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(if image { "![" } else { "[" });
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out.replace("**", "").replace("__", "").replace('`', "").trim().to_string()
+}
+
+// The README's opening blocks, skipping the title, badges, HTML and code.
+pub fn parse(markdown: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph = String::new();
+    let mut list = Vec::new();
+    let mut in_code = false;
+
+    fn flush(blocks: &mut Vec<Block>, paragraph: &mut String, list: &mut Vec<String>) {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(std::mem::take(paragraph)));
+        }
+        if !list.is_empty() {
+            blocks.push(Block::List(std::mem::take(list)));
+        }
+    }
+
+    for line in markdown.lines() {
+        let line = line.trim();
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code || line.starts_with('<') || line.starts_with("![") || line.starts_with("[![") {
+            continue;
+        }
+        if line.is_empty() {
+            flush(&mut blocks, &mut paragraph, &mut list);
+        } else if let Some(heading) = line.strip_prefix('#') {
+            flush(&mut blocks, &mut paragraph, &mut list);
+            // The title is the project name, which the panel already says.
+            if heading.starts_with('#') {
+                blocks.push(Block::Heading(plain(heading.trim_start_matches('#'))));
+            }
+        } else if let Some(item) = line.strip_prefix("- ").or(line.strip_prefix("* ")).or(line.strip_prefix("+ ")) {
+            if !paragraph.is_empty() {
+                flush(&mut blocks, &mut paragraph, &mut list);
+            }
+            list.push(plain(item));
+        } else {
+            if !list.is_empty() {
+                flush(&mut blocks, &mut paragraph, &mut list);
+            }
+            let text = plain(line);
+            if !text.is_empty() {
+                if !paragraph.is_empty() {
+                    paragraph.push(' ');
+                }
+                paragraph.push_str(&text);
+            }
+        }
+        if blocks.len() >= MAX_BLOCKS {
+            break;
+        }
+    }
+    flush(&mut blocks, &mut paragraph, &mut list);
+
+    blocks.truncate(MAX_BLOCKS);
+    // A heading with nothing after it would just dangle.
+    if matches!(blocks.last(), Some(Block::Heading(_))) {
+        blocks.pop();
+    }
+    blocks
+}
@@ -0,0 +1,299 @@
+// What happens when the installer panics. Without this the window just vanishes (there's
+// no console on Windows to print to), so the panic is written to a crash record in the
+// data directory and a message box says where it is before the process exits.
+//
+// Records are JSON, one crash_<date>_<time>.json each, with what a bug report needs: the
+// version and OS, the panic and its backtrace, what the install was doing and the end of
+// the log file. The next launch offers to report the newest one nobody has seen yet, and
+// only the last few are kept.
+//
+// The hook runs on whichever thread panicked, in a process that's already in trouble:
+// nothing in here may panic, and it doesn't touch the Dioxus runtime (so no t!, the
+// message box is in English).
+
+use std::io::Write;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use dioxus::logger::tracing;
+use serde::{Deserialize, Serialize};
+use time::macros::format_description;
+
+use crate::install_state::InstallState;
+use crate::paths::data_dir;
+use crate::{log_file, HELP_URL};
+
+// Only the first panic gets a record, any others are fallout from it.
+static CRASHED: AtomicBool = AtomicBool::new(false);
+
+// The install's state as last seen by the UI, for the record.
+static LAST_STATE: Mutex<String> = Mutex::new(String::new());
+
+// How many of the log file's newest lines go in a record.
+const LOG_LINES: usize = 50;
+
+// Older records are deleted once there are more than this.
+const KEEP: usize = 5;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrashRecord {
+    // UTC, as in the file name.
+    pub time: String,
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: String,
+    pub location: String,
+    pub message: String,
+    pub backtrace: String,
+    // Empty if the crash came before any install was started.
+    pub install_state: String,
+    pub log: Vec<String>,
+    // Set once the user has reported or dismissed it, so it's only offered once.
+    pub reported: bool,
+    // Where it was read from.
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+fn crash_dir() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("crashes"))
+}
+
+// Call whenever the install state changes.
+pub fn note_state(state: &InstallState) {
+    if let Ok(mut last) = LAST_STATE.lock() {
+        *last = format!("{state:?}");
+    }
+}
+
+fn message(info: &PanicHookInfo) -> String {
+    if let Some(message) = info.payload().downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = info.payload().downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "(no message)".to_string()
+    }
+}
+
+// The last `count` lines of the log file, if there is one.
+fn log_tail(path: &Path, count: usize) -> Vec<String> {
+    let Ok(bytes) = std::fs::read(path) else {
+        return Vec::new();
+    };
+    let text = String::from_utf8_lossy(&bytes);
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(count)..].iter().map(|l| l.to_string()).collect()
+}
+
+fn stamp(now: time::OffsetDateTime) -> String {
+    now.format(format_description!("[year]-[month]-[day]_[hour]-[minute]-[second]"))
+        .unwrap_or_else(|_| now.unix_timestamp().to_string())
+}
+
+fn record(info: &PanicHookInfo) -> CrashRecord {
+    let commit = env!("INSTALLER_GIT_COMMIT");
+    // The panic may have come from under the lock, try_lock rather than wait on it.
+    let install_state = LAST_STATE.try_lock().map(|s| s.clone()).unwrap_or_default();
+    CrashRecord {
+        time: stamp(time::OffsetDateTime::now_utc()),
+        version: if commit.is_empty() {
+            env!("CARGO_PKG_VERSION").to_string()
+        } else {
+            format!("{} ({commit})", env!("CARGO_PKG_VERSION"))
+        },
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        thread: std::thread::current().name().unwrap_or("unnamed").to_string(),
+        location: info.location().map(|l| l.to_string()).unwrap_or_else(|| "unknown".to_string()),
+        message: message(info),
+        backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        install_state,
+        log: log_file::current().map(|p| log_tail(&p, LOG_LINES)).unwrap_or_default(),
+        reported: false,
+        path: PathBuf::new(),
+    }
+}
+
+fn save(record: &CrashRecord, path: &Path) -> std::io::Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(record)?)
+}
+
+// crash_<time>.json in `dir`, or None if it couldn't be written. The time only goes down
+// to the second, so a second crash in the same second (another thread, another installer)
+// gets crash_<time>_2.json rather than overwriting the first.
+fn write_record(dir: &Path, record: &CrashRecord) -> Option<PathBuf> {
+    std::fs::create_dir_all(dir).ok()?;
+    let json = serde_json::to_string_pretty(record).ok()?;
+    for n in 1..100 {
+        let name = if n == 1 { format!("crash_{}.json", record.time) } else { format!("crash_{}_{n}.json", record.time) };
+        let path = dir.join(name);
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(json.as_bytes()).ok()?;
+                return Some(path);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+// Every crash file in `dir`, oldest first. The time stamps in the names sort as text.
+// Includes the plain text reports older installers wrote.
+fn crash_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("crash_")))
+        .collect();
+    files.sort();
+    files
+}
+
+// Quietly, it runs in the hook too and logging from there could wait on a lock the
+// panicking thread holds.
+fn prune_in(dir: &Path, keep: usize) {
+    let files = crash_files(dir);
+    for old in &files[..files.len().saturating_sub(keep)] {
+        let _ = std::fs::remove_file(old);
+    }
+}
+
+fn unreported_in(dir: &Path) -> Option<CrashRecord> {
+    let newest = crash_files(dir).into_iter().rev().find(|p| p.extension().is_some_and(|e| e == "json"))?;
+    let mut record: CrashRecord = serde_json::from_str(&std::fs::read_to_string(&newest).ok()?).ok()?;
+    record.path = newest;
+    (!record.reported).then_some(record)
+}
+
+// The newest crash record the user hasn't been asked about, after deleting all but the
+// last few. Call at startup.
+pub fn unreported() -> Option<CrashRecord> {
+    let dir = crash_dir()?;
+    prune_in(&dir, KEEP);
+    unreported_in(&dir)
+}
+
+// Don't offer `record` again.
+pub fn mark_reported(record: &CrashRecord) {
+    let record = CrashRecord { reported: true, ..record.clone() };
+    if let Err(e) = save(&record, &record.path) {
+        tracing::warn!("Couldn't update the crash record {}: {e}", record.path.display());
+    }
+}
+
+fn show_dialog(path: Option<&PathBuf>) {
+    // macOS only allows windows on the main thread, a dialog from anywhere else would
+    // take the process down before it's shown.
+    if cfg!(target_os = "macos") && std::thread::current().name() != Some("main") {
+        return;
+    }
+    let saved = match path {
+        Some(path) => format!("A crash report was saved to:\n{}", path.display()),
+        None => "The crash report couldn't be saved.".to_string(),
+    };
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Error)
+        .set_title("Cobalt Installer crashed")
+        .set_description(format!(
+            "Something went wrong and the installer has to close.\n\n{saved}\n\nIf you ask for help on Discord ({HELP_URL}), please attach it."
+        ))
+        .set_buttons(rfd::MessageButtons::Ok)
+        .show();
+}
+
+pub fn install_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Still printed as usual, for anyone running from a terminal.
+        default_hook(info);
+        if CRASHED.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let path = crash_dir().and_then(|dir| {
+            let path = write_record(&dir, &record(info));
+            prune_in(&dir, KEEP);
+            path
+        });
+        show_dialog(path.as_ref());
+        // Whatever the panic interrupted may be half done, carrying on would be worse.
+        std::process::exit(101);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scratch;
+
+    fn crash_at(time: &str) -> CrashRecord {
+        CrashRecord { time: time.to_string(), message: format!("crashed at {time}"), ..Default::default() }
+    }
+
+    #[test]
+    fn the_newest_unreported_crash_is_offered_once() {
+        let scratch = Scratch::new("crash_unreported");
+        assert_eq!(unreported_in(&scratch.0), None);
+
+        write_record(&scratch.0, &crash_at("2026-01-01_10-00-00")).unwrap();
+        let newest = write_record(&scratch.0, &crash_at("2026-01-02_10-00-00")).unwrap();
+        let record = unreported_in(&scratch.0).unwrap();
+        assert_eq!(record.message, "crashed at 2026-01-02_10-00-00");
+        assert_eq!(record.path, newest);
+
+        mark_reported(&record);
+        assert_eq!(unreported_in(&scratch.0), None);
+        // Still there for a bug report made by hand.
+        let saved: CrashRecord = serde_json::from_str(&std::fs::read_to_string(&newest).unwrap()).unwrap();
+        assert!(saved.reported);
+    }
+
+    #[test]
+    fn crashes_in_the_same_second_are_all_kept() {
+        let scratch = Scratch::new("crash_same_second");
+        let first = write_record(&scratch.0, &crash_at("2026-01-01_10-00-00")).unwrap();
+        let mut second = crash_at("2026-01-01_10-00-00");
+        second.message = "crashed again".to_string();
+        let second = write_record(&scratch.0, &second).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(crash_files(&scratch.0), vec![first, second]);
+        assert_eq!(unreported_in(&scratch.0).unwrap().message, "crashed again");
+    }
+
+    #[test]
+    fn old_crashes_are_pruned() {
+        let scratch = Scratch::new("crash_prune");
+        scratch.file("crash_2025-12-31_09-00-00.txt", b"from an older installer");
+        scratch.file("unrelated.txt", b"");
+        for day in 1..=KEEP {
+            write_record(&scratch.0, &crash_at(&format!("2026-01-0{day}_10-00-00"))).unwrap();
+        }
+        prune_in(&scratch.0, KEEP);
+        let left: Vec<String> =
+            crash_files(&scratch.0).iter().map(|p| p.file_name().unwrap().to_string_lossy().to_string()).collect();
+        assert_eq!(left.len(), KEEP);
+        assert_eq!(left[0], "crash_2026-01-01_10-00-00.json");
+        assert!(scratch.0.join("unrelated.txt").exists());
+    }
+
+    #[test]
+    fn log_tail_keeps_the_newest_lines() {
+        let scratch = Scratch::new("crash_log_tail");
+        let log: String = (0..80).map(|n| format!("line {n}\n")).collect();
+        scratch.file("installer.log", log.as_bytes());
+        let tail = log_tail(&scratch.0.join("installer.log"), LOG_LINES);
+        assert_eq!(tail.len(), LOG_LINES);
+        assert_eq!(tail.first().map(String::as_str), Some("line 30"));
+        assert_eq!(tail.last().map(String::as_str), Some("line 79"));
+        assert!(log_tail(&scratch.0.join("missing.log"), LOG_LINES).is_empty());
+    }
+}
@@ -0,0 +1,202 @@
+// Everything support usually asks for, one question at a time, in a single zip: the
+// session log, the installer's log file, the settings, where the installer looked for
+// emulators and what it found, the install history and a bit about the machine.
+//
+// Paths go through mods_report::redact like in every other report, and URLs lose any
+// credentials or query string. The bundle is built in full before anything is saved, so
+// the user sees exactly what's in it.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use time::macros::format_description;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::activity::{self, LogEntry};
+use crate::manifest::Manifest;
+use crate::mods_report::redact;
+use crate::settings::{self, Setting};
+use crate::paths::EMULATORS;
+use crate::{disk, history, layout, log_file, version_info};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BundleFile {
+    pub name: &'static str,
+    pub contents: String,
+}
+
+// cobalt_diagnostics_<date>_<time>.zip, in UTC.
+pub fn default_file_name() -> String {
+    let now = time::OffsetDateTime::now_utc();
+    let stamp = now
+        .format(format_description!("[year]-[month]-[day]_[hour]-[minute]-[second]"))
+        .unwrap_or_else(|_| now.unix_timestamp().to_string());
+    format!("cobalt_diagnostics_{stamp}.zip")
+}
+
+// `url` without a user name, password or query string, where tokens would be.
+fn redact_url(url: &str) -> String {
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+    match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let rest = rest.rsplit_once('@').filter(|(auth, _)| !auth.contains('/')).map(|(_, host)| host).unwrap_or(rest);
+            format!("{scheme}://{rest}")
+        }
+        None => url.to_string(),
+    }
+}
+
+fn add<T: Serialize + DeserializeOwned + Clone + Send + Sync + PartialEq + 'static>(
+    map: &mut serde_json::Map<String, serde_json::Value>,
+    setting: &Setting<T>,
+) {
+    map.insert(setting.key.to_string(), serde_json::to_value(setting.get()).unwrap_or_default());
+}
+
+fn settings_json() -> String {
+    let mut map = serde_json::Map::new();
+    add(&mut map, &settings::INSTALLATION_TYPE);
+    add(&mut map, &settings::SELECTED_VERSION);
+    add(&mut map, &settings::WINDOW);
+    add(&mut map, &settings::SETUP_DONE);
+    add(&mut map, &settings::CHECK_INSTALLER_UPDATES);
+    add(&mut map, &settings::BACKUP_BEFORE_UPDATE);
+    add(&mut map, &settings::THEME);
+    add(&mut map, &settings::LANGUAGE);
+    add(&mut map, &settings::CLOSE_AFTER_INSTALL);
+    add(&mut map, &settings::UI_SCALE);
+    add(&mut map, &settings::INCLUDE_PRERELEASES);
+    add(&mut map, &settings::PRESERVE_CONFIG);
+    add(&mut map, &settings::VERBOSE_LOGGING);
+    add(&mut map, &settings::EXTRACTION_WORKERS);
+    add(&mut map, &settings::WRITE_BUFFER_KB);
+    add(&mut map, &settings::FLUSH_REMOVABLE);
+    // The ones that can say who the user is.
+    map.insert(settings::SD_CARD_PATH.key.to_string(), redact(Path::new(&settings::SD_CARD_PATH.get())).into());
+    let skipped: Vec<String> = settings::SD_CARD_SKIP_CONFIRM.get().iter().map(|p| redact(Path::new(p))).collect();
+    map.insert(settings::SD_CARD_SKIP_CONFIRM.key.to_string(), skipped.into());
+    map.insert(settings::CUSTOM_RELEASE_URL.key.to_string(), redact_url(&settings::CUSTOM_RELEASE_URL.get()).into());
+    serde_json::to_string_pretty(&map).unwrap_or_default()
+}
+
+// Where each emulator's files would be and whether they're there, then the SD card
+// folder and what the layout checks make of it.
+fn detection(target: &Path) -> String {
+    let mut text = String::new();
+    for emulator in EMULATORS {
+        let data = emulator.data_path();
+        text.push_str(&format!(
+            "{}: {} ({})\n",
+            emulator.name,
+            data.as_deref().map(redact).unwrap_or_else(|| "no data path on this OS".to_string()),
+            if emulator.is_installed() { "found" } else { "not found" },
+        ));
+        if let Some(sd) = emulator.sd_card_path() {
+            let cobalt = Manifest::load(&sd)
+                .map(|m| format!("Cobalt {}", m.version.unwrap_or_else(|| "(unknown version)".to_string())))
+                .unwrap_or_else(|| "no Cobalt from this installer".to_string());
+            text.push_str(&format!("  sd card: {}, {}\n", redact(&sd), cobalt));
+        }
+        if emulator.is_installed() {
+            text.push_str(&format!("  launcher: {}\n", if emulator.launcher().is_some() { "found" } else { "not found" }));
+        }
+    }
+
+    let sd_path = settings::SD_CARD_PATH.get();
+    if sd_path.is_empty() {
+        text.push_str("\nSD card folder: none picked\n");
+    } else {
+        let sd_path = Path::new(&sd_path);
+        text.push_str(&format!(
+            "\nSD card folder: {} ({})\n",
+            redact(sd_path),
+            if sd_path.is_dir() { "exists" } else { "missing" },
+        ));
+        text.push_str(&format!(
+            "  layout check: {}\n",
+            layout::target_warning(sd_path).unwrap_or_else(|| "looks fine".to_string()),
+        ));
+    }
+
+    text.push_str(&format!("\nSelected target: {}\n", redact(target)));
+    text.push_str("Installs this installer knows about:\n");
+    for manifest in Manifest::all() {
+        text.push_str(&format!(
+            "  {} {}\n",
+            redact(&manifest.target),
+            manifest.version.as_deref().unwrap_or("(unknown version)"),
+        ));
+    }
+    text
+}
+
+fn history_json() -> String {
+    let entries: Vec<history::Entry> = history::load()
+        .into_iter()
+        .map(|entry| history::Entry { target: redact(&entry.target).into(), ..entry })
+        .collect();
+    serde_json::to_string_pretty(&entries).unwrap_or_default()
+}
+
+fn system_info(target: &Path) -> String {
+    let free = disk::existing_ancestor(target)
+        .and_then(disk::free_space)
+        .map(disk::format_bytes)
+        .unwrap_or_else(|| "unknown".to_string());
+    format!(
+        "Installer: {}\nOS: {} ({}, {})\nFree space on the target's drive: {free}\nCollected: {}\n",
+        version_info(),
+        std::env::consts::OS,
+        std::env::consts::FAMILY,
+        std::env::consts::ARCH,
+        history::format_timestamp(history::now()),
+    )
+}
+
+// `text` with the home folder swapped for `~` like paths elsewhere, for logs whose
+// messages name paths.
+fn redact_home(text: String) -> String {
+    match dirs::home_dir() {
+        Some(home) => text.replace(&home.display().to_string(), &redact(&home)),
+        None => text,
+    }
+}
+
+fn installer_log() -> String {
+    match log_file::current().and_then(|path| std::fs::read_to_string(path).ok()) {
+        Some(contents) => redact_home(contents),
+        None => "No log file\n".to_string(),
+    }
+}
+
+// Reads a fair few files, so keep it off the UI thread.
+pub fn collect(log: &[LogEntry], target: &Path) -> Vec<BundleFile> {
+    vec![
+        BundleFile { name: "system.txt", contents: system_info(target) },
+        BundleFile { name: "session_log.txt", contents: redact_home(activity::to_text(log, target)) },
+        BundleFile { name: "installer.log", contents: installer_log() },
+        BundleFile { name: "settings.json", contents: settings_json() },
+        BundleFile { name: "detection.txt", contents: detection(target) },
+        BundleFile { name: "history.json", contents: history_json() },
+    ]
+}
+
+pub fn write_zip(files: &[BundleFile], dest: &Path) -> std::io::Result<()> {
+    let result = (|| {
+        let mut zip = ZipWriter::new(File::create(dest)?);
+        for file in files {
+            zip.start_file(file.name, SimpleFileOptions::default())?;
+            zip.write_all(file.contents.as_bytes())?;
+        }
+        zip.finish()?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(dest);
+    }
+    result
+}
@@ -0,0 +1,345 @@
+// Disk helpers: free space lookups, so we can skip optional work (like backups) rather
+// than fill a drive and fail halfway through, path lookups that cope with case, telling
+// SD cards' filesystems apart, flushing an install out to the card, and ejecting the SD
+// card once we're done with it.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::cancel::CancelToken;
+
+// The closest folder to `path` that exists, for free space checks on folders we're
+// about to create.
+pub fn existing_ancestor(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|p| p.exists())
+}
+
+// Bytes available to this user on the filesystem holding `path`, or None if the OS won't
+// tell us. `path` has to exist, so callers pass the closest existing folder.
+#[cfg(unix)]
+pub fn free_space(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return None;
+    }
+    #[allow(clippy::unnecessary_cast)]
+    Some(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+#[cfg(windows)]
+pub fn free_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available = 0u64;
+    let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut()) };
+    (ok != 0).then_some(available)
+}
+
+// "38.2 MB" style sizes for the UI.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+// `path` the way the user would type it, for copying into a file manager or a chat
+// message: no `\\?\` prefix (Explorer won't take one) and Windows separators throughout,
+// since relative paths like MODS_DIR are joined with `/`.
+pub fn display_path(path: &Path) -> String {
+    let text = path.display().to_string();
+    if !cfg!(windows) {
+        return text;
+    }
+    let text = text.replace('/', "\\");
+    if let Some(share) = text.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{share}")
+    } else if let Some(local) = text.strip_prefix(r"\\?\") {
+        local.to_string()
+    } else {
+        text
+    }
+}
+
+// `base` joined with `relative`, matching each folder that already exists regardless of
+// case. The Switch doesn't care about case but Linux and some emulator setups do, so
+// "0100A6301214E000" from one zip and "0100a6301214e000" from another must land in the
+// same folder. Parts that don't exist yet are used as given.
+pub fn resolve_case_insensitive(base: &Path, relative: &str) -> PathBuf {
+    let mut path = base.to_path_buf();
+    for part in relative.split('/').filter(|p| !p.is_empty()) {
+        let exact = path.join(part);
+        if exact.exists() {
+            path = exact;
+            continue;
+        }
+        let matched = std::fs::read_dir(&path).ok().and_then(|entries| {
+            entries
+                .flatten()
+                .find(|e| e.file_name().to_string_lossy().eq_ignore_ascii_case(part))
+                .map(|e| e.path())
+        });
+        path = matched.unwrap_or(exact);
+    }
+    path
+}
+
+// The root of the mounted volume holding `path`: the drive ("E:\") on Windows,
+// /Volumes/<name> on macOS, the longest matching entry in /proc/mounts on Linux.
+pub fn mount_point(path: &Path) -> Option<PathBuf> {
+    match std::env::consts::OS {
+        "windows" => path.ancestors().last().map(Path::to_path_buf),
+        "macos" => {
+            let mut parts = path.components();
+            let root = parts.next()?;
+            let volumes = parts.next()?;
+            let name = parts.next()?;
+            (volumes.as_os_str() == "Volumes").then(|| Path::new(root.as_os_str()).join(volumes).join(name))
+        }
+        _ => proc_mount(path).map(|(mount, _)| mount),
+    }
+}
+
+// The /proc/mounts entry `path` is under, as its mount point and filesystem type.
+fn proc_mount(path: &Path) -> Option<(PathBuf, String)> {
+    let mounts = std::fs::read_to_string("/proc/mounts").ok()?;
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1);
+            Some((fields.next()?, fields.next()?))
+        })
+        // Spaces and the like are octal escaped, e.g. "\040".
+        .map(|(m, kind)| (PathBuf::from(m.replace("\\040", " ").replace("\\011", "\t")), kind.to_string()))
+        .filter(|(m, _)| path.starts_with(m))
+        .max_by_key(|(m, _)| m.components().count())
+}
+
+// The filesystem holding `path` as the OS names it ("vfat", "exfat", "msdos", "FAT32",
+// "ext4", "NTFS", ...), or None if it won't say. `path` has to exist.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn filesystem_type(path: &Path) -> Option<String> {
+    proc_mount(path).map(|(_, kind)| kind)
+}
+
+#[cfg(target_os = "macos")]
+pub fn filesystem_type(path: &Path) -> Option<String> {
+    use std::ffi::{CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stats: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr(stats.f_fstypename.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+#[cfg(windows)]
+pub fn filesystem_type(path: &Path) -> Option<String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetVolumeInformationW;
+
+    let root = mount_point(path)?;
+    let wide: Vec<u16> = root.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut name = [0u16; 64];
+    let ok = unsafe {
+        GetVolumeInformationW(
+            wide.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            name.as_mut_ptr(),
+            name.len() as u32,
+        )
+    };
+    if ok == 0 {
+        return None;
+    }
+    let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+    Some(String::from_utf16_lossy(&name[..len]))
+}
+
+// FAT and exFAT, what SD cards and USB sticks come formatted with.
+pub fn is_fat(filesystem: &str) -> bool {
+    matches!(filesystem.to_ascii_lowercase().as_str(), "vfat" | "msdos" | "exfat" | "fat" | "fat12" | "fat16" | "fat32")
+}
+
+// Make sure a folder's entries (files added, renamed or deleted in it) are on the drive.
+#[cfg(unix)]
+pub fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    match std::fs::File::open(dir)?.sync_all() {
+        // Some filesystems (network and FUSE ones mostly) don't flush folders at all.
+        Err(e) if e.kind() == std::io::ErrorKind::InvalidInput || e.kind() == std::io::ErrorKind::Unsupported => Ok(()),
+        result => result,
+    }
+}
+
+// Windows has no way to flush a folder by itself. It doesn't need one for FAT, where a
+// file's entry lives in its folder and flushing the file writes the entry too.
+#[cfg(windows)]
+pub fn sync_dir(_dir: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+// Wait until `touched` (paths relative to `root`, every file an install wrote or deleted)
+// and the folders holding them are actually on the drive rather than in the OS's write
+// cache, so pulling the card straight afterwards can't leave a broken install. Files
+// that are gone are deleted ones, only their folders need flushing. Reports each
+// (done, total) to `on_progress`, and stops with an `Interrupted` error between files
+// once `cancel` is.
+pub fn flush_to_disk(
+    root: &Path,
+    touched: &[String],
+    cancel: &CancelToken,
+    on_progress: &mut dyn FnMut(usize, usize),
+) -> std::io::Result<()> {
+    let mut folders = BTreeSet::from([root.to_path_buf()]);
+    for name in touched {
+        let path = root.join(name);
+        folders.extend(path.ancestors().skip(1).take_while(|p| p.starts_with(root)).map(Path::to_path_buf));
+    }
+    let total = touched.len() + folders.len();
+    for (done, name) in touched.iter().enumerate() {
+        cancel.check()?;
+        // Opened for writing, Windows won't flush a file opened only for reading.
+        match std::fs::OpenOptions::new().write(true).open(root.join(name)) {
+            Ok(file) => file.sync_all()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        on_progress(done + 1, total);
+    }
+    for (done, folder) in folders.iter().enumerate() {
+        cancel.check()?;
+        sync_dir(folder)?;
+        on_progress(touched.len() + done + 1, total);
+    }
+    Ok(())
+}
+
+// Safely remove the drive holding `path`, the same as the file manager's Eject.
+pub fn eject(path: &Path) -> std::io::Result<()> {
+    let mount = mount_point(path)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "Couldn't tell which drive this is on"))?;
+    if mount.parent().is_none() && std::env::consts::OS != "windows" {
+        return Err(std::io::Error::other("That's the system drive, not a removable one"));
+    }
+    let status = match std::env::consts::OS {
+        "windows" => {
+            // The drive letter without the trailing backslash, e.g. "E:".
+            let drive = mount.to_string_lossy().trim_end_matches('\\').to_string();
+            std::process::Command::new("powershell")
+                .args([
+                    "-NoProfile",
+                    "-NonInteractive",
+                    "-Command",
+                    "(New-Object -ComObject Shell.Application).Namespace(17).ParseName($env:COBALT_DRIVE).InvokeVerb('Eject')",
+                ])
+                .env("COBALT_DRIVE", drive)
+                .status()?
+        }
+        "macos" => std::process::Command::new("diskutil").arg("eject").arg(&mount).status()?,
+        _ => std::process::Command::new("gio").args(["mount", "--eject"]).arg(&mount).status()?,
+    };
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!("Ejecting {} failed ({status})", mount.display())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scratch;
+
+    #[test]
+    fn existing_case_is_reused() {
+        let scratch = Scratch::new("case_insensitive");
+        scratch.file("atmosphere/contents/0100a6301214e000/romfs/a.txt", b"");
+        assert_eq!(
+            resolve_case_insensitive(&scratch.0, "Atmosphere/contents/0100A6301214E000/exefs"),
+            scratch.0.join("atmosphere/contents/0100a6301214e000/exefs"),
+        );
+    }
+
+    #[test]
+    fn ancestor_is_the_closest_existing_folder() {
+        let scratch = Scratch::new("existing_ancestor");
+        assert_eq!(existing_ancestor(&scratch.0.join("engage/mods")), Some(scratch.0.as_path()));
+    }
+
+    #[test]
+    fn fat_is_recognized_however_its_named() {
+        for name in ["vfat", "exfat", "msdos", "FAT32", "exFAT"] {
+            assert!(is_fat(name), "{name}");
+        }
+        for name in ["ext4", "NTFS", "apfs", "btrfs", ""] {
+            assert!(!is_fat(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn flushing_covers_files_and_their_folders() {
+        let scratch = Scratch::new("flush_to_disk");
+        scratch.file("atmosphere/contents/0100A6301214E000/exefs/main.npdm", b"npdm");
+        scratch.file("engage/mods/readme.txt", b"hi");
+        let touched = [
+            "atmosphere/contents/0100A6301214E000/exefs/main.npdm".to_string(),
+            "engage/mods/readme.txt".to_string(),
+            // Deleted by the install.
+            "engage/old.txt".to_string(),
+        ];
+        let mut reports = Vec::new();
+        flush_to_disk(&scratch.0, &touched, &CancelToken::new(), &mut |done, total| reports.push((done, total))).unwrap();
+        // The three files, then the root and its six folders.
+        assert_eq!(reports.len(), 10);
+        assert_eq!(reports.last(), Some(&(10, 10)));
+        assert_eq!(std::fs::read(scratch.0.join("engage/mods/readme.txt")).unwrap(), b"hi");
+
+        let cancel = CancelToken::new();
+        let result = flush_to_disk(&scratch.0, &touched, &cancel, &mut |done, _| {
+            if done == 1 {
+                cancel.cancel();
+            }
+        });
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::Interrupted);
+    }
+
+    #[test]
+    fn bytes_are_rounded_to_a_unit() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MB");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn verbatim_prefixes_are_dropped() {
+        assert_eq!(display_path(Path::new(r"\\?\C:\Users\me/engage/mods")), r"C:\Users\me\engage\mods");
+        assert_eq!(display_path(Path::new(r"\\?\UNC\server\share")), r"\\server\share");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_paths_are_shown_as_is() {
+        assert_eq!(display_path(Path::new("/home/me/.config/Ryujinx/sdcard/engage/mods")), "/home/me/.config/Ryujinx/sdcard/engage/mods");
+    }
+}
@@ -0,0 +1,78 @@
+// Fetching release zips. The download itself works the same everywhere, reporting
+// progress needs the desktop UI.
+//
+// On desktop the zip goes straight to a file as it arrives rather than into memory, a
+// release is big enough that holding it (and then its unpacked files) adds up.
+
+use crate::error::InstallerError;
+
+// Chunks received but not yet written. Bounds the memory a slow disk can make us hold.
+#[cfg(feature = "desktop")]
+const CHUNKS_IN_FLIGHT: usize = 16;
+
+pub async fn download_release(url: &str) -> Result<reqwest::Response, InstallerError> {
+    let network = |source| InstallerError::Network { url: url.to_string(), source };
+    crate::http::get(url).await.map_err(network)?.error_for_status().map_err(network)
+}
+
+// Write the body to `path` as it arrives, reporting the fraction received so far when
+// the server said how big it is. The writing happens on a blocking thread, a few chunks
+// behind the network at most. Returns the size of the download. Stops as soon as
+// `cancel` is, even mid-wait for the next chunk, and a download that didn't finish
+// (cancelled or failed) is deleted rather than left half written.
+#[cfg(feature = "desktop")]
+pub async fn save_with_progress(
+    mut response: reqwest::Response,
+    path: &std::path::Path,
+    cancel: &crate::cancel::CancelToken,
+    mut on_progress: impl FnMut(f32),
+) -> Result<u64, InstallerError> {
+    let url = response.url().to_string();
+    let total = response.content_length().filter(|&t| t > 0);
+    let (tx, rx) = tokio::sync::mpsc::channel(CHUNKS_IN_FLIGHT);
+    let writer = tokio::task::spawn_blocking({
+        let path = path.to_path_buf();
+        move || write_chunks(&path, rx).map_err(|e| InstallerError::filesystem(path, e))
+    });
+
+    let mut received = 0;
+    let read = async {
+        while let Some(chunk) = response.chunk().await.map_err(|source| InstallerError::Network { url: url.clone(), source })? {
+            received += chunk.len() as u64;
+            // Only fails once the writer has given up, its error is the one to report.
+            if tx.send(chunk).await.is_err() {
+                break;
+            }
+            if let Some(total) = total {
+                on_progress(received as f32 / total as f32);
+            }
+        }
+        Ok(())
+    };
+    let read: Result<(), InstallerError> = cancel.run(read).await.and_then(|read| read);
+    drop(tx);
+    let written = writer.await.expect("Blocking task panicked");
+    let result = read.and(written);
+    if result.is_err() {
+        let _ = std::fs::remove_file(path);
+    }
+    result.map(|_| received)
+}
+
+#[cfg(feature = "desktop")]
+fn write_chunks<B: AsRef<[u8]>>(path: &std::path::Path, mut chunks: tokio::sync::mpsc::Receiver<B>) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    while let Some(chunk) = chunks.blocking_recv() {
+        file.write_all(chunk.as_ref())?;
+    }
+    file.flush()
+}
+
+// Where "latest" installs come from: the custom URL from the settings page, if there is one.
+#[cfg(feature = "desktop")]
+pub fn release_url_in_use() -> String {
+    let custom = crate::settings::CUSTOM_RELEASE_URL.get();
+    if custom.is_empty() { crate::RELEASE_URL.to_string() } else { custom }
+}
@@ -0,0 +1,100 @@
+// Everything that can stop an install, as one type the UI can rely on. Each variant
+// carries what it was working on (a URL, a path) and the underlying error, so the log and
+// the error banner's details say exactly what failed where. The messages are English,
+// like the rest of the log; the banner's headline and fix come from `Failure`.
+
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum InstallerError {
+    // Couldn't reach the server, or it didn't send the whole file.
+    #[error("Couldn't download {url}: {source}")]
+    Network {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    // The release zip itself is broken: not a zip, or an entry that doesn't match its CRC.
+    #[error("The archive from {origin} is damaged: {source}")]
+    Archive {
+        origin: String,
+        #[source]
+        source: std::io::Error,
+    },
+    // Reading or writing the target failed.
+    #[error("Couldn't write to {}: {source}", path.display())]
+    Filesystem {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    // The files are on the target, but not where Cobalt looks for them.
+    #[error("{} doesn't look right: {problem}", path.display())]
+    Validation { path: PathBuf, problem: String },
+    #[error("The install was cancelled")]
+    Cancelled,
+    // Something about this machine rather than the install: no data folder, an
+    // unsupported OS.
+    #[error("{problem}")]
+    Environment { problem: String },
+}
+
+impl InstallerError {
+    pub fn filesystem(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        InstallerError::Filesystem { path: path.into(), source }
+    }
+
+    // An error from working on `path`, where `Interrupted` is a stop requested through the
+    // cancel token (see CancelToken::check).
+    pub fn from_io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        match source.kind() {
+            std::io::ErrorKind::Interrupted => InstallerError::Cancelled,
+            _ => InstallerError::filesystem(path, source),
+        }
+    }
+
+    // An error from unzipping `origin` onto `dest`, sorted by what it means: a stop
+    // requested through the cancel token, a broken archive, or trouble with the target.
+    pub fn from_extract(origin: &str, dest: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        use std::io::ErrorKind;
+        match source.kind() {
+            ErrorKind::Interrupted => InstallerError::Cancelled,
+            ErrorKind::InvalidData | ErrorKind::UnexpectedEof | ErrorKind::Unsupported => {
+                InstallerError::Archive { origin: origin.to_string(), source }
+            }
+            _ => InstallerError::filesystem(dest, source),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Error, ErrorKind};
+
+    use super::*;
+
+    #[test]
+    fn extraction_errors_are_sorted() {
+        let sorted = |kind| InstallerError::from_extract("release.zip", "/sd", Error::from(kind));
+        assert!(matches!(sorted(ErrorKind::Interrupted), InstallerError::Cancelled));
+        assert!(matches!(sorted(ErrorKind::InvalidData), InstallerError::Archive { .. }));
+        assert!(matches!(sorted(ErrorKind::UnexpectedEof), InstallerError::Archive { .. }));
+        match sorted(ErrorKind::PermissionDenied) {
+            InstallerError::Filesystem { path, source } => {
+                assert_eq!(path, PathBuf::from("/sd"));
+                assert_eq!(source.kind(), ErrorKind::PermissionDenied);
+            }
+            other => panic!("expected a filesystem error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn messages_name_what_failed() {
+        let error = InstallerError::filesystem("/sd/engage/mods", Error::from(ErrorKind::PermissionDenied));
+        assert!(error.to_string().contains("/sd/engage/mods"), "{error}");
+        let error = InstallerError::Validation { path: "/sd".into(), problem: "no engage folder".to_string() };
+        assert!(error.to_string().contains("no engage folder"), "{error}");
+    }
+}
@@ -0,0 +1,523 @@
+// Unzipping archives onto a target. Release installs and backup restores both go
+// through here, so they get the same path checks and the same verification.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use dioxus::logger::tracing;
+use zip::read::ZipFile;
+use zip::ZipArchive;
+
+use crate::archive_path;
+
+// Files under these folders (relative to the SD root) hold settings the user tweaks.
+// When one already exists on the target it's kept as is instead of being reset to the
+// release's copy.
+pub const PROTECTED_PATHS: &[&str] = &[crate::paths::CONFIG_DIR];
+
+#[derive(Clone, Copy, Default)]
+pub struct ExtractOptions<'a> {
+    // Archive entries to leave out entirely, by exact name.
+    pub exclude: &'a [&'a str],
+    // Folder prefixes whose existing files must not be overwritten.
+    pub preserve_existing: &'a [&'a str],
+    // Called with each file's archive name right after it's been written.
+    pub on_written: Option<&'a dyn Fn(&str)>,
+    // Called with each file's archive name before it's checked or written, for showing
+    // what's being worked on.
+    pub on_entry: Option<&'a dyn Fn(&str)>,
+    // Only extract entries under this folder, with the folder itself dropped from their
+    // paths. Empty to take the archive as is.
+    pub strip_prefix: &'a str,
+    // Checked between entries. Once set, the extraction stops with an `Interrupted` error,
+    // leaving what was written so far.
+    pub cancel: Option<&'a AtomicBool>,
+    // Threads writing small files at once, see `extract_archive`. 0 or 1 writes one file at
+    // a time, on the calling thread.
+    pub workers: usize,
+    // Bytes gathered before each write to a file streamed from the archive. 0 for
+    // DEFAULT_BUFFER. Cheap SD cards do far better with a few big writes than many small
+    // ones.
+    pub buffer_size: usize,
+}
+
+pub const DEFAULT_BUFFER: usize = 64 * 1024;
+
+// Folders this extraction has already made sure of, so a release with thousands of files
+// in a handful of folders asks the filesystem about each folder once rather than once
+// per file.
+#[derive(Default)]
+struct Folders(HashSet<PathBuf>);
+
+impl Folders {
+    fn create(&mut self, path: &Path) -> std::io::Result<()> {
+        if self.0.contains(path) {
+            return Ok(());
+        }
+        std::fs::create_dir_all(path)?;
+        self.0.extend(path.ancestors().map(Path::to_path_buf));
+        Ok(())
+    }
+}
+
+// What an extraction did.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Extracted {
+    // Every file from the archive that's now on the target, named as in the archive,
+    // whether it was written or already there.
+    pub files: Vec<String>,
+    // Written where nothing was before.
+    pub added: Vec<String>,
+    // Written over a different version of the file.
+    pub updated: Vec<String>,
+    // Already identical on the target.
+    pub skipped: usize,
+    // Existing files under `preserve_existing` that were kept.
+    pub preserved: Vec<String>,
+    // Bytes actually written, skipped and preserved files don't count.
+    pub bytes: u64,
+    // The archive's CRC-32 for each file in `files` that now matches it, which is all of
+    // them but the preserved ones.
+    pub checksums: BTreeMap<String, u32>,
+}
+
+// The CRC-32 of the file at `path`, the same checksum zip archives record.
+pub fn file_crc32(path: &Path) -> std::io::Result<u32> {
+    let mut file = File::open(path)?;
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        match file.read(&mut buffer)? {
+            0 => break,
+            n => hasher.update(&buffer[..n]),
+        }
+    }
+    Ok(hasher.finalize())
+}
+
+// Whether `path` already holds exactly this entry's contents.
+fn is_unchanged(path: &Path, size: u64, crc32: u32) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() || metadata.len() != size {
+        return false;
+    }
+    file_crc32(path).is_ok_and(|existing| existing == crc32)
+}
+
+// How many threads to write files with when the caller doesn't say. More than this
+// mostly adds seeking.
+pub const MAX_WORKERS: usize = 4;
+
+// Files up to this size are read into memory and handed to a worker to write. Bigger
+// ones are streamed to disk by the reading thread itself, so at most a few of these are
+// ever held at once.
+const SMALL_FILE: u64 = 1024 * 1024;
+
+// What became of one archive entry.
+enum Outcome {
+    Preserved,
+    Skipped { crc32: u32 },
+    Written { crc32: u32, bytes: u64, existed: bool },
+}
+
+// A small file for a worker to write.
+struct Job {
+    index: usize,
+    path: PathBuf,
+    contents: Vec<u8>,
+}
+
+// Where one entry stands once the reading thread is done with it.
+enum Step {
+    // Nothing more to do. None for entries that don't end up as files on the target.
+    Done(Option<(String, Outcome)>),
+    // Handed to a worker, which reports back when the file is written.
+    Queued(String, Outcome, Job),
+}
+
+// Entries as they're settled, kept in archive order whatever order the workers finish
+// in, so the result doesn't depend on timing.
+struct Tally<'a> {
+    outcomes: Vec<Option<(String, Outcome)>>,
+    errors: Vec<(usize, std::io::Error)>,
+    done: usize,
+    on_written: Option<&'a dyn Fn(&str)>,
+}
+
+impl Tally<'_> {
+    fn settle(&mut self, index: usize, result: std::io::Result<()>, progress: &mut impl FnMut(usize, usize)) {
+        match result {
+            Ok(()) => {
+                if let (Some((name, Outcome::Written { .. })), Some(on_written)) = (&self.outcomes[index], self.on_written) {
+                    on_written(name);
+                }
+            }
+            Err(e) => {
+                self.outcomes[index] = None;
+                self.errors.push((index, e));
+            }
+        }
+        self.done += 1;
+        progress(self.done, self.outcomes.len());
+    }
+
+    // The first entry's error if any failed, whichever failed first in time.
+    fn into_result(self) -> std::io::Result<Extracted> {
+        if let Some((_, error)) = self.errors.into_iter().min_by_key(|(index, _)| *index) {
+            return Err(error);
+        }
+        let mut extracted = Extracted::default();
+        for (name, outcome) in self.outcomes.into_iter().flatten() {
+            extracted.files.push(name.clone());
+            match outcome {
+                Outcome::Preserved => extracted.preserved.push(name),
+                Outcome::Skipped { crc32 } => {
+                    extracted.checksums.insert(name, crc32);
+                    extracted.skipped += 1;
+                }
+                Outcome::Written { crc32, bytes, existed } => {
+                    extracted.checksums.insert(name.clone(), crc32);
+                    extracted.bytes += bytes;
+                    if existed {
+                        extracted.updated.push(name);
+                    } else {
+                        extracted.added.push(name);
+                    }
+                }
+            }
+        }
+        Ok(extracted)
+    }
+}
+
+fn write_file(path: &Path, mut contents: impl Read, buffer_size: usize) -> std::io::Result<u64> {
+    let buffer_size = if buffer_size == 0 { DEFAULT_BUFFER } else { buffer_size };
+    let mut outfile = BufWriter::with_capacity(buffer_size, File::create(path)?);
+    let copied = std::io::copy(&mut contents, &mut outfile)?;
+    outfile.flush()?;
+    Ok(copied)
+}
+
+fn size_mismatch(name: &str, copied: u64, expected: u64) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{name} was {copied} bytes, the archive says {expected}"))
+}
+
+// Everything about entry `file` short of writing a small file out, which is left to a
+// worker when `queue_small` is set.
+fn take_entry(
+    file: &mut ZipFile,
+    index: usize,
+    dest: &Path,
+    options: &ExtractOptions,
+    queue_small: bool,
+    folders: &mut Folders,
+    names: &mut HashSet<String>,
+) -> std::io::Result<Step> {
+    if options.exclude.contains(&file.name()) {
+        tracing::trace!("Skipping {}, it's excluded", file.name());
+        return Ok(Step::Done(None));
+    }
+    let relative = match archive_path::sanitize(file.name()) {
+        Ok(relative) => relative,
+        Err(reason) => {
+            tracing::warn!("Skipping {:?}, {reason}", file.name());
+            return Ok(Step::Done(None));
+        }
+    };
+    // Named as the archive has it, but with `/` whichever separator it used.
+    let name = archive_path::to_name(&relative);
+    // Most targets don't tell case apart, so two names that differ only in case would be
+    // one file there, written twice. The first one wins wherever we are.
+    if !file.is_dir() && !names.insert(name.to_lowercase()) {
+        tracing::warn!("Skipping {name}, the archive already had a file by that name in another case");
+        return Ok(Step::Done(None));
+    }
+    let relative = match relative.strip_prefix(options.strip_prefix) {
+        Ok(stripped) if !stripped.as_os_str().is_empty() => stripped.to_path_buf(),
+        // Outside the folder we were asked for, or that folder's own entry.
+        _ => {
+            tracing::trace!("Skipping {name}, it's outside {}", options.strip_prefix);
+            return Ok(Step::Done(None));
+        }
+    };
+    let outpath = dest.join(&relative);
+    let exists = outpath.is_file();
+    if let (false, Some(on_entry)) = (file.is_dir(), options.on_entry) {
+        on_entry(&name);
+    }
+
+    if file.is_dir() {
+        tracing::debug!("Folder {} created at \"{}\"", name, outpath.display());
+        folders.create(&outpath)?;
+        return Ok(Step::Done(None));
+    }
+    if exists && options.preserve_existing.iter().any(|p| relative.starts_with(p)) {
+        tracing::debug!("Keeping the existing {} at \"{}\"", name, outpath.display());
+        return Ok(Step::Done(Some((name, Outcome::Preserved))));
+    }
+    if exists && is_unchanged(&outpath, file.size(), file.crc32()) {
+        tracing::debug!("File {} is already up to date at \"{}\"", name, outpath.display());
+        return Ok(Step::Done(Some((name, Outcome::Skipped { crc32: file.crc32() }))));
+    }
+
+    let reason = if exists { "it changed" } else { "it's new" };
+    tracing::debug!("File {} extracted to \"{}\" ({} bytes, {reason})", name, outpath.display(), file.size());
+    // Always from here, before any worker writes into the folder.
+    if let Some(p) = outpath.parent() {
+        folders.create(p)?;
+    }
+    let written = Outcome::Written { crc32: file.crc32(), bytes: file.size(), existed: exists };
+    if queue_small && file.size() <= SMALL_FILE {
+        // The zip reader checks the CRC as the last byte is read.
+        let mut contents = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut contents)?;
+        if contents.len() as u64 != file.size() {
+            return Err(size_mismatch(&name, contents.len() as u64, file.size()));
+        }
+        return Ok(Step::Queued(name, written, Job { index, path: outpath, contents }));
+    }
+    let copied = write_file(&outpath, &mut *file, options.buffer_size)?;
+    if copied != file.size() {
+        return Err(size_mismatch(&name, copied, file.size()));
+    }
+    Ok(Step::Done(Some((name, written))))
+}
+
+// Extract every entry of `reader` under `dest`, calling `progress(done, total)` as each
+// entry is finished with. Files already on the target with the same size and CRC as the
+// archive entry are left alone.
+//
+// Entries whose names would land outside `dest` (absolute paths, `..`, see
+// archive_path) are skipped rather than trusted, and so are files whose names only
+// differ in case from an earlier one. Each written file is checked against the size
+// recorded in the archive, and the zip reader checks the CRC as we read, so a corrupt
+// entry fails the extraction instead of leaving a truncated file behind silently.
+//
+// The archive is read in order on the calling thread. With `options.workers` above one,
+// small files are written by that many threads while it reads on, which helps most with
+// releases full of tiny files. The result is the same either way, and when several
+// entries fail it's the first of them in the archive whose error comes back.
+pub fn extract_archive<R: Read + Seek>(
+    reader: R,
+    dest: &Path,
+    options: ExtractOptions,
+    mut progress: impl FnMut(usize, usize),
+) -> std::io::Result<Extracted> {
+    let mut archive = ZipArchive::new(reader)?;
+    let total = archive.len();
+    let workers = options.workers.clamp(1, MAX_WORKERS);
+    let mut tally = Tally { outcomes: (0..total).map(|_| None).collect(), errors: Vec::new(), done: 0, on_written: options.on_written };
+    let mut cancelled = false;
+    let mut folders = Folders::default();
+    // Every file name so far, lowercased.
+    let mut names = HashSet::new();
+    // Room for one job per worker on top of the ones being written, which bounds how much
+    // is held in memory.
+    let (jobs, queue) = std::sync::mpsc::sync_channel::<Job>(workers);
+    let queue = Mutex::new(queue);
+
+    std::thread::scope(|scope| {
+        let (report, reports) = std::sync::mpsc::channel();
+        if workers > 1 {
+            for _ in 0..workers {
+                let (queue, report) = (&queue, report.clone());
+                scope.spawn(move || loop {
+                    let Ok(job) = queue.lock().unwrap_or_else(|e| e.into_inner()).recv() else {
+                        break;
+                    };
+                    // Already whole in memory, so one write and no buffer.
+                    let result = std::fs::write(&job.path, &job.contents);
+                    let _ = report.send((job.index, result));
+                });
+            }
+        }
+        drop(report);
+
+        for i in 0..total {
+            while let Ok((index, result)) = reports.try_recv() {
+                tally.settle(index, result, &mut progress);
+            }
+            if !tally.errors.is_empty() {
+                break;
+            }
+            if options.cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+                cancelled = true;
+                break;
+            }
+            let step = archive
+                .by_index(i)
+                .map_err(std::io::Error::from)
+                .and_then(|mut file| take_entry(&mut file, i, dest, &options, workers > 1, &mut folders, &mut names));
+            match step {
+                Ok(Step::Done(outcome)) => {
+                    tally.outcomes[i] = outcome;
+                    tally.settle(i, Ok(()), &mut progress);
+                }
+                Ok(Step::Queued(name, outcome, job)) => {
+                    tally.outcomes[i] = Some((name, outcome));
+                    // Only fails if every worker is gone, which they aren't while we hold
+                    // `jobs`.
+                    let _ = jobs.send(job);
+                }
+                Err(e) => {
+                    tally.errors.push((i, e));
+                    break;
+                }
+            }
+        }
+
+        // Let the workers finish what they have, then wait for the last reports.
+        drop(jobs);
+        for (index, result) in reports {
+            tally.settle(index, result, &mut progress);
+        }
+    });
+
+    if cancelled && tally.errors.is_empty() {
+        return Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "cancelled"));
+    }
+    tally.into_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::time::Instant;
+
+    use super::*;
+    use crate::Scratch;
+
+    // A release-like mix: lots of tiny files, a folder entry, and one file too big to be
+    // handed to a worker.
+    fn archive(small_files: usize) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        zip.add_directory("romfs/Data/", options).unwrap();
+        for n in 0..small_files {
+            zip.start_file(format!("romfs/Data/{}/msg_{n}.bin", n % 7), options).unwrap();
+            zip.write_all(format!("message {n} ").repeat(n % 300 + 1).as_bytes()).unwrap();
+        }
+        zip.start_file("exefs/subsdk9", options).unwrap();
+        zip.write_all(&vec![7; SMALL_FILE as usize * 2]).unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn extract(bytes: &[u8], dest: &Path, workers: usize) -> (std::io::Result<Extracted>, Vec<(usize, usize)>) {
+        let mut reports = Vec::new();
+        let options = ExtractOptions { workers, ..Default::default() };
+        let result = extract_archive(Cursor::new(bytes), dest, options, |done, total| reports.push((done, total)));
+        (result, reports)
+    }
+
+    // A zip slip archive with a few good files among the bad ones: only the good ones are
+    // written, and only inside the target.
+    #[test]
+    fn unsafe_names_are_skipped() {
+        let scratch = Scratch::new("extract_unsafe_names");
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for name in [
+            "../evil.txt",
+            "engage/../../evil.txt",
+            "..\\evil.txt",
+            "/tmp/evil.txt",
+            "C:\\evil.txt",
+            "engage/CON",
+            "engage/evil. ",
+            "engage\\mods\\readme.txt",
+            "engage/config/Cobalt.toml",
+            "engage/config/cobalt.toml",
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(name.as_bytes()).unwrap();
+        }
+        let bytes = zip.finish().unwrap().into_inner();
+        let dest = scratch.0.join("sd");
+        let extracted = extract_archive(Cursor::new(bytes), &dest, ExtractOptions::default(), |_, _| {}).unwrap();
+        assert_eq!(extracted.files, ["engage/mods/readme.txt", "engage/config/Cobalt.toml"]);
+        assert_eq!(std::fs::read(dest.join("engage/mods/readme.txt")).unwrap(), b"engage\\mods\\readme.txt");
+        assert_eq!(std::fs::read_dir(&scratch.0).unwrap().count(), 1);
+        assert_eq!(std::fs::read_dir(dest.join("engage/config")).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn workers_give_the_same_result() {
+        let scratch = Scratch::new("extract_workers_same");
+        let bytes = archive(200);
+        let (one, _) = extract(&bytes, &scratch.0.join("one"), 1);
+        let (several, reports) = extract(&bytes, &scratch.0.join("several"), MAX_WORKERS);
+        let (one, several) = (one.unwrap(), several.unwrap());
+        assert_eq!(one, several);
+        assert_eq!(several.added.len(), 201);
+        for file in &several.files {
+            let written = std::fs::read(scratch.0.join("several").join(file)).unwrap();
+            assert_eq!(written, std::fs::read(scratch.0.join("one").join(file)).unwrap(), "{file}");
+        }
+        // Every entry reported once, counting up.
+        let total = reports[0].1;
+        assert_eq!(reports.iter().map(|(done, _)| *done).collect::<Vec<_>>(), (1..=total).collect::<Vec<_>>());
+
+        // And again over the top, where everything's already there.
+        let (again, _) = extract(&bytes, &scratch.0.join("several"), MAX_WORKERS);
+        assert_eq!(again.unwrap().skipped, 201);
+    }
+
+    #[test]
+    fn a_failed_write_fails_the_extraction() {
+        let scratch = Scratch::new("extract_workers_failure");
+        // A folder where a file should go can't be written over.
+        std::fs::create_dir_all(scratch.0.join("romfs/Data/3/msg_10.bin")).unwrap();
+        let written = Mutex::new(Vec::new());
+        let record = |name: &str| written.lock().unwrap().push(name.to_string());
+        let options = ExtractOptions { workers: MAX_WORKERS, on_written: Some(&record), ..Default::default() };
+        let result = extract_archive(Cursor::new(archive(200)), &scratch.0, options, |_, _| {});
+        assert!(result.is_err());
+        assert!(!written.lock().unwrap().contains(&"romfs/Data/3/msg_10.bin".to_string()));
+    }
+
+    // The same big file written with a small buffer and a big one, printing both. On an SD
+    // card the big buffer is several times faster, on an SSD the two are close. Only runs
+    // when asked: cargo test --release -- --ignored --nocapture buffer_sizes
+    #[test]
+    #[ignore]
+    fn buffer_sizes_compared() {
+        let scratch = Scratch::new("extract_buffer_timing");
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("romfs/Data/big.bin", options).unwrap();
+        zip.write_all(&vec![3; 64 * 1024 * 1024]).unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+        for buffer_size in [8 * 1024, DEFAULT_BUFFER, 1024 * 1024, 4 * 1024 * 1024] {
+            let dest = scratch.0.join(buffer_size.to_string());
+            let started = Instant::now();
+            let options = ExtractOptions { buffer_size, ..Default::default() };
+            extract_archive(Cursor::new(&bytes), &dest, options, |_, _| {}).unwrap();
+            // Timed to the disk, not just the page cache.
+            File::open(dest.join("romfs/Data/big.bin")).unwrap().sync_all().unwrap();
+            println!("{} KB buffer: {:?}", buffer_size / 1024, started.elapsed());
+        }
+    }
+
+    // A few thousand tiny files written to the temp directory's disk one at a time and then
+    // with workers, printing both. Where creating a file is slow (Windows with real-time
+    // scanning, network shares) the workers win clearly, on Linux writing into the page
+    // cache the two come out close. Only runs when asked:
+    // cargo test --release -- --ignored --nocapture workers_compared
+    #[test]
+    #[ignore]
+    fn workers_compared() {
+        let scratch = Scratch::new("extract_workers_timing");
+        let bytes = archive(4000);
+        for workers in [1, MAX_WORKERS] {
+            let started = Instant::now();
+            extract(&bytes, &scratch.0.join(workers.to_string()), workers).0.unwrap();
+            println!("{workers} workers: {:?}", started.elapsed());
+        }
+    }
+}
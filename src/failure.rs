@@ -0,0 +1,200 @@
+// What went wrong with an install, sorted into the handful of cases helpers see every
+// day, each with the fix they'd suggest. The error banner shows that fix above the
+// technical details, so most users never need to ask.
+
+use std::io::ErrorKind;
+
+use crate::error::InstallerError;
+use crate::i18n;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Failure {
+    ReleaseNotFound,
+    // The server couldn't be reached at all: no connection, DNS, a firewall.
+    Offline,
+    // Reached, but the download didn't finish.
+    Download,
+    PermissionDenied,
+    StorageFull,
+    // The target went away mid-write, usually an SD card pulled out or a drive asleep.
+    TargetGone,
+    // Another program has a file open that we need to replace.
+    FileLocked,
+    DamagedDownload,
+    Write,
+    CancelCleanup,
+    Layout,
+    // Another installer is writing to the same target.
+    TargetInUse,
+}
+
+// Windows has no ErrorKind for these yet.
+#[cfg(windows)]
+const LOCKED_CODES: &[i32] = &[
+    32, // ERROR_SHARING_VIOLATION
+    33, // ERROR_LOCK_VIOLATION
+];
+#[cfg(windows)]
+const REMOVED_CODES: &[i32] = &[
+    21,   // ERROR_NOT_READY
+    1167, // ERROR_DEVICE_NOT_CONNECTED
+];
+#[cfg(unix)]
+const LOCKED_CODES: &[i32] = &[libc::ETXTBSY];
+#[cfg(unix)]
+const REMOVED_CODES: &[i32] = &[libc::ENODEV, libc::ENXIO];
+
+impl Failure {
+    // A failed write to the target.
+    pub fn from_io(error: &std::io::Error) -> Self {
+        match error.raw_os_error() {
+            Some(code) if LOCKED_CODES.contains(&code) => return Failure::FileLocked,
+            Some(code) if REMOVED_CODES.contains(&code) => return Failure::TargetGone,
+            _ => {}
+        }
+        match error.kind() {
+            ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem => Failure::PermissionDenied,
+            ErrorKind::StorageFull | ErrorKind::QuotaExceeded => Failure::StorageFull,
+            ErrorKind::ResourceBusy => Failure::FileLocked,
+            ErrorKind::NotFound => Failure::TargetGone,
+            ErrorKind::InvalidData | ErrorKind::UnexpectedEof => Failure::DamagedDownload,
+            _ => Failure::Write,
+        }
+    }
+
+    pub fn from_download(error: &reqwest::Error) -> Self {
+        if error.is_connect() || error.is_timeout() {
+            Failure::Offline
+        } else {
+            Failure::Download
+        }
+    }
+
+    // None for a cancelled install, which didn't fail.
+    pub fn from_error(error: &InstallerError) -> Option<Self> {
+        match error {
+            InstallerError::Network { source, .. } => Some(Failure::from_download(source)),
+            InstallerError::Archive { .. } => Some(Failure::DamagedDownload),
+            InstallerError::Filesystem { source, .. } => Some(Failure::from_io(source)),
+            InstallerError::Validation { .. } => Some(Failure::Layout),
+            InstallerError::Cancelled => None,
+            InstallerError::Environment { .. } => Some(Failure::Write),
+        }
+    }
+
+    // What the user sees first, unless the call site has something more specific.
+    pub fn summary(self) -> String {
+        i18n::tr(self.summary_key())
+    }
+
+    fn summary_key(self) -> &'static str {
+        match self {
+            Failure::ReleaseNotFound => "error.release_not_found",
+            Failure::Offline => "error.offline",
+            Failure::Download => "error.download",
+            Failure::PermissionDenied => "error.permission_denied",
+            Failure::StorageFull => "error.storage_full",
+            Failure::TargetGone => "error.target_gone",
+            Failure::FileLocked => "error.file_locked",
+            Failure::DamagedDownload => "error.damaged_download",
+            Failure::Write => "error.write_failed",
+            Failure::CancelCleanup => "error.cancel_cleanup",
+            Failure::Layout => "error.layout",
+            Failure::TargetInUse => "error.target_in_use",
+        }
+    }
+
+    pub fn suggestion(self) -> String {
+        i18n::tr(self.suggestion_key())
+    }
+
+    fn suggestion_key(self) -> &'static str {
+        match self {
+            Failure::ReleaseNotFound => "fix.release_not_found",
+            Failure::Offline => "fix.offline",
+            Failure::Download => "fix.download",
+            Failure::PermissionDenied => "fix.permission_denied",
+            Failure::StorageFull => "fix.storage_full",
+            Failure::TargetGone => "fix.target_gone",
+            Failure::FileLocked => "fix.file_locked",
+            Failure::DamagedDownload => "fix.damaged_download",
+            Failure::Write => "fix.write_failed",
+            Failure::CancelCleanup => "fix.cancel_cleanup",
+            Failure::Layout => "fix.layout",
+            Failure::TargetInUse => "fix.target_in_use",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn every_failure() -> Vec<Failure> {
+        vec![
+            Failure::ReleaseNotFound,
+            Failure::Offline,
+            Failure::Download,
+            Failure::PermissionDenied,
+            Failure::StorageFull,
+            Failure::TargetGone,
+            Failure::FileLocked,
+            Failure::DamagedDownload,
+            Failure::Write,
+            Failure::CancelCleanup,
+            Failure::Layout,
+            Failure::TargetInUse,
+        ]
+    }
+
+    #[test]
+    fn every_failure_has_a_suggestion() {
+        for failure in every_failure() {
+            for language in i18n::LANGUAGES {
+                assert!(i18n::has(language, failure.summary_key()), "{failure:?} has no summary in {language:?}");
+                assert!(i18n::has(language, failure.suggestion_key()), "{failure:?} has no suggestion in {language:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn suggestions_are_all_different() {
+        let mut keys: Vec<&str> = every_failure().iter().map(|f| f.suggestion_key()).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), every_failure().len());
+    }
+
+    #[test]
+    fn io_errors_are_sorted() {
+        let failure = |kind| Failure::from_io(&std::io::Error::from(kind));
+        assert_eq!(failure(ErrorKind::PermissionDenied), Failure::PermissionDenied);
+        assert_eq!(failure(ErrorKind::StorageFull), Failure::StorageFull);
+        assert_eq!(failure(ErrorKind::NotFound), Failure::TargetGone);
+        assert_eq!(failure(ErrorKind::InvalidData), Failure::DamagedDownload);
+        assert_eq!(failure(ErrorKind::Other), Failure::Write);
+        assert_eq!(Failure::from_io(&std::io::Error::from_raw_os_error(REMOVED_CODES[0])), Failure::TargetGone);
+        assert_eq!(Failure::from_io(&std::io::Error::from_raw_os_error(LOCKED_CODES[0])), Failure::FileLocked);
+    }
+
+    #[test]
+    fn installer_errors_are_sorted() {
+        let io = |kind| std::io::Error::from(kind);
+        let archive = InstallerError::Archive { origin: "release.zip".to_string(), source: io(ErrorKind::InvalidData) };
+        assert_eq!(Failure::from_error(&archive), Some(Failure::DamagedDownload));
+        let filesystem = InstallerError::filesystem("/sd", io(ErrorKind::StorageFull));
+        assert_eq!(Failure::from_error(&filesystem), Some(Failure::StorageFull));
+        let validation = InstallerError::Validation { path: "/sd".into(), problem: "no exefs".to_string() };
+        assert_eq!(Failure::from_error(&validation), Some(Failure::Layout));
+        assert_eq!(Failure::from_error(&InstallerError::Cancelled), None);
+    }
+
+    // Port 1 has nothing listening, so this fails without touching the network.
+    #[test]
+    fn unreachable_downloads_are_offline() {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let error = runtime.block_on(crate::download::download_release("http://127.0.0.1:1/")).unwrap_err();
+        assert!(matches!(&error, InstallerError::Network { url, .. } if url == "http://127.0.0.1:1/"));
+        assert_eq!(Failure::from_error(&error), Some(Failure::Offline));
+    }
+}
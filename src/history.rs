@@ -0,0 +1,101 @@
+// A running log of everything the installer did to a target, kept in the data directory
+// so it survives installer updates. Mostly for support: "what did you install, when, and
+// where".
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths::data_dir;
+use crate::progress::Phase;
+
+// Older entries are dropped once the file holds this many.
+const MAX_ENTRIES: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    Install,
+    // An install over one that was already there, keeping the user's config.
+    Update,
+    Uninstall,
+    Repair,
+    Restore,
+}
+
+impl Action {
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Action::Install => "history.install",
+            Action::Update => "history.update",
+            Action::Uninstall => "history.uninstall",
+            Action::Repair => "history.repair",
+            Action::Restore => "history.restore",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Outcome {
+    Success,
+    Failed(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    // Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub action: Action,
+    pub version: Option<String>,
+    // The installation type it was run for ("Ryujinx", "SD Card", ...).
+    pub target_type: String,
+    pub target: PathBuf,
+    pub outcome: Outcome,
+    pub duration_secs: f64,
+    pub bytes_written: u64,
+    // Seconds each phase of an install took, in order. Empty for everything else, and in
+    // entries written before phases were timed.
+    #[serde(default)]
+    pub phases: Vec<(Phase, f64)>,
+}
+
+fn history_path() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("history.json"))
+}
+
+// All recorded entries, oldest first. A missing or unreadable file is an empty history.
+pub fn load() -> Vec<Entry> {
+    history_path()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn append(entry: Entry) -> std::io::Result<()> {
+    let path = history_path()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "No data directory"))?;
+    let mut entries = load();
+    entries.push(entry);
+    let overflow = entries.len().saturating_sub(MAX_ENTRIES);
+    entries.drain(..overflow);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(&entries)?)
+}
+
+pub fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// "2026-03-14 18:02 UTC". We stay in UTC, the local offset can't be read reliably from
+// a multithreaded process on every platform.
+pub fn format_timestamp(timestamp: u64) -> String {
+    let format = time::macros::format_description!("[year]-[month]-[day] [hour]:[minute] UTC");
+    time::OffsetDateTime::from_unix_timestamp(timestamp as i64)
+        .ok()
+        .and_then(|t| t.format(format).ok())
+        .unwrap_or_else(|| timestamp.to_string())
+}
@@ -0,0 +1,142 @@
+// The one HTTP client every request goes through: GitHub's API, release and installer
+// downloads, the README. It's built on first use and then shared, so connections and the
+// TLS setup are reused rather than redone for every request, and anything that should
+// apply to all of them (headers, timeouts, retries) goes here.
+//
+// Proxies come from the usual HTTP_PROXY / HTTPS_PROXY / NO_PROXY variables, which
+// reqwest reads by itself.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use dioxus::logger::tracing;
+
+// GitHub rejects API requests without a User-Agent.
+pub const USER_AGENT: &str = concat!("CobaltInstaller/", env!("CARGO_PKG_VERSION"));
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+// Between reads rather than for a whole request, a release download on a slow line can
+// take minutes and still be fine.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+// GitHub sends asset downloads through a redirect or two, nothing legitimate needs more.
+const MAX_REDIRECTS: usize = 5;
+
+// Tries at a GET before giving up, the wait between them doubling from RETRY_DELAY. Only
+// for failures that could go away by themselves: no connection, a timeout, or the server
+// having a bad moment.
+const ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = if cfg!(test) { Duration::from_millis(10) } else { Duration::from_secs(1) };
+
+fn build() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
+        .build()
+}
+
+pub fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        build().unwrap_or_else(|e| {
+            // Only happens if the TLS backend can't start, which the plain client would
+            // hit too when it makes its first request.
+            tracing::error!("Couldn't set up the HTTP client, using the defaults: {e}");
+            reqwest::Client::new()
+        })
+    })
+}
+
+fn is_transient(result: &reqwest::Result<reqwest::Response>) -> bool {
+    match result {
+        Ok(response) => {
+            response.status().is_server_error() || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+        }
+        Err(e) => e.is_connect() || e.is_timeout(),
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+#[cfg(target_os = "android")]
+async fn sleep(duration: Duration) {
+    futures_timer::Delay::new(duration).await;
+}
+
+// GET `url`, trying again after a transient failure. A GET changes nothing, so sending it
+// twice is safe. Whatever the last try got comes back, error statuses included.
+pub async fn get(url: &str) -> reqwest::Result<reqwest::Response> {
+    let mut delay = RETRY_DELAY;
+    let mut attempt = 1;
+    loop {
+        let result = client().get(url).send().await;
+        if attempt == ATTEMPTS || !is_transient(&result) {
+            return result;
+        }
+        match &result {
+            Ok(response) => tracing::warn!("GET {url} got {}, trying again in {delay:?}", response.status()),
+            Err(e) => tracing::warn!("GET {url} failed ({e}), trying again in {delay:?}"),
+        }
+        sleep(delay).await;
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_client_is_built_once() {
+        assert!(build().is_ok());
+        assert!(std::ptr::eq(client(), client()));
+    }
+
+    // Answers each connection with the next status, counting them.
+    fn serve(statuses: &'static [&'static str]) -> (String, std::thread::JoinHandle<usize>) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            for (served, status) in statuses.iter().enumerate() {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    return served;
+                };
+                let _ = stream.read(&mut [0; 4096]);
+                let _ = write!(stream, "HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            }
+            statuses.len()
+        });
+        (url, server)
+    }
+
+    fn status_of(url: &str) -> u16 {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(get(url)).unwrap().status().as_u16()
+    }
+
+    #[test]
+    fn a_server_error_is_tried_again() {
+        let (url, server) = serve(&["503 Service Unavailable", "200 OK"]);
+        assert_eq!(status_of(&url), 200);
+        assert_eq!(server.join().unwrap(), 2);
+    }
+
+    #[test]
+    fn retries_stop_after_the_last_attempt_and_never_follow_a_client_error() {
+        let (url, server) = serve(&["500 Internal Server Error", "502 Bad Gateway", "503 Service Unavailable"]);
+        assert_eq!(status_of(&url), 503);
+        assert_eq!(server.join().unwrap(), ATTEMPTS as usize);
+
+        let (url, _server) = serve(&["404 Not Found", "200 OK"]);
+        assert_eq!(status_of(&url), 404);
+    }
+}
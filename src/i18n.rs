@@ -0,0 +1,1115 @@
+// The UI's strings, per language. Every string is looked up by key through `t!`, in the
+// language picked on the settings page. A key a translation doesn't have yet shows the
+// English text, English has them all.
+//
+// Placeholders are written `{name}` and filled in by name: `t!("hero.version", version = v)`.
+//
+// The activity log, the install history file and the reports users paste for help stay in
+// English so whoever helps them can read them, only what's shown on screen is translated.
+
+use dioxus::logger::tracing;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+pub const LANGUAGES: [Language; 2] = [Language::English, Language::Spanish];
+
+impl Language {
+    pub fn key(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Language {
+        LANGUAGES.into_iter().find(|l| l.key() == key).unwrap_or_default()
+    }
+
+    // In the language itself, so it can be found in the list without reading the current one.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+        }
+    }
+
+    fn strings(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => EN,
+            Language::Spanish => ES,
+        }
+    }
+}
+
+// What the UI is shown in. Components reading a string re-render when it changes. The
+// desktop build starts it from the saved setting and keeps the two in step (see App).
+pub static LANGUAGE: GlobalSignal<Language> = Signal::global(|| {
+    #[cfg(feature = "desktop")]
+    {
+        crate::settings::LANGUAGE.get()
+    }
+    #[cfg(not(feature = "desktop"))]
+    {
+        Language::default()
+    }
+});
+
+fn lookup(language: Language, key: &str) -> Option<&'static str> {
+    language.strings().iter().find(|(k, _)| *k == key).map(|(_, s)| *s)
+}
+
+// Whether `language` has its own text for `key`, rather than falling back to English.
+#[cfg(test)]
+pub fn has(language: Language, key: &str) -> bool {
+    lookup(language, key).is_some()
+}
+
+// `tr` in a given language, for code that runs outside the UI (and tests).
+pub fn tr_in(language: Language, key: &str) -> String {
+    match lookup(language, key).or_else(|| lookup(Language::English, key)) {
+        Some(text) => text.to_string(),
+        None => {
+            // Only a typo in a key gets here, better some English-looking text than nothing.
+            tracing::error!("No string for {key}");
+            key.to_string()
+        }
+    }
+}
+
+pub fn tr_args_in(language: Language, key: &str, args: &[(&str, String)]) -> String {
+    args.iter()
+        .fold(tr_in(language, key), |text, (name, value)| text.replace(&format!("{{{name}}}"), value))
+}
+
+pub fn tr(key: &str) -> String {
+    tr_in(LANGUAGE(), key)
+}
+
+pub fn tr_args(key: &str, args: &[(&str, String)]) -> String {
+    tr_args_in(LANGUAGE(), key, args)
+}
+
+// A string kept as its key and arguments, for something made away from the UI and shown
+// in it later, like an install's warnings. Displays in English, for the log, the command
+// line and copied reports. `text` is in the UI's language.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    pub key: &'static str,
+    pub args: Vec<(&'static str, String)>,
+}
+
+impl Message {
+    pub fn new<const N: usize>(key: &'static str, args: [(&'static str, String); N]) -> Self {
+        Self { key, args: args.into() }
+    }
+
+    pub fn text(&self) -> String {
+        tr_args(self.key, &self.args)
+    }
+}
+
+impl std::fmt::Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&tr_args_in(Language::English, self.key, &self.args))
+    }
+}
+
+#[macro_export]
+macro_rules! t {
+    ($key:literal) => {
+        $crate::i18n::tr($key)
+    };
+    ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::tr_args($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+const EN: &[(&str, &str)] = &[
+    // Shared
+    ("common.back", "Back"),
+    ("common.cancel", "Cancel"),
+    ("common.close", "Close"),
+    ("common.copy", "Copy"),
+    ("common.dismiss", "Dismiss"),
+    ("common.refresh", "Refresh"),
+    ("common.retry", "Retry"),
+    ("common.save", "Save…"),
+    ("common.status", "Status: "),
+    // The main screen
+    ("hero.settings", "Settings"),
+    ("hero.welcome", "Welcome to the Cobalt Installer"),
+    // What is Cobalt? Shown until (or instead of, when offline) the README
+    ("info.title", "What is Cobalt?"),
+    (
+        "info.blurb",
+        "Cobalt is a mod loader for Fire Emblem Engage. Install it once, and from then on it loads whatever mods you put in the engage/mods folder, without touching the game's own files.",
+    ),
+    ("info.feature_mods", "Turn mods on and off by moving folders, nothing to rebuild"),
+    ("info.feature_several", "Several mods can change the game at the same time"),
+    ("info.feature_anywhere", "Works the same on a Switch and on emulators"),
+    ("info.more", "More about Cobalt on GitHub"),
+    ("hero.sammie_alt", "Sammie stares at you, judgingly"),
+    ("hero.having_issues", "Having issues? "),
+    ("hero.get_help", "Get help!"),
+    ("hero.icon_credit", "Sommie icon by badatgames26"),
+    ("hero.version", "Version {version}"),
+    ("status.waiting", "Waiting for you"),
+    ("status.bond_fragments", "50 bond fragments obtained."),
+    // Settings page
+    ("settings.title", "Settings"),
+    ("settings.general", "General"),
+    ("settings.check_updates", "Check for new versions of the installer on launch"),
+    ("settings.backup_before_update", "Back up the current install before overwriting it"),
+    ("settings.close_after_install", "Close automatically after a successful installation"),
+    ("settings.theme", "Theme"),
+    ("settings.ui_scale", "Interface size"),
+    ("settings.ui_scale_note", "Ctrl + = and Ctrl + - (⌘ on macOS) change it from anywhere."),
+    ("settings.language", "Language"),
+    ("settings.network", "Network"),
+    ("settings.include_prereleases", "Include prereleases"),
+    ("settings.custom_release_url", "Custom release zip URL"),
+    (
+        "settings.custom_release_url_note",
+        "Installs this zip instead of the latest release. Leave empty unless someone asked you to test a build.",
+    ),
+    ("settings.advanced", "Advanced"),
+    ("settings.preserve_config", "Keep my Cobalt config files when reinstalling"),
+    ("settings.verbose_logging", "Verbose logging"),
+    ("settings.verbose_logging_note", "Writes everything the installer does to its log file, including where it looked for emulators. Turn it on when asked to while getting help."),
+    ("settings.extraction_workers", "Files written at once"),
+    ("settings.extraction_workers_auto", "Automatic"),
+    ("settings.extraction_workers_note", "Automatic writes one file at a time to SD cards and USB sticks, which get slower when asked to write several, and a few at a time to other drives."),
+    ("settings.write_buffer", "Write buffer"),
+    ("settings.write_buffer_note", "How much of each file is gathered before writing it. Automatic uses big writes for SD cards and USB sticks. If installs to your card are slow, try a bigger buffer."),
+    ("settings.flush_removable", "Wait until SD cards and USB sticks have finished writing"),
+    ("settings.flush_removable_note", "Turning this off makes installs to a card finish sooner, but taking the card out straight afterwards could leave Cobalt broken."),
+    ("settings.restore_defaults", "Restore defaults"),
+    (
+        "settings.reset_warning",
+        "This forgets the installation type, SD card folder and every other setting, and clears downloaded files. Your installs, backups and history are kept.",
+    ),
+    ("settings.clean_up", "Clean up now"),
+    ("settings.clean_up_note", "Deletes downloads and unfinished backups left behind by installs that crashed or were closed. This also happens every time the installer starts."),
+    ("settings.reset", "Reset"),
+    ("settings.reset_all", "Reset installer settings"),
+    ("settings.reset_failed", "Couldn't reset the installer settings: {error}"),
+    ("theme.system", "Same as my system"),
+    ("theme.light", "Light"),
+    ("theme.dark", "Dark"),
+    // Crash report
+    ("crash.banner", "The installer crashed last time. A report was saved, would you like to send it to us?"),
+    ("crash.report", "Report the crash"),
+    // Install controls
+    ("controls.installer_update", "A newer version of the installer ({version}) is available. "),
+    ("controls.release_notes", "Release notes"),
+    ("controls.update_now", "Update now"),
+    ("controls.how_to_install", "How would you like to install Cobalt?"),
+    ("controls.install_for", "Install for {emulator}"),
+    ("controls.install_sd", "Install onto SD card"),
+    ("controls.version", "Version"),
+    ("controls.latest", "Latest"),
+    ("controls.close_warning", "An installation is in progress — closing now may corrupt your Cobalt install."),
+    ("controls.cancelling", "Cancelling…"),
+    ("controls.cancel_and_exit", "Cancel install and exit"),
+    ("controls.keep_installing", "Keep installing"),
+    ("controls.downgrade_warning", "You're about to go back from Cobalt {installed} to Cobalt {older}."),
+    (
+        "controls.downgrade_note",
+        "Mods and plugins made for the newer version may not load or may crash the game. Your mods folder is left alone.",
+    ),
+    ("controls.downgrade_to", "Downgrade to {version}"),
+    ("controls.sd_confirm", "Install Cobalt into this folder?"),
+    ("controls.sd_confirm_version", "Version: {version}"),
+    ("controls.sd_confirm_needed", "The release needs about {needed}."),
+    ("controls.sd_dont_ask", "Don't ask again for this folder"),
+    ("controls.sd_install_here", "Yes, install here"),
+    ("controls.interrupted", "An install of {version} started {started} didn't finish ({count} files were written)."),
+    (
+        "controls.interrupted_note",
+        "Repair runs the install again and only rewrites what's missing or different. Clean up removes the files it wrote.",
+    ),
+    ("controls.repair", "Resume / repair"),
+    ("controls.clean_up", "Clean up"),
+    ("controls.receipt", "Cobalt {version} was put here by installer {installer} on {date} ({count} files)"),
+    ("controls.unknown_version", "(unknown version)"),
+    ("controls.update_available", "Cobalt {latest} is available — you have {installed}"),
+    ("controls.downgrade_cobalt", "Downgrade Cobalt"),
+    ("controls.update_cobalt", "Update Cobalt"),
+    ("controls.install_cobalt", "Install Cobalt"),
+    ("controls.installed_version", "Installed now: {version}"),
+    ("controls.open_mods_folder", "Open Cobalt Mods Folder"),
+    ("controls.mods_summary_one", "1 mod, {size}"),
+    ("controls.mods_summary_many", "{count} mods, {size}"),
+    ("controls.open_patches_folder", "Open Cobalt Patches Folder"),
+    ("controls.restore_backup", "Restore backup"),
+    ("controls.uninstall_cobalt", "Uninstall Cobalt"),
+    ("controls.advanced", "Advanced"),
+    ("controls.open_config_folder", "Open Cobalt Config Folder"),
+    ("controls.open_logs_folder", "Open Logs & Crash Reports"),
+    ("controls.latest_log", "Latest: {name} ({date})"),
+    ("controls.no_logs", "No logs found yet"),
+    ("controls.uninstall_confirm", "Remove Cobalt from this target? Your mods folder is left alone."),
+    ("controls.uninstall", "Uninstall"),
+    // Wizard
+    ("wizard.target", "Where"),
+    ("wizard.confirm", "Confirm"),
+    ("wizard.install", "Install"),
+    ("wizard.next", "Next"),
+    ("wizard.back", "Back"),
+    ("wizard.skip", "Skip to step 3"),
+    ("wizard.destination", "Cobalt will be installed into:"),
+    ("wizard.installed_now", "Cobalt {version} is installed there now."),
+    ("wizard.nothing_installed", "Cobalt isn't installed there yet."),
+    ("wizard.free_space", "{free} free on that drive."),
+    ("wizard.free_space_unknown", "Couldn't check the free space on that drive."),
+    ("wizard.low_space", "That drive might be too full, the release needs about {needed}."),
+    ("wizard.downgrade", "This version is older than the installed one, you'll be asked to confirm."),
+    // Status line
+    ("status.updating_installer", "Updating the installer to {version}"),
+    ("status.installer_updated_restart", "The installer was updated, restart it to use the new version"),
+    ("status.installer_update_failed", "Couldn't update the installer, this version is unchanged: {error}"),
+    ("status.preparing", "Getting ready"),
+    ("status.connecting", "Waiting for the download to start"),
+    ("status.cancelling_cleanup", "Cancelling, cleaning up"),
+    ("status.cancelled", "Installation cancelled"),
+    ("status.extracting_entry", "Extracting {entry}…"),
+    ("status.cleaned_up", "Cleaned up the unfinished install, removed {count} files"),
+    ("status.clean_up_failed", "Couldn't clean up the unfinished install: {error}"),
+    ("status.open_patches_failed", "Couldn't open the patches folder: {error}"),
+    ("status.open_config_failed", "Couldn't open the config folder: {error}"),
+    ("status.open_logs_failed", "Couldn't open the logs folder: {error}"),
+    ("status.release_not_found", "Couldn't find Cobalt {version} on GitHub"),
+    ("status.target_in_use", "Another installer is using this folder"),
+    ("status.download_failed", "Download failed: {error}"),
+    ("status.install_failed", "Installation failed: {error}"),
+    ("status.install_complete", "Installation complete"),
+    ("status.done", "Cobalt {version} installed"),
+    ("status.progress", "{phase} ({percent}%)"),
+    ("status.uninstalling", "Uninstalling Cobalt"),
+    ("status.uninstalled", "Cobalt uninstalled: removed {files} files ({size}) and {dirs} empty folders"),
+    ("status.uninstall_failed", "Uninstall failed: {error}"),
+    ("progress.prepare", "Getting ready"),
+    ("progress.download", "Downloading release"),
+    ("progress.backup", "Backing up the current install"),
+    ("progress.extract", "Extracting files"),
+    ("progress.finalize", "Checking the install"),
+    ("progress.flush", "Finalizing, writing everything to the drive"),
+    ("progress.elapsed", "{phase}: {elapsed} (total {total})"),
+    ("step.cleanup", "Clean up the emulator"),
+    ("step.download", "Download"),
+    ("step.backup", "Back up"),
+    ("step.extract", "Extract"),
+    ("step.verify", "Check the layout"),
+    ("step.tidy", "Tidy up"),
+    ("step.flush", "Write to the drive"),
+    ("notify.installed", "Cobalt {version} installed successfully"),
+    ("notify.failed", "Installation failed — click for details"),
+    // Why an install failed
+    (
+        "error.release_not_found",
+        "No release tagged {version} was found in Cobalt's release list. It may have been removed, or GitHub couldn't be reached.",
+    ),
+    ("error.offline", "Couldn't reach the server Cobalt is downloaded from."),
+    ("error.download", "Couldn't download Cobalt."),
+    ("error.cancel_cleanup", "The install was cancelled, but not everything it wrote could be removed."),
+    ("error.layout", "Cobalt's files didn't end up where the game looks for them."),
+    ("error.target_in_use", "Another installer is already installing into this folder: {holder}."),
+    (
+        "error.permission_denied",
+        "The installer isn't allowed to write to the target folder.",
+    ),
+    ("error.storage_full", "The target drive is full."),
+    ("error.target_gone", "The target folder went away while Cobalt was being written to it."),
+    ("error.file_locked", "A file Cobalt has to replace is in use by another program."),
+    ("error.damaged_download", "The downloaded release is damaged."),
+    ("error.write_failed", "Cobalt's files couldn't be written to the target."),
+    // What usually fixes each kind of failure
+    ("fix.release_not_found", "Pick another version, or go back to Latest."),
+    (
+        "fix.offline",
+        "Check your internet connection. If GitHub is blocked on your network, set a custom release URL (a mirror) on the settings page.",
+    ),
+    ("fix.download", "Press Retry. If it keeps failing, set a custom release URL (a mirror) on the settings page."),
+    (
+        "fix.permission_denied",
+        "Close anything using the folder, like the emulator. If that doesn't help, run the installer as administrator or pick a different folder.",
+    ),
+    ("fix.storage_full", "Free up some space on the target drive, then press Retry."),
+    ("fix.target_gone", "If it's on an SD card, put the card back in and press Retry."),
+    ("fix.file_locked", "Close the emulator and anything else that might have Cobalt's files open, then press Retry."),
+    ("fix.damaged_download", "Press Retry to download it fresh."),
+    ("fix.write_failed", "Press Retry. If it fails again, use Report a bug so we can look at the details."),
+    ("fix.cancel_cleanup", "Press Retry to install over what's left, or use Restore backup."),
+    ("fix.layout", "Check that you picked the root of the SD card, not a folder inside it, then press Retry."),
+    ("fix.target_in_use", "Wait for the other install to finish, or close that installer, then press Retry."),
+    ("error.technical_details", "Technical details"),
+    ("error.open_log", "Open log"),
+    ("error.get_help", "Get help"),
+    ("error.report_bug", "Report a bug"),
+    // About dialog
+    ("about.title", "About"),
+    ("about.commit", "Built from commit "),
+    ("about.release_url", "Cobalt is downloaded from "),
+    ("about.log_level", "Log file level: "),
+    ("about.installer_repo", "Installer source code"),
+    ("about.cobalt_repo", "Cobalt source code"),
+    ("about.licenses", "Third-party licenses"),
+    ("about.no_licenses", "This build doesn't include the license list, see the installer's repository."),
+    ("about.copy", "Copy version info"),
+    ("about.copied", "Copied, paste it into your bug report"),
+    ("about.copy_failed", "Couldn't copy the version info: {error}"),
+    // Activity log
+    ("log.title", "Activity log ({count})"),
+    ("log.empty", "Nothing yet"),
+    ("log.step", "{step} ({count} entries)"),
+    ("log.copy", "Copy log"),
+    ("log.export_diagnostics", "Export diagnostics…"),
+    ("log.diagnostics_contents", "This is everything that goes in the zip. Paths have your user name taken out."),
+    ("log.save_diagnostics", "Save…"),
+    ("log.save_diagnostics_title", "Save the diagnostics"),
+    // After an install
+    ("success.installed", "Cobalt {version} is installed"),
+    ("success.into", "Into "),
+    ("success.open_mods_failed", "Couldn't open the mods folder: {error}"),
+    ("success.starting", "Starting {emulator}"),
+    ("success.start_failed", "Couldn't start {emulator}: {error}"),
+    ("success.emulator_missing", "Couldn't find {emulator} anymore"),
+    ("success.launch", "Launch {emulator}"),
+    ("success.ejecting", "Ejecting the SD card"),
+    ("success.ejected", "The SD card can be removed now"),
+    ("success.eject_failed", "Couldn't eject the SD card: {error}"),
+    ("success.eject", "Eject SD card"),
+    ("success.what_now", "What now? Mods go in their own folder inside engage/mods, the "),
+    ("success.docs", "Cobalt documentation"),
+    ("success.what_now_help", " explains how they're laid out. Stuck, or want to share what you made? Come say hi on the "),
+    ("success.details", "Install details"),
+    ("success.closing", "Closing in {seconds}s. "),
+    ("summary.installed", "Cobalt {version} installed"),
+    ("summary.destination", "Destination: "),
+    ("summary.counts", "Files added: {added}, updated: {updated}, removed: {removed}, unchanged: {unchanged}"),
+    ("summary.added", "Added"),
+    ("summary.updated", "Updated"),
+    ("summary.removed", "Removed"),
+    ("summary.total", "Total written: {size} in {seconds}s"),
+    ("summary.phases", "Time per step:"),
+    ("summary.backed_up", "Previous install backed up to "),
+    ("summary.no_warnings", "No warnings"),
+    ("summary.warnings", "Warnings:"),
+    ("summary.copy", "Copy summary"),
+    // Picking a target
+    ("emulator.detected", " autodetected at "),
+    ("emulator.not_found", "We couldn't find your {emulator} installation."),
+    ("emulator.use_sd", "Please use the SD Card installation type instead."),
+    ("emulator.open_data", "Open this folder"),
+    ("emulator.open_sdcard", "Open the sdcard folder"),
+    ("emulator.folder_gone", "That folder isn't there anymore, so we looked for the emulator again."),
+    ("sd.intro", "Select your SD Card folder, and we'll install Cobalt there."),
+    ("sd.select", "Select SD Card folder"),
+    ("sd.none", "No folder selected"),
+    ("target.label", "Installing to"),
+    ("target.copy", "Copy"),
+    ("target.copied", "Copied the path"),
+    ("target.copy_mods", "Copy the path of the engage/mods folder"),
+    ("target.copied_mods", "Copied the mods folder path"),
+    ("target.open", "Open"),
+    ("target.missing", "This folder doesn't exist yet"),
+    ("target.open_failed", "Couldn't open the folder: {error}"),
+    ("target.no_data_path", "Couldn't work out where {emulator} keeps its files on this system."),
+    ("target.no_sd_path", "Pick your SD card folder to see where Cobalt will go."),
+    // Install warnings, in the summary after an install
+    ("warning.removed_subsdk9", "Removed a broken subsdk9 left in {emulator}'s exefs folder by an old install"),
+    ("warning.subsdk9_failed", "Couldn't remove a broken subsdk9 left in {emulator}'s exefs folder by an old install: {error}"),
+    ("warning.backup_no_space", "not enough free space to back up the previous install, it was overwritten without a backup"),
+    ("warning.backup_cancelled", "the backup was cancelled"),
+    ("warning.backup_failed", "the backup failed ({error}), the previous install was overwritten without one"),
+    ("warning.kept_config", "Kept your existing {file} instead of resetting it"),
+    ("warning.removed_empty_dirs", "Removed {count} folders left empty by files this release dropped"),
+    ("warning.stale_files_failed", "Some files from the previous version couldn't be removed: {error}"),
+    ("warning.kept_after_cancel", "The install was cancelled after it was complete, so it was kept"),
+    ("warning.not_flushed", "Not everything was written out to the drive, eject it safely before unplugging it"),
+    ("log.phase_times", "Time per phase: {phases}"),
+    // Install history
+    ("history.title", "History"),
+    ("history.empty", "Nothing installed with this installer yet."),
+    ("history.entry", "{action} Cobalt {version} for {target_type}"),
+    ("history.install", "Install"),
+    ("history.update", "Update"),
+    ("history.uninstall", "Uninstall"),
+    ("history.repair", "Repair"),
+    ("history.restore", "Restore"),
+    ("history.succeeded", "OK, {size} in {seconds}s"),
+    ("history.succeeded_phases", "OK, {size} in {seconds}s ({phases})"),
+    ("history.failed", "Failed: {reason}"),
+    // Cobalt backups
+    ("backup.pick", "Pick a backup to put back into "),
+    ("backup.none", "No backups yet. One is made automatically before each update."),
+    ("backup.date", "backed up {date}"),
+    ("backup.restore", "Restore"),
+    ("backup.restoring", "Restoring backup ({done}/{total} files)"),
+    ("backup.restored", "Backup restored, Cobalt {version} is installed"),
+    ("backup.restore_failed", "Restore failed: {error}"),
+    // Mods
+    ("mods.title", "Mods"),
+    ("mods.install_zip", "Install mod from zip"),
+    ("mods.back_up", "Back up mods"),
+    ("mods.restore", "Restore mods"),
+    ("mods.copy_to", "Copy mods to…"),
+    ("mods.copy_needs_target", "Set up another emulator or an SD card folder first"),
+    ("mods.check_conflicts", "Check for conflicts"),
+    ("mods.checking", "Checking…"),
+    ("mods.export_report", "Export mods report"),
+    ("mods.install_translation", "Install translation patch"),
+    ("mods.drop_here", "Drop a mod's zip here to install it."),
+    ("mods.looking", "Looking for mods…"),
+    ("mods.read_failed", "Couldn't read the mods folder: {error}"),
+    ("mods.no_folder", "There's no mods folder here yet. Installing Cobalt creates it."),
+    ("mods.none", "No mods installed yet. Put each mod in its own folder inside engage/mods."),
+    ("mods.total", "{count} mods, {size} in total"),
+    ("mods.measuring", "{count} mods, measuring…"),
+    ("mods.files", "{count} files, "),
+    ("mods.enable", "Enable this mod"),
+    ("mods.disable", "Disable this mod"),
+    ("mods.disabled", "disabled"),
+    ("mods.enable_failed", "Couldn't enable {name}: {error}"),
+    ("mods.disable_failed", "Couldn't disable {name}: {error}"),
+    ("mods.nested", "nested one level too deep"),
+    ("mods.nested_explain", "The mod's files are inside an extra {folder} folder, so Cobalt won't find them"),
+    ("mods.fix", "Fix"),
+    ("mods.flattened", "Moved {name}'s files out of the extra folder"),
+    ("mods.flatten_failed", "Couldn't fix {name}: {error}"),
+    ("mods.open_folder", "Open folder"),
+    ("mods.gone", "{name} isn't there anymore, it may have been moved or deleted"),
+    ("mods.open_failed", "Couldn't open {name}'s folder: {error}"),
+    ("mods.delete", "Delete"),
+    ("mods.confirm_delete", "Delete {name}{details}? This can't be undone."),
+    ("mods.delete_details", " ({size}, {count} files)"),
+    ("mods.deleted", "Deleted {name}"),
+    ("mods.delete_failed", "Couldn't delete {name}: {error}"),
+    ("mods.not_a_zip", "{path} isn't a zip file"),
+    ("mods.read_zip_failed", "Couldn't read that zip: {error}"),
+    ("mods.suspicious", "{name} doesn't look like an Engage mod. {problem}"),
+    ("mods.install_anyway", "Install anyway"),
+    ("mods.already_installed", "A mod called {name} is already installed."),
+    ("mods.replace", "Replace it"),
+    ("mods.install_as", "Install as {name}"),
+    ("mods.installing", "Installing {name} ({done}/{total} files)"),
+    ("mods.installed", "Installed {count} files into {path}"),
+    ("mods.install_failed", "Couldn't install the mod: {error}"),
+    ("mods.stray_found", "Found {file} directly in {location}. "),
+    ("mods.stray_explain", "Cobalt only loads game files from inside a named mod folder, like engage/mods/MyMod/{destination}."),
+    ("mods.move_into", "Move into engage/mods/"),
+    ("mods.move_strays", "Move them"),
+    ("mods.strays_moved", "Moved the stray files into engage/mods/{name}"),
+    ("mods.move_strays_failed", "Couldn't move the stray files: {error}"),
+    ("mods.conflicts_failed", "Couldn't check for conflicts: {error}"),
+    ("mods.report_failed", "Couldn't build the mods report: {error}"),
+    ("mods.save_report_title", "Save the mods report"),
+    ("mods.text_file", "Text file"),
+    ("mods.report_saved", "Saved the mods report to {path}"),
+    ("mods.save_report_failed", "Couldn't save the mods report: {error}"),
+    ("mods.back_up_title", "Save a backup of your mods"),
+    ("mods.zip_file", "Zip archive"),
+    ("mods.backing_up", "Backing up mods ({done}/{total} files)"),
+    ("mods.backed_up", "Backed up {count} files ({size}) to {path}"),
+    ("mods.skipped_links", ". Skipped links: {names}"),
+    ("mods.back_up_failed", "Couldn't back up the mods: {error}"),
+    ("mods.pick_backup_title", "Pick a mods backup to restore"),
+    ("mods.read_backup_failed", "Couldn't read that backup: {error}"),
+    ("copy_mods.to", "Copy to"),
+    ("copy_mods.skip", "Already there: skip"),
+    ("copy_mods.replace", "Already there: replace it"),
+    ("copy_mods.copying", "Copying mods ({done}/{total})"),
+    ("copy_mods.copied", "Copied {count} mods to {destination}"),
+    ("copy_mods.skipped", ". Skipped (already there): {names}"),
+    ("copy_mods.failed", "Copying mods failed: {error}"),
+    ("restore_mods.contents", "Mods in {path}:"),
+    ("restore_mods.conflict_skip", "Already installed: skip"),
+    ("restore_mods.conflict_replace", "Already installed: replace it"),
+    ("restore_mods.conflict_rename", "Already installed: restore as a new name"),
+    ("restore_mods.restore", "Restore"),
+    ("restore_mods.skip", "Skip"),
+    ("restore_mods.restoring", "Restoring mods ({done}/{total})"),
+    ("restore_mods.restored", "Restored {count} mods"),
+    ("restore_mods.failed", "Couldn't restore the mods: {error}"),
+    // Plugins
+    ("plugins.title", "Plugins"),
+    ("plugins.install", "Install plugin"),
+    ("plugins.drop_here", "Drop .nro files here to install them."),
+    ("plugins.looking", "Looking for plugins…"),
+    ("plugins.read_failed", "Couldn't read the plugins folder: {error}"),
+    ("plugins.none", "No plugins installed."),
+    ("plugins.part_of_cobalt", "part of Cobalt"),
+    ("plugins.remove", "Remove"),
+    ("plugins.removed", "Removed {name}"),
+    ("plugins.remove_failed", "Couldn't remove {name}: {error}"),
+    ("plugins.skipped_others", "Only .nro files can be installed as plugins, skipped {count} other files"),
+    ("plugins.already_installed", "Already installed: {names}"),
+    ("plugins.replace", "Replace them"),
+    ("plugins.only_new", "Only copy the new ones"),
+    ("plugins.none_copied", "No plugins were copied"),
+    ("plugins.copied", "Copied {names} into {path}"),
+    ("plugins.install_failed", "Couldn't install the plugins: {error}"),
+    // Cobalt installed in more than one place
+    ("installs.intro", "Cobalt is installed in more than one place. Only the one you play from needs it."),
+    ("installs.selected", "selected"),
+    ("installs.unreachable", "not reachable right now"),
+    ("installs.uninstall", "Uninstall from here"),
+    ("installs.removed", "Removed Cobalt from {path}: {count} files ({size})"),
+    // Troubleshooting checks
+    ("check.title", "Troubleshooting"),
+    ("check.intro", "Cobalt not loading? Run the checks and fix whatever they find, or copy the results when asking for help."),
+    ("check.run", "Run checks"),
+    ("check.running", "Checking the connection to GitHub..."),
+    ("check.copy", "Copy results"),
+    ("check.copied", "Copied the check results"),
+    ("check.fix_clean_up", "Remove it"),
+    ("check.fix_repair", "Repair Cobalt"),
+    ("check.fix_select_path", "Pick the folder"),
+    ("check.cleaned_up", "Removed the broken file"),
+    ("check.clean_up_failed", "Couldn't remove the file: {error}"),
+    ("check.detection", "Emulator"),
+    ("check.detection_found", "{emulator} found, installing into its folder at {path}."),
+    ("check.detection_also", "Also found: {others}."),
+    ("check.detection_missing", "{emulator} wasn't found at {path}. Start it once, or pick its SD folder by hand."),
+    ("check.detection_no_path", "Couldn't work out where {emulator} keeps its files on this system."),
+    ("check.sd_none", "No SD card folder picked yet."),
+    ("check.sd_missing", "The SD card folder {path} isn't there. Is the card inserted?"),
+    ("check.sd_picked", "Installing into the SD card folder {path}."),
+    ("check.game", "Fire Emblem Engage"),
+    ("check.game_found", "Engage has been started in {emulator} before."),
+    ("check.game_missing", "No sign Engage has been started in {emulator} yet. Start the game once without mods to make sure it runs."),
+    ("check.leftovers", "Old broken files"),
+    ("check.leftovers_none", "No broken subsdk9 from an old install."),
+    ("check.leftovers_found", "A broken subsdk9 from an old install is still at {path}, it stops Cobalt from loading."),
+    ("check.mods_folder", "Mods folder"),
+    ("check.mods_ok", "{path} exists and can be written to."),
+    ("check.mods_missing", "{path} doesn't exist yet. Installing Cobalt creates it."),
+    ("check.mods_read_only", "{path} can't be written to: {error}"),
+    ("check.free_space", "Free space"),
+    ("check.space_ok", "{free} free."),
+    ("check.space_low", "Only {free} free, keep at least {needed} for installing and backups."),
+    ("check.space_unknown", "Couldn't find out how much space is free."),
+    ("check.cobalt_files", "Cobalt's files"),
+    ("check.cobalt_ok", "All there and unchanged since they were installed."),
+    ("check.cobalt_unverified", "All there. This install is too old to have checksums, so they weren't compared."),
+    ("check.cobalt_missing", "Cobalt isn't installed here yet."),
+    ("check.cobalt_incomplete", "Some of Cobalt's files are missing since they were installed."),
+    ("check.cobalt_damaged", "{count} of Cobalt's files changed since they were installed: {files}"),
+    ("check.github", "Connection to GitHub"),
+    ("check.github_ok", "Reached GitHub, the newest Cobalt is {version}."),
+    ("check.github_no_release", "Reached GitHub, but it didn't list any Cobalt release."),
+    ("toast.update_available", "Cobalt {version} is out"),
+    ("toast.removed_subsdk9", "Removed a broken file an old install left in {emulator}"),
+    ("toast.removed_stale", "Removed {count} files the new version no longer has"),
+    ("toast.diagnostics_saved", "Diagnostics saved"),
+    ("toast.cleaned_up", "Cleaned up {count} leftover files, {size} freed"),
+    ("toast.nothing_to_clean", "Nothing to clean up"),
+    ("hint.installation_type", "Start here: pick how you play Fire Emblem Engage."),
+    ("hint.emulator", "This is where Cobalt will go. If your emulator wasn't found, check it's been run at least once."),
+    ("hint.sd_card", "Now pick your SD card folder, Cobalt goes straight onto it."),
+    ("hint.next", "Got it"),
+    ("hint.skip", "Skip tips"),
+    ("help.title", "What's this?"),
+    ("help.installation_type", "Choose Ryujinx, Citron or Eden if you play on that emulator on this computer. Choose SD Card if you play on a hacked Switch console."),
+    ("help.sd_card", "Select the root of the SD card: the folder that contains the Nintendo and atmosphere folders, not a folder inside them."),
+    ("help.more", "More in the Cobalt documentation"),
+    // Android
+    ("android.picker_failed", "Couldn't open the folder picker: {error}"),
+    ("android.wrong_folder", "That's not Eden's folder. Tap the button again and pick Eden's folder."),
+    ("android.downloading", "Downloading release"),
+    ("android.installing", "Installing into Eden"),
+    ("android.write_failed", "Install failed: couldn't write into Eden's folder"),
+    ("android.intro", "This installs Cobalt into the Eden emulator."),
+    ("android.access_granted", "Eden folder access granted."),
+    ("android.grant_first", "First, grant access to Eden's folder."),
+    ("android.grant_steps", "In the file picker, open the menu (top-left), choose Eden, then tap \"Use this folder\"."),
+    ("android.grant", "Grant Eden folder access"),
+];
+
+const ES: &[(&str, &str)] = &[
+    ("common.back", "Volver"),
+    ("common.cancel", "Cancelar"),
+    ("common.close", "Cerrar"),
+    ("common.copy", "Copiar"),
+    ("common.dismiss", "Descartar"),
+    ("common.refresh", "Actualizar"),
+    ("common.retry", "Reintentar"),
+    ("common.save", "Guardar…"),
+    ("common.status", "Estado: "),
+    ("hero.settings", "Ajustes"),
+    ("hero.welcome", "Bienvenido al instalador de Cobalt"),
+    ("info.title", "¿Qué es Cobalt?"),
+    (
+        "info.blurb",
+        "Cobalt es un cargador de mods para Fire Emblem Engage. Se instala una vez y a partir de ahí carga los mods que pongas en la carpeta engage/mods, sin tocar los archivos del juego.",
+    ),
+    ("info.feature_mods", "Activa y desactiva mods moviendo carpetas, sin reconstruir nada"),
+    ("info.feature_several", "Varios mods pueden cambiar el juego a la vez"),
+    ("info.feature_anywhere", "Funciona igual en una Switch y en emuladores"),
+    ("info.more", "Más sobre Cobalt en GitHub"),
+    ("hero.sammie_alt", "Sammie te mira con cara de juicio"),
+    ("hero.having_issues", "¿Tienes problemas? "),
+    ("hero.get_help", "¡Pide ayuda!"),
+    ("hero.icon_credit", "Icono de Sommie por badatgames26"),
+    ("hero.version", "Versión {version}"),
+    ("status.waiting", "Esperándote"),
+    ("status.bond_fragments", "Has obtenido 50 fragmentos de vínculo."),
+    ("settings.title", "Ajustes"),
+    ("settings.general", "General"),
+    ("settings.check_updates", "Buscar nuevas versiones del instalador al abrirlo"),
+    ("settings.backup_before_update", "Hacer una copia de seguridad de la instalación actual antes de sobrescribirla"),
+    ("settings.close_after_install", "Cerrar automáticamente después de una instalación correcta"),
+    ("settings.theme", "Tema"),
+    ("settings.ui_scale", "Tamaño de la interfaz"),
+    ("settings.ui_scale_note", "Ctrl + = y Ctrl + - (⌘ en macOS) lo cambian desde cualquier parte."),
+    ("settings.language", "Idioma"),
+    ("settings.network", "Red"),
+    ("settings.include_prereleases", "Incluir versiones preliminares"),
+    ("settings.custom_release_url", "URL de un zip de versión personalizado"),
+    (
+        "settings.custom_release_url_note",
+        "Instala este zip en lugar de la última versión. Déjalo vacío salvo que alguien te haya pedido probar una compilación.",
+    ),
+    ("settings.advanced", "Avanzado"),
+    ("settings.preserve_config", "Conservar mis archivos de configuración de Cobalt al reinstalar"),
+    ("settings.verbose_logging", "Registro detallado"),
+    ("settings.verbose_logging_note", "Escribe en el archivo de registro todo lo que hace el instalador, incluido dónde buscó los emuladores. Actívalo cuando te lo pidan al buscar ayuda."),
+    ("settings.extraction_workers", "Archivos escritos a la vez"),
+    ("settings.extraction_workers_auto", "Automático"),
+    ("settings.extraction_workers_note", "En automático se escribe un archivo a la vez en tarjetas SD y memorias USB, que se vuelven más lentas si se les pide escribir varios, y unos pocos a la vez en otras unidades."),
+    ("settings.write_buffer", "Búfer de escritura"),
+    ("settings.write_buffer_note", "Cuánto de cada archivo se reúne antes de escribirlo. En automático se usan escrituras grandes en tarjetas SD y memorias USB. Si las instalaciones en tu tarjeta son lentas, prueba un búfer más grande."),
+    ("settings.flush_removable", "Esperar a que las tarjetas SD y memorias USB terminen de escribir"),
+    ("settings.flush_removable_note", "Desactivarlo hace que las instalaciones en una tarjeta terminen antes, pero sacar la tarjeta justo después podría dejar Cobalt roto."),
+    ("settings.restore_defaults", "Restablecer valores predeterminados"),
+    (
+        "settings.reset_warning",
+        "Esto olvida el tipo de instalación, la carpeta de la tarjeta SD y todos los demás ajustes, y borra los archivos descargados. Tus instalaciones, copias de seguridad e historial se conservan.",
+    ),
+    ("settings.clean_up", "Limpiar ahora"),
+    ("settings.clean_up_note", "Borra las descargas y las copias de seguridad sin terminar que dejaron instalaciones que fallaron o se cerraron. Esto también se hace cada vez que se abre el instalador."),
+    ("settings.reset", "Restablecer"),
+    ("settings.reset_all", "Restablecer los ajustes del instalador"),
+    ("settings.reset_failed", "No se pudieron restablecer los ajustes del instalador: {error}"),
+    ("theme.system", "Igual que mi sistema"),
+    ("theme.light", "Claro"),
+    ("theme.dark", "Oscuro"),
+    ("crash.banner", "El instalador se cerró inesperadamente la última vez. Se guardó un informe, ¿quieres enviárnoslo?"),
+    ("crash.report", "Informar del fallo"),
+    ("controls.installer_update", "Hay una versión más nueva del instalador ({version}). "),
+    ("controls.release_notes", "Notas de la versión"),
+    ("controls.update_now", "Actualizar ahora"),
+    ("controls.how_to_install", "¿Cómo quieres instalar Cobalt?"),
+    ("controls.install_for", "Instalar para {emulator}"),
+    ("controls.install_sd", "Instalar en la tarjeta SD"),
+    ("controls.version", "Versión"),
+    ("controls.latest", "La más reciente"),
+    ("controls.close_warning", "Hay una instalación en curso: si cierras ahora, tu instalación de Cobalt podría dañarse."),
+    ("controls.cancelling", "Cancelando…"),
+    ("controls.cancel_and_exit", "Cancelar la instalación y salir"),
+    ("controls.keep_installing", "Seguir instalando"),
+    ("controls.downgrade_warning", "Vas a volver de Cobalt {installed} a Cobalt {older}."),
+    (
+        "controls.downgrade_note",
+        "Los mods y plugins hechos para la versión más nueva podrían no cargar o cerrar el juego. Tu carpeta de mods no se toca.",
+    ),
+    ("controls.downgrade_to", "Volver a {version}"),
+    ("controls.sd_confirm", "¿Instalar Cobalt en esta carpeta?"),
+    ("controls.sd_confirm_version", "Versión: {version}"),
+    ("controls.sd_confirm_needed", "La versión necesita unos {needed}."),
+    ("controls.sd_dont_ask", "No volver a preguntar para esta carpeta"),
+    ("controls.sd_install_here", "Sí, instalar aquí"),
+    ("controls.interrupted", "Una instalación de {version} iniciada el {started} no terminó (se escribieron {count} archivos)."),
+    (
+        "controls.interrupted_note",
+        "Reparar vuelve a ejecutar la instalación y solo reescribe lo que falta o es distinto. Limpiar elimina los archivos que escribió.",
+    ),
+    ("controls.repair", "Continuar / reparar"),
+    ("controls.clean_up", "Limpiar"),
+    ("controls.receipt", "Cobalt {version} fue instalado aquí por el instalador {installer} el {date} ({count} archivos)"),
+    ("controls.unknown_version", "(versión desconocida)"),
+    ("controls.update_available", "Cobalt {latest} está disponible; tienes la {installed}"),
+    ("controls.downgrade_cobalt", "Volver a una versión anterior de Cobalt"),
+    ("controls.update_cobalt", "Actualizar Cobalt"),
+    ("controls.install_cobalt", "Instalar Cobalt"),
+    ("controls.installed_version", "Instalada ahora: {version}"),
+    ("controls.open_mods_folder", "Abrir la carpeta de mods de Cobalt"),
+    ("controls.mods_summary_one", "1 mod, {size}"),
+    ("controls.mods_summary_many", "{count} mods, {size}"),
+    ("controls.open_patches_folder", "Abrir la carpeta de parches de Cobalt"),
+    ("controls.restore_backup", "Restaurar copia de seguridad"),
+    ("controls.uninstall_cobalt", "Desinstalar Cobalt"),
+    ("controls.advanced", "Avanzado"),
+    ("controls.open_config_folder", "Abrir la carpeta de configuración de Cobalt"),
+    ("controls.open_logs_folder", "Abrir registros e informes de fallos"),
+    ("controls.latest_log", "Último: {name} ({date})"),
+    ("controls.no_logs", "Todavía no hay registros"),
+    ("controls.uninstall_confirm", "¿Quitar Cobalt de este destino? Tu carpeta de mods no se toca."),
+    ("controls.uninstall", "Desinstalar"),
+    ("wizard.target", "Dónde"),
+    ("wizard.confirm", "Confirmar"),
+    ("wizard.install", "Instalar"),
+    ("wizard.next", "Siguiente"),
+    ("wizard.back", "Atrás"),
+    ("wizard.skip", "Saltar al paso 3"),
+    ("wizard.destination", "Cobalt se instalará en:"),
+    ("wizard.installed_now", "Ahí está instalado Cobalt {version}."),
+    ("wizard.nothing_installed", "Cobalt todavía no está instalado ahí."),
+    ("wizard.free_space", "{free} libres en esa unidad."),
+    ("wizard.free_space_unknown", "No se pudo comprobar el espacio libre en esa unidad."),
+    ("wizard.low_space", "Puede que esa unidad esté demasiado llena, la versión necesita unos {needed}."),
+    ("wizard.downgrade", "Esta versión es más antigua que la instalada, se te pedirá confirmación."),
+    ("status.updating_installer", "Actualizando el instalador a {version}"),
+    ("status.installer_updated_restart", "El instalador se actualizó, reinícialo para usar la nueva versión"),
+    ("status.installer_update_failed", "No se pudo actualizar el instalador, esta versión sigue igual: {error}"),
+    ("status.preparing", "Preparando"),
+    ("status.connecting", "Esperando a que empiece la descarga"),
+    ("status.cancelling_cleanup", "Cancelando y limpiando"),
+    ("status.cancelled", "Instalación cancelada"),
+    ("status.extracting_entry", "Extrayendo {entry}…"),
+    ("status.cleaned_up", "Se limpió la instalación sin terminar, se eliminaron {count} archivos"),
+    ("status.clean_up_failed", "No se pudo limpiar la instalación sin terminar: {error}"),
+    ("status.open_patches_failed", "No se pudo abrir la carpeta de parches: {error}"),
+    ("status.open_config_failed", "No se pudo abrir la carpeta de configuración: {error}"),
+    ("status.open_logs_failed", "No se pudo abrir la carpeta de registros: {error}"),
+    ("status.release_not_found", "No se encontró Cobalt {version} en GitHub"),
+    ("status.target_in_use", "Otro instalador está usando esta carpeta"),
+    ("status.download_failed", "La descarga falló: {error}"),
+    ("status.install_failed", "La instalación falló: {error}"),
+    ("status.install_complete", "Instalación completada"),
+    ("status.done", "Cobalt {version} instalado"),
+    ("status.progress", "{phase} ({percent} %)"),
+    ("status.uninstalling", "Desinstalando Cobalt"),
+    ("status.uninstalled", "Cobalt desinstalado: se eliminaron {files} archivos ({size}) y {dirs} carpetas vacías"),
+    ("status.uninstall_failed", "La desinstalación falló: {error}"),
+    ("progress.prepare", "Preparando"),
+    ("progress.download", "Descargando la versión"),
+    ("progress.backup", "Haciendo una copia de seguridad de la instalación actual"),
+    ("progress.extract", "Extrayendo archivos"),
+    ("progress.finalize", "Comprobando la instalación"),
+    ("progress.flush", "Finalizando, escribiendo todo en la unidad"),
+    ("progress.elapsed", "{phase}: {elapsed} (total {total})"),
+    ("step.cleanup", "Limpiar el emulador"),
+    ("step.download", "Descargar"),
+    ("step.backup", "Copia de seguridad"),
+    ("step.extract", "Extraer"),
+    ("step.verify", "Comprobar la estructura"),
+    ("step.tidy", "Ordenar"),
+    ("step.flush", "Escribir en la unidad"),
+    ("notify.installed", "Cobalt {version} se instaló correctamente"),
+    ("notify.failed", "La instalación falló: haz clic para ver los detalles"),
+    (
+        "error.release_not_found",
+        "No se encontró ninguna versión con la etiqueta {version} en la lista de versiones de Cobalt. Puede que se haya retirado o que no se pudiera conectar con GitHub.",
+    ),
+    ("error.offline", "No se pudo conectar con el servidor desde el que se descarga Cobalt."),
+    ("error.download", "No se pudo descargar Cobalt."),
+    ("error.cancel_cleanup", "La instalación se canceló, pero no se pudo eliminar todo lo que había escrito."),
+    ("error.layout", "Los archivos de Cobalt no quedaron donde el juego los busca."),
+    ("error.target_in_use", "Otro instalador ya está instalando en esta carpeta: {holder}."),
+    (
+        "error.permission_denied",
+        "El instalador no tiene permiso para escribir en la carpeta de destino.",
+    ),
+    ("error.storage_full", "La unidad de destino está llena."),
+    ("error.target_gone", "La carpeta de destino desapareció mientras se escribía Cobalt en ella."),
+    ("error.file_locked", "Otro programa está usando un archivo que Cobalt tiene que reemplazar."),
+    ("error.damaged_download", "La versión descargada está dañada."),
+    ("error.write_failed", "No se pudieron escribir los archivos de Cobalt en el destino."),
+    ("fix.release_not_found", "Elige otra versión o vuelve a La más reciente."),
+    (
+        "fix.offline",
+        "Comprueba tu conexión a internet. Si GitHub está bloqueado en tu red, configura una URL de versión personalizada (un espejo) en los ajustes.",
+    ),
+    ("fix.download", "Pulsa Reintentar. Si sigue fallando, configura una URL de versión personalizada (un espejo) en los ajustes."),
+    (
+        "fix.permission_denied",
+        "Cierra lo que esté usando la carpeta, como el emulador. Si no sirve, ejecuta el instalador como administrador o elige otra carpeta.",
+    ),
+    ("fix.storage_full", "Libera espacio en la unidad de destino y pulsa Reintentar."),
+    ("fix.target_gone", "Si está en una tarjeta SD, vuelve a insertarla y pulsa Reintentar."),
+    ("fix.file_locked", "Cierra el emulador y cualquier otro programa que pueda tener abiertos los archivos de Cobalt, y pulsa Reintentar."),
+    ("fix.damaged_download", "Pulsa Reintentar para descargarla de nuevo."),
+    ("fix.write_failed", "Pulsa Reintentar. Si vuelve a fallar, usa Informar de un error para que podamos ver los detalles."),
+    ("fix.cancel_cleanup", "Pulsa Reintentar para instalar sobre lo que quedó, o usa Restaurar copia de seguridad."),
+    ("fix.layout", "Comprueba que elegiste la raíz de la tarjeta SD y no una carpeta dentro de ella, y pulsa Reintentar."),
+    ("fix.target_in_use", "Espera a que termine la otra instalación o cierra ese instalador, y pulsa Reintentar."),
+    ("error.technical_details", "Detalles técnicos"),
+    ("error.open_log", "Abrir el registro"),
+    ("error.get_help", "Pedir ayuda"),
+    ("error.report_bug", "Informar de un error"),
+    ("about.title", "Acerca de"),
+    ("about.commit", "Compilado desde el commit "),
+    ("about.release_url", "Cobalt se descarga de "),
+    ("about.log_level", "Nivel del archivo de registro: "),
+    ("about.installer_repo", "Código fuente del instalador"),
+    ("about.cobalt_repo", "Código fuente de Cobalt"),
+    ("about.licenses", "Licencias de terceros"),
+    ("about.no_licenses", "Esta compilación no incluye la lista de licencias, consulta el repositorio del instalador."),
+    ("about.copy", "Copiar la información de versión"),
+    ("about.copied", "Copiado, pégalo en tu informe de error"),
+    ("about.copy_failed", "No se pudo copiar la información de versión: {error}"),
+    ("log.title", "Registro de actividad ({count})"),
+    ("log.empty", "Nada por ahora"),
+    ("log.step", "{step} ({count} entradas)"),
+    ("log.copy", "Copiar el registro"),
+    ("log.export_diagnostics", "Exportar diagnóstico…"),
+    ("log.diagnostics_contents", "Esto es todo lo que va en el zip. Las rutas no incluyen tu nombre de usuario."),
+    ("log.save_diagnostics", "Guardar…"),
+    ("log.save_diagnostics_title", "Guardar el diagnóstico"),
+    ("success.installed", "Cobalt {version} está instalado"),
+    ("success.into", "En "),
+    ("success.open_mods_failed", "No se pudo abrir la carpeta de mods: {error}"),
+    ("success.starting", "Iniciando {emulator}"),
+    ("success.start_failed", "No se pudo iniciar {emulator}: {error}"),
+    ("success.emulator_missing", "Ya no se encuentra {emulator}"),
+    ("success.launch", "Iniciar {emulator}"),
+    ("success.ejecting", "Expulsando la tarjeta SD"),
+    ("success.ejected", "Ya puedes sacar la tarjeta SD"),
+    ("success.eject_failed", "No se pudo expulsar la tarjeta SD: {error}"),
+    ("success.eject", "Expulsar la tarjeta SD"),
+    ("success.what_now", "¿Y ahora qué? Cada mod va en su propia carpeta dentro de engage/mods, la "),
+    ("success.docs", "documentación de Cobalt"),
+    ("success.what_now_help", " explica cómo se organizan. ¿Te has atascado o quieres compartir lo que has hecho? Pásate a saludar por "),
+    ("success.details", "Detalles de la instalación"),
+    ("success.closing", "Se cerrará en {seconds} s. "),
+    ("summary.installed", "Cobalt {version} instalado"),
+    ("summary.destination", "Destino: "),
+    ("summary.counts", "Archivos añadidos: {added}, actualizados: {updated}, eliminados: {removed}, sin cambios: {unchanged}"),
+    ("summary.added", "Añadidos"),
+    ("summary.updated", "Actualizados"),
+    ("summary.removed", "Eliminados"),
+    ("summary.total", "Total escrito: {size} en {seconds} s"),
+    ("summary.phases", "Tiempo por paso:"),
+    ("summary.backed_up", "Copia de seguridad de la instalación anterior en "),
+    ("summary.no_warnings", "Sin advertencias"),
+    ("summary.warnings", "Advertencias:"),
+    ("summary.copy", "Copiar el resumen"),
+    ("emulator.detected", " detectado en "),
+    ("emulator.not_found", "No encontramos tu instalación de {emulator}."),
+    ("emulator.use_sd", "Usa el tipo de instalación en tarjeta SD en su lugar."),
+    ("emulator.open_data", "Abrir esta carpeta"),
+    ("emulator.open_sdcard", "Abrir la carpeta sdcard"),
+    ("emulator.folder_gone", "Esa carpeta ya no existe, así que volvimos a buscar el emulador."),
+    ("sd.intro", "Elige la carpeta de tu tarjeta SD e instalaremos Cobalt ahí."),
+    ("sd.select", "Elegir la carpeta de la tarjeta SD"),
+    ("sd.none", "No hay ninguna carpeta elegida"),
+    ("target.label", "Se instalará en"),
+    ("target.copy", "Copiar"),
+    ("target.copied", "Ruta copiada"),
+    ("target.copy_mods", "Copiar la ruta de la carpeta engage/mods"),
+    ("target.copied_mods", "Ruta de la carpeta de mods copiada"),
+    ("target.open", "Abrir"),
+    ("target.missing", "Esta carpeta todavía no existe"),
+    ("target.open_failed", "No se pudo abrir la carpeta: {error}"),
+    ("target.no_data_path", "No se pudo averiguar dónde guarda {emulator} sus archivos en este sistema."),
+    ("target.no_sd_path", "Elige la carpeta de tu tarjeta SD para ver dónde irá Cobalt."),
+    // Comprobaciones
+    ("warning.removed_subsdk9", "Se eliminó un subsdk9 dañado que una instalación antigua dejó en la carpeta exefs de {emulator}"),
+    ("warning.subsdk9_failed", "No se pudo eliminar un subsdk9 dañado que una instalación antigua dejó en la carpeta exefs de {emulator}: {error}"),
+    ("warning.backup_no_space", "no hay espacio libre suficiente para hacer una copia de la instalación anterior, se sobrescribió sin copia"),
+    ("warning.backup_cancelled", "se canceló la copia de seguridad"),
+    ("warning.backup_failed", "la copia de seguridad falló ({error}), la instalación anterior se sobrescribió sin ella"),
+    ("warning.kept_config", "Se conservó tu {file} en lugar de restablecerlo"),
+    ("warning.removed_empty_dirs", "Se eliminaron {count} carpetas que quedaron vacías por archivos que esta versión ya no tiene"),
+    ("warning.stale_files_failed", "No se pudieron eliminar algunos archivos de la versión anterior: {error}"),
+    ("warning.kept_after_cancel", "La instalación se canceló cuando ya estaba completa, así que se conservó"),
+    ("warning.not_flushed", "No todo se llegó a escribir en la unidad, expúlsala de forma segura antes de desconectarla"),
+    ("log.phase_times", "Tiempo por fase: {phases}"),
+    ("history.title", "Historial"),
+    ("history.empty", "Todavía no se ha instalado nada con este instalador."),
+    ("history.entry", "{action} Cobalt {version} para {target_type}"),
+    ("history.install", "Instalación de"),
+    ("history.update", "Actualización a"),
+    ("history.uninstall", "Desinstalación de"),
+    ("history.repair", "Reparación de"),
+    ("history.restore", "Restauración de"),
+    ("history.succeeded", "Correcto, {size} en {seconds}s"),
+    ("history.succeeded_phases", "Correcto, {size} en {seconds}s ({phases})"),
+    ("history.failed", "Falló: {reason}"),
+    ("backup.pick", "Elige una copia de seguridad para volver a poner en "),
+    ("backup.none", "Todavía no hay copias de seguridad. Se hace una automáticamente antes de cada actualización."),
+    ("backup.date", "copia del {date}"),
+    ("backup.restore", "Restaurar"),
+    ("backup.restoring", "Restaurando la copia de seguridad ({done}/{total} archivos)"),
+    ("backup.restored", "Copia de seguridad restaurada, Cobalt {version} está instalado"),
+    ("backup.restore_failed", "La restauración falló: {error}"),
+    ("mods.title", "Mods"),
+    ("mods.install_zip", "Instalar mod desde un zip"),
+    ("mods.back_up", "Hacer copia de los mods"),
+    ("mods.restore", "Restaurar mods"),
+    ("mods.copy_to", "Copiar mods a…"),
+    ("mods.copy_needs_target", "Configura primero otro emulador o una carpeta de tarjeta SD"),
+    ("mods.check_conflicts", "Buscar conflictos"),
+    ("mods.checking", "Comprobando…"),
+    ("mods.export_report", "Exportar informe de mods"),
+    ("mods.install_translation", "Instalar parche de traducción"),
+    ("mods.drop_here", "Suelta aquí el zip de un mod para instalarlo."),
+    ("mods.looking", "Buscando mods…"),
+    ("mods.read_failed", "No se pudo leer la carpeta de mods: {error}"),
+    ("mods.no_folder", "Todavía no hay carpeta de mods aquí. Instalar Cobalt la crea."),
+    ("mods.none", "Todavía no hay mods instalados. Pon cada mod en su propia carpeta dentro de engage/mods."),
+    ("mods.total", "{count} mods, {size} en total"),
+    ("mods.measuring", "{count} mods, midiendo…"),
+    ("mods.files", "{count} archivos, "),
+    ("mods.enable", "Activar este mod"),
+    ("mods.disable", "Desactivar este mod"),
+    ("mods.disabled", "desactivado"),
+    ("mods.enable_failed", "No se pudo activar {name}: {error}"),
+    ("mods.disable_failed", "No se pudo desactivar {name}: {error}"),
+    ("mods.nested", "un nivel de carpeta de más"),
+    ("mods.nested_explain", "Los archivos del mod están dentro de una carpeta {folder} de más, así que Cobalt no los encontrará"),
+    ("mods.fix", "Arreglar"),
+    ("mods.flattened", "Se sacaron los archivos de {name} de la carpeta de más"),
+    ("mods.flatten_failed", "No se pudo arreglar {name}: {error}"),
+    ("mods.open_folder", "Abrir carpeta"),
+    ("mods.gone", "{name} ya no está, puede que se haya movido o borrado"),
+    ("mods.open_failed", "No se pudo abrir la carpeta de {name}: {error}"),
+    ("mods.delete", "Borrar"),
+    ("mods.confirm_delete", "¿Borrar {name}{details}? No se puede deshacer."),
+    ("mods.delete_details", " ({size}, {count} archivos)"),
+    ("mods.deleted", "Se borró {name}"),
+    ("mods.delete_failed", "No se pudo borrar {name}: {error}"),
+    ("mods.not_a_zip", "{path} no es un archivo zip"),
+    ("mods.read_zip_failed", "No se pudo leer ese zip: {error}"),
+    ("mods.suspicious", "{name} no parece un mod de Engage. {problem}"),
+    ("mods.install_anyway", "Instalar de todos modos"),
+    ("mods.already_installed", "Ya hay un mod llamado {name} instalado."),
+    ("mods.replace", "Reemplazarlo"),
+    ("mods.install_as", "Instalar como {name}"),
+    ("mods.installing", "Instalando {name} ({done}/{total} archivos)"),
+    ("mods.installed", "Se instalaron {count} archivos en {path}"),
+    ("mods.install_failed", "No se pudo instalar el mod: {error}"),
+    ("mods.stray_found", "Se encontró {file} directamente en {location}. "),
+    ("mods.stray_explain", "Cobalt solo carga archivos del juego desde dentro de una carpeta de mod con nombre, como engage/mods/MiMod/{destination}."),
+    ("mods.move_into", "Mover a engage/mods/"),
+    ("mods.move_strays", "Moverlos"),
+    ("mods.strays_moved", "Se movieron los archivos sueltos a engage/mods/{name}"),
+    ("mods.move_strays_failed", "No se pudieron mover los archivos sueltos: {error}"),
+    ("mods.conflicts_failed", "No se pudieron buscar conflictos: {error}"),
+    ("mods.report_failed", "No se pudo crear el informe de mods: {error}"),
+    ("mods.save_report_title", "Guardar el informe de mods"),
+    ("mods.text_file", "Archivo de texto"),
+    ("mods.report_saved", "Informe de mods guardado en {path}"),
+    ("mods.save_report_failed", "No se pudo guardar el informe de mods: {error}"),
+    ("mods.back_up_title", "Guardar una copia de seguridad de tus mods"),
+    ("mods.zip_file", "Archivo zip"),
+    ("mods.backing_up", "Haciendo copia de los mods ({done}/{total} archivos)"),
+    ("mods.backed_up", "Se copiaron {count} archivos ({size}) en {path}"),
+    ("mods.skipped_links", ". Enlaces omitidos: {names}"),
+    ("mods.back_up_failed", "No se pudo hacer la copia de los mods: {error}"),
+    ("mods.pick_backup_title", "Elige una copia de mods para restaurar"),
+    ("mods.read_backup_failed", "No se pudo leer esa copia: {error}"),
+    ("copy_mods.to", "Copiar a"),
+    ("copy_mods.skip", "Ya está: omitir"),
+    ("copy_mods.replace", "Ya está: reemplazarlo"),
+    ("copy_mods.copying", "Copiando mods ({done}/{total})"),
+    ("copy_mods.copied", "Se copiaron {count} mods a {destination}"),
+    ("copy_mods.skipped", ". Omitidos (ya estaban): {names}"),
+    ("copy_mods.failed", "La copia de mods falló: {error}"),
+    ("restore_mods.contents", "Mods en {path}:"),
+    ("restore_mods.conflict_skip", "Ya instalado: omitir"),
+    ("restore_mods.conflict_replace", "Ya instalado: reemplazarlo"),
+    ("restore_mods.conflict_rename", "Ya instalado: restaurar con otro nombre"),
+    ("restore_mods.restore", "Restaurar"),
+    ("restore_mods.skip", "Omitir"),
+    ("restore_mods.restoring", "Restaurando mods ({done}/{total})"),
+    ("restore_mods.restored", "Se restauraron {count} mods"),
+    ("restore_mods.failed", "No se pudieron restaurar los mods: {error}"),
+    ("plugins.title", "Plugins"),
+    ("plugins.install", "Instalar plugin"),
+    ("plugins.drop_here", "Suelta aquí archivos .nro para instalarlos."),
+    ("plugins.looking", "Buscando plugins…"),
+    ("plugins.read_failed", "No se pudo leer la carpeta de plugins: {error}"),
+    ("plugins.none", "No hay plugins instalados."),
+    ("plugins.part_of_cobalt", "parte de Cobalt"),
+    ("plugins.remove", "Quitar"),
+    ("plugins.removed", "Se quitó {name}"),
+    ("plugins.remove_failed", "No se pudo quitar {name}: {error}"),
+    ("plugins.skipped_others", "Solo se pueden instalar archivos .nro como plugins, se omitieron {count} archivos más"),
+    ("plugins.already_installed", "Ya instalados: {names}"),
+    ("plugins.replace", "Reemplazarlos"),
+    ("plugins.only_new", "Copiar solo los nuevos"),
+    ("plugins.none_copied", "No se copió ningún plugin"),
+    ("plugins.copied", "Se copió {names} en {path}"),
+    ("plugins.install_failed", "No se pudieron instalar los plugins: {error}"),
+    ("installs.intro", "Cobalt está instalado en más de un sitio. Solo lo necesita el que usas para jugar."),
+    ("installs.selected", "seleccionado"),
+    ("installs.unreachable", "no accesible ahora mismo"),
+    ("installs.uninstall", "Desinstalar de aquí"),
+    ("installs.removed", "Se quitó Cobalt de {path}: {count} archivos ({size})"),
+    ("check.title", "Solución de problemas"),
+    ("check.intro", "¿Cobalt no carga? Ejecuta las comprobaciones y arregla lo que encuentren, o copia los resultados al pedir ayuda."),
+    ("check.run", "Ejecutar comprobaciones"),
+    ("check.running", "Comprobando la conexión con GitHub..."),
+    ("check.copy", "Copiar resultados"),
+    ("check.copied", "Resultados copiados"),
+    ("check.fix_clean_up", "Eliminarlo"),
+    ("check.fix_repair", "Reparar Cobalt"),
+    ("check.fix_select_path", "Elegir la carpeta"),
+    ("check.cleaned_up", "Se eliminó el archivo dañado"),
+    ("check.clean_up_failed", "No se pudo eliminar el archivo: {error}"),
+    ("check.detection", "Emulador"),
+    ("check.detection_found", "{emulator} encontrado, se instala en su carpeta {path}."),
+    ("check.detection_also", "También encontrados: {others}."),
+    ("check.detection_missing", "No se encontró {emulator} en {path}. Ábrelo una vez, o elige su carpeta SD a mano."),
+    ("check.detection_no_path", "No se pudo averiguar dónde guarda {emulator} sus archivos en este sistema."),
+    ("check.sd_none", "Todavía no has elegido la carpeta de la tarjeta SD."),
+    ("check.sd_missing", "La carpeta de la tarjeta SD {path} no está. ¿Está la tarjeta insertada?"),
+    ("check.sd_picked", "Se instala en la carpeta de la tarjeta SD {path}."),
+    ("check.game", "Fire Emblem Engage"),
+    ("check.game_found", "Engage ya se ha abierto antes en {emulator}."),
+    ("check.game_missing", "No hay señales de que Engage se haya abierto en {emulator}. Abre el juego una vez sin mods para asegurarte de que funciona."),
+    ("check.leftovers", "Archivos dañados antiguos"),
+    ("check.leftovers_none", "No hay ningún subsdk9 dañado de una instalación antigua."),
+    ("check.leftovers_found", "Todavía hay un subsdk9 dañado de una instalación antigua en {path}, impide que Cobalt cargue."),
+    ("check.mods_folder", "Carpeta de mods"),
+    ("check.mods_ok", "{path} existe y se puede escribir en ella."),
+    ("check.mods_missing", "{path} todavía no existe. Instalar Cobalt la crea."),
+    ("check.mods_read_only", "No se puede escribir en {path}: {error}"),
+    ("check.free_space", "Espacio libre"),
+    ("check.space_ok", "{free} libres."),
+    ("check.space_low", "Solo quedan {free} libres, deja al menos {needed} para instalar y hacer copias de seguridad."),
+    ("check.space_unknown", "No se pudo saber cuánto espacio queda libre."),
+    ("check.cobalt_files", "Archivos de Cobalt"),
+    ("check.cobalt_ok", "Están todos y no han cambiado desde que se instalaron."),
+    ("check.cobalt_unverified", "Están todos. Esta instalación es demasiado antigua para tener sumas de comprobación, así que no se compararon."),
+    ("check.cobalt_missing", "Cobalt todavía no está instalado aquí."),
+    ("check.cobalt_incomplete", "Faltan algunos archivos de Cobalt desde que se instalaron."),
+    ("check.cobalt_damaged", "{count} archivos de Cobalt cambiaron desde que se instalaron: {files}"),
+    ("check.github", "Conexión con GitHub"),
+    ("check.github_ok", "GitHub responde, la versión más reciente de Cobalt es {version}."),
+    ("check.github_no_release", "GitHub responde, pero no muestra ninguna versión de Cobalt."),
+    ("toast.update_available", "Ya está disponible Cobalt {version}"),
+    ("toast.removed_subsdk9", "Se eliminó un archivo dañado que una instalación antigua dejó en {emulator}"),
+    ("toast.removed_stale", "Se eliminaron {count} archivos que la nueva versión ya no tiene"),
+    ("toast.diagnostics_saved", "Diagnóstico guardado"),
+    ("toast.cleaned_up", "Se limpiaron {count} archivos sobrantes, se liberaron {size}"),
+    ("toast.nothing_to_clean", "No hay nada que limpiar"),
+    ("hint.installation_type", "Empieza aquí: elige cómo juegas a Fire Emblem Engage."),
+    ("hint.emulator", "Aquí es donde irá Cobalt. Si no se encontró tu emulador, comprueba que lo has abierto al menos una vez."),
+    ("hint.sd_card", "Ahora elige la carpeta de tu tarjeta SD, Cobalt se instala directamente en ella."),
+    ("hint.next", "Entendido"),
+    ("hint.skip", "Omitir consejos"),
+    ("help.title", "¿Qué es esto?"),
+    ("help.installation_type", "Elige Ryujinx, Citron o Eden si juegas con ese emulador en este ordenador. Elige tarjeta SD si juegas en una consola Switch modificada."),
+    ("help.sd_card", "Elige la raíz de la tarjeta SD: la carpeta que contiene las carpetas Nintendo y atmosphere, no una carpeta dentro de ellas."),
+    ("help.more", "Más información en la documentación de Cobalt"),
+    ("android.picker_failed", "No se pudo abrir el selector de carpetas: {error}"),
+    ("android.wrong_folder", "Esa no es la carpeta de Eden. Vuelve a pulsar el botón y elige la carpeta de Eden."),
+    ("android.downloading", "Descargando la versión"),
+    ("android.installing", "Instalando en Eden"),
+    ("android.write_failed", "La instalación falló: no se pudo escribir en la carpeta de Eden"),
+    ("android.intro", "Esto instala Cobalt en el emulador Eden."),
+    ("android.access_granted", "Acceso a la carpeta de Eden concedido."),
+    ("android.grant_first", "Primero, concede acceso a la carpeta de Eden."),
+    ("android.grant_steps", "En el selector de archivos, abre el menú (arriba a la izquierda), elige Eden y pulsa \"Usar esta carpeta\"."),
+    ("android.grant", "Conceder acceso a la carpeta de Eden"),
+];
@@ -0,0 +1,383 @@
+// The steps of an install that touch the disk, each on a blocking thread so the window
+// keeps repainting: extracting the release, rolling back a cancelled install, backing up
+// the previous one, and the small stuff around them. Even creating a folder can stall
+// for seconds on an SD card that's gone to sleep, so none of it runs on the UI thread.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dioxus::logger::tracing;
+
+use crate::cancel::CancelToken;
+use crate::error::InstallerError;
+use crate::i18n::Message;
+use crate::manifest::Manifest;
+use crate::paths::cache_dir;
+use crate::{backup, disk, extract, marker};
+
+// A downloaded release zip, waiting in the cache folder to be extracted. Deleted when
+// dropped, whether the install got that far or not.
+pub struct ArchiveFile(PathBuf);
+
+impl ArchiveFile {
+    // A fresh file name in `dir`, nothing is created until the download writes it.
+    pub fn in_dir(dir: &Path) -> Result<Self, InstallerError> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        std::fs::create_dir_all(dir).map_err(|e| InstallerError::filesystem(dir, e))?;
+        let name = format!("release_{}_{}.zip", std::process::id(), COUNT.fetch_add(1, Ordering::Relaxed));
+        Ok(Self(dir.join(name)))
+    }
+
+    // Under the data directory's cache folder.
+    pub fn new() -> Result<Self, InstallerError> {
+        let dir = cache_dir().ok_or_else(|| InstallerError::Environment { problem: "no data directory on this system".to_string() })?;
+        Self::in_dir(&dir.join("downloads"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ArchiveFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.0) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Couldn't delete the downloaded archive {}: {e}", self.0.display());
+            }
+        }
+    }
+}
+
+// What extract_release reports back while it runs.
+pub enum ExtractUpdate {
+    Progress(usize, usize),
+    // The archive name of the file being extracted.
+    Entry(String),
+}
+
+// How extract_release writes, from the settings and the target.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WriteOptions {
+    // Keep existing files under extract::PROTECTED_PATHS.
+    pub preserve_config: bool,
+    // See ExtractOptions.
+    pub workers: usize,
+    pub buffer_size: usize,
+}
+
+// The write buffer for FAT and exFAT targets. SD cards erase in blocks of a few MB, many
+// small writes into one block cost far more than one big one.
+const REMOVABLE_BUFFER: usize = 1024 * 1024;
+
+// A new entry name at most this often, a pack of tiny files would otherwise re-render the
+// window for every one of them.
+const ENTRY_UPDATE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+// What a target is treated as when its filesystem can't be told: a removable drive, the
+// cautious choice.
+fn removable(filesystem: Option<&str>) -> bool {
+    filesystem.is_none_or(disk::is_fat)
+}
+
+// Whether an install onto `target` is flushed out to the drive before it counts as done.
+// SD cards always are, and any other FAT or exFAT drive, that's what USB sticks and card
+// readers come as.
+pub fn needs_flush(target: &Path, sd_card: bool) -> bool {
+    sd_card || removable(disk::existing_ancestor(target).and_then(disk::filesystem_type).as_deref())
+}
+
+// Flush everything the install wrote or deleted under `target` out to the drive, see
+// disk::flush_to_disk.
+pub async fn flush_install(
+    target: PathBuf,
+    touched: Vec<String>,
+    cancel: CancelToken,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<(), InstallerError> {
+    run_blocking_with_progress(
+        move |progress| {
+            disk::flush_to_disk(&target, &touched, &cancel, progress).map_err(|e| InstallerError::from_io(&target, e))
+        },
+        on_progress,
+    )
+    .await
+}
+
+impl WriteOptions {
+    // How to write onto `target`. `workers` and `buffer_kb` are the user's choices, 0 for
+    // automatic. Automatic suits FAT and exFAT (SD cards, USB sticks) with one file at a
+    // time, since parallel writes make the card jump between files, and big writes. Other
+    // drives get a few files at a time and the usual buffer.
+    pub fn for_target(target: &Path, preserve_config: bool, workers: u32, buffer_kb: u32) -> Self {
+        let filesystem = disk::existing_ancestor(target).and_then(disk::filesystem_type);
+        let removable = removable(filesystem.as_deref());
+        let options = Self {
+            preserve_config,
+            workers: match workers {
+                0 if removable => 1,
+                0 => extract::MAX_WORKERS,
+                n => (n as usize).min(extract::MAX_WORKERS),
+            },
+            buffer_size: match buffer_kb {
+                0 if removable => REMOVABLE_BUFFER,
+                0 => extract::DEFAULT_BUFFER,
+                kb => kb as usize * 1024,
+            },
+        };
+        tracing::debug!(
+            "Writing to {} ({}) with {} workers and a {} buffer",
+            target.display(),
+            filesystem.as_deref().unwrap_or("unknown filesystem"),
+            options.workers,
+            disk::format_bytes(options.buffer_size as u64)
+        );
+        options
+    }
+}
+
+// Extract on a blocking thread so the window (and its Cancel button) stays live. The
+// archive is read from disk an entry at a time, never all at once. The marker comes back
+// with the result, still unfinished. `origin` is where the archive came from, for the
+// error if it turns out to be broken.
+pub async fn extract_release(
+    archive: PathBuf,
+    origin: String,
+    dest: PathBuf,
+    marker: Option<marker::Marker>,
+    cancel: CancelToken,
+    write: WriteOptions,
+    mut on_update: impl FnMut(ExtractUpdate),
+) -> (Result<extract::Extracted, InstallerError>, Option<marker::Marker>) {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let task = tokio::task::spawn_blocking(move || {
+        let result = {
+            let record = |name: &str| {
+                if let Some(marker) = &marker {
+                    marker.record(name);
+                }
+            };
+            let last_entry = std::cell::Cell::new(None::<std::time::Instant>);
+            let entry = |name: &str| {
+                if last_entry.get().is_none_or(|last| last.elapsed() >= ENTRY_UPDATE_INTERVAL) {
+                    last_entry.set(Some(std::time::Instant::now()));
+                    let _ = tx.send(ExtractUpdate::Entry(name.to_string()));
+                }
+            };
+            let options = extract::ExtractOptions {
+                preserve_existing: if write.preserve_config { extract::PROTECTED_PATHS } else { &[] },
+                on_written: Some(&record),
+                on_entry: Some(&entry),
+                cancel: Some(cancel.flag()),
+                workers: write.workers,
+                buffer_size: write.buffer_size,
+                ..Default::default()
+            };
+            std::fs::File::open(&archive)
+                .map_err(|e| InstallerError::filesystem(&archive, e))
+                .and_then(|file| {
+                    extract::extract_archive(std::io::BufReader::new(file), &dest, options, |done, total| {
+                        let _ = tx.send(ExtractUpdate::Progress(done, total));
+                    })
+                    .map_err(|e| InstallerError::from_extract(&origin, &dest, e))
+                })
+        };
+        (result, marker)
+    });
+    while let Some(update) = rx.recv().await {
+        on_update(update);
+    }
+    task.await.expect("Blocking task panicked")
+}
+
+// Put the target back the way it was before a cancelled install, as far as possible.
+// Returns what to tell the user.
+pub async fn roll_back_install(target: PathBuf, previous: Option<Manifest>, backup: Option<PathBuf>) -> std::io::Result<String> {
+    tokio::task::spawn_blocking(move || {
+        let Some(leftover) = marker::find(&target) else {
+            return Ok("Installation cancelled, but there was no record of what it wrote to clean up".to_string());
+        };
+        let previous_files = previous.map(|m| m.files).unwrap_or_default();
+        let (removed, overwritten) = marker::roll_back(&target, &leftover, &previous_files)?;
+        let message = match (overwritten.len(), backup) {
+            (0, _) => format!("Installation cancelled, removed the {removed} files it had written"),
+            (n, Some(backup)) => {
+                backup::restore(&backup, &target, None, |_, _| {})?;
+                format!("Installation cancelled, removed {removed} new files and restored {n} from the backup")
+            }
+            (n, None) => format!(
+                "Installation cancelled, removed {removed} new files. {n} files from the previous install were already replaced and there's no backup to restore them from, run Install again to finish or Restore backup if you have an older one"
+            ),
+        };
+        marker::discard(&target)?;
+        Ok(message)
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+pub async fn create_mods_directory(sdcard_path: PathBuf) -> Result<(), InstallerError> {
+    run_blocking(move || {
+        let mods_path = sdcard_path.join("engage/mods");
+        if mods_path.exists() {
+            tracing::info!("Mods directory already exists");
+            return Ok(());
+        }
+        std::fs::create_dir_all(&mods_path).map_err(|e| InstallerError::filesystem(mods_path, e))
+    })
+    .await
+}
+
+// Run one blocking job on tokio's blocking pool and wait for it without holding up the
+// window.
+pub async fn run_blocking<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> T {
+    tokio::task::spawn_blocking(work).await.expect("Blocking task panicked")
+}
+
+// Run blocking filesystem work on tokio's blocking pool, handing each (done, total)
+// progress report to `on_progress` back on the UI side as it arrives.
+pub async fn run_blocking_with_progress<T: Send + 'static>(
+    work: impl FnOnce(&mut dyn FnMut(usize, usize)) -> T + Send + 'static,
+    mut on_progress: impl FnMut(usize, usize),
+) -> T {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let task = tokio::task::spawn_blocking(move || {
+        work(&mut |done, total| {
+            let _ = tx.send((done, total));
+        })
+    });
+    while let Some((done, total)) = rx.recv().await {
+        on_progress(done, total);
+    }
+    task.await.expect("Blocking task panicked")
+}
+
+// Back up what `previous` installed, reporting each (done, total) to `on_progress`. The zip is
+// written on a blocking thread so the window keeps repainting. On failure, or when the
+// data directory's drive is too full, returns the warning to show instead. Cancelled
+// through `cancel`, it leaves no zip behind and the caller finds `cancel` set.
+pub async fn backup_install(previous: Manifest, cancel: CancelToken, on_progress: impl FnMut(usize, usize)) -> Result<PathBuf, Message> {
+    let required = backup::required_space(&previous);
+    let available = backup::backups_dir()
+        .as_deref()
+        .and_then(disk::existing_ancestor)
+        .and_then(disk::free_space);
+    if available.is_some_and(|available| available < required) {
+        tracing::warn!("Not enough space for a backup ({required} bytes needed), skipping it");
+        return Err(Message::new("warning.backup_no_space", []));
+    }
+
+    let result = run_blocking_with_progress(
+        move |progress| backup::create(&previous, &cancel, progress),
+        on_progress,
+    )
+    .await;
+
+    match result {
+        Ok(path) => {
+            tracing::info!("Backed up the previous install to {}", path.display());
+            Ok(path)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+            tracing::info!("Backup cancelled");
+            Err(Message::new("warning.backup_cancelled", []))
+        }
+        Err(e) => {
+            tracing::error!("Backup failed: {e}");
+            Err(Message::new("warning.backup_failed", [("error", e.to_string())]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    // The longest the UI thread may go without getting a turn while an extraction runs.
+    const MAX_STALL: Duration = Duration::from_millis(250);
+
+    fn archive_of(path: &Path, megabytes: usize) {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        let chunk = vec![0x5a; 1024 * 1024];
+        for file in 0..megabytes.div_ceil(16) {
+            zip.start_file(format!("romfs/data/blob_{file}.bin"), options).unwrap();
+            for _ in 0..16.min(megabytes - file * 16) {
+                zip.write_all(&chunk).unwrap();
+            }
+        }
+        zip.finish().unwrap();
+    }
+
+    // Extracts on a single-threaded runtime, like the window's, with a task next to it
+    // standing in for the UI: if the extraction ran on that thread, the task would be
+    // starved until it finished.
+    fn assert_stays_responsive(name: &str, megabytes: usize) {
+        let scratch = crate::Scratch::new(name);
+        let archive = ArchiveFile::in_dir(&scratch.0.join("downloads")).unwrap();
+        archive_of(archive.path(), megabytes);
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let (longest_stall, updates) = runtime.block_on(async {
+            let done = Arc::new(AtomicBool::new(false));
+            let ui = tokio::spawn({
+                let done = done.clone();
+                async move {
+                    let mut last = Instant::now();
+                    let mut longest = Duration::ZERO;
+                    while !done.load(Ordering::Relaxed) {
+                        tokio::task::yield_now().await;
+                        longest = longest.max(last.elapsed());
+                        last = Instant::now();
+                    }
+                    longest
+                }
+            });
+            let mut updates = 0;
+            let (result, _) = extract_release(
+                archive.path().to_path_buf(),
+                "test.zip".to_string(),
+                scratch.0.join("sd"),
+                None,
+                CancelToken::new(),
+                WriteOptions::default(),
+                |_| updates += 1,
+            )
+            .await;
+            result.expect("Extraction failed");
+            done.store(true, Ordering::Relaxed);
+            (ui.await.unwrap(), updates)
+        });
+        assert!(updates > 0, "no progress came back");
+        assert!(longest_stall < MAX_STALL, "the UI thread was stuck for {longest_stall:?}");
+    }
+
+    #[test]
+    fn chosen_write_options_are_kept() {
+        let scratch = crate::Scratch::new("write_options");
+        let options = WriteOptions::for_target(&scratch.0, true, 2, 256);
+        assert!(options.preserve_config);
+        assert_eq!((options.workers, options.buffer_size), (2, 256 * 1024));
+        // No more workers than extract_archive takes.
+        assert_eq!(WriteOptions::for_target(&scratch.0, false, 64, 0).workers, extract::MAX_WORKERS);
+        let automatic = WriteOptions::for_target(&scratch.0, false, 0, 0);
+        assert!(automatic.workers >= 1 && automatic.buffer_size >= extract::DEFAULT_BUFFER);
+    }
+
+    #[test]
+    fn extraction_leaves_the_ui_thread_free() {
+        assert_stays_responsive("responsive_small", 8);
+    }
+
+    // A release-sized extraction and then some. Writes a few hundred MB to the temp
+    // directory, so it only runs when asked: cargo test -- --ignored
+    #[test]
+    #[ignore]
+    fn large_extraction_leaves_the_ui_thread_free() {
+        assert_stays_responsive("responsive_large", 400);
+    }
+}
@@ -0,0 +1,266 @@
+// Where an install is, start to finish. The status line, the progress bar and which
+// buttons are enabled are all worked out from this, and the install pipeline only ever
+// moves it from one state to the next.
+
+use crate::i18n::{self, Language};
+
+// What the progress bar shows for a running step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Progress {
+    // Something's happening but there's no telling how far along it is.
+    Indeterminate,
+    Fraction(f32),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum InstallState {
+    #[default]
+    Idle,
+    // Finding the release and tidying the target before the download.
+    Preparing,
+    // Putting the target back after a cancelled install.
+    CleaningUp,
+    // The download has started but no bytes have arrived yet.
+    Connecting,
+    // `progress` is the whole install's, from 0 to 1, not just this step's.
+    Downloading { progress: f32 },
+    BackingUp { progress: f32 },
+    Extracting { progress: f32 },
+    Verifying,
+    // Waiting for a removable drive to finish writing, see install::needs_flush.
+    Flushing { progress: f32 },
+    Done { version: String },
+    // `progress` is where the bar stopped, it stays there in the error color.
+    Failed { error: String, progress: f32 },
+    Cancelled,
+}
+
+impl InstallState {
+    // Whether an install is underway, so the install buttons stay disabled and closing
+    // the window asks first.
+    pub fn is_running(&self) -> bool {
+        matches!(
+            self,
+            InstallState::Preparing
+                | InstallState::CleaningUp
+                | InstallState::Connecting
+                | InstallState::Downloading { .. }
+                | InstallState::BackingUp { .. }
+                | InstallState::Extracting { .. }
+                | InstallState::Verifying
+                | InstallState::Flushing { .. }
+        )
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self, InstallState::Failed { .. })
+    }
+
+    // Every running step can be cancelled except the cleanup after a cancel.
+    pub fn can_cancel(&self) -> bool {
+        self.is_running() && *self != InstallState::CleaningUp
+    }
+
+    // What the progress bar shows, None to hide it. Every running state shows something,
+    // so the window never looks stuck while an install is underway.
+    pub fn progress(&self) -> Option<Progress> {
+        match self {
+            InstallState::Downloading { progress }
+            | InstallState::BackingUp { progress }
+            | InstallState::Extracting { progress }
+            | InstallState::Flushing { progress }
+            | InstallState::Failed { progress, .. } => Some(Progress::Fraction(*progress)),
+            InstallState::Preparing
+            | InstallState::CleaningUp
+            | InstallState::Connecting
+            | InstallState::Verifying => Some(Progress::Indeterminate),
+            InstallState::Idle | InstallState::Done { .. } | InstallState::Cancelled => None,
+        }
+    }
+
+    // The class the status line and the action zone get, so a failed run never looks like
+    // a finished one in a screenshot.
+    pub fn style_class(&self) -> &'static str {
+        match self {
+            InstallState::Idle => "state_idle",
+            InstallState::Preparing
+            | InstallState::CleaningUp
+            | InstallState::Connecting
+            | InstallState::Downloading { .. }
+            | InstallState::BackingUp { .. }
+            | InstallState::Extracting { .. }
+            | InstallState::Verifying
+            | InstallState::Flushing { .. } => "state_running",
+            InstallState::Done { .. } => "state_done",
+            InstallState::Failed { .. } => "state_failed",
+            InstallState::Cancelled => "state_cancelled",
+        }
+    }
+
+    pub fn status_text(&self) -> String {
+        self.status_text_in(i18n::LANGUAGE())
+    }
+
+    pub fn status_text_in(&self, language: Language) -> String {
+        let with_percent = |phase: &str, progress: f32| {
+            let percent = (progress.clamp(0.0, 1.0) * 100.0).round();
+            i18n::tr_args_in(
+                language,
+                "status.progress",
+                &[("phase", i18n::tr_in(language, phase)), ("percent", percent.to_string())],
+            )
+        };
+        match self {
+            InstallState::Idle => i18n::tr_in(language, "status.waiting"),
+            InstallState::Preparing => i18n::tr_in(language, "status.preparing"),
+            InstallState::CleaningUp => i18n::tr_in(language, "status.cancelling_cleanup"),
+            InstallState::Connecting => i18n::tr_in(language, "status.connecting"),
+            InstallState::Downloading { progress } => with_percent("progress.download", *progress),
+            InstallState::BackingUp { progress } => with_percent("progress.backup", *progress),
+            InstallState::Extracting { progress } => with_percent("progress.extract", *progress),
+            InstallState::Verifying => i18n::tr_in(language, "progress.finalize"),
+            InstallState::Flushing { progress } => with_percent("progress.flush", *progress),
+            InstallState::Done { version } => {
+                i18n::tr_args_in(language, "status.done", &[("version", version.clone())])
+            }
+            InstallState::Failed { error, .. } => {
+                i18n::tr_args_in(language, "status.install_failed", &[("error", error.clone())])
+            }
+            InstallState::Cancelled => i18n::tr_in(language, "status.cancelled"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn every_state() -> Vec<InstallState> {
+        vec![
+            InstallState::Idle,
+            InstallState::Preparing,
+            InstallState::CleaningUp,
+            InstallState::Connecting,
+            InstallState::Downloading { progress: 0.25 },
+            InstallState::BackingUp { progress: 0.6 },
+            InstallState::Extracting { progress: 0.641 },
+            InstallState::Verifying,
+            InstallState::Flushing { progress: 0.97 },
+            InstallState::Done { version: "1.4.2".to_string() },
+            InstallState::Failed { error: "disk full".to_string(), progress: 0.5 },
+            InstallState::Cancelled,
+        ]
+    }
+
+    #[test]
+    fn english_status_text() {
+        let texts: Vec<String> = every_state().iter().map(|s| s.status_text_in(Language::English)).collect();
+        assert_eq!(
+            texts,
+            [
+                "Waiting for you",
+                "Getting ready",
+                "Cancelling, cleaning up",
+                "Waiting for the download to start",
+                "Downloading release (25%)",
+                "Backing up the current install (60%)",
+                "Extracting files (64%)",
+                "Checking the install",
+                "Finalizing, writing everything to the drive (97%)",
+                "Cobalt 1.4.2 installed",
+                "Installation failed: disk full",
+                "Installation cancelled",
+            ]
+        );
+    }
+
+    #[test]
+    fn every_state_is_translated() {
+        for language in i18n::LANGUAGES {
+            for state in every_state() {
+                let text = state.status_text_in(language);
+                assert!(!text.contains('{'), "{state:?} left a placeholder in {language:?}: {text}");
+                assert!(!text.contains("status.") && !text.contains("progress."), "{state:?} has no text in {language:?}");
+            }
+        }
+        for key in [
+            "status.waiting",
+            "status.preparing",
+            "status.cancelling_cleanup",
+            "status.connecting",
+            "status.progress",
+            "progress.download",
+            "progress.backup",
+            "progress.extract",
+            "progress.finalize",
+            "progress.flush",
+            "status.done",
+            "status.install_failed",
+            "status.cancelled",
+        ] {
+            for language in i18n::LANGUAGES {
+                assert!(i18n::has(language, key), "{language:?} is missing {key}");
+            }
+        }
+    }
+
+    #[test]
+    fn percent_is_clamped_and_rounded() {
+        let text = |progress| InstallState::Extracting { progress }.status_text_in(Language::English);
+        assert_eq!(text(-0.5), "Extracting files (0%)");
+        assert_eq!(text(0.996), "Extracting files (100%)");
+        assert_eq!(text(2.0), "Extracting files (100%)");
+    }
+
+    #[test]
+    fn running_states_disable_installs() {
+        let running: Vec<bool> = every_state().iter().map(|s| s.is_running()).collect();
+        assert_eq!(running, [false, true, true, true, true, true, true, true, true, false, false, false]);
+    }
+
+    #[test]
+    fn a_failed_install_keeps_its_progress() {
+        let failed = InstallState::Failed { error: "x".to_string(), progress: 0.4 };
+        assert!(failed.is_failed());
+        assert_eq!(failed.progress(), Some(Progress::Fraction(0.4)));
+        assert_eq!(InstallState::Done { version: "1".to_string() }.progress(), None);
+    }
+
+    #[test]
+    fn every_state_has_a_style() {
+        let classes: Vec<&str> = every_state().iter().map(|s| s.style_class()).collect();
+        assert_eq!(
+            classes,
+            [
+                "state_idle",
+                "state_running",
+                "state_running",
+                "state_running",
+                "state_running",
+                "state_running",
+                "state_running",
+                "state_running",
+                "state_running",
+                "state_done",
+                "state_failed",
+                "state_cancelled",
+            ]
+        );
+    }
+
+    #[test]
+    fn only_running_states_look_running() {
+        for state in every_state() {
+            assert_eq!(state.style_class() == "state_running", state.is_running(), "{state:?}");
+        }
+    }
+
+    #[test]
+    fn running_states_always_show_progress() {
+        for state in every_state().into_iter().filter(|s| s.is_running()) {
+            assert!(state.progress().is_some(), "{state:?} shows no progress");
+        }
+        assert_eq!(InstallState::Connecting.progress(), Some(Progress::Indeterminate));
+        assert_eq!(InstallState::Verifying.progress(), Some(Progress::Indeterminate));
+    }
+}
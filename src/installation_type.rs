@@ -0,0 +1,107 @@
+// Where the user wants Cobalt: one of the emulators we know, or an SD card folder they
+// pick themselves.
+//
+// Stored by name, the same strings the select used before this was an enum, so existing
+// settings carry over. A name we don't know (an option renamed or dropped in a later
+// version) falls back to the default instead of failing to load.
+
+use dioxus::logger::tracing;
+use serde::{Deserialize, Serialize};
+
+use crate::paths::{get_emulator, Emulator};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum InstallationType {
+    #[default]
+    Ryujinx,
+    Citron,
+    Eden,
+    SdCard,
+}
+
+// In the order the select shows them.
+pub const INSTALLATION_TYPES: [InstallationType; 4] =
+    [InstallationType::Ryujinx, InstallationType::Citron, InstallationType::Eden, InstallationType::SdCard];
+
+impl InstallationType {
+    pub fn name(self) -> &'static str {
+        match self {
+            InstallationType::Ryujinx => "Ryujinx",
+            InstallationType::Citron => "Citron",
+            InstallationType::Eden => "Eden",
+            InstallationType::SdCard => "SD Card",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<InstallationType> {
+        INSTALLATION_TYPES.into_iter().find(|t| t.name() == name)
+    }
+
+    // None for SD cards.
+    pub fn emulator(self) -> Option<&'static Emulator> {
+        get_emulator(self.name())
+    }
+
+    pub fn is_sd_card(self) -> bool {
+        self == InstallationType::SdCard
+    }
+}
+
+impl From<String> for InstallationType {
+    fn from(name: String) -> Self {
+        InstallationType::from_name(&name).unwrap_or_else(|| {
+            let fallback = InstallationType::default();
+            tracing::warn!("Unknown installation type {name:?} in the settings, using {}", fallback.name());
+            fallback
+        })
+    }
+}
+
+impl From<InstallationType> for String {
+    fn from(installation_type: InstallationType) -> Self {
+        installation_type.name().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::{resolve_target, TargetError};
+
+    const GARBAGE: &[&str] = &["", "ryujinx", "Yuzu", "SD card", "SdCard", "\u{0}", "Eden ", "🐉"];
+
+    #[test]
+    fn names_round_trip() {
+        for installation_type in INSTALLATION_TYPES {
+            assert_eq!(InstallationType::from_name(installation_type.name()), Some(installation_type));
+            let json = serde_json::to_string(&installation_type).unwrap();
+            assert_eq!(serde_json::from_str::<InstallationType>(&json).unwrap(), installation_type);
+        }
+    }
+
+    // A stored value from another version, or a damaged settings file, must still give
+    // something the rest of the installer can resolve rather than take the app down.
+    #[test]
+    fn unknown_names_fall_back_to_the_default() {
+        for name in GARBAGE {
+            assert_eq!(InstallationType::from_name(name), None, "{name:?}");
+            let installation_type = InstallationType::from(name.to_string());
+            assert_eq!(installation_type, InstallationType::default());
+            let json = serde_json::to_string(name).unwrap();
+            assert_eq!(serde_json::from_str::<InstallationType>(&json).unwrap(), InstallationType::default());
+            match resolve_target(installation_type, "") {
+                Ok(_) | Err(TargetError::EmulatorNotFound(_) | TargetError::NoDataPath(_)) => {}
+                Err(e) => panic!("{name:?} resolved to {e:?}"),
+            }
+        }
+    }
+
+    // Not even a string: Setting::get sees the error and uses the default.
+    #[test]
+    fn values_of_the_wrong_type_fail_to_load() {
+        for json in ["42", "null", "[]", "{\"name\": \"Ryujinx\"}"] {
+            assert!(serde_json::from_str::<InstallationType>(json).is_err(), "{json}");
+        }
+    }
+}
@@ -0,0 +1,803 @@
+// An install as an ordered list of named steps: clean up, download, back up, extract,
+// verify, tidy up, flush. Each step works on the shared `Install` (what to install where,
+// and what the steps before it produced), reports its progress through a hook, and may
+// know how to undo itself. The UI builds an `Install`, runs `Installer::release()` over
+// it and shows each step's status as it goes; it never calls a stage directly.
+//
+// A step that fails stops the run and leaves everything as it is, so the run can be
+// picked up again from that step. A cancel (the token in `Install`, checked between steps
+// and by the steps themselves) rolls back what already ran, newest first. Once a step
+// that commits the install is done there's no going back though: the old release's files
+// are gone, so a cancel after that only stops the step it came in and keeps the install.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::time::Instant;
+
+use dioxus::logger::tracing::{self, Instrument};
+
+use crate::activity::Severity;
+use crate::cancel::CancelToken;
+use crate::error::InstallerError;
+use crate::extract::Extracted;
+use crate::i18n::Message;
+use crate::install::{self, ArchiveFile, ExtractUpdate, WriteOptions};
+use crate::manifest::Manifest;
+use crate::marker::Marker;
+use crate::paths::Emulator;
+use crate::progress::Phase;
+use crate::{cleanup, disk, download, layout, receipt, uninstall};
+
+// What a step hands back to whoever is running the installer, as it goes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StepEvent {
+    // How far through the step, 0 to 1.
+    Progress(f32),
+    // A line for the activity log.
+    Note(Severity, String),
+    // The file being written, None once there isn't one.
+    Entry(Option<String>),
+}
+
+pub type Report<'a> = &'a mut dyn FnMut(StepEvent);
+pub type StepFuture<'a> = Pin<Box<dyn Future<Output = Result<(), InstallerError>> + 'a>>;
+pub type StepFn = for<'a> fn(&'a mut Install, Report<'a>) -> StepFuture<'a>;
+
+pub struct InstallStep {
+    // For the log, the history and error messages, in English.
+    pub name: &'static str,
+    pub label_key: &'static str,
+    // Which part of the progress bar it fills.
+    pub phase: Phase,
+    // Whether it has anything to do for this install, a step that doesn't is skipped.
+    pub applies: fn(&Install) -> bool,
+    pub execute: StepFn,
+    // Undoes the step after a cancel, also when it was the one cancelled halfway.
+    pub roll_back: Option<StepFn>,
+    // Once it's done the install is kept, whatever gets cancelled after it.
+    pub commits: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StepStatus {
+    Pending,
+    Running,
+    Done,
+    Skipped,
+    Failed(String),
+    Cancelled,
+    RolledBack,
+}
+
+impl StepStatus {
+    pub fn class(&self) -> &'static str {
+        match self {
+            StepStatus::Pending => "pending",
+            StepStatus::Running => "running",
+            StepStatus::Done => "done",
+            StepStatus::Skipped => "skipped",
+            StepStatus::Failed(_) => "failed",
+            StepStatus::Cancelled => "cancelled",
+            StepStatus::RolledBack => "rolled_back",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InstallerEvent {
+    // Step `index` changed status.
+    Status(usize, StepStatus),
+    // From step `index` while it runs or rolls back.
+    Step(usize, StepEvent),
+    // A cancel came through and the finished steps are being undone.
+    RollingBack,
+}
+
+// Why a run stopped short.
+#[derive(Debug)]
+pub struct StepError {
+    // The step that failed or was cancelled, run_from it to try again.
+    pub step: usize,
+    pub name: &'static str,
+    pub error: InstallerError,
+    // Set when undoing a cancelled install failed too.
+    pub rollback: Option<InstallerError>,
+}
+
+// What to install where, and what the steps have done about it so far.
+pub struct Install {
+    pub target: PathBuf,
+    pub url: String,
+    pub version: Option<String>,
+    // The install being replaced, if there's a manifest for it.
+    pub previous: Option<Manifest>,
+    // Whose exefs folder to check for a broken subsdk9, for emulator targets.
+    pub emulator: Option<&'static Emulator>,
+    pub backup: bool,
+    // An update says so in the log when it can't tell which files the release dropped.
+    pub update: bool,
+    pub write: WriteOptions,
+    pub flush: bool,
+    pub cancel: CancelToken,
+    // Where the download goes, None for the cache folder.
+    pub downloads: Option<PathBuf>,
+
+    pub archive: Option<ArchiveFile>,
+    pub archive_size: u64,
+    pub backup_path: Option<PathBuf>,
+    pub marker: Option<Marker>,
+    pub extracted: Option<Extracted>,
+    // Files the previous install had and this release doesn't, now deleted.
+    pub removed: Vec<String>,
+    pub removed_subsdk9: bool,
+    // For the summary, things that went differently than asked but didn't stop the install.
+    pub warnings: Vec<Message>,
+}
+
+impl Install {
+    pub fn new(target: PathBuf, url: String) -> Self {
+        Self {
+            target,
+            url,
+            version: None,
+            previous: None,
+            emulator: None,
+            backup: false,
+            update: false,
+            write: WriteOptions::default(),
+            flush: false,
+            cancel: CancelToken::new(),
+            downloads: None,
+            archive: None,
+            archive_size: 0,
+            backup_path: None,
+            marker: None,
+            extracted: None,
+            removed: Vec::new(),
+            removed_subsdk9: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    // Record a finished install: the manifest for the next update, and the marker off the
+    // target. Only after every step is done, so nothing claims an install a cancel could
+    // still take back. Returns the manifest, None if there was nothing extracted.
+    pub async fn finish(&mut self) -> Option<Manifest> {
+        let extracted = self.extracted.as_ref()?;
+        let manifest = Manifest {
+            checksums: extracted.checksums.clone(),
+            ..Manifest::new(self.target.clone(), self.version.clone(), extracted.files.clone())
+        };
+        if let Err(e) = manifest.save() {
+            tracing::error!("Couldn't save the install manifest: {e}");
+        }
+        if let Some(marker) = self.marker.take() {
+            if let Err(e) = marker.finish() {
+                tracing::error!("Couldn't remove the install marker: {e}");
+            }
+        }
+        // Or a card pulled now could come back with the marker, and the next launch would
+        // offer to clean up a finished install.
+        if self.flush {
+            let root = self.target.clone();
+            if let Err(e) = install::run_blocking(move || disk::sync_dir(&root)).await {
+                tracing::warn!("Couldn't flush {} after removing the install marker: {e}", self.target.display());
+            }
+        }
+        Some(manifest)
+    }
+}
+
+pub struct Installer {
+    steps: Vec<InstallStep>,
+    status: Vec<StepStatus>,
+}
+
+impl Installer {
+    pub fn new(steps: Vec<InstallStep>) -> Self {
+        let status = vec![StepStatus::Pending; steps.len()];
+        Self { steps, status }
+    }
+
+    // Installing a release, start to finish.
+    pub fn release() -> Self {
+        Self::new(vec![
+            InstallStep {
+                name: "Cleanup",
+                label_key: "step.cleanup",
+                phase: Phase::Prepare,
+                applies: |install| install.emulator.is_some(),
+                execute: clean_up,
+                roll_back: None,
+                commits: false,
+            },
+            InstallStep {
+                name: "Download",
+                label_key: "step.download",
+                phase: Phase::Download,
+                applies: |_| true,
+                execute: download,
+                roll_back: Some(discard_download),
+                commits: false,
+            },
+            InstallStep {
+                name: "Backup",
+                label_key: "step.backup",
+                phase: Phase::Backup,
+                applies: |install| install.backup && install.previous.is_some(),
+                execute: back_up,
+                roll_back: Some(discard_backup),
+                commits: false,
+            },
+            InstallStep {
+                name: "Extraction",
+                label_key: "step.extract",
+                phase: Phase::Extract,
+                applies: |_| true,
+                execute: extract,
+                roll_back: Some(undo_extract),
+                commits: false,
+            },
+            InstallStep {
+                name: "Install layout check",
+                label_key: "step.verify",
+                phase: Phase::Finalize,
+                applies: |_| true,
+                execute: verify,
+                roll_back: None,
+                commits: false,
+            },
+            InstallStep {
+                name: "Tidying up",
+                label_key: "step.tidy",
+                phase: Phase::Finalize,
+                applies: |_| true,
+                execute: tidy_up,
+                roll_back: None,
+                commits: true,
+            },
+            InstallStep {
+                name: "Flush",
+                label_key: "step.flush",
+                phase: Phase::Flush,
+                applies: |install| install.flush,
+                execute: flush,
+                roll_back: None,
+                commits: false,
+            },
+        ])
+    }
+
+    pub fn steps(&self) -> &[InstallStep] {
+        &self.steps
+    }
+
+    pub fn status(&self) -> &[StepStatus] {
+        &self.status
+    }
+
+    pub async fn run(&mut self, install: &mut Install, on_event: impl FnMut(InstallerEvent)) -> Result<(), StepError> {
+        self.run_from(0, install, on_event).await
+    }
+
+    // Run the steps from `first` on, in order. The ones before it are taken as done, as
+    // they were by an earlier run of the same install.
+    pub async fn run_from(
+        &mut self,
+        first: usize,
+        install: &mut Install,
+        mut on_event: impl FnMut(InstallerEvent),
+    ) -> Result<(), StepError> {
+        for index in first..self.steps.len() {
+            if !(self.steps[index].applies)(install) {
+                self.set(index, StepStatus::Skipped, &mut on_event);
+                continue;
+            }
+            if install.cancel.is_cancelled() {
+                return self.on_cancel(index, install, &mut on_event).await;
+            }
+            self.set(index, StepStatus::Running, &mut on_event);
+            let span = self.span(index, install);
+            let started = Instant::now();
+            tracing::debug!(parent: &span, "Started");
+            let execute = self.steps[index].execute;
+            let result = execute(install, &mut |event| on_event(InstallerEvent::Step(index, event)))
+                .instrument(span.clone())
+                .await;
+            let outcome = match &result {
+                Ok(()) => "done",
+                Err(InstallerError::Cancelled) => "cancelled",
+                Err(_) => "failed",
+            };
+            let finished = format!("{} {outcome} after {:.1}s", self.steps[index].name, started.elapsed().as_secs_f64());
+            span.in_scope(|| on_event(InstallerEvent::Step(index, StepEvent::Note(Severity::Info, finished))));
+            match result {
+                Ok(()) => self.set(index, StepStatus::Done, &mut on_event),
+                Err(InstallerError::Cancelled) => return self.on_cancel(index, install, &mut on_event).await,
+                Err(error) => {
+                    self.set(index, StepStatus::Failed(error.to_string()), &mut on_event);
+                    return Err(StepError { step: index, name: self.steps[index].name, error, rollback: None });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Whether a step that commits the install is done.
+    fn committed(&self) -> bool {
+        self.steps.iter().zip(&self.status).any(|(step, status)| step.commits && *status == StepStatus::Done)
+    }
+
+    // A cancel at step `index`: rolled back, or once the install is committed, the steps
+    // left are dropped and the install kept with a warning saying so.
+    async fn on_cancel(
+        &mut self,
+        index: usize,
+        install: &mut Install,
+        on_event: &mut impl FnMut(InstallerEvent),
+    ) -> Result<(), StepError> {
+        if !self.committed() {
+            return Err(self.cancelled(index, install, on_event).await);
+        }
+        self.set(index, StepStatus::Cancelled, on_event);
+        for later in index + 1..self.steps.len() {
+            self.set(later, StepStatus::Skipped, on_event);
+        }
+        let message = format!("{} was cancelled after the install was complete, so the install was kept", self.steps[index].name);
+        on_event(InstallerEvent::Step(index, StepEvent::Note(Severity::Warning, message)));
+        install.warnings.push(Message::new("warning.kept_after_cancel", []));
+        Ok(())
+    }
+
+    // Undo the steps that ran, newest first, the cancelled one included since it may have
+    // got partway.
+    async fn cancelled(&mut self, index: usize, install: &mut Install, on_event: &mut impl FnMut(InstallerEvent)) -> StepError {
+        let ran = self.status[index] == StepStatus::Running;
+        self.set(index, StepStatus::Cancelled, on_event);
+        on_event(InstallerEvent::RollingBack);
+        let mut rollback = None;
+        for done in (0..=index).rev() {
+            if !(self.status[done] == StepStatus::Done || done == index && ran) {
+                continue;
+            }
+            let Some(roll_back) = self.steps[done].roll_back else {
+                continue;
+            };
+            let span = tracing::info_span!(parent: self.span(done, install), "roll_back");
+            match roll_back(install, &mut |event| on_event(InstallerEvent::Step(done, event))).instrument(span).await {
+                Ok(()) => self.set(done, StepStatus::RolledBack, on_event),
+                Err(e) => {
+                    rollback = Some(e);
+                    break;
+                }
+            }
+        }
+        StepError { step: index, name: self.steps[index].name, error: InstallerError::Cancelled, rollback }
+    }
+
+    // What everything a step logs is tagged with, in the log file and the diagnostics.
+    fn span(&self, index: usize, install: &Install) -> tracing::Span {
+        tracing::info_span!(
+            "step",
+            name = self.steps[index].name,
+            target = %install.target.display(),
+            version = install.version.as_deref().unwrap_or("unknown"),
+        )
+    }
+
+    fn set(&mut self, index: usize, status: StepStatus, on_event: &mut impl FnMut(InstallerEvent)) {
+        self.status[index] = status.clone();
+        on_event(InstallerEvent::Status(index, status));
+    }
+}
+
+fn note(report: &mut dyn FnMut(StepEvent), severity: Severity, message: impl Into<String>) {
+    report(StepEvent::Note(severity, message.into()));
+}
+
+fn clean_up<'a>(install: &'a mut Install, report: Report<'a>) -> StepFuture<'a> {
+    Box::pin(async move {
+        let Some(emulator) = install.emulator else {
+            return Ok(());
+        };
+        match install::run_blocking(move || cleanup::delete_bad_subsdk9(emulator)).await {
+            Ok(true) => {
+                note(report, Severity::Info, "Removed a broken subsdk9 from the exefs folder");
+                install.removed_subsdk9 = true;
+                install.warnings.push(Message::new("warning.removed_subsdk9", [("emulator", emulator.name.to_string())]));
+            }
+            Ok(false) => {}
+            Err(e) => install.warnings.push(Message::new(
+                "warning.subsdk9_failed",
+                [("emulator", emulator.name.to_string()), ("error", e.to_string())],
+            )),
+        }
+        Ok(())
+    })
+}
+
+fn download<'a>(install: &'a mut Install, report: Report<'a>) -> StepFuture<'a> {
+    Box::pin(async move {
+        note(report, Severity::Info, format!("Downloading {}", install.url));
+        // Deleted again once it's been extracted, or when the install is dropped.
+        let archive = match &install.downloads {
+            Some(dir) => ArchiveFile::in_dir(dir)?,
+            None => ArchiveFile::new()?,
+        };
+        let response = install.cancel.run(download::download_release(&install.url)).await??;
+        let size = download::save_with_progress(response, archive.path(), &install.cancel, |fraction| {
+            report(StepEvent::Progress(fraction))
+        })
+        .await?;
+        note(report, Severity::Info, format!("Downloaded {}", disk::format_bytes(size)));
+        install.archive = Some(archive);
+        install.archive_size = size;
+        Ok(())
+    })
+}
+
+fn discard_download<'a>(install: &'a mut Install, _: Report<'a>) -> StepFuture<'a> {
+    Box::pin(async move {
+        install.archive = None;
+        Ok(())
+    })
+}
+
+// A backup that can't be made is a warning, not a reason to stop.
+fn back_up<'a>(install: &'a mut Install, report: Report<'a>) -> StepFuture<'a> {
+    Box::pin(async move {
+        let Some(previous) = install.previous.clone() else {
+            return Ok(());
+        };
+        let on_progress = |done: usize, total: usize| report(StepEvent::Progress(done as f32 / total.max(1) as f32));
+        match install::backup_install(previous, install.cancel.clone(), on_progress).await {
+            Err(_) if install.cancel.is_cancelled() => Err(InstallerError::Cancelled),
+            Ok(path) => {
+                note(report, Severity::Info, format!("Backed up the current install to {}", path.display()));
+                install.backup_path = Some(path);
+                Ok(())
+            }
+            Err(warning) => {
+                note(report, Severity::Warning, format!("Backup skipped: {warning}"));
+                install.warnings.push(warning);
+                Ok(())
+            }
+        }
+    })
+}
+
+// A cancelled install leaves the previous one as it was, a backup of it made on the way is
+// only clutter. Undone after the extraction, which may have restored from it.
+fn discard_backup<'a>(install: &'a mut Install, report: Report<'a>) -> StepFuture<'a> {
+    Box::pin(async move {
+        let Some(path) = install.backup_path.take() else {
+            return Ok(());
+        };
+        match std::fs::remove_file(&path) {
+            Ok(()) => note(report, Severity::Info, format!("Deleted the backup made for this install, {}", path.display())),
+            Err(e) => note(report, Severity::Warning, format!("Couldn't delete the backup {}: {e}", path.display())),
+        }
+        Ok(())
+    })
+}
+
+fn extract<'a>(install: &'a mut Install, report: Report<'a>) -> StepFuture<'a> {
+    Box::pin(async move {
+        let Some(archive) = install.archive.as_ref().map(|a| a.path().to_path_buf()) else {
+            return Err(InstallerError::Environment { problem: "there's no downloaded release to extract".to_string() });
+        };
+        // Journal what gets written, so a crash or a closed window mid-extraction can be
+        // repaired or cleaned up on the next launch.
+        if install.marker.is_none() {
+            match Marker::begin(&install.target, install.version.as_deref()) {
+                Ok(marker) => install.marker = Some(marker),
+                Err(e) => note(report, Severity::Warning, format!("Couldn't write the install marker: {e}")),
+            }
+        }
+        note(report, Severity::Info, format!("Extracting into {}", install.target.display()));
+        // Log every quarter of the way, not every file.
+        let mut quarters_logged = 0;
+        let (result, marker) = install::extract_release(
+            archive,
+            install.url.clone(),
+            install.target.clone(),
+            install.marker.take(),
+            install.cancel.clone(),
+            install.write,
+            |update| match update {
+                ExtractUpdate::Progress(done, total) => {
+                    report(StepEvent::Progress(done as f32 / total.max(1) as f32));
+                    let quarters = done * 4 / total.max(1);
+                    if quarters > quarters_logged {
+                        quarters_logged = quarters;
+                        note(report, Severity::Info, format!("Extracted {done} of {total} entries"));
+                    }
+                }
+                ExtractUpdate::Entry(name) => report(StepEvent::Entry(Some(name))),
+            },
+        )
+        .await;
+        install.marker = marker;
+        report(StepEvent::Entry(None));
+        let extracted = result?;
+        // Kept after a failure, for another go at this step.
+        install.archive = None;
+        note(
+            report,
+            Severity::Info,
+            format!(
+                "Extraction done: {} added, {} updated, {} unchanged",
+                extracted.added.len(),
+                extracted.updated.len(),
+                extracted.skipped,
+            ),
+        );
+        for kept in &extracted.preserved {
+            install.warnings.push(Message::new("warning.kept_config", [("file", kept.clone())]));
+        }
+        install.extracted = Some(extracted);
+        Ok(())
+    })
+}
+
+// The marker has to be dropped first so it can be read back (and deleted).
+fn undo_extract<'a>(install: &'a mut Install, report: Report<'a>) -> StepFuture<'a> {
+    Box::pin(async move {
+        install.marker = None;
+        let target = install.target.clone();
+        let message = install::roll_back_install(target.clone(), install.previous.clone(), install.backup_path.clone())
+            .await
+            .map_err(|e| InstallerError::filesystem(&target, e))?;
+        note(report, Severity::Warning, message);
+        Ok(())
+    })
+}
+
+// The files made it, but are they where Cobalt looks? A wrong target fails here, before a
+// manifest claims a working install. The marker stays behind, so the interrupted install
+// banner offers to clean the misplaced files up.
+fn verify<'a>(install: &'a mut Install, report: Report<'a>) -> StepFuture<'a> {
+    Box::pin(async move {
+        if let Err(e) = install::create_mods_directory(install.target.clone()).await {
+            note(report, Severity::Warning, format!("Couldn't create the mods folder: {e}"));
+        }
+        let files = install.extracted.as_ref().map(|e| e.files.clone()).unwrap_or_default();
+        let target = install.target.clone();
+        install::run_blocking(move || layout::verify_install(&target, &files))
+            .await
+            .map_err(|problem| InstallerError::Validation { path: install.target.clone(), problem })
+    })
+}
+
+// Updating over a known install: drop what the old release had and this one doesn't, so
+// the target ends up matching the release exactly. Then the receipt, which the flush
+// after this covers too.
+fn tidy_up<'a>(install: &'a mut Install, report: Report<'a>) -> StepFuture<'a> {
+    Box::pin(async move {
+        let files = install.extracted.as_ref().map(|e| e.files.clone()).unwrap_or_default();
+        if let Some(previous) = &install.previous {
+            let (target, previous_files, current_files) = (install.target.clone(), previous.files.clone(), files.clone());
+            let result = install::run_blocking(move || {
+                let stale = uninstall::remove_stale_files(&target, &previous_files, &current_files)?;
+                let dirs = uninstall::remove_empty_dirs(&target, &stale);
+                Ok::<_, std::io::Error>((stale, dirs))
+            })
+            .await;
+            match result {
+                Ok((stale, dirs)) => {
+                    if !stale.is_empty() {
+                        note(report, Severity::Info, format!("Removed {} files this release dropped", stale.len()));
+                    }
+                    if dirs > 0 {
+                        install.warnings.push(Message::new("warning.removed_empty_dirs", [("count", dirs.to_string())]));
+                    }
+                    install.removed = stale;
+                }
+                Err(e) => {
+                    note(report, Severity::Error, format!("Couldn't remove files dropped from this release: {e}"));
+                    install.warnings.push(Message::new("warning.stale_files_failed", [("error", e.to_string())]));
+                }
+            }
+        } else if install.update {
+            // Someone else's install, or one from before manifests: there's no telling which
+            // of its files this release dropped.
+            note(
+                report,
+                Severity::Info,
+                "No record of the previous install's files, so any this release dropped were left in place",
+            );
+        }
+        // Journalled like the release's files, so a cancel after this takes it off again.
+        if let Some(marker) = &install.marker {
+            marker.record(receipt::RECEIPT_FILE);
+        }
+        if let Err(e) = receipt::Receipt::new(install.version.clone(), files.len()).save(&install.target) {
+            note(report, Severity::Error, format!("Couldn't write the install receipt: {e}"));
+        }
+        Ok(())
+    })
+}
+
+// "Complete" has to mean the files are on the card, not still in the OS's cache with the
+// card about to be pulled out.
+fn flush<'a>(install: &'a mut Install, report: Report<'a>) -> StepFuture<'a> {
+    Box::pin(async move {
+        let touched: Vec<String> = install
+            .extracted
+            .iter()
+            .flat_map(|e| e.added.iter().chain(&e.updated))
+            .chain(&install.removed)
+            .cloned()
+            .chain([receipt::RECEIPT_FILE.to_string()])
+            .collect();
+        let on_progress = |done: usize, total: usize| report(StepEvent::Progress(done as f32 / total.max(1) as f32));
+        let result = install::flush_install(install.target.clone(), touched, install.cancel.clone(), on_progress).await;
+        if let Err(InstallerError::Cancelled) = result {
+            install.warnings.push(Message::new("warning.not_flushed", []));
+        }
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_on<T>(future: impl Future<Output = T>) -> T {
+        tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+    }
+
+    // Steps that only leave a trace in the warnings, so a test can see what ran.
+    fn step(name: &'static str, execute: StepFn) -> InstallStep {
+        InstallStep {
+            name,
+            label_key: "",
+            phase: Phase::Prepare,
+            applies: |_| true,
+            execute,
+            roll_back: Some(|install, _| {
+                Box::pin(async move {
+                    install.warnings.push(Message::new("rolled back", []));
+                    Ok(())
+                })
+            }),
+            commits: false,
+        }
+    }
+
+    fn works<'a>(install: &'a mut Install, report: Report<'a>) -> StepFuture<'a> {
+        Box::pin(async move {
+            report(StepEvent::Progress(1.0));
+            install.warnings.push(Message::new("worked", []));
+            Ok(())
+        })
+    }
+
+    // Fails until the warnings say it's been retried.
+    fn fails_once<'a>(install: &'a mut Install, _: Report<'a>) -> StepFuture<'a> {
+        Box::pin(async move {
+            if install.warnings.iter().any(|w| w.key == "retry") {
+                install.warnings.push(Message::new("worked", []));
+                Ok(())
+            } else {
+                Err(InstallerError::Environment { problem: "not yet".to_string() })
+            }
+        })
+    }
+
+    fn cancels<'a>(install: &'a mut Install, _: Report<'a>) -> StepFuture<'a> {
+        Box::pin(async move {
+            install.cancel.cancel();
+            Err(InstallerError::Cancelled)
+        })
+    }
+
+    fn install() -> Install {
+        Install::new(PathBuf::from("/nowhere"), String::new())
+    }
+
+    fn keys(install: &Install) -> Vec<&str> {
+        install.warnings.iter().map(|w| w.key).collect()
+    }
+
+    #[test]
+    fn steps_run_in_order_and_skip_what_doesnt_apply() {
+        let mut installer = Installer::new(vec![
+            step("first", works),
+            InstallStep { applies: |_| false, ..step("skipped", works) },
+            step("last", works),
+        ]);
+        let mut install = install();
+        let mut events = Vec::new();
+        assert!(block_on(installer.run(&mut install, |e| events.push(e))).is_ok());
+        assert_eq!(installer.status(), [StepStatus::Done, StepStatus::Skipped, StepStatus::Done]);
+        assert_eq!(keys(&install), ["worked", "worked"]);
+        assert_eq!(events[0], InstallerEvent::Status(0, StepStatus::Running));
+        assert_eq!(events[1], InstallerEvent::Step(0, StepEvent::Progress(1.0)));
+        assert!(matches!(&events[2], InstallerEvent::Step(0, StepEvent::Note(_, note)) if note.starts_with("first done after")));
+        assert_eq!(events[4], InstallerEvent::Status(1, StepStatus::Skipped));
+    }
+
+    #[test]
+    fn a_failed_step_can_be_retried_from_where_it_stopped() {
+        let mut installer = Installer::new(vec![step("first", works), step("flaky", fails_once), step("last", works)]);
+        let mut install = install();
+        let error = block_on(installer.run(&mut install, |_| {})).unwrap_err();
+        assert_eq!((error.step, error.name), (1, "flaky"));
+        assert!(matches!(installer.status()[1], StepStatus::Failed(_)));
+        assert_eq!(installer.status()[2], StepStatus::Pending);
+        // Nothing was rolled back, a failure isn't a cancel.
+        assert_eq!(keys(&install), ["worked"]);
+
+        install.warnings.push(Message::new("retry", []));
+        assert!(block_on(installer.run_from(error.step, &mut install, |_| {})).is_ok());
+        assert_eq!(keys(&install), ["worked", "retry", "worked", "worked"]);
+        assert!(installer.status().iter().all(|s| *s == StepStatus::Done));
+    }
+
+    #[test]
+    fn a_cancel_rolls_back_what_ran_newest_first() {
+        let mut installer = Installer::new(vec![
+            InstallStep { roll_back: None, ..step("no undo", works) },
+            step("undone", works),
+            step("cancelled", cancels),
+            step("never", works),
+        ]);
+        let mut install = install();
+        let mut rolling_back = false;
+        let error = block_on(installer.run(&mut install, |e| rolling_back |= e == InstallerEvent::RollingBack)).unwrap_err();
+        assert!(matches!(error.error, InstallerError::Cancelled));
+        assert!(error.rollback.is_none());
+        assert!(rolling_back);
+        assert_eq!(
+            installer.status(),
+            [StepStatus::Done, StepStatus::RolledBack, StepStatus::RolledBack, StepStatus::Pending]
+        );
+        // The cancelled step's own rollback first, then the one before it.
+        assert_eq!(keys(&install), ["worked", "worked", "rolled back", "rolled back"]);
+    }
+
+    #[test]
+    fn a_cancel_between_steps_stops_before_the_next() {
+        let mut installer = Installer::new(vec![step("first", works), step("second", works)]);
+        let mut install = install();
+        let cancel = install.cancel.clone();
+        let error = block_on(installer.run(&mut install, |e| {
+            if e == InstallerEvent::Status(0, StepStatus::Done) {
+                cancel.cancel();
+            }
+        }))
+        .unwrap_err();
+        assert_eq!(error.step, 1);
+        assert_eq!(installer.status(), [StepStatus::RolledBack, StepStatus::Cancelled]);
+        assert_eq!(keys(&install), ["worked", "rolled back"]);
+    }
+
+    #[test]
+    fn a_cancel_after_the_install_is_committed_keeps_it() {
+        let mut installer = Installer::new(vec![
+            step("written", works),
+            InstallStep { commits: true, ..step("committed", works) },
+            step("cancelled", cancels),
+            step("never", works),
+        ]);
+        let mut install = install();
+        assert!(block_on(installer.run(&mut install, |e| assert_ne!(e, InstallerEvent::RollingBack))).is_ok());
+        assert_eq!(
+            installer.status(),
+            [StepStatus::Done, StepStatus::Done, StepStatus::Cancelled, StepStatus::Skipped]
+        );
+        assert_eq!(keys(&install), ["worked", "worked", "warning.kept_after_cancel"]);
+    }
+
+    #[test]
+    fn a_cancelled_install_deletes_its_backup() {
+        let scratch = crate::Scratch::new("installer_discard_backup");
+        scratch.file("backup.zip", b"PK");
+        let mut install = Install { backup_path: Some(scratch.0.join("backup.zip")), ..install() };
+        assert!(block_on(discard_backup(&mut install, &mut |_| {})).is_ok());
+        assert!(install.backup_path.is_none());
+        assert!(!scratch.0.join("backup.zip").exists());
+    }
+}
@@ -0,0 +1,87 @@
+// Is there a newer build of the installer itself?
+//
+// The installer ships from its own GitHub releases, separately from Cobalt. The answer
+// is cached for a day so launching the installer a few times doesn't keep hitting the
+// API, and any failure just means no banner.
+
+use dioxus::logger::tracing;
+use serde::{Deserialize, Serialize};
+
+use crate::paths::cache_dir;
+use crate::release::{self, Release};
+
+const INSTALLER_RELEASES_API: &str = "https://api.github.com/repos/DivineDragonFanClub/cobalt-installer/releases/latest";
+
+const CHECK_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+// The last answer from GitHub, whether or not it was newer than us.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct CachedCheck {
+    // Seconds since the Unix epoch.
+    checked_at: u64,
+    latest: Release,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstallerUpdate {
+    pub version: String,
+    // The release page, for the banner's link.
+    pub url: String,
+    // What a self-update downloads.
+    pub release: Release,
+}
+
+fn cache_path() -> Option<std::path::PathBuf> {
+    cache_dir().map(|d| d.join("installer_update.json"))
+}
+
+fn load_cached() -> Option<CachedCheck> {
+    let contents = std::fs::read_to_string(cache_path()?).ok()?;
+    let cached: CachedCheck = serde_json::from_str(&contents).ok()?;
+    (crate::history::now().saturating_sub(cached.checked_at) < CHECK_INTERVAL_SECS).then_some(cached)
+}
+
+fn save_cached(cached: &CachedCheck) {
+    let Some(path) = cache_path() else {
+        return;
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, serde_json::to_string(cached)?));
+    if let Err(e) = result {
+        tracing::info!("Couldn't cache the installer update check: {e}");
+    }
+}
+
+async fn fetch_latest() -> reqwest::Result<Release> {
+    crate::http::get(INSTALLER_RELEASES_API)
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+}
+
+// The newer installer release, if there is one.
+pub async fn check() -> Option<InstallerUpdate> {
+    let cached = match load_cached() {
+        Some(cached) => cached,
+        None => match fetch_latest().await {
+            Ok(latest) => {
+                let cached = CachedCheck { checked_at: crate::history::now(), latest };
+                save_cached(&cached);
+                cached
+            }
+            Err(e) => {
+                tracing::info!("Couldn't check for installer updates: {e}");
+                return None;
+            }
+        },
+    };
+    let latest = cached.latest;
+    release::is_newer(&latest.tag_name, env!("CARGO_PKG_VERSION")).then(|| InstallerUpdate {
+        version: latest.version().to_string(),
+        url: latest.html_url.clone(),
+        release: latest,
+    })
+}
@@ -0,0 +1,159 @@
+// Does a folder look like an SD card root? The SD card selector warns with these checks
+// before an install, and the same checks explain a broken layout after one, so both say
+// the same thing about the same mistake.
+
+use std::path::Path;
+
+use crate::manifest::Manifest;
+
+// Folders that live directly in the SD root. Picking one of them as the target means the
+// path points one level too deep.
+const SD_ROOT_FOLDERS: &[&str] = &["atmosphere", "engage"];
+
+// Folders that live further down still, e.g. engage/mods or atmosphere/contents.
+const NESTED_FOLDERS: &[&str] = &["mods", "contents", "exefs", "romfs", "skyline"];
+
+// What's wrong with `target` as an SD root, if anything looks off.
+pub fn target_warning(target: &Path) -> Option<String> {
+    let name = target.file_name()?.to_string_lossy().to_lowercase();
+    if SD_ROOT_FOLDERS.contains(&name.as_str()) {
+        return Some(format!(
+            "This is the {name} folder inside an SD card, your SD path points one level too deep. Pick the folder that contains {name} instead."
+        ));
+    }
+    if NESTED_FOLDERS.contains(&name.as_str()) {
+        return Some(format!(
+            "This looks like the {name} folder somewhere inside an SD card, not the SD card itself. Pick the folder that contains atmosphere and engage."
+        ));
+    }
+    for folder in SD_ROOT_FOLDERS {
+        if target.join(folder).join(folder).is_dir() {
+            return Some(format!(
+                "Found {folder}/{folder} in this folder, an earlier install went one level too deep. You may want to remove the inner {folder} folder."
+            ));
+        }
+    }
+    None
+}
+
+// Cobalt's loader, which any Cobalt install has whoever put it there.
+const COBALT_FILES: &[&str] = &[
+    "atmosphere/contents/0100A6301214E000/exefs/main.npdm",
+    "atmosphere/contents/0100A6301214E000/exefs/subsdk9",
+];
+
+// Whether `target` already has Cobalt on it, installed by us or not.
+pub fn has_cobalt(target: &Path) -> bool {
+    !target.as_os_str().is_empty()
+        && COBALT_FILES.iter().all(|f| crate::disk::resolve_case_insensitive(target, f).is_file())
+}
+
+// The title ID Cobalt's files are installed under.
+pub const ENGAGE_TITLE_ID: &str = "0100A6301214E000";
+
+// The exefs files from `manifest` that are missing from `target` or no longer match the
+// checksum recorded when they were written. None when the manifest predates checksums,
+// so there's nothing to compare against.
+pub fn damaged_files(target: &Path, manifest: &Manifest) -> Option<Vec<String>> {
+    let critical: Vec<(&String, &u32)> = manifest
+        .checksums
+        .iter()
+        .filter(|(f, _)| f.split('/').any(|part| part.eq_ignore_ascii_case("exefs")))
+        .collect();
+    if critical.is_empty() {
+        return None;
+    }
+    Some(
+        critical
+            .into_iter()
+            .filter(|(f, crc32)| crate::extract::file_crc32(&target.join(f)).ok() != Some(**crc32))
+            .map(|(f, _)| f.clone())
+            .collect(),
+    )
+}
+
+// Check what an install left on `target` against the layout Cobalt expects: the engage
+// folder at the root and every exefs file from the release where the loader looks for
+// it. `files` are the release's files as extracted.
+pub fn verify_install(target: &Path, files: &[String]) -> Result<(), String> {
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if SD_ROOT_FOLDERS.contains(&name.as_str()) && target.join(&name).is_dir() {
+        let inner = if name == "engage" { "engage/engage/mods" } else { "atmosphere/atmosphere" };
+        return Err(format!("Found {inner} — your SD path points one level too deep"));
+    }
+
+    if !target.join("engage").is_dir() {
+        return Err(format!("There's no engage folder in {} after installing", target.display()));
+    }
+
+    let missing: Vec<&String> = files
+        .iter()
+        .filter(|f| f.split('/').any(|part| part.eq_ignore_ascii_case("exefs")))
+        .filter(|f| !target.join(f).is_file())
+        .collect();
+    if let Some(first) = missing.first() {
+        return Err(format!(
+            "{first} is missing after installing ({} exefs files in total), the game won't load Cobalt without it",
+            missing.len()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scratch;
+
+    const SUBSDK9: &str = "atmosphere/contents/0100A6301214E000/exefs/subsdk9";
+    const NPDM: &str = "atmosphere/contents/0100A6301214E000/exefs/main.npdm";
+
+    #[test]
+    fn folders_inside_an_sd_card_are_flagged() {
+        assert!(target_warning(Path::new("/media/switch/atmosphere")).is_some());
+        assert!(target_warning(Path::new("/media/switch/engage/mods")).is_some());
+        assert!(target_warning(Path::new("/media/SWITCH")).is_none());
+    }
+
+    #[test]
+    fn cobalt_needs_both_loader_files() {
+        let scratch = Scratch::new("has_cobalt");
+        assert!(!has_cobalt(&scratch.0));
+        scratch.file(SUBSDK9, b"loader");
+        assert!(!has_cobalt(&scratch.0));
+        scratch.file(NPDM, b"npdm");
+        assert!(has_cobalt(&scratch.0));
+        assert!(!has_cobalt(Path::new("")));
+    }
+
+    #[test]
+    fn verify_wants_engage_and_every_exefs_file() {
+        let scratch = Scratch::new("verify_install");
+        let files = vec![SUBSDK9.to_string(), NPDM.to_string(), "engage/config/cobalt.toml".to_string()];
+        assert!(verify_install(&scratch.0, &files).is_err());
+        std::fs::create_dir_all(scratch.0.join("engage")).unwrap();
+        scratch.file(SUBSDK9, b"loader");
+        let missing = verify_install(&scratch.0, &files).unwrap_err();
+        assert!(missing.contains("main.npdm"), "{missing}");
+        scratch.file(NPDM, b"npdm");
+        assert_eq!(verify_install(&scratch.0, &files), Ok(()));
+    }
+
+    #[test]
+    fn changed_exefs_files_are_damaged() {
+        let scratch = Scratch::new("damaged_files");
+        let subsdk9 = scratch.file(SUBSDK9, b"loader");
+        let mut manifest = Manifest::new(scratch.0.clone(), None, vec![SUBSDK9.to_string()]);
+        assert_eq!(damaged_files(&scratch.0, &manifest), None);
+
+        manifest.checksums.insert(SUBSDK9.to_string(), crate::extract::file_crc32(&subsdk9).unwrap());
+        assert_eq!(damaged_files(&scratch.0, &manifest), Some(Vec::new()));
+
+        std::fs::write(&subsdk9, b"something else").unwrap();
+        assert_eq!(damaged_files(&scratch.0, &manifest), Some(vec![SUBSDK9.to_string()]));
+    }
+}
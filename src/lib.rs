@@ -0,0 +1,167 @@
+// The installer's core: finding emulators and SD cards, downloading releases, writing
+// them onto a target and cleaning up after old installs. Nothing in here needs a window,
+// the Dioxus components in main.rs call into it and show what comes back, so it can be
+// tested (and reused) on its own.
+//
+// Fallible functions return a Result for the caller to report, they don't unwrap.
+
+#[cfg(feature = "desktop")]
+pub mod activity;
+#[cfg(feature = "desktop")]
+pub mod archive_path;
+#[cfg(feature = "desktop")]
+pub mod backup;
+#[cfg(feature = "desktop")]
+pub mod bug_report;
+#[cfg(feature = "desktop")]
+pub mod cancel;
+#[cfg(feature = "desktop")]
+pub mod checks;
+#[cfg(feature = "desktop")]
+pub mod cleanup;
+#[cfg(feature = "desktop")]
+pub mod cli;
+#[cfg(feature = "desktop")]
+pub mod cobalt_info;
+#[cfg(feature = "desktop")]
+pub mod crash;
+#[cfg(feature = "desktop")]
+pub mod diagnostics;
+#[cfg(feature = "desktop")]
+pub mod disk;
+pub mod download;
+pub mod error;
+#[cfg(feature = "desktop")]
+pub mod extract;
+#[cfg(feature = "desktop")]
+pub mod failure;
+#[cfg(feature = "desktop")]
+pub mod history;
+pub mod http;
+pub mod i18n;
+#[cfg(feature = "desktop")]
+pub mod install;
+#[cfg(feature = "desktop")]
+pub mod install_state;
+#[cfg(feature = "desktop")]
+pub mod installation_type;
+#[cfg(feature = "desktop")]
+pub mod installer;
+#[cfg(feature = "desktop")]
+pub mod installer_update;
+#[cfg(feature = "desktop")]
+pub mod layout;
+#[cfg(feature = "desktop")]
+pub mod log_file;
+#[cfg(feature = "desktop")]
+pub mod manifest;
+#[cfg(feature = "desktop")]
+pub mod marker;
+#[cfg(feature = "desktop")]
+pub mod misplaced;
+#[cfg(feature = "desktop")]
+pub mod mod_validation;
+#[cfg(feature = "desktop")]
+pub mod mods;
+#[cfg(feature = "desktop")]
+pub mod mods_backup;
+#[cfg(feature = "desktop")]
+pub mod mods_report;
+#[cfg(feature = "desktop")]
+pub mod notify;
+#[cfg(feature = "desktop")]
+pub mod paths;
+#[cfg(feature = "desktop")]
+pub mod plugins;
+#[cfg(feature = "desktop")]
+pub mod progress;
+#[cfg(feature = "desktop")]
+pub mod receipt;
+#[cfg(feature = "desktop")]
+pub mod release;
+#[cfg(feature = "desktop")]
+pub mod run_guard;
+#[cfg(feature = "desktop")]
+pub mod self_update;
+#[cfg(feature = "desktop")]
+pub mod settings;
+#[cfg(feature = "desktop")]
+pub mod settings_migration;
+#[cfg(feature = "desktop")]
+pub mod single_instance;
+#[cfg(feature = "desktop")]
+pub mod summary;
+#[cfg(feature = "desktop")]
+pub mod system;
+#[cfg(feature = "desktop")]
+pub mod sweep;
+#[cfg(feature = "desktop")]
+pub mod target_lock;
+#[cfg(feature = "desktop")]
+pub mod theme;
+#[cfg(feature = "desktop")]
+pub mod toast;
+#[cfg(feature = "desktop")]
+pub mod ui_scale;
+#[cfg(feature = "desktop")]
+pub mod uninstall;
+#[cfg(feature = "desktop")]
+pub mod window_state;
+#[cfg(feature = "desktop")]
+pub mod wizard;
+
+pub const RELEASE_URL: &str = "https://github.com/Raytwo/Cobalt/releases/latest/download/release.zip";
+
+pub const HELP_URL: &str = "https://discord.gg/BH6XhKsKdS";
+
+#[cfg(feature = "desktop")]
+pub const MODS_DOCS_URL: &str = "https://github.com/Raytwo/Cobalt/wiki";
+
+#[cfg(feature = "desktop")]
+pub const INSTALLER_REPO_URL: &str = "https://github.com/DivineDragonFanClub/cobalt-installer";
+
+#[cfg(feature = "desktop")]
+pub const COBALT_REPO_URL: &str = "https://github.com/Raytwo/Cobalt";
+
+// What support asks for first, as plain text to paste into a bug report.
+#[cfg(feature = "desktop")]
+pub fn version_info() -> String {
+    let commit = env!("INSTALLER_GIT_COMMIT");
+    let mut info = format!("Cobalt Installer {}", env!("CARGO_PKG_VERSION"));
+    if !commit.is_empty() {
+        info.push_str(&format!(" ({commit})"));
+    }
+    info.push_str(&format!("\nOS: {} {}", std::env::consts::OS, std::env::consts::ARCH));
+    info.push_str(&format!("\nRelease URL: {}", download::release_url_in_use()));
+    info
+}
+
+// A fresh folder in the system temp directory for tests that need real files, deleted
+// again when dropped.
+#[cfg(test)]
+pub(crate) struct Scratch(pub std::path::PathBuf);
+
+#[cfg(test)]
+impl Scratch {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("cobalt_installer_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).expect("Couldn't create a scratch folder");
+        Self(path)
+    }
+
+    // Create `relative` (and its parents) as a file holding `contents`.
+    pub fn file(&self, relative: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = self.0.join(relative);
+        std::fs::create_dir_all(path.parent().expect("A file has a parent")).expect("Couldn't create a folder");
+        std::fs::write(&path, contents).expect("Couldn't write a file");
+        path
+    }
+}
+
+#[cfg(test)]
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,333 @@
+// Where tracing output goes. On Windows there's no console to print to, so besides the
+// console (when there is one) everything is written to installer.log in the data
+// directory, where support can ask for it and the diagnostics bundle picks it up.
+//
+// The file starts over each day and whenever it grows past MAX_BYTES, keeping the last
+// few as installer.1.log (the newest) to installer.4.log. Each session starts with a
+// header line saying which installer and OS wrote what follows.
+//
+// RUST_LOG works as usual. The verbose logging setting turns the file up to everything
+// the installer traces, path probing and per-file decisions included, without a restart.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use dioxus::logger::tracing;
+use time::macros::format_description;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::fmt::format::{DefaultFields, FmtSpan, Format};
+use tracing_subscriber::fmt::{self, MakeWriter};
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
+
+use crate::paths::data_dir;
+
+const FILE_NAME: &str = "installer.log";
+
+const MAX_BYTES: u64 = 2 * 1024 * 1024;
+
+// The current file and the rotated ones before it.
+const KEEP: usize = 5;
+
+// The installer's own crates at trace level, everything else at debug. hyper's debug
+// output is a line per connection event, which helps nobody.
+const VERBOSE: &str = "debug,cobalt_installer=trace,CobaltInstaller=trace,hyper_util=warn";
+
+// Swaps the file's filter when the verbose setting changes.
+static FILE_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+// What the file is getting right now, for the About dialog.
+static LEVEL: Mutex<String> = Mutex::new(String::new());
+
+pub fn logs_dir() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("logs"))
+}
+
+// This session's log file.
+pub fn current() -> Option<PathBuf> {
+    logs_dir().map(|d| d.join(FILE_NAME))
+}
+
+fn rotated(dir: &Path, n: usize) -> PathBuf {
+    dir.join(format!("installer.{n}.log"))
+}
+
+fn ignore_missing(result: std::io::Result<()>) -> std::io::Result<()> {
+    match result {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        other => other,
+    }
+}
+
+// installer.log becomes installer.1.log, that one installer.2.log and so on, and the
+// oldest is dropped.
+fn rotate(dir: &Path) -> std::io::Result<()> {
+    ignore_missing(std::fs::remove_file(rotated(dir, KEEP - 1)))?;
+    for n in (1..KEEP - 1).rev() {
+        ignore_missing(std::fs::rename(rotated(dir, n), rotated(dir, n + 1)))?;
+    }
+    ignore_missing(std::fs::rename(dir.join(FILE_NAME), rotated(dir, 1)))
+}
+
+fn today() -> time::Date {
+    time::OffsetDateTime::now_utc().date()
+}
+
+// Whether what's in `path` belongs in the previous files: written on an earlier day, or
+// already as big as a log gets.
+fn is_stale(path: &Path, max_bytes: u64) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    let earlier_day = metadata
+        .modified()
+        .map(|modified| time::OffsetDateTime::from(modified).date() < today())
+        .unwrap_or(false);
+    earlier_day || metadata.len() >= max_bytes
+}
+
+// The log file, moving on to a fresh one when it gets too big or the day changes.
+struct RotatingFile {
+    dir: PathBuf,
+    // None only between closing the old file and opening the new one.
+    file: Option<File>,
+    written: u64,
+    day: time::Date,
+    max_bytes: u64,
+}
+
+impl RotatingFile {
+    fn open(dir: &Path, max_bytes: u64) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(FILE_NAME);
+        if is_stale(&path, max_bytes) {
+            rotate(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self { dir: dir.to_path_buf(), file: Some(file), written, day: today(), max_bytes })
+    }
+
+    fn start_over(&mut self) -> std::io::Result<()> {
+        // Windows won't rename a file that's still open.
+        self.file = None;
+        rotate(&self.dir)?;
+        self.file = Some(File::create(self.dir.join(FILE_NAME))?);
+        self.written = 0;
+        self.day = today();
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.written > 0 && (self.written + buf.len() as u64 > self.max_bytes || self.day != today()) {
+            self.start_over()?;
+        }
+        let file = self.file.as_mut().ok_or_else(|| std::io::Error::other("the log file is closed"))?;
+        let written = file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.as_mut().map_or(Ok(()), File::flush)
+    }
+}
+
+fn header() -> String {
+    let now = time::OffsetDateTime::now_utc();
+    let started = now
+        .format(format_description!("[year]-[month]-[day] [hour]:[minute]:[second]"))
+        .unwrap_or_else(|_| now.unix_timestamp().to_string());
+    let commit = env!("INSTALLER_GIT_COMMIT");
+    let commit = if commit.is_empty() { String::new() } else { format!(" ({commit})") };
+    format!(
+        "==== Cobalt Installer {}{commit} on {} {}, started {started} UTC ====\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    )
+}
+
+fn rust_log() -> Option<String> {
+    std::env::var("RUST_LOG").ok().filter(|v| !v.trim().is_empty())
+}
+
+// RUST_LOG if it's set, otherwise the same levels dioxus would pick.
+fn default_filter() -> EnvFilter {
+    let level = if cfg!(debug_assertions) { tracing::Level::DEBUG } else { tracing::Level::INFO };
+    let filter = EnvFilter::builder().with_default_directive(level.into()).from_env_lossy();
+    match "hyper_util=warn".parse() {
+        Ok(quiet) => filter.add_directive(quiet),
+        Err(_) => filter,
+    }
+}
+
+// The file's filter and how to describe it.
+fn file_filter(verbose: bool) -> (EnvFilter, String) {
+    if verbose {
+        return (EnvFilter::new(VERBOSE), "trace (verbose logging)".to_string());
+    }
+    let level = match rust_log() {
+        Some(rust_log) => format!("RUST_LOG={rust_log}"),
+        None if cfg!(debug_assertions) => "debug".to_string(),
+        None => "info".to_string(),
+    };
+    (default_filter(), level)
+}
+
+fn set_level(level: String) {
+    *LEVEL.lock().unwrap_or_else(|e| e.into_inner()) = level;
+}
+
+// The log file's level as shown in the About dialog. Empty when there's no log file.
+pub fn level() -> String {
+    LEVEL.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+// Turn the file's verbose logging on or off. The console keeps following RUST_LOG.
+pub fn set_verbose(verbose: bool) {
+    let Some(handle) = FILE_FILTER.get() else {
+        return;
+    };
+    let (filter, level) = file_filter(verbose);
+    match handle.reload(filter) {
+        Ok(()) => {
+            tracing::info!("Log file level: {level}");
+            set_level(level);
+        }
+        Err(e) => tracing::warn!("Couldn't change the log level: {e}"),
+    }
+}
+
+// Each line with the spans it was logged in (an install step's name, target and version)
+// and a line with a span's timings when it closes, so the file and the diagnostics bundle
+// show which step every line came from and how long each took.
+fn file_format<W: for<'a> MakeWriter<'a> + 'static>(writer: W) -> fmt::Layer<Registry, DefaultFields, Format, W> {
+    fmt::layer().with_writer(writer).with_ansi(false).with_span_events(FmtSpan::CLOSE)
+}
+
+// Send tracing to the console and the log file. Called first thing, before any window
+// (and so before settings can be read, the verbose setting is applied once the app
+// starts). If the file can't be opened the console still gets everything and the error
+// comes back for logging, it's never a reason not to start. The command line prints its
+// own progress, so it goes without the console.
+pub fn init(console: bool) -> std::io::Result<PathBuf> {
+    let console =
+        console.then(|| tracing_subscriber::fmt::layer().with_writer(std::io::stderr).with_filter(default_filter()));
+
+    let opened = logs_dir()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no data directory on this system"))
+        .and_then(|dir| {
+            let mut file = RotatingFile::open(&dir, MAX_BYTES)?;
+            file.write_all(header().as_bytes())?;
+            Ok((dir.join(FILE_NAME), file))
+        });
+    let (path, file_layer) = match opened {
+        Ok((path, file)) => {
+            let (filter, level) = file_filter(false);
+            let (filter, handle) = reload::Layer::new(filter);
+            let _ = FILE_FILTER.set(handle);
+            set_level(level);
+            let layer = file_format(Mutex::new(file)).with_filter(filter);
+            (Ok(path), Some(layer))
+        }
+        Err(e) => (Err(e), None),
+    };
+
+    // Only fails if a subscriber is already set, which then gets the output instead. The
+    // file's layer goes first, its reload handle is typed on the bare registry.
+    let _ = tracing_subscriber::registry().with(file_layer).with(console).try_init();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scratch;
+
+    #[test]
+    fn rotation_keeps_the_last_few() {
+        let scratch = Scratch::new("log_rotation");
+        for session in 0..KEEP + 2 {
+            scratch.file(FILE_NAME, session.to_string().as_bytes());
+            rotate(&scratch.0).unwrap();
+        }
+        assert!(!scratch.0.join(FILE_NAME).exists());
+        // The newest first, the oldest dropped.
+        for n in 1..KEEP {
+            let contents = std::fs::read_to_string(rotated(&scratch.0, n)).unwrap();
+            assert_eq!(contents, (KEEP + 2 - n).to_string());
+        }
+        assert!(!rotated(&scratch.0, KEEP).exists());
+    }
+
+    #[test]
+    fn a_full_file_starts_over() {
+        let scratch = Scratch::new("log_size");
+        let mut file = RotatingFile::open(&scratch.0, 10).unwrap();
+        file.write_all(b"first line\n").unwrap();
+        file.write_all(b"second\n").unwrap();
+        file.flush().unwrap();
+        assert_eq!(std::fs::read_to_string(scratch.0.join(FILE_NAME)).unwrap(), "second\n");
+        assert_eq!(std::fs::read_to_string(rotated(&scratch.0, 1)).unwrap(), "first line\n");
+
+        // A file that's already full when a session starts is moved aside too.
+        drop(file);
+        scratch.file(FILE_NAME, b"0123456789");
+        let mut file = RotatingFile::open(&scratch.0, 10).unwrap();
+        file.write_all(b"new\n").unwrap();
+        assert_eq!(std::fs::read_to_string(rotated(&scratch.0, 1)).unwrap(), "0123456789");
+        assert_eq!(std::fs::read_to_string(rotated(&scratch.0, 2)).unwrap(), "first line\n");
+    }
+
+    #[test]
+    fn verbose_filter_is_valid() {
+        assert!(EnvFilter::try_new(VERBOSE).is_ok());
+        assert!(file_filter(true).1.starts_with("trace"));
+    }
+
+    #[derive(Clone, Default)]
+    struct Captured(std::sync::Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn lines_say_which_step_they_came_from() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::registry().with(file_format(move || writer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("step", name = "Download", target = "/sd", version = "v1.0.0");
+            span.in_scope(|| tracing::info!("Downloaded 5 MB"));
+        });
+        let text = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2, "{text}");
+        assert!(lines[0].contains("step{name=\"Download\" target=\"/sd\" version=\"v1.0.0\"}"), "{text}");
+        assert!(lines[0].ends_with("Downloaded 5 MB"), "{text}");
+        assert!(lines[1].contains("close") && lines[1].contains("time.busy"), "{text}");
+        // Plain text, no colour codes.
+        assert!(!text.contains('\u{1b}'), "{text}");
+    }
+
+    #[test]
+    fn header_names_the_version_and_os() {
+        let header = header();
+        assert!(header.contains(env!("CARGO_PKG_VERSION")), "{header}");
+        assert!(header.contains(std::env::consts::OS), "{header}");
+        assert!(header.ends_with('\n'));
+    }
+}
@@ -6,7 +6,7 @@
 use std::path::{Path, PathBuf};
 
 #[cfg(feature = "desktop")]
-use dioxus::desktop::use_window;
+use dioxus::desktop::{use_window, use_wry_event_handler};
 use dioxus::{logger::tracing, prelude::*};
 
 const FAVICON: Asset = asset!("/assets/favicon.ico");
@@ -14,80 +14,40 @@ const MAIN_CSS: Asset = asset!("/assets/main.css");
 const SAMMIE: Asset = asset!("/assets/SAMMIE.png");
 
 #[cfg(feature = "desktop")]
-use dirs::home_dir;
-
+use cobalt_installer::{
+    activity, backup, bug_report, checks, cli, cobalt_info, crash, diagnostics, disk, extract, failure, history, install,
+    install_state, installation_type, installer_update, layout, log_file, manifest, marker, misplaced, mod_validation,
+    mods, mods_backup, mods_report, notify, plugins, progress, receipt, release, run_guard, self_update, settings,
+    settings_migration, single_instance, summary, sweep, target_lock, theme, toast, ui_scale, uninstall, window_state,
+    wizard,
+};
+use cobalt_installer::error::InstallerError;
+use cobalt_installer::{download, i18n, t, HELP_URL, RELEASE_URL};
 #[cfg(feature = "desktop")]
-use std::process::{Child, Command};
+use cobalt_installer::{version_info, COBALT_REPO_URL, INSTALLER_REPO_URL, MODS_DOCS_URL};
 
 #[cfg(feature = "desktop")]
-use std::io::{Read, Write};
+use cobalt_installer::cancel::CancelToken;
 #[cfg(feature = "desktop")]
-use zip::ZipArchive;
-
+use cobalt_installer::install::{run_blocking, run_blocking_with_progress};
 #[cfg(feature = "desktop")]
-use dioxus_sdk::storage::*;
-
-// Everything about locating an emulator on the host filesystem is desktop only.
-// On Android we don't hunt for install folders, the user hands us Eden's folder
-// through the system picker instead (see the `saf` module below).
+use cobalt_installer::installer::{Install, Installer, InstallerEvent, StepError, StepEvent, StepStatus};
 #[cfg(feature = "desktop")]
-struct Emulator {
-    name: &'static str,
-    linux_data_path: &'static str,
-    macos_data_path: &'static str,
-    windows_data_folder: &'static str,
-    sd_card_folder: &'static str,
-}
-
+use cobalt_installer::paths::{
+    cache_dir, data_dir, does_engage_folder_exist, does_engage_mods_folder_exist, newest_file, open_dir,
+    open_engage_mods_folder, open_engage_subfolder, resolve_target, reveal_dir, target_type_of, TargetError, CONFIG_DIR,
+    EMULATORS, LOGS_DIR, PATCHES_DIR,
+};
 #[cfg(feature = "desktop")]
-impl Emulator {
-    fn data_path(&self) -> Option<PathBuf> {
-        match std::env::consts::OS {
-            "macos" => home_dir().map(|h| h.join(self.macos_data_path)),
-            "windows" => std::env::var_os("APPDATA").map(|a| PathBuf::from(a).join(self.windows_data_folder)),
-            "linux" => home_dir().map(|h| h.join(self.linux_data_path)),
-            other => todo!("Unsupported platform: {other}"),
-        }
-    }
-
-    fn sd_card_path(&self) -> Option<PathBuf> {
-        self.data_path().map(|p| p.join(self.sd_card_folder))
-    }
-
-    fn is_installed(&self) -> bool {
-        self.data_path().map(|p| p.exists()).unwrap_or(false)
-    }
-}
-
+use failure::Failure;
 #[cfg(feature = "desktop")]
-static EMULATORS: &[Emulator] = &[
-    Emulator {
-        name: "Ryujinx",
-        linux_data_path: ".config/Ryujinx",
-        macos_data_path: "Library/Application Support/Ryujinx",
-        windows_data_folder: "Ryujinx",
-        sd_card_folder: "sdcard",
-    },
-    Emulator {
-        name: "Citron",
-        linux_data_path: ".local/share/citron", // I got this from the docs https://citron-emu.org/docs/installation
-        macos_data_path: ".local/share/citron",
-        windows_data_folder: "citron",
-        sd_card_folder: "sdmc",
-    },
-    Emulator {
-        name: "Eden",
-        linux_data_path: ".local/share/eden", // Assuming based on how Eden has the same structure as Citron, it's not mentioned in the docs.
-        macos_data_path: ".local/share/eden",
-        windows_data_folder: "eden",
-        sd_card_folder: "sdmc",
-    },
-];
-
+use install_state::{InstallState, Progress};
 #[cfg(feature = "desktop")]
-fn get_emulator(name: &str) -> Option<&'static Emulator> {
-    EMULATORS.iter().find(|e| e.name == name)
-}
+use installation_type::InstallationType;
+#[cfg(feature = "desktop")]
+use manifest::Manifest;
+#[cfg(feature = "desktop")]
+use wizard::Step;
 
 fn main() {
     // Desktop and Android launch differently. Desktop wires up a data directory
@@ -95,10 +55,47 @@ fn main() {
     // renderer (no `dirs` paths, they come back None there).
     #[cfg(feature = "desktop")]
     {
+        // With arguments it's the command line install, no window at all.
+        let command = cli::parse(&std::env::args().skip(1).collect::<Vec<_>>());
+        if command.is_some() {
+            cli::attach_console();
+        }
+        match log_file::init(command.is_none()) {
+            Ok(path) => tracing::info!("Logging to {}", path.display()),
+            Err(e) => tracing::warn!("Not logging to a file: {e}"),
+        }
+        crash::install_hook();
+        if let Some(command) = command {
+            std::process::exit(cli::run(command));
+        }
+        // Kept until the process exits. If it never gets dropped, the next launch finds
+        // our PID gone and takes the lock over.
+        let _instance = match single_instance::acquire() {
+            Ok(instance) => instance,
+            Err(pid) => {
+                tracing::info!("Another installer (PID {pid}) is already running, handing over to it");
+                single_instance::request_focus();
+                single_instance::show_already_running();
+                return;
+            }
+        };
         dioxus_sdk::storage::set_dir!();
+        settings_migration::run();
+        self_update::remove_leftovers();
+        // Off the main thread, a slow data drive shouldn't hold up the window.
+        std::thread::spawn(sweep::sweep);
+        let theme = settings::THEME.get();
         LaunchBuilder::new()
             .with_cfg(
-                dioxus_desktop::Config::new().with_data_directory(dirs::data_local_dir().unwrap().join("CobaltInstaller"))
+                dioxus_desktop::Config::new()
+                    .with_data_directory(data_dir().unwrap())
+                    .with_background_color(theme.background_color())
+                    .with_custom_head(theme::head(theme))
+                    .with_on_window(|window, _| {
+                        if let Some(state) = settings::WINDOW.get() {
+                            state.restore(&window);
+                        }
+                    })
             )
             .launch(App);
     }
@@ -107,7 +104,9 @@ fn main() {
     dioxus::launch(App);
 }
 
-const RELEASE_URL: &str = "https://github.com/Raytwo/Cobalt/releases/latest/download/release.zip";
+// Written by build.rs, one line per crate we ship.
+#[cfg(feature = "desktop")]
+const THIRD_PARTY_LICENSES: &str = include_str!(concat!(env!("OUT_DIR"), "/third_party_licenses.txt"));
 
 // On Android the target lives under Android/data, which is off limits to plain
 // file access. All the writing happens on the Kotlin side (see android/MainActivity.kt),
@@ -191,93 +190,77 @@ mod saf {
     }
 }
 
-#[cfg(feature = "desktop")]
-fn open_dir(path: impl AsRef<Path>) -> std::io::Result<Child> {
-    let cmd = match std::env::consts::OS {
-        "macos" => "open",
-        "windows" => "explorer",
-        "linux" => "xdg-open",
-        other => todo!("Unsupported platform: {other}"),
-    };
-    Command::new(cmd).arg(path.as_ref()).spawn()
-}
+#[component]
+fn App() -> Element {
+    #[cfg(feature = "desktop")]
+    {
+        // Before anything below reads a setting.
+        settings::use_provider();
 
-#[cfg(feature = "desktop")]
-fn construct_bad_subsdk9_path(emulator: &Emulator) -> Option<PathBuf> {
-    emulator.data_path().map(|base| {
-        base.join("mods/contents/0100a6301214e000/skyline/exefs/subsdk9")
-    })
-}
+        let window = use_window();
+        window.set_always_on_top(false);
 
-#[cfg(feature = "desktop")]
-async fn delete_bad_subsdk9(emulator: &Emulator) {
-    if let Some(path) = construct_bad_subsdk9_path(emulator) {
-        if path.exists() {
-            tracing::info!("Deleting bad subsdk9");
-            std::fs::remove_file(path).unwrap();
-        } else {
-            tracing::info!("No bad subsdk9 found");
-        }
-    } else {
-        tracing::error!("Could not find {} folder", emulator.name);
-    }
-}
+        // Follow moves and resizes in memory, and save when the window loses focus or is
+        // closed rather than on every step of a drag.
+        let mut window_state = use_signal(|| settings::WINDOW.get());
+        use_wry_event_handler({
+            let window = window.clone();
+            move |event, _| {
+                let dioxus::desktop::tao::event::Event::WindowEvent { event, .. } = event else {
+                    return;
+                };
+                match event {
+                    dioxus::desktop::WindowEvent::Moved(_) | dioxus::desktop::WindowEvent::Resized(_) => {
+                        let captured = window_state::WindowState::capture(&window.window, window_state());
+                        window_state.set(captured);
+                    }
+                    dioxus::desktop::WindowEvent::Focused(false) | dioxus::desktop::WindowEvent::CloseRequested
+                        if window_state() != settings::WINDOW.get() =>
+                    {
+                        settings::WINDOW.set(&window_state());
+                    }
+                    _ => {}
+                }
+            }
+        });
 
-async fn download_release() -> reqwest::Response {
-    reqwest::get(RELEASE_URL)
-        .await
-        .unwrap()
-}
+        // The page starts out in the saved theme (see theme::head), this follows changes.
+        let theme = settings::THEME.use_synced();
+        use_effect(move || theme::apply(theme()));
 
-#[cfg(feature = "desktop")]
-async fn extract_release(zip_archive_bytes: &[u8], dest: PathBuf) {
-    let reader = std::io::Cursor::new(zip_archive_bytes);
-    let mut archive = ZipArchive::new(reader).unwrap();
+        let language = settings::LANGUAGE.use_synced();
+        use_effect(move || *i18n::LANGUAGE.write() = language());
 
-    let files: Vec<String> = archive.file_names().map(String::from).collect();
-    for name in files {
-        let mut file = archive.by_name(&name).unwrap();
-        let outpath = dest.join(file.name());
+        let verbose_logging = settings::VERBOSE_LOGGING.use_synced();
+        use_effect(move || log_file::set_verbose(verbose_logging()));
 
-        if file.is_dir() {
-            tracing::info!("File {} extracted to \"{}\"", name, outpath.display());
-            std::fs::create_dir_all(&outpath).unwrap();
-        } else {
-            println!(
-                "File {} extracted to \"{}\" ({} bytes)",
-                name,
-                outpath.display(),
-                file.size()
-            );
-            if let Some(p) = outpath.parent() {
-                if !p.exists() {
-                    std::fs::create_dir_all(&p).unwrap();
-                }
+        let mut scale = settings::UI_SCALE.use_synced();
+        use_effect(move || ui_scale::apply(scale()));
+        use_future(move || async move {
+            let mut shortcuts = ui_scale::listen_for_shortcuts();
+            while let Ok(up) = shortcuts.recv::<bool>().await {
+                scale.set(ui_scale::step(scale(), up));
             }
-            let mut outfile = std::fs::File::create(&outpath).unwrap();
-            let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer).unwrap();
-            outfile.write_all(&buffer).unwrap();
-        }
-    }
-}
-
-#[cfg(feature = "desktop")]
-async fn create_mods_directory(sdcard_path: PathBuf) {
-    let mods_path = sdcard_path.join("engage/mods");
-    if !mods_path.exists() {
-        std::fs::create_dir_all(mods_path).unwrap();
-    } else {
-        tracing::info!("Mods directory already exists");
-    }
-}
+        });
 
-#[component]
-fn App() -> Element {
-    #[cfg(feature = "desktop")]
-    {
-        let window = use_window();
-        window.set_always_on_top(false);
+        // Another copy of the installer was started and closed itself, show this one.
+        use_future({
+            let window = window.clone();
+            move || {
+                let window = window.clone();
+                async move {
+                    let mut interval = tokio::time::interval(std::time::Duration::from_millis(500));
+                    loop {
+                        interval.tick().await;
+                        if single_instance::take_focus_request() {
+                            window.set_minimized(false);
+                            window.set_visible(true);
+                            window.set_focus();
+                        }
+                    }
+                }
+            }
+        });
     }
     rsx! {
         document::Link { rel: "icon", href: FAVICON }
@@ -287,245 +270,3847 @@ fn App() -> Element {
     }
 }
 
-#[cfg(feature = "desktop")]
-fn open_engage_mods_folder(path: impl AsRef<Path>) {
-    let mods_path = path.as_ref().join("engage").join("mods");
-    open_dir(mods_path)
-        .expect("Failed to open mods folder");
-}
-
-#[cfg(feature = "desktop")]
-fn does_engage_mods_folder_exist(path: impl AsRef<Path>) -> bool {
-    let mods_path = path.as_ref().join("engage").join("mods");
-    mods_path.exists()
-}
-
-
 #[component]
 pub fn Hero() -> Element {
     // Shared shell: the welcome header, the status line, and the easter egg. The
     // platform specific controls live in `Controls`, which has a desktop and an
     // Android version below.
-    let mut status_message = use_signal(|| "Waiting for you".to_string());
+    let mut status_message = use_signal(|| t!("status.waiting"));
     let mut num_clicks = use_signal(|| 0);
+    // Bumped by a settings reset. Controls is keyed on it, so it's rebuilt from scratch and
+    // reads the (now default) settings again, like on a first launch.
+    let controls_generation = use_signal(|| 0);
+    let mut show_settings = use_signal(|| false);
+    let mut show_about = use_signal(|| false);
 
     use_effect(move || {
         if num_clicks() == 5 {
-            status_message.set("50 bond fragments obtained.".to_string());
+            status_message.set(t!("status.bond_fragments"));
         }
     });
 
     rsx! {
         div { id: "hero",
+            if cfg!(feature = "desktop") {
+                button {
+                    id: "settings_button",
+                    title: t!("hero.settings"),
+                    onclick: move |_| show_settings.set(true),
+                    "⚙"
+                }
+            }
+            if show_settings() {
+                SettingsPanel { controls_generation, status_message, num_clicks, show: show_settings }
+            }
+            if show_about() {
+                AboutDialog { show: show_about }
+            }
             div {
                 div { id: "welcome",
-                    h1 { "Welcome to the Cobalt Installer" }
+                    h1 { {t!("hero.welcome")} }
                     img {
                         id: "sammie",
                         src: SAMMIE,
-                        alt: "Sammie stares at you, judgingly",
+                        alt: t!("hero.sammie_alt"),
                         onclick: move |_| {
                             num_clicks.set(num_clicks() + 1);
                         },
                     }
+                    CobaltInfo {}
                 }
             }
             div { id: "main-container",
-                Controls { status_message }
+                Controls { key: "{controls_generation}", status_message }
                 div { id: "credits",
                     p {
-                        "Having issues? "
-                        a { href: "https://discord.gg/BH6XhKsKdS", "Get help!" }
+                        {t!("hero.having_issues")}
+                        a { href: HELP_URL, {t!("hero.get_help")} }
+                    }
+                    p { {t!("hero.icon_credit")} }
+                    p {
+                        {t!("hero.version", version = env!("CARGO_PKG_VERSION"))}
+                        if cfg!(feature = "desktop") {
+                            " · "
+                            a {
+                                href: "#",
+                                onclick: move |e| {
+                                    e.prevent_default();
+                                    show_about.set(true);
+                                },
+                                {t!("about.title")}
+                            }
+                        }
                     }
-                    p { "Sommie icon by badatgames26" }
-                    p { "Version {env!(\"CARGO_PKG_VERSION\")}" }
                 }
             }
         }
     }
 }
 
-// Desktop controls: pick an emulator (or a raw SD card folder), then download and
-// unzip Cobalt straight onto the host filesystem.
+// The settings page, over the main screen so nothing underneath (like a running install)
+// is unmounted while it's open. Every control saves as soon as it changes.
 #[cfg(feature = "desktop")]
 #[component]
-fn Controls(mut status_message: Signal<String>) -> Element {
-    let mut installation_type = use_storage::<LocalStorage, String>("installation_type".into(), || { "Ryujinx".to_string()});
+fn SettingsPanel(
+    mut controls_generation: Signal<i32>,
+    mut status_message: Signal<String>,
+    mut num_clicks: Signal<i32>,
+    mut show: Signal<bool>,
+) -> Element {
+    let mut confirm_reset = use_signal(|| false);
 
-    let user_selected_sdcard_path = use_storage::<LocalStorage, String>("sd_card_path".into(), || { "".to_string()});
+    let mut check_installer_updates = settings::CHECK_INSTALLER_UPDATES.use_synced();
+    let mut backup_before_update = settings::BACKUP_BEFORE_UPDATE.use_synced();
+    let mut include_prereleases = settings::INCLUDE_PRERELEASES.use_synced();
+    let mut custom_release_url = settings::CUSTOM_RELEASE_URL.use_synced();
+    let mut preserve_config = settings::PRESERVE_CONFIG.use_synced();
+    let mut verbose_logging = settings::VERBOSE_LOGGING.use_synced();
+    let mut extraction_workers = settings::EXTRACTION_WORKERS.use_synced();
+    let mut write_buffer_kb = settings::WRITE_BUFFER_KB.use_synced();
+    let mut flush_removable = settings::FLUSH_REMOVABLE.use_synced();
+    let mut theme = settings::THEME.use_synced();
+    let mut language = settings::LANGUAGE.use_synced();
+    let mut close_after_install = settings::CLOSE_AFTER_INSTALL.use_synced();
+    let mut scale = settings::UI_SCALE.use_synced();
 
-    let is_install_ready = {
-        if installation_type() == "SD Card" {
-            !user_selected_sdcard_path().is_empty()
-        } else if let Some(emulator) = get_emulator(&installation_type()) {
-            emulator.is_installed()
+    let clean_up = move |_| async move {
+        let swept = run_blocking(sweep::sweep).await;
+        if swept.files == 0 {
+            toast::push(activity::Severity::Info, t!("toast.nothing_to_clean"));
         } else {
-            false
+            toast::push(
+                activity::Severity::Info,
+                t!("toast.cleaned_up", count = swept.files, size = disk::format_bytes(swept.bytes)),
+            );
         }
     };
 
-    let mut cobalt_mod_path = use_signal(|| PathBuf::new());
-
-    use_effect(move || {
-        let sdcard_path = if installation_type() == "SD Card" {
-            PathBuf::from(user_selected_sdcard_path())
-        } else if let Some(emulator) = get_emulator(&installation_type()) {
-            emulator.sd_card_path().expect("Could not find emulator folder")
-        } else {
+    let reset = move |_| {
+        confirm_reset.set(false);
+        if let Err(e) = settings::reset() {
+            tracing::error!("Couldn't reset the installer settings: {e}");
+            status_message.set(t!("settings.reset_failed", error = e));
             return;
-        };
-
-        cobalt_mod_path.set(sdcard_path);
-    });
-
-    let install_cobalt = move |_| async move {
-        tracing::info!("Extracting release to {:?}", cobalt_mod_path);
-
-        if let Some(emulator) = get_emulator(&installation_type()) {
-            delete_bad_subsdk9(emulator).await;
         }
-        tracing::info!("Downloading release");
-        status_message.set("Downloading release".to_string());
-        let response = download_release().await;
-        let zip_archive_bytes = response.bytes().await.unwrap();
-
-        tracing::info!("Extracting release to {:?}", cobalt_mod_path);
-        extract_release(&zip_archive_bytes, cobalt_mod_path()).await;
-        create_mods_directory(cobalt_mod_path()).await;
-        tracing::info!("Installation complete");
-        status_message.set("Installation complete".to_string());
+        status_message.set(t!("status.waiting"));
+        num_clicks.set(0);
+        controls_generation += 1;
+        show.set(false);
     };
 
     rsx! {
-        div {
-            id: "installation_type_container",
-            class: "message_zone first",
-            label { r#for: "installation_type_select", "How would you like to install Cobalt?" }
-            select {
-                id: "installation_type_select",
-                value: installation_type,
-                onchange: move |e| {
-                    installation_type.set(e.value());
-                },
-                for emu in EMULATORS {
-                    option { label: "Install for {emu.name}", value: "{emu.name}" }
+        div { id: "settings_overlay", class: "overlay",
+            div { id: "settings_panel", class: "message_zone first dialog",
+                div { class: "dialog_header",
+                    h2 { {t!("settings.title")} }
+                    button { class: "close", title: t!("common.close"), onclick: move |_| show.set(false), "✕" }
+                }
+                fieldset {
+                    legend { {t!("settings.general")} }
+                    label {
+                        input {
+                            r#type: "checkbox",
+                            checked: check_installer_updates(),
+                            onchange: move |e| check_installer_updates.set(e.checked()),
+                        }
+                        {t!("settings.check_updates")}
+                    }
+                    label {
+                        input {
+                            r#type: "checkbox",
+                            checked: backup_before_update(),
+                            onchange: move |e| backup_before_update.set(e.checked()),
+                        }
+                        {t!("settings.backup_before_update")}
+                    }
+                    label {
+                        input {
+                            r#type: "checkbox",
+                            checked: close_after_install(),
+                            onchange: move |e| close_after_install.set(e.checked()),
+                        }
+                        {t!("settings.close_after_install")}
+                    }
+                    label { r#for: "language_select", {t!("settings.language")} }
+                    select {
+                        id: "language_select",
+                        value: language().key(),
+                        onchange: move |e| language.set(i18n::Language::from_key(&e.value())),
+                        for l in i18n::LANGUAGES {
+                            option { value: l.key(), label: l.native_name() }
+                        }
+                    }
+                    label { r#for: "theme_select", {t!("settings.theme")} }
+                    select {
+                        id: "theme_select",
+                        value: theme().key(),
+                        onchange: move |e| theme.set(theme::Theme::from_key(&e.value())),
+                        for option_theme in theme::THEMES {
+                            option { value: option_theme.key(), label: option_theme.label() }
+                        }
+                    }
+                    label { r#for: "ui_scale_select", {t!("settings.ui_scale")} }
+                    select {
+                        id: "ui_scale_select",
+                        value: "{scale}",
+                        onchange: move |e| {
+                            if let Ok(value) = e.value().parse() {
+                                scale.set(value);
+                            }
+                        },
+                        for option_scale in ui_scale::options() {
+                            option { value: "{option_scale}", label: "{option_scale}%" }
+                        }
+                    }
+                    div { class: "note", {t!("settings.ui_scale_note")} }
+                    button {
+                        class: "secondary",
+                        onclick: move |_| settings::restore_defaults(settings::Section::General),
+                        {t!("settings.restore_defaults")}
+                    }
+                }
+                fieldset {
+                    legend { {t!("settings.network")} }
+                    label {
+                        input {
+                            r#type: "checkbox",
+                            checked: include_prereleases(),
+                            onchange: move |e| include_prereleases.set(e.checked()),
+                        }
+                        {t!("settings.include_prereleases")}
+                    }
+                    label { r#for: "custom_release_url", {t!("settings.custom_release_url")} }
+                    input {
+                        id: "custom_release_url",
+                        r#type: "url",
+                        placeholder: RELEASE_URL,
+                        value: custom_release_url,
+                        oninput: move |e| custom_release_url.set(e.value().trim().to_string()),
+                    }
+                    div { class: "note", {t!("settings.custom_release_url_note")} }
+                    button {
+                        class: "secondary",
+                        onclick: move |_| settings::restore_defaults(settings::Section::Network),
+                        {t!("settings.restore_defaults")}
+                    }
+                }
+                fieldset {
+                    legend { {t!("settings.advanced")} }
+                    label {
+                        input {
+                            r#type: "checkbox",
+                            checked: preserve_config(),
+                            onchange: move |e| preserve_config.set(e.checked()),
+                        }
+                        {t!("settings.preserve_config")}
+                    }
+                    label {
+                        input {
+                            r#type: "checkbox",
+                            checked: verbose_logging(),
+                            onchange: move |e| verbose_logging.set(e.checked()),
+                        }
+                        {t!("settings.verbose_logging")}
+                    }
+                    div { class: "note", {t!("settings.verbose_logging_note")} }
+                    label { r#for: "extraction_workers_select", {t!("settings.extraction_workers")} }
+                    select {
+                        id: "extraction_workers_select",
+                        value: "{extraction_workers}",
+                        onchange: move |e| {
+                            if let Ok(value) = e.value().parse() {
+                                extraction_workers.set(value);
+                            }
+                        },
+                        option { value: "0", label: t!("settings.extraction_workers_auto") }
+                        for count in 1..=extract::MAX_WORKERS {
+                            option { value: "{count}", label: "{count}" }
+                        }
+                    }
+                    div { class: "note", {t!("settings.extraction_workers_note")} }
+                    label { r#for: "write_buffer_select", {t!("settings.write_buffer")} }
+                    select {
+                        id: "write_buffer_select",
+                        value: "{write_buffer_kb}",
+                        onchange: move |e| {
+                            if let Ok(value) = e.value().parse() {
+                                write_buffer_kb.set(value);
+                            }
+                        },
+                        option { value: "0", label: t!("settings.extraction_workers_auto") }
+                        for kb in settings::WRITE_BUFFER_CHOICES_KB {
+                            option { value: "{kb}", label: disk::format_bytes(*kb as u64 * 1024) }
+                        }
+                    }
+                    div { class: "note", {t!("settings.write_buffer_note")} }
+                    label {
+                        input {
+                            r#type: "checkbox",
+                            checked: flush_removable(),
+                            onchange: move |e| flush_removable.set(e.checked()),
+                        }
+                        {t!("settings.flush_removable")}
+                    }
+                    div { class: "note", {t!("settings.flush_removable_note")} }
+                    button {
+                        class: "secondary",
+                        onclick: move |_| settings::restore_defaults(settings::Section::Advanced),
+                        {t!("settings.restore_defaults")}
+                    }
+                }
+                div { class: "action_zone_buttons",
+                    button { id: "clean_up_button", class: "secondary", onclick: clean_up, {t!("settings.clean_up")} }
+                }
+                div { class: "note", {t!("settings.clean_up_note")} }
+                if confirm_reset() {
+                    div { {t!("settings.reset_warning")} }
+                    div { class: "action_zone_buttons",
+                        button { class: "primary", onclick: reset, {t!("settings.reset")} }
+                        button { class: "secondary", onclick: move |_| confirm_reset.set(false), {t!("common.cancel")} }
+                    }
+                } else {
+                    button {
+                        class: "secondary",
+                        onclick: move |_| confirm_reset.set(true),
+                        {t!("settings.reset_all")}
+                    }
                 }
-                option { label: "Install onto SD card", value: "SD Card" }
             }
         }
-        if installation_type() == "SD Card" {
-            SdCardSelector { selected_sdcard_path: user_selected_sdcard_path }
-        }
-        if get_emulator(&installation_type()).is_some() {
-            EmulatorMessageZone { emulator_name: installation_type() }
+    }
+}
+
+#[cfg(feature = "desktop")]
+#[component]
+fn AboutDialog(mut show: Signal<bool>) -> Element {
+    let mut notice = use_signal(String::new);
+    let commit = env!("INSTALLER_GIT_COMMIT");
+    let log_level = log_file::level();
+
+    let copy_info = move |_| async move {
+        match try_copy_to_clipboard(&version_info()).await {
+            Ok(()) => notice.set(t!("about.copied")),
+            Err(e) => notice.set(t!("about.copy_failed", error = e)),
         }
+    };
 
-        div {
-            id: "action_zone",
-            class: {if is_install_ready { "message_zone third" } else { "message_zone disabled" }},
-            div { class: "action_zone_buttons",
-                button {
-                    id: "install_button",
-                    class: "primary",
-                    onclick: install_cobalt,
-                    disabled: !is_install_ready,
-                    "Install Cobalt"
+    rsx! {
+        div { id: "about_overlay", class: "overlay",
+            div { id: "about_panel", class: "message_zone first dialog",
+                div { class: "dialog_header",
+                    h2 { {t!("about.title")} }
+                    button { class: "close", title: t!("common.close"), onclick: move |_| show.set(false), "✕" }
                 }
-                button {
-                    id: "open_mods_folder_button",
-                    class: "secondary",
-                    disabled: !does_engage_mods_folder_exist(cobalt_mod_path()),
-                    onclick: move |_| {
-                        open_engage_mods_folder(cobalt_mod_path());
-                    },
-                    "Open Cobalt Mods Folder"
+                div { {t!("hero.version", version = env!("CARGO_PKG_VERSION"))} }
+                if !commit.is_empty() {
+                    div { {t!("about.commit")} code { {commit} } }
+                }
+                div { {t!("about.release_url")} code { {download::release_url_in_use()} } }
+                if !log_level.is_empty() {
+                    div { {t!("about.log_level")} code { {log_level} } }
+                }
+                div { class: "about_links",
+                    a { href: INSTALLER_REPO_URL, {t!("about.installer_repo")} }
+                    a { href: COBALT_REPO_URL, {t!("about.cobalt_repo")} }
+                }
+                details {
+                    summary { {t!("about.licenses")} }
+                    if THIRD_PARTY_LICENSES.is_empty() {
+                        div { class: "note", {t!("about.no_licenses")} }
+                    } else {
+                        pre { {THIRD_PARTY_LICENSES} }
+                    }
+                }
+                div { class: "action_zone_buttons",
+                    button { class: "secondary", onclick: copy_info, {t!("about.copy")} }
+                }
+                if !notice().is_empty() {
+                    div { class: "note", {notice} }
                 }
-            }
-            code { class: "status",
-                "Status: "
-                {status_message}
             }
         }
     }
 }
 
-// Android controls: Eden only. The user grants Eden's folder through the system
-// picker (once, it sticks), then we download Cobalt and hand the bytes to Kotlin
-// to write through the Storage Access Framework.
-#[cfg(target_os = "android")]
+#[cfg(not(feature = "desktop"))]
 #[component]
-fn Controls(mut status_message: Signal<String>) -> Element {
-    // Seed from any grant the user gave on a previous run.
-    let mut tree_uri = use_signal(|| saf::persisted_tree_uri());
+fn AboutDialog(show: Signal<bool>) -> Element {
+    rsx! {}
+}
 
-    let is_install_ready = tree_uri().is_some();
+// Collapsed under the welcome header. Shows the cached README straight away and swaps in
+// a fresh one if the background fetch brings one, the bundled blurb until then.
+#[cfg(feature = "desktop")]
+#[component]
+fn CobaltInfo() -> Element {
+    let mut readme = use_signal(cobalt_info::cached);
 
-    let grant_access = move |_| {
-        if let Err(e) = saf::request_tree_access() {
-            status_message.set(format!("Couldn't open the folder picker: {e}"));
-            return;
+    use_future(move || async move {
+        if let Some(markdown) = cobalt_info::refresh().await {
+            readme.set(Some(markdown));
         }
-        // The picker runs in the system UI on its own, so we can't await it. Poll for the
-        // outcome of THIS pick (not the persisted grant, which could be a stale one from a
-        // previous pick) so the most recent choice always wins.
-        spawn(async move {
-            for _ in 0..600 {
-                futures_timer::Delay::new(std::time::Duration::from_millis(300)).await;
-                match saf::pick_outcome() {
-                    1 => {
-                        tree_uri.set(saf::persisted_tree_uri());
-                        break;
+    });
+
+    let blocks = readme.read().as_deref().map(cobalt_info::parse).filter(|b| !b.is_empty());
+
+    rsx! {
+        details { id: "cobalt_info",
+            summary { {t!("info.title")} }
+            match blocks {
+                Some(blocks) => rsx! {
+                    for block in blocks {
+                        match block {
+                            cobalt_info::Block::Heading(text) => rsx! { h3 { {text} } },
+                            cobalt_info::Block::Paragraph(text) => rsx! { p { {text} } },
+                            cobalt_info::Block::List(items) => rsx! {
+                                ul {
+                                    for item in items {
+                                        li { {item} }
+                                    }
+                                }
+                            },
+                        }
                     }
-                    2 => {
-                        tree_uri.set(None);
-                        status_message.set("That's not Eden's folder. Tap the button again and pick Eden's folder.".to_string());
-                        break;
+                },
+                None => rsx! {
+                    p { {t!("info.blurb")} }
+                    ul {
+                        li { {t!("info.feature_mods")} }
+                        li { {t!("info.feature_several")} }
+                        li { {t!("info.feature_anywhere")} }
                     }
-                    _ => {}
-                }
+                },
             }
-        });
+            a { href: COBALT_REPO_URL, {t!("info.more")} }
+        }
+    }
+}
+
+#[cfg(not(feature = "desktop"))]
+#[component]
+fn CobaltInfo() -> Element {
+    rsx! {}
+}
+
+// Android has no settings page yet.
+#[cfg(not(feature = "desktop"))]
+#[component]
+fn SettingsPanel(controls_generation: Signal<i32>, status_message: Signal<String>, num_clicks: Signal<i32>, show: Signal<bool>) -> Element {
+    rsx! {}
+}
+
+// Desktop controls: pick an emulator (or a raw SD card folder), then download and
+// unzip Cobalt straight onto the host filesystem.
+#[cfg(feature = "desktop")]
+#[component]
+fn Controls(mut status_message: Signal<String>) -> Element {
+    let mut installation_type = settings::INSTALLATION_TYPE.use_synced();
+
+    let user_selected_sdcard_path = settings::SD_CARD_PATH.use_synced();
+
+    let is_install_ready = match installation_type().emulator() {
+        Some(emulator) => emulator.is_installed(),
+        None => !user_selected_sdcard_path().is_empty(),
     };
 
-    let install_cobalt = move |_| async move {
-        status_message.set("Downloading release".to_string());
-        let response = download_release().await;
-        let zip_archive_bytes = match response.bytes().await {
-            Ok(bytes) => bytes,
-            Err(e) => {
-                status_message.set(format!("Download failed: {e}"));
-                return;
+    let mut cobalt_mod_path = use_signal(PathBuf::new);
+
+    // Bumped to look for the emulators again, when a folder found earlier turns out to be gone.
+    let detection = use_signal(|| 0u32);
+
+    // Where the files will go, or why that can't be worked out yet.
+    let resolved_target = use_memo(move || {
+        detection.read();
+        resolve_target(installation_type(), &user_selected_sdcard_path())
+    });
+
+    use_effect(move || {
+        let sdcard_path = match resolved_target() {
+            Ok(path) => path,
+            Err(reason) => {
+                tracing::info!("No install target: {reason}");
+                PathBuf::new()
             }
         };
 
-        // Clean up a stray subsdk9 from an old bad install before writing the new one.
-        let _ = saf::delete_bad_subsdk9();
+        cobalt_mod_path.set(sdcard_path);
+    });
 
-        status_message.set("Installing into Eden".to_string());
-        match saf::install_zip(&zip_archive_bytes) {
-            Ok(true) => status_message.set("Installation complete".to_string()),
-            Ok(false) => status_message.set("Install failed: couldn't write into Eden's folder".to_string()),
-            Err(e) => status_message.set(format!("Install failed: {e}")),
-        }
-    };
+    // First launch only: a callout on the installation type, then one on where it
+    // installs, each dismissed in turn.
+    let mut first_run_completed = settings::FIRST_RUN_COMPLETED.use_synced();
+    let mut showing_target_hint = use_signal(|| false);
+    let hint_on_type = !first_run_completed() && !showing_target_hint();
+    let hint_on_target = !first_run_completed() && showing_target_hint();
 
-    rsx! {
-        div {
-            id: "installation_type_container",
-            class: "message_zone first",
-            div { "This installs Cobalt into the Eden emulator." }
+    let mut include_prereleases = settings::INCLUDE_PRERELEASES.use_synced();
+
+    // The toggle lives on the settings page.
+    let check_installer_updates = settings::CHECK_INSTALLER_UPDATES.use_synced();
+
+    let mut updating_installer = use_signal(|| false);
+
+    let installer_update = use_resource(move || async move {
+        if check_installer_updates() {
+            installer_update::check().await
+        } else {
+            None
+        }
+    });
+
+    // Resolved in the background on launch (and again when the channel changes). A failed
+    // lookup just means no update badge, it's never reported.
+    let latest_release = use_resource(move || async move {
+        match release::latest(include_prereleases()).await {
+            Ok(release) => release,
+            Err(e) => {
+                tracing::info!("Couldn't check for Cobalt updates: {e}");
+                None
+            }
+        }
+    });
+
+    // The tag picked in the version dropdown, or empty for "latest".
+    let mut selected_version = settings::SELECTED_VERSION.use_synced();
+
+    let releases = use_resource(move || async move {
+        match release::list(include_prereleases()).await {
+            Ok(releases) => releases,
+            Err(e) => {
+                tracing::info!("Couldn't list Cobalt releases: {e}");
+                Vec::new()
+            }
+        }
+    });
+
+    let mut backup_before_update = settings::BACKUP_BEFORE_UPDATE.use_synced();
+
+    let mut installed_manifest = use_signal(|| None::<Manifest>);
+
+    use_effect(move || {
+        installed_manifest.set(Manifest::load(&cobalt_mod_path()));
+    });
+
+    // "7 mods, 412 MB" per target, worked out in the background when a target is picked
+    // and again after every install. Kept for the session so switching back and forth
+    // between targets doesn't start from "—" each time.
+    let mut mods_summaries = use_signal(std::collections::HashMap::<PathBuf, (usize, u64)>::new);
+
+    use_effect(move || {
+        installed_manifest.read();
+        let target = cobalt_mod_path();
+        spawn(async move {
+            let measured = target.clone();
+            let result = tokio::task::spawn_blocking(move || mods::summary(&measured))
+                .await
+                .expect("Blocking task panicked");
+            match result {
+                Ok(Some(summary)) => {
+                    mods_summaries.write().insert(target, summary);
+                }
+                Ok(None) => {
+                    mods_summaries.write().remove(&target);
+                }
+                Err(e) => {
+                    tracing::info!("Couldn't summarize the mods in {}: {e}", target.display());
+                    mods_summaries.write().remove(&target);
+                }
+            }
+        });
+    });
+
+    let mods_summary = match mods_summaries.read().get(&cobalt_mod_path()) {
+        Some((1, size)) => t!("controls.mods_summary_one", size = disk::format_bytes(*size)),
+        Some((count, size)) => t!("controls.mods_summary_many", count = count, size = disk::format_bytes(*size)),
+        None => "—".to_string(),
+    };
+
+    // The newest log or crash report on the target, looked up again when the target
+    // changes or the logs button is used.
+    let mut newest_log = use_signal(|| None::<(String, u64)>);
+
+    use_effect(move || {
+        newest_log.set(newest_file(&cobalt_mod_path().join(LOGS_DIR)));
+    });
+
+    // The receipt on the target itself, which may come from another computer's installer.
+    let mut target_receipt = use_signal(|| None::<receipt::Receipt>);
+
+    // Whether Cobalt's loader is on the target, however it got there.
+    let mut cobalt_files_found = use_signal(|| false);
+
+    use_effect(move || {
+        installed_manifest.read();
+        target_receipt.set(receipt::Receipt::load(&cobalt_mod_path()));
+        cobalt_files_found.set(layout::has_cobalt(&cobalt_mod_path()));
+    });
+
+    // Cobalt is already on the target, so the main button updates it instead of installing.
+    let has_existing_install =
+        use_memo(move || installed_manifest().is_some() || target_receipt().is_some() || cobalt_files_found());
+    let primary_action = move || if has_existing_install() { history::Action::Update } else { history::Action::Install };
+
+    let mut show_restore = use_signal(|| false);
+
+    let installed_version = installed_manifest().and_then(|m| m.version).map(|v| v.trim_start_matches('v').to_string());
+
+    // For under the update button. Installs we didn't make may still have a receipt.
+    let installed_version_label = installed_version
+        .clone()
+        .or_else(|| target_receipt().and_then(|r| r.cobalt_version).map(|v| v.trim_start_matches('v').to_string()))
+        .unwrap_or_else(|| t!("controls.unknown_version"));
+
+    let update_available = match (latest_release.read().as_ref(), installed_version.as_ref()) {
+        (Some(Some(latest)), Some(installed)) if release::is_newer(latest.version(), installed) => {
+            Some(latest.version().to_string())
+        }
+        _ => None,
+    };
+
+    // Said once per version in a toast, the update banner keeps saying it after that.
+    let mut announced_update = use_signal(|| None::<String>);
+    use_effect(move || {
+        let installed = installed_manifest().and_then(|m| m.version);
+        let latest = latest_release.read().clone().flatten();
+        if let (Some(latest), Some(installed)) = (latest, installed) {
+            let version = latest.version().to_string();
+            if release::is_newer(&version, installed.trim_start_matches('v'))
+                && announced_update.peek().as_ref() != Some(&version)
+            {
+                toast::push(activity::Severity::Info, t!("toast.update_available", version = version.clone()));
+                announced_update.set(Some(version));
+            }
+        }
+    });
+
+    // What the install button would put on the target. Both lookups run in the background,
+    // until they land (or if they fail) this is None.
+    let target_release = if selected_version().is_empty() {
+        latest_release.read().clone().flatten()
+    } else {
+        releases.read().iter().flatten().find(|r| r.tag_name == selected_version()).cloned()
+    };
+
+    // Whether that would go backwards, for the button label. The confirmation itself is up
+    // to run_install, which checks whatever release it ends up resolving.
+    let is_downgrade = match (target_release.as_ref(), installed_version.as_ref()) {
+        (Some(target), Some(installed)) => release::is_older(target.version(), installed),
+        _ => false,
+    };
+
+    // (installed, older, action) while an install that would downgrade waits for the
+    // user's go-ahead.
+    let mut pending_downgrade = use_signal(|| None::<(String, String, history::Action)>);
+
+    // The action of an SD card install waiting on the confirmation card, and whether the
+    // user just said yes to it.
+    let mut pending_sd_confirm = use_signal(|| None::<history::Action>);
+    let mut sd_confirmed = use_signal(|| false);
+    let mut sd_dont_ask = use_signal(|| false);
+
+    // " 1.4.2 (38.1 MB)" after the install button's verb, once we know what it'll install.
+    let target_release_label = {
+        match target_release.as_ref() {
+            Some(release) => match release.zip_asset() {
+                Some(asset) => format!(" {} ({})", release.version(), disk::format_bytes(asset.size)),
+                None => format!(" {}", release.version()),
+            },
+            None => String::new(),
+        }
+    };
+
+    let target_version_label = match target_release.as_ref() {
+        Some(release) => release.version().to_string(),
+        None if !selected_version().is_empty() => selected_version().trim_start_matches('v').to_string(),
+        None => t!("controls.latest"),
+    };
+
+    let install_button_label = if is_downgrade {
+        t!("controls.downgrade_cobalt")
+    } else if has_existing_install() {
+        t!("controls.update_cobalt")
+    } else {
+        t!("controls.install_cobalt")
+    };
+
+    let history_entries = use_signal(history::load);
+
+    // The last successful install, shown until the next action starts.
+    let mut install_summary = use_signal(|| None::<summary::InstallSummary>);
+
+    // Everything the installer did this session, for the log panel.
+    let activity = use_signal(Vec::<activity::LogEntry>::new);
+
+    // Where the running (or last) install is. The status line, progress bar and install
+    // buttons all follow it.
+    let mut install_state = use_signal(InstallState::default);
+    use_effect(move || crash::note_state(&install_state.read()));
+
+    // A crash from an earlier session nobody has been asked to report yet.
+    let mut last_crash = use_signal(crash::unreported);
+
+    // Anything else that reports on the status line (opening folders, uninstalling, ...)
+    // takes over from a finished install's state. A failure stays up until the next run.
+    use_effect(move || {
+        status_message.read();
+        let state = install_state.peek().clone();
+        if !state.is_running() && !state.is_failed() {
+            install_state.set(InstallState::Idle);
+        }
+    });
+
+    // Swaps the action zone for the "what now" screen after a successful install, until
+    // the user goes back.
+    let mut show_success = use_signal(|| false);
+
+    // Seconds until the window closes by itself, when that's turned on. Only ever started
+    // by a successful install, and gone for good once the user does anything.
+    let mut close_countdown = use_signal(|| None::<u32>);
+
+    // Why the last run failed, shown above the action zone until the next one starts.
+    let mut install_error = use_signal(|| None::<FailedRun>);
+
+    // The running install's, cancelled by the Cancel button or the close prompt. Every
+    // stage checks it, a new one is made for each install.
+    let mut cancel = use_signal(CancelToken::new);
+
+    // When the running install started, its current phase and when that started, for the
+    // timer next to the progress bar. `clock_tick` moves it along once a second.
+    let mut install_clock = use_signal(|| None::<(std::time::Instant, progress::Phase, std::time::Instant)>);
+    let mut clock_tick = use_signal(|| 0u32);
+    let clock_running = use_memo(move || install_clock().is_some());
+
+    // Restarted (and the old one dropped) whenever an install starts or ends, so it only
+    // ticks while there's a clock to move.
+    use_resource(move || async move {
+        if !clock_running() {
+            return;
+        }
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+        // The first tick is immediate.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            clock_tick += 1;
+        }
+    });
+    let elapsed_text = install_clock().map(|(started, phase, phase_started)| {
+        clock_tick.read();
+        t!(
+            "progress.elapsed",
+            phase = i18n::tr(phase.label_key()),
+            elapsed = progress::format_elapsed(phase_started.elapsed()),
+            total = progress::format_elapsed(started.elapsed()),
+        )
+    });
+
+    // The file extraction is on, shown under the progress bar so a slow card doesn't
+    // look stuck.
+    let mut current_entry = use_signal(|| None::<String>);
+
+    // Each step of the last install and how it went, by label key.
+    let mut step_status = use_signal(Vec::<(&'static str, StepStatus)>::new);
+
+    // Left on the target by an install that never finished, if any.
+    let mut interrupted = use_signal(|| None::<marker::Interrupted>);
+
+    use_effect(move || {
+        interrupted.set(marker::find(&cobalt_mod_path()));
+    });
+
+    // `action` is what goes in the history: a normal install, or a repair finishing off an
+    // interrupted one. Unless `allow_older` is set, resolving a release older than the
+    // installed one stops here and asks for confirmation first.
+    let install_steps = move |action: history::Action, allow_older: bool| async move {
+        // SD card folders are picked by hand, so nothing is written before the user has
+        // seen exactly where it goes. A downgrade's second run already got past this.
+        let confirmed = sd_confirmed.replace(false);
+        if installation_type().is_sd_card()
+            && !allow_older
+            && !confirmed
+            && !settings::SD_CARD_SKIP_CONFIRM.get().contains(&user_selected_sdcard_path())
+        {
+            sd_dont_ask.set(false);
+            pending_sd_confirm.set(Some(action));
+            install_state.set(InstallState::Idle);
+            return;
+        }
+        pending_sd_confirm.set(None);
+        // Everything below works on the target as it was when the install started, so
+        // changing the installation type or SD folder mid-run can't redirect the writes.
+        let target = cobalt_mod_path();
+        let target_type = installation_type();
+        let previous_manifest = installed_manifest();
+        activity::push(activity, activity::Severity::Info, format!("Starting an install into {}", target.display()));
+        let started = std::time::Instant::now();
+        install_summary.set(None);
+        pending_downgrade.set(None);
+        install_state.set(InstallState::Preparing);
+        install_error.set(None);
+        show_success.set(false);
+        close_countdown.set(None);
+        cancel.set(CancelToken::new());
+        let cancel = cancel();
+
+        // One bar across every phase, each phase's share depending on which ones run.
+        let mut pipeline = progress::Pipeline::new(&[
+            progress::Phase::Prepare,
+            progress::Phase::Download,
+            progress::Phase::Backup,
+            progress::Phase::Extract,
+            progress::Phase::Finalize,
+            progress::Phase::Flush,
+        ]);
+        let mut report = move |pipeline: &progress::Pipeline| {
+            if let Some(state) = pipeline.state() {
+                install_state.set(state);
+            }
+            install_clock.set(pipeline.phase().zip(pipeline.phase_started()).map(|(phase, at)| (started, phase, at)));
+        };
+        pipeline.start(progress::Phase::Prepare);
+        report(&pipeline);
+
+        let custom_release_url = settings::CUSTOM_RELEASE_URL.get();
+
+        // A tag picked in the dropdown has to be found, there's no plain URL to fall back to.
+        // For "latest", prefer the release the update check resolved so the manifest knows
+        // which tag went in. If GitHub's API is unreachable we still install from RELEASE_URL.
+        let release = if !selected_version().is_empty() {
+            let listed = releases.read().as_ref().and_then(|list| {
+                list.iter().find(|r| r.tag_name == selected_version()).cloned()
+            });
+            let found = match listed {
+                Some(release) => Some(release),
+                None => release::list(include_prereleases())
+                    .await
+                    .ok()
+                    .and_then(|list| list.into_iter().find(|r| r.tag_name == selected_version())),
+            };
+            if found.is_none() {
+                let message = format!("Couldn't find Cobalt {} on GitHub", selected_version());
+                activity::push(activity, activity::Severity::Error, message.clone());
+                record_history(history_entries, history::Entry {
+                    timestamp: history::now(),
+                    action,
+                    version: Some(selected_version()),
+                    target_type: target_type.name().to_string(),
+                    target: target.clone(),
+                    outcome: history::Outcome::Failed(message),
+                    duration_secs: started.elapsed().as_secs_f64(),
+                    bytes_written: 0,
+                    phases: pipeline.phase_times(),
+                });
+                install_state.set(InstallState::Failed {
+                    error: t!("status.release_not_found", version = selected_version()),
+                    progress: 0.0,
+                });
+                install_error.set(Some(FailedRun::new(
+                    Failure::ReleaseNotFound,
+                    t!("status.release_not_found", version = selected_version()),
+                    t!("error.release_not_found", version = selected_version()),
+                )));
+                return;
+            }
+            found
+        } else if !custom_release_url.is_empty() {
+            // A custom build from the settings page, we don't know which version it is.
+            None
+        } else {
+            match latest_release.read().as_ref() {
+                Some(Some(release)) => Some(release.clone()),
+                _ => release::latest(include_prereleases()).await.ok().flatten(),
+            }
+        };
+        let url = release
+            .as_ref()
+            .and_then(|r| r.zip_asset())
+            .map(|a| a.browser_download_url.clone())
+            .unwrap_or_else(|| if custom_release_url.is_empty() { RELEASE_URL.to_string() } else { custom_release_url });
+
+        // Whichever way we got here (a picked tag, "latest" on another channel, a repair),
+        // never go backwards without the user saying so.
+        let installed = previous_manifest.as_ref().and_then(|m| m.version.clone());
+        if let (false, Some(release), Some(installed)) = (allow_older, release.as_ref(), installed) {
+            if release::is_older(release.version(), &installed) {
+                activity::push(
+                    activity,
+                    activity::Severity::Info,
+                    format!("{} is older than the installed {installed}, asking first", release.tag_name),
+                );
+                pending_downgrade.set(Some((
+                    installed.trim_start_matches('v').to_string(),
+                    release.version().to_string(),
+                    action,
+                )));
+                install_state.set(InstallState::Idle);
+                return;
+            }
+        }
+        interrupted.set(None);
+
+        // Held until this returns, whichever way the install ends.
+        let _lock = match target_lock::acquire(&target) {
+            Ok(lock) => lock,
+            Err(holder) => {
+                let message = format!("Not installing, {holder} is already installing into {}", target.display());
+                activity::push(activity, activity::Severity::Error, message.clone());
+                record_history(history_entries, history::Entry {
+                    timestamp: history::now(),
+                    action,
+                    version: release.map(|r| r.tag_name),
+                    target_type: target_type.name().to_string(),
+                    target: target.clone(),
+                    outcome: history::Outcome::Failed(message.clone()),
+                    duration_secs: started.elapsed().as_secs_f64(),
+                    bytes_written: 0,
+                    phases: pipeline.phase_times(),
+                });
+                install_state.set(InstallState::Failed { error: t!("status.target_in_use"), progress: 0.0 });
+                install_error.set(Some(FailedRun::new(
+                    Failure::TargetInUse,
+                    t!("error.target_in_use", holder = holder),
+                    format!("{message}\nLock file: {}", target.join(target_lock::LOCK_FILE).display()),
+                )));
+                return;
+            }
+        };
+
+        // An update never resets the user's config, the setting only decides for installs.
+        let preserve_config = action == history::Action::Update || settings::PRESERVE_CONFIG.get();
+        let mut install = Install {
+            version: release.map(|r| r.tag_name),
+            previous: previous_manifest,
+            emulator: target_type.emulator(),
+            backup: backup_before_update(),
+            update: action == history::Action::Update,
+            write: install::WriteOptions::for_target(
+                &target,
+                preserve_config,
+                settings::EXTRACTION_WORKERS.get(),
+                settings::WRITE_BUFFER_KB.get(),
+            ),
+            flush: settings::FLUSH_REMOVABLE.get() && install::needs_flush(&target, target_type.is_sd_card()),
+            cancel,
+            ..Install::new(target.clone(), url)
+        };
+        let mut installer = Installer::release();
+        step_status.set(installer.steps().iter().map(|s| (s.label_key, StepStatus::Pending)).collect());
+        let phases: Vec<progress::Phase> = installer.steps().iter().map(|s| s.phase).collect();
+        let steps: Vec<activity::LogStep> =
+            installer.steps().iter().map(|s| activity::LogStep { name: s.name, label_key: s.label_key }).collect();
+        let result = installer
+            .run(&mut install, |event| match event {
+                InstallerEvent::Status(index, status) => {
+                    match status {
+                        StepStatus::Running => {
+                            pipeline.start(phases[index]);
+                            report(&pipeline);
+                        }
+                        StepStatus::Skipped => pipeline.skip(phases[index]),
+                        _ => {}
+                    }
+                    step_status.write()[index].1 = status;
+                }
+                InstallerEvent::Step(_, StepEvent::Progress(fraction)) => {
+                    pipeline.update(fraction);
+                    report(&pipeline);
+                }
+                InstallerEvent::Step(index, StepEvent::Note(severity, message)) => {
+                    activity::push_in(activity, Some(steps[index]), severity, message)
+                }
+                InstallerEvent::Step(_, StepEvent::Entry(entry)) => current_entry.set(entry),
+                InstallerEvent::RollingBack => install_state.set(InstallState::CleaningUp),
+            })
+            .await;
+        if let (true, Some(emulator)) = (install.removed_subsdk9, install.emulator) {
+            toast::push(activity::Severity::Info, t!("toast.removed_subsdk9", emulator = emulator.name));
+        }
+
+        let version = install.version.clone();
+        let extracted = install.extracted.take().unwrap_or_default();
+        let record = |outcome: history::Outcome, phases: Vec<(progress::Phase, f64)>| {
+            record_history(history_entries, history::Entry {
+                timestamp: history::now(),
+                action,
+                version: version.clone(),
+                target_type: target_type.name().to_string(),
+                target: target.clone(),
+                outcome,
+                duration_secs: started.elapsed().as_secs_f64(),
+                bytes_written: extracted.bytes,
+                phases,
+            })
+        };
+        match result {
+            Ok(()) => {}
+            Err(StepError { error: InstallerError::Cancelled, rollback: None, .. }) => {
+                // Up to the extraction, each step deletes its own half finished files and
+                // there's nothing to record.
+                let wrote = installer
+                    .steps()
+                    .iter()
+                    .zip(installer.status())
+                    .any(|(step, status)| step.phase == progress::Phase::Extract && *status == StepStatus::RolledBack);
+                if wrote {
+                    record(history::Outcome::Failed("Cancelled".to_string()), pipeline.phase_times());
+                } else {
+                    activity::push(activity, activity::Severity::Warning, "Installation cancelled, nothing was written");
+                }
+                install_state.set(InstallState::Cancelled);
+                return;
+            }
+            Err(StepError { error: InstallerError::Cancelled, rollback: Some(e), .. }) => {
+                activity::push(activity, activity::Severity::Error, format!("Couldn't clean up after cancelling: {e}"));
+                install_state.set(InstallState::Failed { error: e.to_string(), progress: pipeline.overall() });
+                install_error.set(Some(FailedRun::new(Failure::CancelCleanup, Failure::CancelCleanup.summary(), error_chain(&e))));
+                interrupted.set(marker::find(&target));
+                record(history::Outcome::Failed("Cancelled".to_string()), pipeline.phase_times());
+                return;
+            }
+            Err(StepError { step, name, error, .. }) => {
+                activity::push_in(activity, Some(steps[step]), activity::Severity::Error, format!("{name} failed: {error}"));
+                record(history::Outcome::Failed(format!("{name} failed: {error}")), pipeline.phase_times());
+                install_state.set(InstallState::Failed { error: error.to_string(), progress: pipeline.overall() });
+                install_error.set(FailedRun::from_error(&error));
+                // Closed first, so it can be read back.
+                drop(install.marker.take());
+                interrupted.set(marker::find(&target));
+                return;
+            }
+        }
+
+        if !install.removed.is_empty() {
+            toast::push(activity::Severity::Info, t!("toast.removed_stale", count = install.removed.len()));
+        }
+        install.extracted = Some(extracted.clone());
+        installed_manifest.set(install.finish().await);
+
+        pipeline.finish();
+        let duration_secs = started.elapsed().as_secs_f64();
+        record(history::Outcome::Success, pipeline.phase_times());
+
+        activity::push(
+            activity,
+            activity::Severity::Info,
+            format!("Installation complete in {duration_secs:.1}s, {} written", disk::format_bytes(extracted.bytes)),
+        );
+        let phases: Vec<String> =
+            pipeline.phase_times().iter().map(|(phase, secs)| format!("{} {secs:.1}s", i18n::tr(phase.label_key()))).collect();
+        activity::push(activity, activity::Severity::Info, t!("log.phase_times", phases = phases.join(", ")));
+        install_state.set(InstallState::Done {
+            version: version
+                .as_deref()
+                .map(|v| v.trim_start_matches('v').to_string())
+                .unwrap_or_else(|| t!("controls.unknown_version")),
+        });
+        show_success.set(true);
+        settings::FIRST_RUN_COMPLETED.set(&true);
+        if settings::CLOSE_AFTER_INSTALL.get() {
+            close_countdown.set(Some(AUTO_CLOSE_SECS));
+        }
+        install_summary.set(Some(summary::InstallSummary {
+            version,
+            destination: target,
+            added: extracted.added,
+            updated: extracted.updated,
+            removed: install.removed,
+            files_skipped: extracted.skipped,
+            bytes_written: extracted.bytes,
+            duration_secs,
+            phases: pipeline.phase_times(),
+            backup: install.backup_path,
+            warnings: install.warnings,
+        }));
+    };
+
+    // Whether an install is running, start to finish. The install buttons are disabled and
+    // closing the window asks first. The guard is what actually stops a second run, the
+    // state only drives the UI.
+    let run_guard = use_signal(run_guard::RunGuard::default);
+    let installing = use_memo(move || install_state().is_running());
+
+    let run_install = move |action: history::Action, allow_older: bool| async move {
+        let started = run_guard.read().try_start();
+        let running = match started {
+            Ok(running) => running,
+            Err(e) => {
+                activity::push(activity, activity::Severity::Warning, format!("Not starting another install, {e}"));
+                return;
+            }
+        };
+        install_steps(action, allow_older).await;
+        install_clock.set(None);
+        // The run is over, a notification waiting on a click mustn't hold up the next one
+        // or closing the window.
+        drop(running);
+
+        // Only worth a notification if the user is looking at something else. A run that
+        // stopped to ask about a downgrade, or was cancelled, needs no telling.
+        let message = if let Some(summary) = install_summary() {
+            t!("notify.installed", version = summary.version_label())
+        } else if install_state().is_failed() {
+            t!("notify.failed")
+        } else {
+            return;
+        };
+        notify_finished(activity, message).await;
+    };
+
+    // Closing mid-install hides the window instead (that's the only alternative to closing
+    // tao gives us), and the close request brings it straight back with a prompt.
+    let window = use_window();
+    let mut close_requested = use_signal(|| false);
+    let mut exit_when_done = use_signal(|| false);
+
+    use_wry_event_handler(move |event, _| {
+        if let dioxus::desktop::tao::event::Event::WindowEvent {
+            event: dioxus::desktop::WindowEvent::CloseRequested,
+            ..
+        } = event
+        {
+            if run_guard.peek().is_running() {
+                close_requested.set(true);
+            }
+        }
+    });
+
+    use_effect({
+        let window = window.clone();
+        move || {
+            if close_requested() {
+                window.set_visible(true);
+                window.set_focus();
+            }
+        }
+    });
+
+    use_effect(move || {
+        if installing() {
+            window.set_close_behavior(dioxus::desktop::WindowCloseBehaviour::WindowHides);
+        } else {
+            window.set_close_behavior(dioxus::desktop::WindowCloseBehaviour::WindowCloses);
+            close_requested.set(false);
+            if *exit_when_done.peek() {
+                window.close();
+            }
+        }
+    });
+
+    let mut confirm_uninstall = use_signal(|| false);
+
+    // Everywhere else mods could be copied to: the installed emulators and the remembered
+    // SD card folder, minus the target that's selected.
+    let other_targets: Vec<(String, PathBuf)> = EMULATORS
+        .iter()
+        .filter(|e| e.is_installed())
+        .filter_map(|e| e.sd_card_path().map(|p| (e.name.to_string(), p)))
+        .chain(
+            (!user_selected_sdcard_path().is_empty())
+                .then(|| (InstallationType::SdCard.name().to_string(), PathBuf::from(user_selected_sdcard_path()))),
+        )
+        .filter(|(_, path)| *path != cobalt_mod_path())
+        .collect();
+
+    let run_uninstall = move || async move {
+        let Some(manifest) = installed_manifest() else {
+            return;
+        };
+        let started = std::time::Instant::now();
+        install_summary.set(None);
+        status_message.set(t!("status.uninstalling"));
+        activity::push(activity, activity::Severity::Info, format!("Uninstalling Cobalt from {}", cobalt_mod_path().display()));
+        let version = manifest.version.clone();
+        let result = tokio::task::spawn_blocking(move || uninstall::uninstall(&manifest))
+            .await
+            .expect("Blocking task panicked");
+        let (outcome, bytes) = match result {
+            Ok(removed) => {
+                if let Err(e) = Manifest::remove(&cobalt_mod_path()) {
+                    tracing::error!("Couldn't remove the install manifest: {e}");
+                }
+                installed_manifest.set(None);
+                let message = format!(
+                    "Cobalt uninstalled: removed {} files ({}) and {} empty folders",
+                    removed.files_removed,
+                    disk::format_bytes(removed.bytes_removed),
+                    removed.dirs_removed,
+                );
+                activity::push(activity, activity::Severity::Info, message);
+                status_message.set(t!(
+                    "status.uninstalled",
+                    files = removed.files_removed,
+                    size = disk::format_bytes(removed.bytes_removed),
+                    dirs = removed.dirs_removed,
+                ));
+                (history::Outcome::Success, removed.bytes_removed)
+            }
+            Err(e) => {
+                activity::push(activity, activity::Severity::Error, format!("Uninstall failed: {e}"));
+                status_message.set(t!("status.uninstall_failed", error = e));
+                (history::Outcome::Failed(e.to_string()), 0)
+            }
+        };
+        record_history(history_entries, history::Entry {
+            timestamp: history::now(),
+            action: history::Action::Uninstall,
+            version,
+            target_type: installation_type().name().to_string(),
+            target: cobalt_mod_path(),
+            outcome,
+            // For uninstalls this is what was removed rather than written.
+            bytes_written: bytes,
+            duration_secs: started.elapsed().as_secs_f64(),
+            phases: Vec::new(),
+        });
+    };
+
+    let interrupted_notice = interrupted().map(|leftover| {
+        let version = match leftover.version {
+            Some(v) => format!("Cobalt {}", v.trim_start_matches('v')),
+            None => "Cobalt".to_string(),
+        };
+        t!(
+            "controls.interrupted",
+            version = version,
+            started = history::format_timestamp(leftover.started_at),
+            count = leftover.files.len(),
+        )
+    });
+
+    let install_cobalt = move |_| async move {
+        run_install(primary_action(), false).await;
+    };
+
+    let clean_up_interrupted = move |_| async move {
+        let Some(leftover) = interrupted() else {
+            return;
+        };
+        let target = cobalt_mod_path();
+        let result = tokio::task::spawn_blocking(move || marker::clean_up(&target, &leftover))
+            .await
+            .expect("Blocking task panicked");
+        match result {
+            Ok(removed) => {
+                interrupted.set(None);
+                activity::push(activity, activity::Severity::Info, format!("Cleaned up an unfinished install, removed {removed} files"));
+                status_message.set(t!("status.cleaned_up", count = removed));
+            }
+            Err(e) => {
+                activity::push(activity, activity::Severity::Error, format!("Couldn't clean up the unfinished install: {e}"));
+                status_message.set(t!("status.clean_up_failed", error = e));
+            }
+        }
+    };
+
+    let status_text = match install_state() {
+        InstallState::Idle => status_message(),
+        state => state.status_text(),
+    };
+    let status_class = format!("status {}", install_state().style_class());
+
+    // Returning users whose target still works skip straight to the install step.
+    let mut step = use_signal(|| if settings::SETUP_DONE.get() && is_install_ready { Step::Install } else { Step::Target });
+
+    // Free space where the files will go. Only looked up again when the target changes.
+    let free_space = use_memo(move || disk::existing_ancestor(&cobalt_mod_path()).and_then(disk::free_space));
+
+    // Whether the downloaded zip shares a drive with the target. Both unknown counts as
+    // the same, better to ask for too much room than too little.
+    let zip_on_target_drive = use_memo(move || {
+        let mount = |path: &Path| disk::existing_ancestor(path).and_then(disk::mount_point);
+        cache_dir().is_none_or(|cache| mount(&cache) == mount(&cobalt_mod_path()))
+    });
+
+    // Rough room the release needs once unpacked. Its contents compress to around a third,
+    // and the zip itself is streamed to a file in the cache folder and only deleted once
+    // it's been extracted, so it needs room too when that's on the same drive.
+    let space_needed = target_release
+        .as_ref()
+        .and_then(|r| r.zip_asset())
+        .map(|a| a.size * 3 + if zip_on_target_drive() { a.size } else { 0 });
+    let low_space = matches!((free_space(), space_needed), (Some(free), Some(needed)) if free < needed);
+
+    let start_from_wizard = move |_| async move {
+        settings::SETUP_DONE.set(&true);
+        step.set(Step::Install);
+        run_install(primary_action(), false).await;
+    };
+
+    rsx! {
+        toast::Toasts {}
+        if let Some(Some(update)) = installer_update() {
+            div { id: "installer_update_banner",
+                {t!("controls.installer_update", version = update.version)}
+                a { href: "{update.url}", {t!("controls.release_notes")} }
+                button {
+                    class: "secondary",
+                    disabled: updating_installer(),
+                    onclick: move |_| {
+                        let release = update.release.clone();
+                        async move {
+                            updating_installer.set(true);
+                            status_message.set(t!("status.updating_installer", version = release.version()));
+                            match self_update::update(&release).await {
+                                Ok(exe) => {
+                                    if let Err(e) = self_update::relaunch(&exe) {
+                                        tracing::error!("Couldn't restart the installer: {e}");
+                                        status_message.set(t!("status.installer_updated_restart"));
+                                    }
+                                }
+                                Err(e) => {
+                                    tracing::error!("Installer update failed: {e}");
+                                    status_message.set(t!("status.installer_update_failed", error = e));
+                                }
+                            }
+                            updating_installer.set(false);
+                        }
+                    },
+                    {t!("controls.update_now")}
+                }
+            }
+        }
+        if let Some(record) = last_crash() {
+            div { id: "crash_banner",
+                {t!("crash.banner")}
+                a {
+                    href: bug_report::crash_issue_url(&record, installation_type()),
+                    onclick: {
+                        let record = record.clone();
+                        move |_| {
+                            crash::mark_reported(&record);
+                            last_crash.set(None);
+                        }
+                    },
+                    {t!("crash.report")}
+                }
+                button {
+                    class: "secondary",
+                    onclick: move |_| {
+                        crash::mark_reported(&record);
+                        last_crash.set(None);
+                    },
+                    {t!("common.dismiss")}
+                }
+            }
+        }
+        WizardSteps { current: step, has_target: is_install_ready, locked: installing() }
+        match step() {
+            Step::Target => rsx! {
+                div {
+                    id: "installation_type_container",
+                    class: if hint_on_type { "message_zone first hint_highlight" } else { "message_zone first" },
+                    div { class: "label_with_help",
+                        label { r#for: "installation_type_select", {t!("controls.how_to_install")} }
+                        Help { text: t!("help.installation_type") }
+                    }
+                    select {
+                        id: "installation_type_select",
+                        value: installation_type().name(),
+                        disabled: installing(),
+                        onchange: move |e| {
+                            installation_type.set(InstallationType::from(e.value()));
+                        },
+                        for option_type in installation_type::INSTALLATION_TYPES {
+                            option {
+                                label: if option_type.is_sd_card() {
+                                    t!("controls.install_sd")
+                                } else {
+                                    t!("controls.install_for", emulator = option_type.name())
+                                },
+                                value: option_type.name(),
+                            }
+                        }
+                    }
+                    if hint_on_type {
+                        Hint {
+                            text: t!("hint.installation_type"),
+                            on_next: move |_| showing_target_hint.set(true),
+                            on_dismiss: move |_| first_run_completed.set(true),
+                        }
+                    }
+                }
+                div { class: if hint_on_target { "hint_highlight" } else { "" },
+                    if installation_type().is_sd_card() {
+                        SdCardSelector { selected_sdcard_path: user_selected_sdcard_path, locked: installing() }
+                    } else {
+                        EmulatorMessageZone { installation_type: installation_type(), detection }
+                    }
+                    if hint_on_target {
+                        Hint {
+                            text: if installation_type().is_sd_card() { t!("hint.sd_card") } else { t!("hint.emulator") },
+                            on_next: move |_| first_run_completed.set(true),
+                            on_dismiss: move |_| first_run_completed.set(true),
+                        }
+                    }
+                }
+                TargetPath { resolved: resolved_target() }
+                InstalledTargets {
+                    current: cobalt_mod_path(),
+                    installed_manifest,
+                    history_entries,
+                    status_message,
+                }
+                div { class: "wizard_nav",
+                    button {
+                        class: "primary",
+                        disabled: !is_install_ready,
+                        onclick: move |_| step.set(Step::Confirm),
+                        {t!("wizard.next")}
+                    }
+                }
+                code { class: "{status_class}",
+                    {t!("common.status")}
+                    {status_text}
+                }
+            },
+            Step::Confirm => rsx! {
+                div { id: "confirm_step", class: "message_zone first",
+                    div { {t!("wizard.destination")} }
+                    TargetPath { resolved: resolved_target() }
+                    div {
+                        match installed_version.as_ref() {
+                            Some(installed) => t!("wizard.installed_now", version = installed),
+                            None => t!("wizard.nothing_installed"),
+                        }
+                    }
+                    label { id: "prerelease_toggle",
+                        input {
+                            r#type: "checkbox",
+                            checked: include_prereleases(),
+                            disabled: installing(),
+                            onchange: move |e| include_prereleases.set(e.checked()),
+                        }
+                        {t!("settings.include_prereleases")}
+                    }
+                    div { id: "version_picker",
+                        label { r#for: "version_select", {t!("controls.version")} }
+                        select {
+                            id: "version_select",
+                            value: selected_version,
+                            disabled: installing(),
+                            onchange: move |e| {
+                                pending_downgrade.set(None);
+                                selected_version.set(e.value());
+                            },
+                            option { value: "", label: t!("controls.latest") }
+                            for r in releases.read().iter().flatten() {
+                                option { value: "{r.tag_name}", label: "{r.version()}" }
+                            }
+                            // Keep a remembered pick visible even if the list couldn't be fetched.
+                            if !selected_version().is_empty()
+                                && !releases.read().iter().flatten().any(|r| r.tag_name == selected_version())
+                            {
+                                option { value: "{selected_version}", label: "{selected_version().trim_start_matches('v')}" }
+                            }
+                        }
+                    }
+                    if installed_manifest().is_some() {
+                        label { id: "backup_toggle",
+                            input {
+                                r#type: "checkbox",
+                                checked: backup_before_update(),
+                                onchange: move |e| backup_before_update.set(e.checked()),
+                            }
+                            {t!("settings.backup_before_update")}
+                        }
+                    }
+                    div {
+                        match free_space() {
+                            Some(free) => t!("wizard.free_space", free = disk::format_bytes(free)),
+                            None => t!("wizard.free_space_unknown"),
+                        }
+                    }
+                    if low_space || is_downgrade || interrupted_notice.is_some() {
+                        ul { id: "confirm_warnings",
+                            if let (true, Some(needed)) = (low_space, space_needed) {
+                                li { {t!("wizard.low_space", needed = disk::format_bytes(needed))} }
+                            }
+                            if is_downgrade {
+                                li { {t!("wizard.downgrade")} }
+                            }
+                            if let Some(notice) = interrupted_notice.clone() {
+                                li { {notice} }
+                            }
+                        }
+                    }
+                }
+                div { class: "wizard_nav",
+                    button {
+                        class: "secondary",
+                        onclick: move |_| step.set(Step::Target),
+                        {t!("wizard.back")}
+                    }
+                    button {
+                        class: "primary",
+                        disabled: !is_install_ready || installing(),
+                        onclick: start_from_wizard,
+                        {install_button_label}
+                        {target_release_label.clone()}
+                    }
+                    button {
+                        class: "secondary",
+                        onclick: move |_| step.set(Step::Install),
+                        {t!("wizard.skip")}
+                    }
+                }
+            },
+            Step::Install => rsx! {
+                if let Some(failed) = install_error() {
+                    ErrorBanner {
+                        failed,
+                        installation_type: installation_type(),
+                        log: activity,
+                        on_retry: move |_| async move {
+                            run_install(primary_action(), false).await;
+                        },
+                        on_dismiss: move |_| install_error.set(None),
+                    }
+                }
+                if let (true, Some(summary)) = (show_success(), install_summary()) {
+                    SuccessScreen {
+                        summary,
+                        installation_type: installation_type(),
+                        close_countdown,
+                        on_back: move |_| show_success.set(false),
+                    }
+                } else {
+                    div {
+                        id: "action_zone",
+                        class: if is_install_ready {
+                            "message_zone third {install_state().style_class()}"
+                        } else {
+                            "message_zone disabled {install_state().style_class()}"
+                        },
+                        TargetPath { resolved: resolved_target() }
+                        if close_requested() {
+                            div { id: "close_confirm",
+                                div { {t!("controls.close_warning")} }
+                                div { class: "action_zone_buttons",
+                                    button {
+                                        class: "primary",
+                                        disabled: exit_when_done(),
+                                        onclick: move |_| {
+                                            cancel.read().cancel();
+                                            exit_when_done.set(true);
+                                        },
+                                        if exit_when_done() { {t!("controls.cancelling")} } else { {t!("controls.cancel_and_exit")} }
+                                    }
+                                    button {
+                                        class: "secondary",
+                                        disabled: exit_when_done(),
+                                        onclick: move |_| close_requested.set(false),
+                                        {t!("controls.keep_installing")}
+                                    }
+                                }
+                            }
+                        }
+                        if let Some(action) = pending_sd_confirm() {
+                            div { id: "sd_confirm",
+                                div { class: "summary_title", {t!("controls.sd_confirm")} }
+                                code { {cobalt_mod_path().display().to_string()} }
+                                div { {t!("controls.sd_confirm_version", version = target_version_label.clone())} }
+                                div {
+                                    match free_space() {
+                                        Some(free) => t!("wizard.free_space", free = disk::format_bytes(free)),
+                                        None => t!("wizard.free_space_unknown"),
+                                    }
+                                    if let Some(needed) = space_needed {
+                                        " "
+                                        {t!("controls.sd_confirm_needed", needed = disk::format_bytes(needed))}
+                                    }
+                                }
+                                div {
+                                    match installed_version.as_ref() {
+                                        Some(installed) => t!("wizard.installed_now", version = installed),
+                                        None => t!("wizard.nothing_installed"),
+                                    }
+                                }
+                                if let Some(warning) = layout::target_warning(&cobalt_mod_path()) {
+                                    div { class: "layout_warning", {warning} }
+                                }
+                                if low_space {
+                                    div { class: "layout_warning", {t!("wizard.low_space", needed = disk::format_bytes(space_needed.unwrap_or_default()))} }
+                                }
+                                label {
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: sd_dont_ask(),
+                                        onchange: move |e| sd_dont_ask.set(e.checked()),
+                                    }
+                                    {t!("controls.sd_dont_ask")}
+                                }
+                                div { class: "action_zone_buttons",
+                                    button {
+                                        class: "primary",
+                                        disabled: installing(),
+                                        onclick: move |_| async move {
+                                            if sd_dont_ask() {
+                                                let mut skipped = settings::SD_CARD_SKIP_CONFIRM.get();
+                                                skipped.push(user_selected_sdcard_path());
+                                                settings::SD_CARD_SKIP_CONFIRM.set(&skipped);
+                                            }
+                                            sd_confirmed.set(true);
+                                            run_install(action, false).await;
+                                        },
+                                        {t!("controls.sd_install_here")}
+                                    }
+                                    button {
+                                        class: "secondary",
+                                        onclick: move |_| pending_sd_confirm.set(None),
+                                        {t!("common.cancel")}
+                                    }
+                                }
+                            }
+                        }
+                        if let Some((installed, older, action)) = pending_downgrade() {
+                            div { id: "downgrade_confirm",
+                                div { {t!("controls.downgrade_warning", installed = installed, older = older)} }
+                                div { class: "note", {t!("controls.downgrade_note")} }
+                                div { class: "action_zone_buttons",
+                                    button {
+                                        class: "primary",
+                                        disabled: installing(),
+                                        onclick: move |_| async move {
+                                            run_install(action, true).await;
+                                        },
+                                        {t!("controls.downgrade_to", version = older)}
+                                    }
+                                    button {
+                                        class: "secondary",
+                                        onclick: move |_| pending_downgrade.set(None),
+                                        {t!("common.cancel")}
+                                    }
+                                }
+                            }
+                        }
+                        if let Some(notice) = interrupted_notice {
+                            div { id: "interrupted_banner",
+                                div { {notice} }
+                                div { class: "note", {t!("controls.interrupted_note")} }
+                                div { class: "action_zone_buttons",
+                                    button {
+                                        class: "primary",
+                                        disabled: !is_install_ready || installing(),
+                                        onclick: move |_| async move {
+                                            run_install(history::Action::Repair, false).await;
+                                        },
+                                        {t!("controls.repair")}
+                                    }
+                                    button { class: "secondary", onclick: clean_up_interrupted, {t!("controls.clean_up")} }
+                                }
+                            }
+                        }
+                        if let Some(receipt) = target_receipt() {
+                            div { class: "note",
+                                {t!(
+                                    "controls.receipt",
+                                    version = receipt.cobalt_version.map(|v| v.trim_start_matches('v').to_string()).unwrap_or_else(|| t!("controls.unknown_version")),
+                                    installer = receipt.installer_version,
+                                    date = history::format_timestamp(receipt.installed_at),
+                                    count = receipt.file_count,
+                                )}
+                            }
+                        }
+                        if let (Some(latest), Some(installed)) = (update_available.as_ref(), installed_version.as_ref()) {
+                            div { id: "update_banner",
+                                {t!("controls.update_available", latest = latest, installed = installed)}
+                            }
+                        }
+                        div { class: "action_zone_buttons",
+                            div { class: "button_with_note",
+                                button {
+                                    id: "install_button",
+                                    class: "primary",
+                                    onclick: install_cobalt,
+                                    disabled: !is_install_ready || installing(),
+                                    {install_button_label.clone()}
+                                    {target_release_label.clone()}
+                                }
+                                if has_existing_install() {
+                                    span { class: "note",
+                                        {t!("controls.installed_version", version = installed_version_label.clone())}
+                                    }
+                                }
+                            }
+                            div { class: "button_with_note",
+                                div { class: "button_row",
+                                    button {
+                                        id: "open_mods_folder_button",
+                                        class: "secondary",
+                                        disabled: !does_engage_mods_folder_exist(cobalt_mod_path()),
+                                        onclick: move |_| {
+                                            if let Err(e) = open_engage_mods_folder(cobalt_mod_path()) {
+                                                activity::push(activity, activity::Severity::Error, format!("Couldn't open the mods folder: {e}"));
+                                            }
+                                        },
+                                        {t!("controls.open_mods_folder")}
+                                    }
+                                    CopyModsPath { target: cobalt_mod_path() }
+                                }
+                                span { class: "note", {mods_summary} }
+                            }
+                            button {
+                                id: "open_patches_folder_button",
+                                class: "secondary",
+                                disabled: !does_engage_folder_exist(cobalt_mod_path()),
+                                onclick: move |_| {
+                                    if let Err(e) = open_engage_subfolder(cobalt_mod_path(), PATCHES_DIR) {
+                                        tracing::error!("Couldn't open the patches folder: {e}");
+                                        status_message.set(t!("status.open_patches_failed", error = e));
+                                    }
+                                },
+                                {t!("controls.open_patches_folder")}
+                            }
+                            button {
+                                id: "restore_backup_button",
+                                class: "secondary",
+                                disabled: !is_install_ready,
+                                onclick: move |_| show_restore.set(!show_restore()),
+                                {t!("controls.restore_backup")}
+                            }
+                            button {
+                                id: "uninstall_button",
+                                class: "secondary",
+                                disabled: installed_manifest().is_none() || installing(),
+                                onclick: move |_| confirm_uninstall.set(true),
+                                {t!("controls.uninstall_cobalt")}
+                            }
+                        }
+                        ProgressBar { progress: install_state().progress(), failed: install_state().is_failed() }
+                        if let Some(elapsed) = elapsed_text.clone() {
+                            p { class: "elapsed note", {elapsed} }
+                        }
+                        if install_state().is_running() || install_state().is_failed() {
+                            ol { id: "install_steps",
+                                for (key, status) in step_status() {
+                                    li { key: "{key}", class: status.class(), {i18n::tr(key)} }
+                                }
+                            }
+                        }
+                        if let (Some(entry), true) = (current_entry(), install_state().can_cancel()) {
+                            p { class: "current_entry", {t!("status.extracting_entry", entry = entry)} }
+                        }
+                        if install_state().can_cancel() {
+                            div { class: "action_zone_buttons",
+                                button {
+                                    id: "cancel_button",
+                                    class: "secondary",
+                                    onclick: move |_| cancel.read().cancel(),
+                                    {t!("common.cancel")}
+                                }
+                            }
+                        }
+                        div { class: "action_zone_buttons advanced",
+                            span { class: "note", {t!("controls.advanced")} }
+                            button {
+                                id: "open_config_folder_button",
+                                class: "secondary",
+                                disabled: !is_install_ready || cobalt_mod_path().as_os_str().is_empty(),
+                                onclick: move |_| {
+                                    if let Err(e) = open_engage_subfolder(cobalt_mod_path(), CONFIG_DIR) {
+                                        tracing::error!("Couldn't open the config folder: {e}");
+                                        status_message.set(t!("status.open_config_failed", error = e));
+                                    }
+                                },
+                                {t!("controls.open_config_folder")}
+                            }
+                            button {
+                                id: "open_logs_folder_button",
+                                class: "secondary",
+                                disabled: !cobalt_mod_path().join(LOGS_DIR).is_dir(),
+                                onclick: move |_| {
+                                    newest_log.set(newest_file(&cobalt_mod_path().join(LOGS_DIR)));
+                                    if let Err(e) = open_dir(cobalt_mod_path().join(LOGS_DIR)) {
+                                        tracing::error!("Couldn't open the logs folder: {e}");
+                                        status_message.set(t!("status.open_logs_failed", error = e));
+                                    }
+                                },
+                                {t!("controls.open_logs_folder")}
+                            }
+                            span { class: "note",
+                                match newest_log() {
+                                    Some((name, modified)) => t!("controls.latest_log", name = name, date = history::format_timestamp(modified)),
+                                    None => t!("controls.no_logs"),
+                                }
+                            }
+                        }
+                        if confirm_uninstall() {
+                            div { id: "uninstall_confirm",
+                                div { {t!("controls.uninstall_confirm")} }
+                                div { class: "action_zone_buttons",
+                                    button {
+                                        class: "primary",
+                                        onclick: move |_| async move {
+                                            confirm_uninstall.set(false);
+                                            run_uninstall().await;
+                                        },
+                                        {t!("controls.uninstall")}
+                                    }
+                                    button {
+                                        class: "secondary",
+                                        onclick: move |_| confirm_uninstall.set(false),
+                                        {t!("common.cancel")}
+                                    }
+                                }
+                            }
+                        }
+                        if let Some(summary) = install_summary() {
+                            InstallSummaryPanel { summary }
+                        } else {
+                            code { class: "{status_class}",
+                                {t!("common.status")}
+                                {status_text}
+                            }
+                        }
+                    }
+                }
+                ActivityLog { entries: activity, target: cobalt_mod_path() }
+                ChecksPanel {
+                    installation_type: installation_type(),
+                    busy: installing(),
+                    on_repair: move |_| {
+                        spawn(async move {
+                            run_install(history::Action::Repair, false).await;
+                        });
+                    },
+                }
+                if show_restore() {
+                    RestoreBackup {
+                        target: cobalt_mod_path(),
+                        target_type: installation_type().name().to_string(),
+                        installed_manifest,
+                        history_entries,
+                        install_summary,
+                        status_message,
+                    }
+                }
+                if is_install_ready {
+                    ModsPanel { target: cobalt_mod_path, other_targets }
+                    PluginsPanel { target: cobalt_mod_path, installed_manifest }
+                }
+                HistoryPanel { entries: history_entries }
+                div { class: "wizard_nav",
+                    button {
+                        class: "secondary",
+                        disabled: installing(),
+                        onclick: move |_| step.set(Step::Confirm),
+                        {t!("wizard.back")}
+                    }
+                }
+            },
+        }
+    }
+}
+
+// "1 Where · 2 Confirm · 3 Install" across the top. Steps past the first can only be
+// jumped to once there's a target, and none while an install runs so the target can't
+// change under it.
+#[cfg(feature = "desktop")]
+#[component]
+fn WizardSteps(mut current: Signal<Step>, has_target: bool, locked: bool) -> Element {
+    rsx! {
+        ol { id: "wizard_steps",
+            for step in wizard::STEPS {
+                li {
+                    button {
+                        class: if step == current() { "current" } else { "" },
+                        disabled: locked || (step.needs_target() && !has_target),
+                        onclick: move |_| current.set(step),
+                        "{step.number()}. {step.label()}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Tell the user an install finished when the window isn't focused. Clicking the
+// notification (where the platform reports that) brings the installer to the front,
+// elsewhere the taskbar entry asks for attention instead. Never fails the install.
+#[cfg(feature = "desktop")]
+async fn notify_finished(activity: Signal<Vec<activity::LogEntry>>, message: String) {
+    let window = dioxus::desktop::window();
+    if window.is_focused() {
+        return;
+    }
+    window.request_user_attention(Some(dioxus::desktop::tao::window::UserAttentionType::Informational));
+    let result = tokio::task::spawn_blocking(move || notify::show(&message))
+        .await
+        .expect("Blocking task panicked");
+    match result {
+        Ok(true) => {
+            window.set_visible(true);
+            window.set_minimized(false);
+            window.set_focus();
+        }
+        Ok(false) => {}
+        Err(e) => activity::push(activity, activity::Severity::Warning, format!("Couldn't show a notification: {e}")),
+    }
+}
+
+// Put `text` on the clipboard through the webview, which works the same on Windows,
+// macOS and Linux. The async Clipboard API needs a secure context some webviews don't
+// grant the app, so fall back to the old select-and-copy trick when it's missing or
+// refuses. The script returns whether either worked.
+#[cfg(feature = "desktop")]
+fn clipboard_script(text: &str) -> String {
+    let text = serde_json::to_string(text).unwrap_or_default();
+    format!(
+        r#"
+        const text = {text};
+        try {{
+            if (navigator.clipboard && navigator.clipboard.writeText) {{
+                await navigator.clipboard.writeText(text);
+                return true;
+            }}
+        }} catch (e) {{}}
+        const area = document.createElement("textarea");
+        area.value = text;
+        document.body.appendChild(area);
+        area.select();
+        const copied = document.execCommand("copy");
+        area.remove();
+        return copied;
+        "#
+    )
+}
+
+#[cfg(feature = "desktop")]
+fn copy_to_clipboard(text: &str) {
+    document::eval(&clipboard_script(text));
+}
+
+// Like copy_to_clipboard, for when a failed copy needs reporting.
+#[cfg(feature = "desktop")]
+async fn try_copy_to_clipboard(text: &str) -> Result<(), String> {
+    match document::eval(&clipboard_script(text)).await {
+        Ok(serde_json::Value::Bool(true)) => Ok(()),
+        Ok(_) => Err("the clipboard refused the text".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// Why an install failed: a sentence for the user, what usually fixes it, and the details
+// for whoever helps them.
+#[cfg(feature = "desktop")]
+#[derive(Clone, Debug, PartialEq)]
+struct FailedRun {
+    failure: Failure,
+    summary: String,
+    details: String,
+}
+
+#[cfg(feature = "desktop")]
+impl FailedRun {
+    fn new(failure: Failure, summary: impl Into<String>, details: impl Into<String>) -> Self {
+        Self { failure, summary: summary.into(), details: details.into() }
+    }
+
+    // The banner for an install error, or None when the install was only cancelled.
+    fn from_error(error: &InstallerError) -> Option<Self> {
+        let failure = Failure::from_error(error)?;
+        Some(Self::new(failure, failure.summary(), error_chain(error)))
+    }
+}
+
+// An error and everything it wraps, one per line.
+#[cfg(feature = "desktop")]
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut chain = error.to_string();
+    let mut source = error.source();
+    while let Some(e) = source {
+        chain.push_str(&format!("\ncaused by: {e}"));
+        source = e.source();
+    }
+    chain
+}
+
+// Sits above the action zone rather than in it, so the install controls stay where they
+// are.
+#[cfg(feature = "desktop")]
+#[component]
+fn ErrorBanner(
+    failed: FailedRun,
+    installation_type: InstallationType,
+    log: Signal<Vec<activity::LogEntry>>,
+    on_retry: EventHandler<MouseEvent>,
+    on_dismiss: EventHandler<MouseEvent>,
+) -> Element {
+    let report_url = bug_report::issue_url(&failed.summary, &failed.details, installation_type, &log.read());
+
+    // The log file in the file manager, or the activity log if there's no file to show.
+    let open_log = move |_| {
+        match log_file::current().filter(|p| p.is_file()).map(reveal_dir) {
+            Some(Ok(_)) => return,
+            Some(Err(e)) => tracing::error!("Couldn't show the log file: {e}"),
+            None => {}
+        }
+        document::eval(
+            r#"
+            const log = document.getElementById("activity_log");
+            if (log) {
+                log.open = true;
+                log.scrollIntoView({ behavior: "smooth" });
+            }
+            "#,
+        );
+    };
+
+    rsx! {
+        div { id: "error_banner", class: "message_zone",
+            div { class: "error_summary", "{failed.summary}" }
+            div { class: "error_suggestion", {failed.failure.suggestion()} }
+            details {
+                summary { {t!("error.technical_details")} }
+                pre { "{failed.details}" }
+            }
+            div { class: "action_zone_buttons",
+                button { class: "primary", onclick: move |e| on_retry.call(e), {t!("common.retry")} }
+                a { class: "button_link report", href: report_url, {t!("error.report_bug")} }
+                button { class: "secondary", onclick: open_log, {t!("error.open_log")} }
+                a { class: "button_link", href: HELP_URL, {t!("error.get_help")} }
+                button { class: "secondary", onclick: move |e| on_dismiss.call(e), {t!("common.dismiss")} }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "desktop")]
+#[component]
+fn LogLine(entry: activity::LogEntry) -> Element {
+    rsx! {
+        div { class: "log_entry {entry.severity.class()}",
+            span { class: "log_time", {entry.time()} }
+            " {entry.message}"
+        }
+    }
+}
+
+// The session's activity, newest at the bottom. Sticks to the bottom as entries come in,
+// unless the user has scrolled up to read something.
+#[cfg(feature = "desktop")]
+#[component]
+fn ActivityLog(entries: Signal<Vec<activity::LogEntry>>, target: PathBuf) -> Element {
+    use_effect(move || {
+        entries.read();
+        document::eval(
+            r#"
+            const list = document.getElementById("log_entries");
+            if (list) {
+                if (!list.dataset.watched) {
+                    list.dataset.watched = "1";
+                    list.dataset.pinned = "1";
+                    list.addEventListener("scroll", () => {
+                        const atBottom = list.scrollHeight - list.scrollTop - list.clientHeight < 20;
+                        list.dataset.pinned = atBottom ? "1" : "";
+                    });
+                }
+                if (list.dataset.pinned) {
+                    list.scrollTop = list.scrollHeight;
+                }
+            }
+            "#,
+        );
+    });
+
+    let shown: Vec<activity::LogEntry> = {
+        let entries = entries.read();
+        entries[entries.len().saturating_sub(activity::SHOWN_ENTRIES)..].to_vec()
+    };
+    let groups = activity::groups(&shown);
+
+    // The diagnostics bundle, built and shown before the user picks where to save it.
+    let mut diagnostics_preview = use_signal(|| None::<Vec<diagnostics::BundleFile>>);
+
+    let preview_diagnostics = {
+        let target = target.clone();
+        move |_| {
+            let target = target.clone();
+            async move {
+                let log = entries.read().clone();
+                let files = tokio::task::spawn_blocking(move || diagnostics::collect(&log, &target))
+                    .await
+                    .expect("Blocking task panicked");
+                diagnostics_preview.set(Some(files));
+            }
+        }
+    };
+
+    // Everything, not just what's shown.
+    let copy_log = move |_| {
+        let target = target.clone();
+        async move {
+            let text = activity::to_text(&entries.read(), &target);
+            match try_copy_to_clipboard(&text).await {
+                Ok(()) => activity::push(entries, activity::Severity::Info, "Copied the log to the clipboard"),
+                Err(e) => activity::push(entries, activity::Severity::Error, format!("Couldn't copy the log: {e}")),
+            }
+        }
+    };
+
+    let save_diagnostics = move |_| async move {
+        let Some(files) = diagnostics_preview() else {
+            return;
+        };
+        let Some(dest) = rfd::AsyncFileDialog::new()
+            .set_title(t!("log.save_diagnostics_title"))
+            .set_file_name(diagnostics::default_file_name())
+            .add_filter("Zip archive", &["zip"])
+            .save_file()
+            .await
+        else {
+            return;
+        };
+        let dest = dest.path().to_path_buf();
+        let written = dest.clone();
+        let result = tokio::task::spawn_blocking(move || diagnostics::write_zip(&files, &written))
+            .await
+            .expect("Blocking task panicked");
+        match result {
+            Ok(()) => {
+                activity::push(entries, activity::Severity::Info, format!("Saved diagnostics to {}", dest.display()));
+                toast::push(activity::Severity::Info, t!("toast.diagnostics_saved"));
+                diagnostics_preview.set(None);
+            }
+            Err(e) => activity::push(entries, activity::Severity::Error, format!("Couldn't save the diagnostics: {e}")),
+        }
+    };
+
+    rsx! {
+        details { id: "activity_log", class: "message_zone first",
+            summary { {t!("log.title", count = entries.read().len())} }
+            div { id: "log_entries",
+                if shown.is_empty() {
+                    div { class: "note", {t!("log.empty")} }
+                }
+                for (n, group) in groups.iter().enumerate() {
+                    if let Some(step) = group.step {
+                        // The running step, and any with something to look at, start open.
+                        details {
+                            class: "log_group",
+                            open: n + 1 == groups.len() || group.entries.iter().any(|e| e.severity != activity::Severity::Info),
+                            summary { {t!("log.step", step = i18n::tr(step.label_key), count = group.entries.len())} }
+                            for entry in group.entries.clone() {
+                                LogLine { entry }
+                            }
+                        }
+                    } else {
+                        for entry in group.entries.clone() {
+                            LogLine { entry }
+                        }
+                    }
+                }
+            }
+            div { class: "action_zone_buttons",
+                button { class: "secondary", disabled: entries.read().is_empty(), onclick: copy_log, {t!("log.copy")} }
+                button { class: "secondary", onclick: preview_diagnostics, {t!("log.export_diagnostics")} }
+            }
+            if let Some(files) = diagnostics_preview() {
+                div { id: "diagnostics_preview",
+                    div { {t!("log.diagnostics_contents")} }
+                    for file in files {
+                        details {
+                            summary { "{file.name} ({disk::format_bytes(file.contents.len() as u64)})" }
+                            pre { {file.contents} }
+                        }
+                    }
+                    div { class: "action_zone_buttons",
+                        button { class: "primary", onclick: save_diagnostics, {t!("log.save_diagnostics")} }
+                        button { class: "secondary", onclick: move |_| diagnostics_preview.set(None), {t!("common.cancel")} }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// "Run checks": the troubleshooting checklist, each result with its fix where there is one.
+#[cfg(feature = "desktop")]
+#[component]
+fn ChecksPanel(installation_type: InstallationType, busy: bool, on_repair: EventHandler<()>) -> Element {
+    let mut results = use_signal(|| None::<Vec<checks::Check>>);
+    let mut running = use_signal(|| false);
+
+    let run_checks = move || {
+        let target = resolve_target(installation_type, &settings::SD_CARD_PATH.get());
+        spawn(async move {
+            running.set(true);
+            let language = i18n::LANGUAGE();
+            let mut found = tokio::task::spawn_blocking(move || checks::run_local(language, installation_type, target))
+                .await
+                .expect("Blocking task panicked");
+            results.set(Some(found.clone()));
+            found.push(checks::github(language).await);
+            results.set(Some(found));
+            running.set(false);
+        });
+    };
+
+    let mut apply_fix = move |fix: checks::Fix| match fix {
+        checks::Fix::CleanUp(path) => {
+            match std::fs::remove_file(&path) {
+                Ok(()) => toast::push(activity::Severity::Info, t!("check.cleaned_up")),
+                Err(e) => toast::push(activity::Severity::Warning, t!("check.clean_up_failed", error = e)),
+            }
+            run_checks();
+        }
+        checks::Fix::Repair => {
+            on_repair.call(());
+            results.set(None);
+        }
+        checks::Fix::SelectPath => {
+            spawn(async move {
+                let Some(folder) = rfd::AsyncFileDialog::new().set_title(t!("sd.select")).pick_folder().await else {
+                    return;
+                };
+                settings::SD_CARD_PATH.set(&folder.path().display().to_string());
+                settings::INSTALLATION_TYPE.set(&InstallationType::SdCard);
+                results.set(None);
+            });
+        }
+    };
+
+    let copy_results = move |_| {
+        if let Some(found) = results() {
+            copy_to_clipboard(&checks::to_text(&found));
+            toast::push(activity::Severity::Info, t!("check.copied"));
+        }
+    };
+
+    rsx! {
+        details { id: "checks_panel", class: "message_zone first",
+            summary { {t!("check.title")} }
+            div { class: "note", {t!("check.intro")} }
+            div { class: "action_zone_buttons",
+                button { class: "primary", disabled: running() || busy, onclick: move |_| run_checks(), {t!("check.run")} }
+                button { class: "secondary", disabled: running() || results().is_none(), onclick: copy_results, {t!("check.copy")} }
+            }
+            if let Some(found) = results() {
+                div { id: "check_results",
+                    for check in found {
+                        div { class: "check_row {check.status.class()}",
+                            span { class: "check_status",
+                                match check.status {
+                                    checks::Status::Pass => "✓",
+                                    checks::Status::Warn => "!",
+                                    checks::Status::Fail => "✕",
+                                }
+                            }
+                            div { class: "check_text",
+                                b { {check.name.clone()} }
+                                " {check.detail}"
+                            }
+                            if let Some(fix) = check.fix.clone() {
+                                button {
+                                    class: "secondary small",
+                                    disabled: busy,
+                                    onclick: move |_| apply_fix(fix.clone()),
+                                    match fix {
+                                        checks::Fix::CleanUp(_) => t!("check.fix_clean_up"),
+                                        checks::Fix::Repair => t!("check.fix_repair"),
+                                        checks::Fix::SelectPath => t!("check.fix_select_path"),
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    if running() {
+                        div { class: "note", {t!("check.running")} }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// What a new user does after a successful install: find the mods folder, start the game,
+// take the SD card out. The full install summary is a click away.
+#[cfg(feature = "desktop")]
+#[component]
+fn SuccessScreen(
+    summary: summary::InstallSummary,
+    installation_type: InstallationType,
+    mut close_countdown: Signal<Option<u32>>,
+    on_back: EventHandler<MouseEvent>,
+) -> Element {
+    let mut notice = use_signal(String::new);
+    let destination = summary.destination.clone();
+    let launcher_emulator = installation_type.emulator().filter(|e| e.launcher().is_some());
+    let is_sd_card = installation_type.is_sd_card();
+
+    // Any click, key press or scroll anywhere in the window stops the countdown.
+    use_future(move || async move {
+        if close_countdown.peek().is_none() {
+            return;
+        }
+        let mut interacted = document::eval(
+            r#"
+            const kinds = ["pointerdown", "keydown", "wheel"];
+            const stop = () => {
+                kinds.forEach((kind) => window.removeEventListener(kind, stop, true));
+                dioxus.send(true);
+            };
+            kinds.forEach((kind) => window.addEventListener(kind, stop, true));
+            "#,
+        );
+        if interacted.recv::<bool>().await.is_ok() {
+            close_countdown.set(None);
+        }
+    });
+
+    use_future(move || async move {
+        while let Some(seconds) = close_countdown() {
+            if seconds == 0 {
+                dioxus::desktop::window().close();
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            // Checked again, it may have been stopped while we slept.
+            if let Some(seconds) = close_countdown() {
+                close_countdown.set(Some(seconds.saturating_sub(1)));
+            }
+        }
+    });
+
+    rsx! {
+        div { id: "action_zone", class: "message_zone third",
+            div { id: "success_screen",
+                div { class: "summary_title", {t!("success.installed", version = summary.version_label())} }
+                div { class: "note", {t!("success.into")} code { {destination.display().to_string()} } }
+                div { class: "action_zone_buttons",
+                    button {
+                        class: "primary",
+                        onclick: {
+                            let destination = destination.clone();
+                            move |_| {
+                                if let Err(e) = open_engage_subfolder(&destination, mods::MODS_DIR) {
+                                    notice.set(t!("success.open_mods_failed", error = e));
+                                }
+                            }
+                        },
+                        {t!("controls.open_mods_folder")}
+                    }
+                    if let Some(emulator) = launcher_emulator {
+                        button {
+                            class: "secondary",
+                            onclick: move |_| {
+                                let result = emulator.launcher().map(|mut command| command.spawn());
+                                match result {
+                                    Some(Ok(_)) => notice.set(t!("success.starting", emulator = emulator.name)),
+                                    Some(Err(e)) => notice.set(t!("success.start_failed", emulator = emulator.name, error = e)),
+                                    None => notice.set(t!("success.emulator_missing", emulator = emulator.name)),
+                                }
+                            },
+                            {t!("success.launch", emulator = emulator.name)}
+                        }
+                    }
+                    if is_sd_card {
+                        button {
+                            class: "secondary",
+                            onclick: {
+                                let destination = destination.clone();
+                                move |_| {
+                                    let destination = destination.clone();
+                                    async move {
+                                        notice.set(t!("success.ejecting"));
+                                        let result = tokio::task::spawn_blocking(move || disk::eject(&destination))
+                                            .await
+                                            .expect("Blocking task panicked");
+                                        match result {
+                                            Ok(()) => notice.set(t!("success.ejected")),
+                                            Err(e) => notice.set(t!("success.eject_failed", error = e)),
+                                        }
+                                    }
+                                }
+                            },
+                            {t!("success.eject")}
+                        }
+                    }
+                    button { class: "secondary", onclick: move |e| on_back.call(e), {t!("common.back")} }
+                }
+                if !notice().is_empty() {
+                    div { class: "note", {notice} }
+                }
+                if let Some(seconds) = close_countdown() {
+                    div { id: "close_countdown", class: "note",
+                        {t!("success.closing", seconds = seconds)}
+                        a {
+                            href: "#",
+                            onclick: move |e| {
+                                e.prevent_default();
+                                close_countdown.set(None);
+                            },
+                            {t!("common.cancel")}
+                        }
+                    }
+                }
+                div { class: "what_now",
+                    {t!("success.what_now")}
+                    a { href: MODS_DOCS_URL, {t!("success.docs")} }
+                    {t!("success.what_now_help")}
+                    a { href: HELP_URL, "Discord" }
+                    "."
+                }
+                details {
+                    summary { {t!("success.details")} }
+                    InstallSummaryPanel { summary }
+                }
+            }
+        }
+    }
+}
+
+// Hidden while `progress` is None, otherwise filled to that fraction, or a pulse sliding
+// across for steps with nothing to measure.
+#[cfg(feature = "desktop")]
+#[component]
+fn ProgressBar(progress: Option<Progress>, failed: bool) -> Element {
+    match progress {
+        None => rsx! {},
+        Some(Progress::Indeterminate) => rsx! {
+            div { class: "progress_bar indeterminate",
+                div { class: "progress_fill" }
+            }
+        },
+        Some(Progress::Fraction(fraction)) => {
+            let percent = (fraction.clamp(0.0, 1.0) * 100.0).round();
+            rsx! {
+                div { class: if failed { "progress_bar failed" } else { "progress_bar" },
+                    div { class: "progress_fill", width: "{percent}%" }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "desktop")]
+#[component]
+fn InstallSummaryPanel(summary: summary::InstallSummary) -> Element {
+    let destination = summary.destination.clone();
+    let text = summary.to_text();
+
+    rsx! {
+        div { id: "install_summary",
+            div { class: "summary_title", {t!("summary.installed", version = summary.version_label())} }
+            div {
+                {t!("summary.destination")}
+                a {
+                    href: "#",
+                    onclick: move |e| {
+                        e.prevent_default();
+                        if let Err(e) = open_dir(&destination) {
+                            tracing::error!("Couldn't open {}: {e}", destination.display());
+                        }
+                    },
+                    code { {summary.destination.display().to_string()} }
+                }
+            }
+            div {
+                {t!(
+                    "summary.counts",
+                    added = summary.added.len(),
+                    updated = summary.updated.len(),
+                    removed = summary.removed.len(),
+                    unchanged = summary.files_skipped,
+                )}
+            }
+            for (label, files) in [
+                (t!("summary.added"), summary.added.clone()),
+                (t!("summary.updated"), summary.updated.clone()),
+                (t!("summary.removed"), summary.removed.clone()),
+            ] {
+                if !files.is_empty() {
+                    details {
+                        summary { "{label} ({files.len()})" }
+                        ul {
+                            for file in files {
+                                li { code { "{file}" } }
+                            }
+                        }
+                    }
+                }
+            }
+            div {
+                {t!(
+                    "summary.total",
+                    size = disk::format_bytes(summary.bytes_written),
+                    seconds = format!("{:.1}", summary.duration_secs),
+                )}
+            }
+            if !summary.phases.is_empty() {
+                div { class: "note",
+                    {t!("summary.phases")}
+                    " "
+                    {summary.phases.iter().map(|(phase, secs)| format!("{} {secs:.1}s", i18n::tr(phase.label_key()))).collect::<Vec<_>>().join(", ")}
+                }
+            }
+            if let Some(backup) = &summary.backup {
+                div { {t!("summary.backed_up")} code { {backup.display().to_string()} } }
+            }
+            if summary.warnings.is_empty() {
+                div { class: "note", {t!("summary.no_warnings")} }
+            } else {
+                div { {t!("summary.warnings")} }
+                ul {
+                    for warning in summary.warnings.iter() {
+                        li { {warning.text()} }
+                    }
+                }
+            }
+            button {
+                class: "secondary",
+                onclick: move |_| copy_to_clipboard(&text),
+                {t!("summary.copy")}
+            }
+        }
+    }
+}
+
+// Append to the history file and refresh what the History panel shows. A history that
+// can't be written is logged, never a reason to fail the run it describes.
+#[cfg(feature = "desktop")]
+fn record_history(mut entries: Signal<Vec<history::Entry>>, entry: history::Entry) {
+    if let Err(e) = history::append(entry) {
+        tracing::error!("Couldn't write the install history: {e}");
+    }
+    entries.set(history::load());
+}
+
+// How long the success screen waits before closing the window, when that's turned on.
+#[cfg(feature = "desktop")]
+const AUTO_CLOSE_SECS: u32 = 10;
+
+// How many entries the History panel shows. The file itself keeps more.
+#[cfg(feature = "desktop")]
+const HISTORY_SHOWN: usize = 10;
+
+#[cfg(feature = "desktop")]
+#[component]
+fn HistoryPanel(entries: Signal<Vec<history::Entry>>) -> Element {
+    rsx! {
+        details { id: "history_panel", class: "message_zone first",
+            summary { {t!("history.title")} }
+            if entries().is_empty() {
+                div { class: "note", {t!("history.empty")} }
+            }
+            for entry in entries().into_iter().rev().take(HISTORY_SHOWN) {
+                div { class: "history_entry",
+                    span { class: "note", {history::format_timestamp(entry.timestamp)} }
+                    span {
+                        {t!(
+                            "history.entry",
+                            action = i18n::tr(entry.action.label_key()),
+                            version = entry.version.clone().unwrap_or_else(|| t!("controls.unknown_version")).trim_start_matches('v'),
+                            target_type = entry.target_type,
+                        )}
+                    }
+                    span { class: "note",
+                        match &entry.outcome {
+                            history::Outcome::Success if !entry.phases.is_empty() => t!(
+                                "history.succeeded_phases",
+                                size = disk::format_bytes(entry.bytes_written),
+                                seconds = format!("{:.0}", entry.duration_secs),
+                                phases = entry.phases.iter().map(|(phase, secs)| format!("{} {secs:.0}s", i18n::tr(phase.label_key()))).collect::<Vec<_>>().join(", "),
+                            ),
+                            history::Outcome::Success => t!(
+                                "history.succeeded",
+                                size = disk::format_bytes(entry.bytes_written),
+                                seconds = format!("{:.0}", entry.duration_secs),
+                            ),
+                            history::Outcome::Failed(reason) => t!("history.failed", reason = reason),
+                        }
+                    }
+                    code { class: "note", {entry.target.display().to_string()} }
+                }
+            }
+        }
+    }
+}
+
+// The restore screen: every backup made of `target`, newest first, each of which can be
+// put back over it.
+#[cfg(feature = "desktop")]
+#[component]
+fn RestoreBackup(
+    target: PathBuf,
+    target_type: String,
+    mut installed_manifest: Signal<Option<Manifest>>,
+    history_entries: Signal<Vec<history::Entry>>,
+    mut install_summary: Signal<Option<summary::InstallSummary>>,
+    mut status_message: Signal<String>,
+) -> Element {
+    let backups = use_signal({
+        let target = target.clone();
+        move || backup::list(&target)
+    });
+    let mut restoring = use_signal(|| false);
+
+    rsx! {
+        div { id: "restore_zone", class: "message_zone second",
+            div { {t!("backup.pick")}
+                code { {target.display().to_string()} }
+            }
+            if backups().is_empty() {
+                div { class: "note", {t!("backup.none")} }
+            }
+            for backup in backups() {
+                div { class: "backup_row",
+                    span { "Cobalt {backup.version.trim_start_matches('v')}" }
+                    span { class: "note", {t!("backup.date", date = backup.date)} }
+                    button {
+                        class: "secondary",
+                        disabled: restoring(),
+                        onclick: {
+                            let target = target.clone();
+                            let target_type = target_type.clone();
+                            move |_| {
+                                let path = backup.path.clone();
+                                let target = target.clone();
+                                let target_type = target_type.clone();
+                                let backup_version = backup.version.clone();
+                                async move {
+                                    restoring.set(true);
+                                    install_summary.set(None);
+                                    let started = std::time::Instant::now();
+                                    let current = installed_manifest();
+                                    let restore_target = target.clone();
+                                    let result = run_blocking_with_progress(
+                                        move |progress| backup::restore(&path, &restore_target, current.as_ref(), progress),
+                                        move |done, total| status_message.set(t!("backup.restoring", done = done, total = total)),
+                                    )
+                                    .await;
+                                    let (version, outcome, bytes_written) = match result {
+                                        Ok((manifest, bytes)) => {
+                                            if let Err(e) = manifest.save() {
+                                                tracing::error!("Couldn't save the install manifest: {e}");
+                                            }
+                                            let version = manifest.version.clone();
+                                            status_message.set(t!(
+                                                "backup.restored",
+                                                version = version.clone().unwrap_or_else(|| t!("controls.unknown_version")).trim_start_matches('v'),
+                                            ));
+                                            installed_manifest.set(Some(manifest));
+                                            (version, history::Outcome::Success, bytes)
+                                        }
+                                        Err(e) => {
+                                            tracing::error!("Restore failed: {e}");
+                                            status_message.set(t!("backup.restore_failed", error = e));
+                                            (Some(backup_version), history::Outcome::Failed(e.to_string()), 0)
+                                        }
+                                    };
+                                    record_history(history_entries, history::Entry {
+                                        timestamp: history::now(),
+                                        action: history::Action::Restore,
+                                        version,
+                                        target_type,
+                                        target,
+                                        outcome,
+                                        duration_secs: started.elapsed().as_secs_f64(),
+                                        bytes_written,
+                                        phases: Vec::new(),
+                                    });
+                                    restoring.set(false);
+                                }
+                            }
+                        },
+                        {t!("backup.restore")}
+                    }
+                }
+            }
+        }
+    }
+}
+
+// What's in engage/mods on the selected target. Listed off the UI thread, and again
+// whenever the window comes back into focus, since that's usually after the user has
+// been moving mods around in their file manager.
+#[cfg(feature = "desktop")]
+#[component]
+fn ModsPanel(target: Signal<PathBuf>, other_targets: Vec<(String, PathBuf)>) -> Element {
+    let mut refresh = use_signal(|| 0);
+    // The outcome of the last action taken on a mod, if it needs saying.
+    let mut notice = use_signal(String::new);
+    // A dropped or picked zip whose mod name is already taken, waiting on the user.
+    let mut pending_zip = use_signal(|| None::<mods::ModArchive>);
+    let mut installing_mod = use_signal(|| false);
+    // A zip that doesn't look like an Engage mod, with why, until the user decides.
+    let mut suspicious_zip = use_signal(|| None::<(mods::ModArchive, String)>);
+    let mut show_copy = use_signal(|| false);
+
+    let strays = use_resource(move || async move {
+        refresh.read();
+        let target = target();
+        tokio::task::spawn_blocking(move || misplaced::scan(&target))
+            .await
+            .expect("Blocking task panicked")
+    });
+    let mut stray_mod_name = use_signal(|| "UserMod".to_string());
+
+    // The last conflict check, as copyable text.
+    let mut conflict_report = use_signal(|| None::<String>);
+    let mut checking_conflicts = use_signal(|| false);
+
+    let check_conflicts = move |_| async move {
+        checking_conflicts.set(true);
+        let mod_target = target();
+        let result = tokio::task::spawn_blocking(move || mods::find_conflicts(&mod_target))
+            .await
+            .expect("Blocking task panicked");
+        match result {
+            Ok(conflicts) => conflict_report.set(Some(mods::conflicts_report(&conflicts))),
+            Err(e) => {
+                tracing::error!("Conflict check failed: {e}");
+                notice.set(t!("mods.conflicts_failed", error = e));
+            }
+        }
+        checking_conflicts.set(false);
+    };
+
+    // The exported mods report, shown until it's copied, saved or closed.
+    let mut mods_report = use_signal(|| None::<String>);
+
+    let export_report = move |_| async move {
+        let report_target = target();
+        let result = tokio::task::spawn_blocking(move || mods_report::build(&report_target))
+            .await
+            .expect("Blocking task panicked");
+        match result {
+            Ok(report) => mods_report.set(Some(report)),
+            Err(e) => {
+                tracing::error!("Couldn't build the mods report: {e}");
+                notice.set(t!("mods.report_failed", error = e));
+            }
+        }
+    };
+
+    let save_report = move |report: String| async move {
+        let Some(dest) = rfd::AsyncFileDialog::new()
+            .set_title(t!("mods.save_report_title"))
+            .set_file_name("cobalt_mods_report.txt")
+            .add_filter(t!("mods.text_file"), &["txt"])
+            .save_file()
+            .await
+        else {
+            return;
+        };
+        match std::fs::write(dest.path(), report) {
+            Ok(()) => notice.set(t!("mods.report_saved", path = dest.path().display())),
+            Err(e) => notice.set(t!("mods.save_report_failed", error = e)),
+        }
+    };
+
+    let mut backing_up_mods = use_signal(|| false);
+
+    let back_up_mods = move |_| async move {
+        let Some(dest) = rfd::AsyncFileDialog::new()
+            .set_title(t!("mods.back_up_title"))
+            .set_file_name(mods_backup::default_file_name())
+            .add_filter(t!("mods.zip_file"), &["zip"])
+            .save_file()
+            .await
+        else {
+            return;
+        };
+        backing_up_mods.set(true);
+        let dest = dest.path().to_path_buf();
+        let mod_target = target();
+        let result = run_blocking_with_progress(
+            move |progress| mods_backup::create(&mod_target, &dest, progress),
+            move |done, total| notice.set(t!("mods.backing_up", done = done, total = total)),
+        )
+        .await;
+        match result {
+            Ok(backup) => {
+                let mut message = t!(
+                    "mods.backed_up",
+                    count = backup.files,
+                    size = disk::format_bytes(backup.size),
+                    path = backup.path.display(),
+                );
+                if !backup.skipped.is_empty() {
+                    message.push_str(&t!("mods.skipped_links", names = backup.skipped.join(", ")));
+                }
+                notice.set(message);
+            }
+            Err(e) => {
+                tracing::error!("Mods backup failed: {e}");
+                notice.set(t!("mods.back_up_failed", error = e));
+            }
+        }
+        backing_up_mods.set(false);
+    };
+
+    // A mods backup picked for restoring, with what to do about each mod in it.
+    let mut restore_plan = use_signal(|| None::<mods_backup::RestorePlan>);
+
+    let pick_mods_backup = move |_| async move {
+        let Some(picked) = rfd::AsyncFileDialog::new()
+            .set_title(t!("mods.pick_backup_title"))
+            .add_filter(t!("mods.zip_file"), &["zip"])
+            .pick_file()
+            .await
+        else {
+            return;
+        };
+        let zip_path = picked.path().to_path_buf();
+        let mod_target = target();
+        let plan = tokio::task::spawn_blocking(move || mods_backup::plan_restore(&mod_target, &zip_path))
+            .await
+            .expect("Blocking task panicked");
+        match plan {
+            Ok(plan) => restore_plan.set(Some(plan)),
+            Err(e) => notice.set(t!("mods.read_backup_failed", error = e)),
+        }
+    };
+
+    // The mod waiting on a delete confirmation, with its size once it's been measured.
+    let mut confirm_delete = use_signal(|| None::<(mods::ModEntry, Option<u64>)>);
+
+    let install_mod = move |archive: mods::ModArchive, name: String, overwrite: bool| async move {
+        pending_zip.set(None);
+        installing_mod.set(true);
+        let mod_target = target();
+        let mod_name = name.clone();
+        let result = run_blocking_with_progress(
+            move |progress| mods::install_zip(&mod_target, &archive, &mod_name, overwrite, progress),
+            {
+                let name = name.clone();
+                move |done, total| notice.set(t!("mods.installing", name = name, done = done, total = total))
+            },
+        )
+        .await;
+        match result {
+            Ok(extracted) => notice.set(t!(
+                "mods.installed",
+                count = extracted.files.len(),
+                path = mods::mods_dir(&target()).join(&name).display(),
+            )),
+            Err(e) => {
+                tracing::error!("Couldn't install the mod: {e}");
+                notice.set(t!("mods.install_failed", error = e));
+            }
+        }
+        installing_mod.set(false);
+        refresh += 1;
+    };
+
+    // Past the structure check: ask about an existing mod of that name, or go ahead.
+    let continue_mod_install = move |archive: mods::ModArchive| async move {
+        suspicious_zip.set(None);
+        if mods::exists(&target(), &archive.name) {
+            pending_zip.set(Some(archive));
+        } else {
+            let name = archive.name.clone();
+            install_mod(archive, name, false).await;
+        }
+    };
+
+    // Where a dropped or picked file goes first: check it's a zip, work out the mod's
+    // name, and ask before touching an existing mod.
+    let add_mod_zip = move |path: PathBuf| async move {
+        if !path.extension().is_some_and(|e| e.eq_ignore_ascii_case("zip")) {
+            notice.set(t!("mods.not_a_zip", path = path.display()));
+            return;
+        }
+        let inspected = tokio::task::spawn_blocking(move || mods::inspect_zip(&path))
+            .await
+            .expect("Blocking task panicked");
+        match inspected {
+            Ok(archive) => match mod_validation::check(&archive.files) {
+                Some(problem) => suspicious_zip.set(Some((archive, problem))),
+                None => continue_mod_install(archive).await,
+            },
+            Err(e) => notice.set(t!("mods.read_zip_failed", error = e)),
+        }
+    };
+
+    // Same as a mod zip, but only once it's checked out as a translation patch, since
+    // those are the ones people most often unpack into the wrong place by hand.
+    let add_translation_zip = move |path: PathBuf| async move {
+        let inspected = tokio::task::spawn_blocking(move || mods::inspect_zip(&path))
+            .await
+            .expect("Blocking task panicked");
+        let archive = match inspected {
+            Ok(archive) => archive,
+            Err(e) => {
+                notice.set(t!("mods.read_zip_failed", error = e));
+                return;
+            }
+        };
+        if let Err(problem) = mods::check_translation_patch(&archive) {
+            notice.set(problem);
+            return;
+        }
+        continue_mod_install(archive).await;
+    };
+
+    use_wry_event_handler(move |event, _| {
+        if let dioxus::desktop::tao::event::Event::WindowEvent {
+            event: dioxus::desktop::WindowEvent::Focused(true),
+            ..
+        } = event
+        {
+            refresh += 1;
+        }
+    });
+
+    let listing = use_resource(move || async move {
+        refresh.read();
+        let target = target();
+        tokio::task::spawn_blocking(move || mods::list(&target))
+            .await
+            .expect("Blocking task panicked")
+            .map_err(|e| e.to_string())
+    });
+
+    // Each mod's size, filled in one folder at a time once the listing lands. Measuring
+    // big texture mods takes a while, so the rows show up first and the sizes follow.
+    let mut sizes = use_signal(std::collections::HashMap::<PathBuf, u64>::new);
+
+    use_effect(move || {
+        let Some(Ok(Some(found))) = listing() else {
+            return;
+        };
+        sizes.write().clear();
+        spawn(async move {
+            for m in found {
+                let path = m.path.clone();
+                let size = tokio::task::spawn_blocking(move || mods::dir_size(&path))
+                    .await
+                    .expect("Blocking task panicked");
+                match size {
+                    Ok(size) => {
+                        sizes.write().insert(m.path, size);
+                    }
+                    Err(e) => tracing::info!("Couldn't measure {}: {e}", m.name),
+                }
+            }
+        });
+    });
+
+    rsx! {
+        div {
+            id: "mods_panel",
+            class: "message_zone second",
+            // Needed for the webview to allow dropping here at all.
+            ondragover: move |e| e.prevent_default(),
+            ondrop: move |e| async move {
+                e.prevent_default();
+                if installing_mod() {
+                    return;
+                }
+                if let Some(file) = e.data_transfer().files().first() {
+                    add_mod_zip(file.path()).await;
+                }
+            },
+            div { class: "mods_header",
+                span { {t!("mods.title")} }
+                div { class: "action_zone_buttons",
+                    label { class: "mod_zip_label", r#for: "mod_zip_select", {t!("mods.install_zip")} }
+                    input {
+                        id: "mod_zip_select",
+                        r#type: "file",
+                        accept: ".zip",
+                        display: "none",
+                        disabled: installing_mod(),
+                        onchange: move |e| async move {
+                            if let Some(file) = e.files().first() {
+                                add_mod_zip(file.path()).await;
+                            }
+                        },
+                    }
+                    button {
+                        class: "secondary",
+                        disabled: backing_up_mods() || !mods::mods_dir(&target()).is_dir(),
+                        onclick: back_up_mods,
+                        {t!("mods.back_up")}
+                    }
+                    button {
+                        class: "secondary",
+                        disabled: restore_plan().is_some(),
+                        onclick: pick_mods_backup,
+                        {t!("mods.restore")}
+                    }
+                    button {
+                        class: "secondary",
+                        disabled: other_targets.is_empty() || show_copy(),
+                        title: if other_targets.is_empty() { t!("mods.copy_needs_target") } else { String::new() },
+                        onclick: move |_| show_copy.set(true),
+                        {t!("mods.copy_to")}
+                    }
+                    button {
+                        class: "secondary",
+                        disabled: checking_conflicts(),
+                        onclick: check_conflicts,
+                        if checking_conflicts() { {t!("mods.checking")} } else { {t!("mods.check_conflicts")} }
+                    }
+                    button { class: "secondary", onclick: export_report, {t!("mods.export_report")} }
+                    label { class: "mod_zip_label", r#for: "translation_zip_select", {t!("mods.install_translation")} }
+                    input {
+                        id: "translation_zip_select",
+                        r#type: "file",
+                        accept: ".zip",
+                        display: "none",
+                        disabled: installing_mod(),
+                        onchange: move |e| async move {
+                            if let Some(file) = e.files().first() {
+                                add_translation_zip(file.path()).await;
+                            }
+                        },
+                    }
+                    button { class: "secondary", onclick: move |_| refresh += 1, {t!("common.refresh")} }
+                }
+            }
+            if let Some(found) = strays().filter(|s| !s.is_empty()) {
+                div { class: "mod_prompt",
+                    for stray in found.iter() {
+                        div {
+                            {t!("mods.stray_found", file = stray.path.file_name().unwrap_or_default().to_string_lossy(), location = stray.location)}
+                            {t!("mods.stray_explain", destination = stray.destination)}
+                        }
+                    }
+                    div { class: "mod_row",
+                        label { r#for: "stray_mod_name", {t!("mods.move_into")} }
+                        input {
+                            id: "stray_mod_name",
+                            value: stray_mod_name,
+                            oninput: move |e| stray_mod_name.set(e.value()),
+                        }
+                    }
+                    for (from, to) in misplaced::preview(&target(), &found, &stray_mod_name()) {
+                        div { class: "note", "{from.display()} → {to.display()}" }
+                    }
+                    div { class: "action_zone_buttons",
+                        button {
+                            class: "primary",
+                            onclick: move |_| {
+                                let found = found.clone();
+                                async move {
+                                    let name = stray_mod_name();
+                                    let stray_target = target();
+                                    let moved_name = name.clone();
+                                    let result = tokio::task::spawn_blocking(move || misplaced::move_into_mod(&stray_target, &found, &moved_name))
+                                        .await
+                                        .expect("Blocking task panicked");
+                                    match result {
+                                        Ok(()) => notice.set(t!("mods.strays_moved", name = name)),
+                                        Err(e) => notice.set(t!("mods.move_strays_failed", error = e)),
+                                    }
+                                    refresh += 1;
+                                }
+                            },
+                            {t!("mods.move_strays")}
+                        }
+                    }
+                }
+            }
+            if let (true, Some(Ok(Some(found)))) = (show_copy(), listing()) {
+                CopyMods { mods: found, other_targets: other_targets.clone(), show: show_copy, notice }
+            }
+            if restore_plan().is_some() {
+                RestoreMods { target, plan: restore_plan, notice, refresh }
+            }
+            if let Some(report) = conflict_report() {
+                div { class: "conflict_report",
+                    pre { {report.clone()} }
+                    div { class: "action_zone_buttons",
+                        button { class: "secondary", onclick: move |_| copy_to_clipboard(&report), {t!("common.copy")} }
+                        button { class: "secondary", onclick: move |_| conflict_report.set(None), {t!("common.close")} }
+                    }
+                }
+            }
+            if let Some(report) = mods_report() {
+                div { class: "conflict_report",
+                    pre { {report.clone()} }
+                    div { class: "action_zone_buttons",
+                        button {
+                            class: "secondary",
+                            onclick: {
+                                let report = report.clone();
+                                move |_| copy_to_clipboard(&report)
+                            },
+                            {t!("common.copy")}
+                        }
+                        button { class: "secondary", onclick: move |_| save_report(report.clone()), {t!("common.save")} }
+                        button { class: "secondary", onclick: move |_| mods_report.set(None), {t!("common.close")} }
+                    }
+                }
+            }
+            div { class: "note", {t!("mods.drop_here")} }
+            if let Some((archive, problem)) = suspicious_zip() {
+                div { class: "mod_prompt",
+                    div { {t!("mods.suspicious", name = archive.name, problem = problem)} }
+                    div { class: "action_zone_buttons",
+                        button {
+                            class: "secondary",
+                            onclick: move |_| continue_mod_install(archive.clone()),
+                            {t!("mods.install_anyway")}
+                        }
+                        button { class: "primary", onclick: move |_| suspicious_zip.set(None), {t!("common.cancel")} }
+                    }
+                }
+            }
+            if let Some(archive) = pending_zip() {
+                div { class: "mod_prompt",
+                    div { {t!("mods.already_installed", name = archive.name)} }
+                    div { class: "action_zone_buttons",
+                        button {
+                            class: "primary",
+                            onclick: {
+                                let archive = archive.clone();
+                                move |_| {
+                                    let archive = archive.clone();
+                                    async move {
+                                        let name = archive.name.clone();
+                                        install_mod(archive, name, true).await;
+                                    }
+                                }
+                            },
+                            {t!("mods.replace")}
+                        }
+                        button {
+                            class: "secondary",
+                            onclick: {
+                                let archive = archive.clone();
+                                move |_| {
+                                    let archive = archive.clone();
+                                    async move {
+                                        let name = mods::unique_name(&target(), &archive.name);
+                                        install_mod(archive, name, false).await;
+                                    }
+                                }
+                            },
+                            {t!("mods.install_as", name = mods::unique_name(&target(), &archive.name))}
+                        }
+                        button { class: "secondary", onclick: move |_| pending_zip.set(None), {t!("common.cancel")} }
+                    }
+                }
+            }
+            if let Some((m, size)) = confirm_delete() {
+                div { class: "mod_prompt",
+                    div {
+                        {t!(
+                            "mods.confirm_delete",
+                            name = m.name,
+                            details = size.map(|size| t!("mods.delete_details", size = disk::format_bytes(size), count = m.file_count)).unwrap_or_default(),
+                        )}
+                    }
+                    div { class: "action_zone_buttons",
+                        button {
+                            class: "primary",
+                            onclick: {
+                                let m = m.clone();
+                                move |_| {
+                                    let m = m.clone();
+                                    async move {
+                                        confirm_delete.set(None);
+                                        let name = m.name.clone();
+                                        let mod_target = target();
+                                        let result = tokio::task::spawn_blocking(move || mods::delete(&mod_target, &m))
+                                            .await
+                                            .expect("Blocking task panicked");
+                                        match result {
+                                            Ok(()) => notice.set(t!("mods.deleted", name = name)),
+                                            Err(e) => {
+                                                tracing::error!("Couldn't delete {name}: {e}");
+                                                notice.set(t!("mods.delete_failed", name = name, error = e));
+                                            }
+                                        }
+                                        refresh += 1;
+                                    }
+                                }
+                            },
+                            {t!("mods.delete")}
+                        }
+                        button { class: "secondary", onclick: move |_| confirm_delete.set(None), {t!("common.cancel")} }
+                    }
+                }
+            }
+            if !notice().is_empty() {
+                div { class: "note", {notice} }
+            }
+            match listing() {
+                None => rsx! { div { class: "note", {t!("mods.looking")} } },
+                Some(Err(e)) => rsx! { div { class: "note", {t!("mods.read_failed", error = e)} } },
+                Some(Ok(None)) => rsx! {
+                    div { class: "note", {t!("mods.no_folder")} }
+                },
+                Some(Ok(Some(mods))) if mods.is_empty() => rsx! {
+                    div { class: "note", {t!("mods.none")} }
+                },
+                Some(Ok(Some(mods))) => rsx! {
+                    div { class: "note",
+                        if mods.iter().all(|m| sizes.read().contains_key(&m.path)) {
+                            {t!("mods.total", count = mods.len(), size = disk::format_bytes(mods.iter().map(|m| sizes.read()[&m.path]).sum()))}
+                        } else {
+                            {t!("mods.measuring", count = mods.len())}
+                        }
+                    }
+                    for m in mods {
+                        div { class: if m.enabled { "mod_row" } else { "mod_row disabled" },
+                            input {
+                                r#type: "checkbox",
+                                title: if m.enabled { t!("mods.disable") } else { t!("mods.enable") },
+                                checked: m.enabled,
+                                onchange: {
+                                    let m = m.clone();
+                                    move |e: FormEvent| {
+                                        let m = m.clone();
+                                        let enabled = e.checked();
+                                        async move {
+                                            let name = m.name.clone();
+                                            let result = tokio::task::spawn_blocking(move || mods::set_enabled(&m, enabled))
+                                                .await
+                                                .expect("Blocking task panicked");
+                                            match result {
+                                                Ok(_) => notice.set(String::new()),
+                                                Err(e) => {
+                                                    tracing::error!("Couldn't toggle {name}: {e}");
+                                                    notice.set(if enabled {
+                                                        t!("mods.enable_failed", name = name, error = e)
+                                                    } else {
+                                                        t!("mods.disable_failed", name = name, error = e)
+                                                    });
+                                                }
+                                            }
+                                            refresh += 1;
+                                        }
+                                    }
+                                },
+                            }
+                            span { {m.name.clone()} }
+                            if !m.enabled {
+                                span { class: "note", {t!("mods.disabled")} }
+                            }
+                            if let Some(inner) = m.nested.clone() {
+                                span { class: "mod_warning", title: t!("mods.nested_explain", folder = inner),
+                                    {t!("mods.nested")}
+                                }
+                                button {
+                                    class: "secondary",
+                                    onclick: {
+                                        let m = m.clone();
+                                        move |_| {
+                                            let m = m.clone();
+                                            async move {
+                                                let name = m.name.clone();
+                                                let result = tokio::task::spawn_blocking(move || mods::flatten(&m))
+                                                    .await
+                                                    .expect("Blocking task panicked");
+                                                match result {
+                                                    Ok(()) => notice.set(t!("mods.flattened", name = name)),
+                                                    Err(e) => {
+                                                        tracing::error!("Couldn't flatten {name}: {e}");
+                                                        notice.set(t!("mods.flatten_failed", name = name, error = e));
+                                                    }
+                                                }
+                                                refresh += 1;
+                                            }
+                                        }
+                                    },
+                                    {t!("mods.fix")}
+                                }
+                            }
+                            span { class: "note",
+                                {t!("mods.files", count = m.file_count)}
+                                match sizes.read().get(&m.path) {
+                                    Some(size) => disk::format_bytes(*size),
+                                    None => "…".to_string(),
+                                }
+                            }
+                            button {
+                                class: "secondary",
+                                onclick: {
+                                    let m = m.clone();
+                                    move |_| {
+                                        if !m.path.is_dir() {
+                                            notice.set(t!("mods.gone", name = m.name));
+                                            refresh += 1;
+                                            return;
+                                        }
+                                        if let Err(e) = reveal_dir(&m.path) {
+                                            tracing::error!("Couldn't open {}: {e}", m.path.display());
+                                            notice.set(t!("mods.open_failed", name = m.name, error = e));
+                                        }
+                                    }
+                                },
+                                {t!("mods.open_folder")}
+                            }
+                            button {
+                                class: "secondary",
+                                onclick: {
+                                    let m = m.clone();
+                                    move |_| {
+                                        let m = m.clone();
+                                        async move {
+                                            confirm_delete.set(Some((m.clone(), None)));
+                                            let path = m.path.clone();
+                                            let size = tokio::task::spawn_blocking(move || mods::dir_size(&path))
+                                                .await
+                                                .expect("Blocking task panicked")
+                                                .ok();
+                                            // Only if the user hasn't moved on to something else meanwhile.
+                                            if confirm_delete().is_some_and(|(pending, _)| pending == m) {
+                                                confirm_delete.set(Some((m, size)));
+                                            }
+                                        }
+                                    }
+                                },
+                                {t!("mods.delete")}
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+// Skyline plugins on the selected target, and a place to drop new ones. Cobalt's own
+// plugin comes from the release, so it's listed but can't be removed from here.
+#[cfg(feature = "desktop")]
+#[component]
+fn PluginsPanel(target: Signal<PathBuf>, installed_manifest: Signal<Option<Manifest>>) -> Element {
+    let mut refresh = use_signal(|| 0);
+    let mut notice = use_signal(String::new);
+    // Picked files that would replace installed plugins, waiting on the user.
+    let mut pending = use_signal(|| None::<(Vec<PathBuf>, Vec<String>)>);
+
+    let listing = use_resource(move || async move {
+        refresh.read();
+        let target = target();
+        tokio::task::spawn_blocking(move || plugins::list(&target))
+            .await
+            .expect("Blocking task panicked")
+            .map_err(|e| e.to_string())
+    });
+
+    let install = move |files: Vec<PathBuf>, overwrite: bool| async move {
+        pending.set(None);
+        let plugin_target = target();
+        let result = tokio::task::spawn_blocking(move || plugins::install(&plugin_target, &files, overwrite))
+            .await
+            .expect("Blocking task panicked");
+        match result {
+            Ok(copied) if copied.is_empty() => notice.set(t!("plugins.none_copied")),
+            Ok(copied) => notice.set(t!(
+                "plugins.copied",
+                names = copied.join(", "),
+                path = plugins::plugins_dir(&target()).display(),
+            )),
+            Err(e) => {
+                tracing::error!("Couldn't install plugins: {e}");
+                notice.set(t!("plugins.install_failed", error = e));
+            }
+        }
+        refresh += 1;
+    };
+
+    let add_plugins = move |paths: Vec<PathBuf>| async move {
+        let (files, others): (Vec<PathBuf>, Vec<PathBuf>) = paths.into_iter().partition(|p| plugins::is_plugin(p));
+        if !others.is_empty() {
+            notice.set(t!("plugins.skipped_others", count = others.len()));
+        }
+        if files.is_empty() {
+            return;
+        }
+        let duplicates = plugins::duplicates(&target(), &files);
+        if duplicates.is_empty() {
+            install(files, false).await;
+        } else {
+            pending.set(Some((files, duplicates)));
+        }
+    };
+
+    let cobalt_files = installed_manifest().map(|m| m.files).unwrap_or_default();
+
+    rsx! {
+        div {
+            id: "plugins_panel",
+            class: "message_zone second",
+            ondragover: move |e| e.prevent_default(),
+            ondrop: move |e| async move {
+                e.prevent_default();
+                add_plugins(e.data_transfer().files().iter().map(|f| f.path()).collect()).await;
+            },
+            div { class: "mods_header",
+                span { {t!("plugins.title")} }
+                div { class: "action_zone_buttons",
+                    label { class: "mod_zip_label", r#for: "plugin_select", {t!("plugins.install")} }
+                    input {
+                        id: "plugin_select",
+                        r#type: "file",
+                        accept: ".nro",
+                        multiple: true,
+                        display: "none",
+                        onchange: move |e| async move {
+                            add_plugins(e.files().iter().map(|f| f.path()).collect()).await;
+                        },
+                    }
+                    button { class: "secondary", onclick: move |_| refresh += 1, {t!("common.refresh")} }
+                }
+            }
+            div { class: "note", {t!("plugins.drop_here")} }
+            if let Some((files, duplicates)) = pending() {
+                div { class: "mod_prompt",
+                    div { {t!("plugins.already_installed", names = duplicates.join(", "))} }
+                    div { class: "action_zone_buttons",
+                        button {
+                            class: "primary",
+                            onclick: {
+                                let files = files.clone();
+                                move |_| install(files.clone(), true)
+                            },
+                            {t!("plugins.replace")}
+                        }
+                        button {
+                            class: "secondary",
+                            onclick: move |_| install(files.clone(), false),
+                            {t!("plugins.only_new")}
+                        }
+                        button { class: "secondary", onclick: move |_| pending.set(None), {t!("common.cancel")} }
+                    }
+                }
+            }
+            if !notice().is_empty() {
+                div { class: "note", {notice} }
+            }
+            match listing() {
+                None => rsx! { div { class: "note", {t!("plugins.looking")} } },
+                Some(Err(e)) => rsx! { div { class: "note", {t!("plugins.read_failed", error = e)} } },
+                Some(Ok(found)) if found.is_empty() => rsx! { div { class: "note", {t!("plugins.none")} } },
+                Some(Ok(found)) => rsx! {
+                    for plugin in found {
+                        div { class: "mod_row",
+                            span { {plugin.name.clone()} }
+                            span { class: "note", {disk::format_bytes(plugin.size)} }
+                            if cobalt_files.iter().any(|f| f.eq_ignore_ascii_case(&format!("{}/{}", plugins::PLUGINS_DIR, plugin.name))) {
+                                span { class: "note", {t!("plugins.part_of_cobalt")} }
+                            } else {
+                                button {
+                                    class: "secondary",
+                                    onclick: move |_| {
+                                        let plugin = plugin.clone();
+                                        async move {
+                                            let name = plugin.name.clone();
+                                            let result = tokio::task::spawn_blocking(move || plugins::remove(&plugin))
+                                                .await
+                                                .expect("Blocking task panicked");
+                                            match result {
+                                                Ok(()) => notice.set(t!("plugins.removed", name = name)),
+                                                Err(e) => notice.set(t!("plugins.remove_failed", name = name, error = e)),
+                                            }
+                                            refresh += 1;
+                                        }
+                                    },
+                                    {t!("plugins.remove")}
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+// Copy some of the selected target's mods to another target, e.g. from Ryujinx, where
+// they were tried out, to the SD card that goes back into the console.
+#[cfg(feature = "desktop")]
+#[component]
+fn CopyMods(
+    mods: Vec<mods::ModEntry>,
+    other_targets: Vec<(String, PathBuf)>,
+    mut show: Signal<bool>,
+    mut notice: Signal<String>,
+) -> Element {
+    let mut destination = use_signal(|| 0usize);
+    // Folder paths of the mods left unticked. Everything's ticked to start with.
+    let mut unselected = use_signal(std::collections::HashSet::<PathBuf>::new);
+    // Conflicting mods the user chose to replace. The rest are skipped.
+    let mut replace = use_signal(std::collections::HashSet::<PathBuf>::new);
+    let mut copying = use_signal(|| false);
+
+    let Some((dest_label, dest_path)) = other_targets.get(destination()).cloned() else {
+        return rsx! {};
+    };
+    let conflicting: std::collections::HashSet<PathBuf> = mods
+        .iter()
+        .filter(|m| mods::exists(&dest_path, &m.name))
+        .map(|m| m.path.clone())
+        .collect();
+
+    let run_copy = {
+        let mods = mods.clone();
+        let dest_path = dest_path.clone();
+        move |_| {
+            let chosen: Vec<(mods::ModEntry, bool)> = mods
+                .iter()
+                .filter(|m| !unselected.read().contains(&m.path))
+                .map(|m| (m.clone(), replace.read().contains(&m.path)))
+                .collect();
+            let dest_path = dest_path.clone();
+            let dest_label = dest_label.clone();
+            async move {
+                copying.set(true);
+                let result = run_blocking_with_progress(
+                    move |progress| {
+                        let mut copied = Vec::new();
+                        let mut skipped = Vec::new();
+                        for (i, (m, overwrite)) in chosen.iter().enumerate() {
+                            match mods::copy_to(m, &dest_path, *overwrite)? {
+                                Some(_) => copied.push(m.name.clone()),
+                                None => skipped.push(m.name.clone()),
+                            }
+                            progress(i + 1, chosen.len());
+                        }
+                        Ok::<_, std::io::Error>((copied, skipped))
+                    },
+                    move |done, total| notice.set(t!("copy_mods.copying", done = done, total = total)),
+                )
+                .await;
+                match result {
+                    Ok((copied, skipped)) => {
+                        let mut report = t!("copy_mods.copied", count = copied.len(), destination = dest_label);
+                        if !copied.is_empty() {
+                            report.push_str(&format!(": {}", copied.join(", ")));
+                        }
+                        if !skipped.is_empty() {
+                            report.push_str(&t!("copy_mods.skipped", names = skipped.join(", ")));
+                        }
+                        notice.set(report);
+                        show.set(false);
+                    }
+                    Err(e) => {
+                        tracing::error!("Copying mods failed: {e}");
+                        notice.set(t!("copy_mods.failed", error = e));
+                    }
+                }
+                copying.set(false);
+            }
+        }
+    };
+
+    rsx! {
+        div { class: "mod_prompt",
+            div { class: "mod_row",
+                label { r#for: "copy_destination", {t!("copy_mods.to")} }
+                select {
+                    id: "copy_destination",
+                    disabled: copying(),
+                    onchange: move |e| {
+                        destination.set(e.value().parse().unwrap_or(0));
+                        replace.write().clear();
+                    },
+                    for (i, (label, path)) in other_targets.iter().enumerate() {
+                        option { value: "{i}", selected: i == destination(), "{label} ({path.display()})" }
+                    }
+                }
+            }
+            for m in mods {
+                div { class: "mod_row",
+                    input {
+                        r#type: "checkbox",
+                        disabled: copying(),
+                        checked: !unselected.read().contains(&m.path),
+                        onchange: {
+                            let path = m.path.clone();
+                            move |e: FormEvent| {
+                                if e.checked() {
+                                    unselected.write().remove(&path);
+                                } else {
+                                    unselected.write().insert(path.clone());
+                                }
+                            }
+                        },
+                    }
+                    span { {m.name.clone()} }
+                    if conflicting.contains(&m.path) {
+                        select {
+                            disabled: copying(),
+                            onchange: {
+                                let path = m.path.clone();
+                                move |e: FormEvent| {
+                                    if e.value() == "replace" {
+                                        replace.write().insert(path.clone());
+                                    } else {
+                                        replace.write().remove(&path);
+                                    }
+                                }
+                            },
+                            option { value: "skip", selected: !replace.read().contains(&m.path), {t!("copy_mods.skip")} }
+                            option { value: "replace", selected: replace.read().contains(&m.path), {t!("copy_mods.replace")} }
+                        }
+                    }
+                }
+            }
+            div { class: "action_zone_buttons",
+                button { class: "primary", disabled: copying(), onclick: run_copy, {t!("common.copy")} }
+                button { class: "secondary", disabled: copying(), onclick: move |_| show.set(false), {t!("common.cancel")} }
+            }
+        }
+    }
+}
+
+// Preview of a mods backup: every mod folder in it, and for the ones already on the
+// target, whether to skip, replace or keep both.
+#[cfg(feature = "desktop")]
+#[component]
+fn RestoreMods(
+    target: Signal<PathBuf>,
+    mut plan: Signal<Option<mods_backup::RestorePlan>>,
+    mut notice: Signal<String>,
+    mut refresh: Signal<i32>,
+) -> Element {
+    use mods_backup::Resolution;
+
+    let mut restoring = use_signal(|| false);
+    let Some(current) = plan() else {
+        return rsx! {};
+    };
+
+    let run_restore = move |_| async move {
+        let Some(chosen) = plan() else {
+            return;
+        };
+        restoring.set(true);
+        let mod_target = target();
+        let result = run_blocking_with_progress(
+            move |progress| mods_backup::restore(&mod_target, &chosen, progress),
+            move |done, total| notice.set(t!("restore_mods.restoring", done = done, total = total)),
+        )
+        .await;
+        match result {
+            Ok(restored) => notice.set(t!("restore_mods.restored", count = restored)),
+            Err(e) => {
+                tracing::error!("Mods restore failed: {e}");
+                notice.set(t!("restore_mods.failed", error = e));
+            }
+        }
+        restoring.set(false);
+        plan.set(None);
+        refresh += 1;
+    };
+
+    rsx! {
+        div { class: "mod_prompt",
+            div { {t!("restore_mods.contents", path = current.zip_path.display())} }
+            for (i, planned) in current.mods.iter().cloned().enumerate() {
+                div { class: "mod_row",
+                    span { {planned.folder.clone()} }
+                    select {
+                        disabled: restoring(),
+                        onchange: move |e| {
+                            let resolution = match e.value().as_str() {
+                                "restore" => Resolution::Restore,
+                                "rename" => Resolution::Rename,
+                                _ => Resolution::Skip,
+                            };
+                            if let Some(plan) = plan.write().as_mut() {
+                                plan.mods[i].resolution = resolution;
+                            }
+                        },
+                        if planned.conflict {
+                            option { value: "skip", selected: planned.resolution == Resolution::Skip, {t!("restore_mods.conflict_skip")} }
+                            option { value: "restore", selected: planned.resolution == Resolution::Restore, {t!("restore_mods.conflict_replace")} }
+                            option { value: "rename", selected: planned.resolution == Resolution::Rename, {t!("restore_mods.conflict_rename")} }
+                        } else {
+                            option { value: "restore", selected: planned.resolution == Resolution::Restore, {t!("restore_mods.restore")} }
+                            option { value: "skip", selected: planned.resolution == Resolution::Skip, {t!("restore_mods.skip")} }
+                        }
+                    }
+                }
+            }
+            div { class: "action_zone_buttons",
+                button { class: "primary", disabled: restoring(), onclick: run_restore, {t!("restore_mods.restore")} }
+                button { class: "secondary", disabled: restoring(), onclick: move |_| plan.set(None), {t!("common.cancel")} }
+            }
+        }
+    }
+}
+
+// Shown when Cobalt is installed in more than one place, e.g. in Ryujinx and on an SD card
+// used before, so the copy that's no longer played can be removed before it confuses
+// anyone.
+#[cfg(feature = "desktop")]
+#[component]
+fn InstalledTargets(
+    current: PathBuf,
+    mut installed_manifest: Signal<Option<Manifest>>,
+    history_entries: Signal<Vec<history::Entry>>,
+    mut status_message: Signal<String>,
+) -> Element {
+    let mut refresh = use_signal(|| 0);
+    let manifests = use_memo(move || {
+        refresh.read();
+        installed_manifest.read();
+        Manifest::all()
+    });
+    let mut removing = use_signal(|| false);
+
+    if manifests.read().len() < 2 {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { id: "installed_targets", class: "message_zone second",
+            div { {t!("installs.intro")} }
+            for manifest in manifests() {
+                div {
+                    class: if manifest.target.exists() { "target_row" } else { "target_row unreachable" },
+                    span { {format!("Cobalt {}", manifest.version.clone().unwrap_or_else(|| t!("controls.unknown_version")).trim_start_matches('v'))} }
+                    code { {manifest.target.display().to_string()} }
+                    if manifest.target == current {
+                        span { class: "note", {t!("installs.selected")} }
+                    }
+                    if !manifest.target.exists() {
+                        span { class: "note", {t!("installs.unreachable")} }
+                    }
+                    button {
+                        class: "secondary",
+                        disabled: removing() || !manifest.target.exists(),
+                        onclick: {
+                            let current = current.clone();
+                            move |_| {
+                                let manifest = manifest.clone();
+                                let current = current.clone();
+                                async move {
+                                    removing.set(true);
+                                    let started = std::time::Instant::now();
+                                    let target = manifest.target.clone();
+                                    let version = manifest.version.clone();
+                                    let result = tokio::task::spawn_blocking(move || uninstall::uninstall(&manifest))
+                                        .await
+                                        .expect("Blocking task panicked");
+                                    let (outcome, bytes) = match result {
+                                        Ok(removed) => {
+                                            if let Err(e) = Manifest::remove(&target) {
+                                                tracing::error!("Couldn't remove the install manifest: {e}");
+                                            }
+                                            if target == current {
+                                                installed_manifest.set(None);
+                                            }
+                                            status_message.set(t!(
+                                                "installs.removed",
+                                                path = target.display(),
+                                                count = removed.files_removed,
+                                                size = disk::format_bytes(removed.bytes_removed),
+                                            ));
+                                            (history::Outcome::Success, removed.bytes_removed)
+                                        }
+                                        Err(e) => {
+                                            tracing::error!("Uninstall from {} failed: {e}", target.display());
+                                            status_message.set(t!("status.uninstall_failed", error = e));
+                                            (history::Outcome::Failed(e.to_string()), 0)
+                                        }
+                                    };
+                                    record_history(history_entries, history::Entry {
+                                        timestamp: history::now(),
+                                        action: history::Action::Uninstall,
+                                        version,
+                                        target_type: target_type_of(&target).name().to_string(),
+                                        target,
+                                        outcome,
+                                        duration_secs: started.elapsed().as_secs_f64(),
+                                        bytes_written: bytes,
+                                        phases: Vec::new(),
+                                    });
+                                    refresh += 1;
+                                    removing.set(false);
+                                }
+                            }
+                        },
+                        {t!("installs.uninstall")}
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Android controls: Eden only. The user grants Eden's folder through the system
+// picker (once, it sticks), then we download Cobalt and hand the bytes to Kotlin
+// to write through the Storage Access Framework.
+#[cfg(target_os = "android")]
+#[component]
+fn Controls(mut status_message: Signal<String>) -> Element {
+    // Seed from any grant the user gave on a previous run.
+    let mut tree_uri = use_signal(|| saf::persisted_tree_uri());
+
+    let is_install_ready = tree_uri().is_some();
+
+    let grant_access = move |_| {
+        if let Err(e) = saf::request_tree_access() {
+            status_message.set(t!("android.picker_failed", error = e));
+            return;
+        }
+        // The picker runs in the system UI on its own, so we can't await it. Poll for the
+        // outcome of THIS pick (not the persisted grant, which could be a stale one from a
+        // previous pick) so the most recent choice always wins.
+        spawn(async move {
+            for _ in 0..600 {
+                futures_timer::Delay::new(std::time::Duration::from_millis(300)).await;
+                match saf::pick_outcome() {
+                    1 => {
+                        tree_uri.set(saf::persisted_tree_uri());
+                        break;
+                    }
+                    2 => {
+                        tree_uri.set(None);
+                        status_message.set(t!("android.wrong_folder"));
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        });
+    };
+
+    let install_cobalt = move |_| async move {
+        status_message.set(t!("android.downloading"));
+        let downloaded = match download::download_release(RELEASE_URL).await {
+            Ok(response) => response
+                .bytes()
+                .await
+                .map_err(|source| InstallerError::Network { url: RELEASE_URL.to_string(), source }),
+            Err(e) => Err(e),
+        };
+        let zip_archive_bytes = match downloaded {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                status_message.set(t!("status.download_failed", error = e));
+                return;
+            }
+        };
+
+        // Clean up a stray subsdk9 from an old bad install before writing the new one.
+        let _ = saf::delete_bad_subsdk9();
+
+        status_message.set(t!("android.installing"));
+        match saf::install_zip(&zip_archive_bytes) {
+            Ok(true) => status_message.set(t!("status.install_complete")),
+            Ok(false) => status_message.set(t!("android.write_failed")),
+            Err(e) => status_message.set(t!("status.install_failed", error = e)),
+        }
+    };
+
+    rsx! {
+        div {
+            id: "installation_type_container",
+            class: "message_zone first",
+            div { {t!("android.intro")} }
         }
         div { class: "message_zone second",
             if tree_uri().is_some() {
-                div { "Eden folder access granted." }
+                div { {t!("android.access_granted")} }
             } else {
-                div { "First, grant access to Eden's folder." }
-                div { "In the file picker, open the menu (top-left), choose Eden, then tap \"Use this folder\"." }
+                div { {t!("android.grant_first")} }
+                div { {t!("android.grant_steps")} }
             }
             button {
                 class: "secondary",
                 onclick: grant_access,
-                "Grant Eden folder access"
+                {t!("android.grant")}
             }
         }
         div {
@@ -537,56 +4122,209 @@ fn Controls(mut status_message: Signal<String>) -> Element {
                     class: "primary",
                     onclick: install_cobalt,
                     disabled: !is_install_ready,
-                    "Install Cobalt"
+                    {t!("controls.install_cobalt")}
                 }
             }
             code { class: "status",
-                "Status: "
+                {t!("common.status")}
                 {status_message}
             }
         }
     }
 }
 
+// The resolved target with Copy and Open buttons, or why there isn't one.
+#[cfg(feature = "desktop")]
+#[component]
+fn TargetPath(resolved: Result<PathBuf, TargetError>) -> Element {
+    let mut notice = use_signal(String::new);
+
+    let path = match resolved {
+        Ok(path) => path,
+        Err(reason) => {
+            return rsx! {
+                div { class: "target_path unresolved", {reason.to_string()} }
+            };
+        }
+    };
+    let shown = disk::display_path(&path);
+    let exists = path.is_dir();
+
+    rsx! {
+        div { class: "target_path",
+            span { class: "note", {t!("target.label")} }
+            code { {shown.clone()} }
+            button {
+                class: "secondary",
+                onclick: move |_| {
+                    copy_to_clipboard(&shown);
+                    toast::push(activity::Severity::Info, t!("target.copied"));
+                },
+                {t!("target.copy")}
+            }
+            CopyModsPath { target: path.clone() }
+            button {
+                class: "secondary",
+                disabled: !exists,
+                title: if exists { "" } else { t!("target.missing") },
+                onclick: move |_| {
+                    if let Err(e) = open_dir(&path) {
+                        notice.set(t!("target.open_failed", error = e));
+                    }
+                },
+                {t!("target.open")}
+            }
+            if !notice().is_empty() {
+                span { class: "note", {notice} }
+            }
+        }
+    }
+}
+
+// A small button that copies the target's engage/mods folder, the path guides tell people
+// to go to. Works before the folder exists, it's where mods should go either way.
+#[cfg(feature = "desktop")]
+#[component]
+fn CopyModsPath(target: PathBuf) -> Element {
+    rsx! {
+        button {
+            class: "secondary icon_button",
+            title: t!("target.copy_mods"),
+            onclick: move |_| {
+                copy_to_clipboard(&disk::display_path(&mods::mods_dir(&target)));
+                toast::push(activity::Severity::Info, t!("target.copied_mods"));
+            },
+            "⧉"
+        }
+    }
+}
+
+// A first-run callout under whatever it points at. "Got it" moves on to the next hint,
+// "Skip tips" ends them for good.
+#[cfg(feature = "desktop")]
+#[component]
+fn Hint(text: String, on_next: EventHandler<MouseEvent>, on_dismiss: EventHandler<MouseEvent>) -> Element {
+    rsx! {
+        div { class: "hint",
+            span { {text} }
+            button { class: "primary", onclick: move |e| on_next.call(e), {t!("hint.next")} }
+            button { class: "secondary", onclick: move |e| on_dismiss.call(e), {t!("hint.skip")} }
+        }
+    }
+}
+
+// A "?" that shows `text` and a link to the docs. Clicking anywhere else closes it, the
+// backdrop behind the popover catches that click.
+#[cfg(feature = "desktop")]
+#[component]
+fn Help(text: String) -> Element {
+    let mut open = use_signal(|| false);
+
+    rsx! {
+        span { class: "help",
+            button {
+                class: "help_button",
+                title: t!("help.title"),
+                onclick: move |_| open.toggle(),
+                "?"
+            }
+            if open() {
+                div { class: "help_backdrop", onclick: move |_| open.set(false) }
+                div { class: "help_popover",
+                    p { {text} }
+                    a { href: MODS_DOCS_URL, {t!("help.more")} }
+                }
+            }
+        }
+    }
+}
+
+// The detected path opens the emulator's folder, the button next to it the sdcard folder
+// Cobalt goes in. A folder deleted since it was detected bumps `detection` instead, so the
+// message (and the install target) catch up.
 #[cfg(feature = "desktop")]
 #[component]
-pub fn EmulatorMessageZone(emulator_name: String) -> Element {
-    let Some(emulator) = get_emulator(&emulator_name) else {
+pub fn EmulatorMessageZone(installation_type: InstallationType, mut detection: Signal<u32>) -> Element {
+    let mut notice = use_signal(String::new);
+
+    let Some(emulator) = installation_type.emulator() else {
         return rsx! {};
     };
+    // Checked again whenever detection runs.
+    detection.read();
+
+    let has_sdcard = emulator.sd_card_path().is_some_and(|p| p.is_dir());
+
+    let mut open = move |path: Option<PathBuf>| {
+        match path.filter(|p| p.is_dir()) {
+            Some(path) => {
+                if let Err(e) = open_dir(&path) {
+                    notice.set(t!("target.open_failed", error = e));
+                } else {
+                    notice.set(String::new());
+                }
+            }
+            None => {
+                tracing::info!("{}'s folder is gone, detecting again", emulator.name);
+                notice.set(t!("emulator.folder_gone"));
+                *detection.write() += 1;
+            }
+        }
+    };
 
     rsx! {
         div { class: "message_zone second",
             div {
                 if emulator.is_installed() {
                     {emulator.name}
-                    " autodetected at "
-                    code { {emulator.data_path().unwrap().display().to_string()} }
+                    {t!("emulator.detected")}
+                    button {
+                        class: "path_link",
+                        title: t!("emulator.open_data"),
+                        onclick: move |_| open(emulator.data_path()),
+                        code { {emulator.data_path().unwrap().display().to_string()} }
+                    }
+                    // Not there until the emulator (or an install) makes it.
+                    button {
+                        class: "secondary small",
+                        disabled: !has_sdcard,
+                        title: if has_sdcard { "" } else { t!("target.missing") },
+                        onclick: move |_| open(emulator.sd_card_path()),
+                        {t!("emulator.open_sdcard")}
+                    }
                 } else {
-                    div { "We couldn't find your {emulator.name} installation." }
-                    div { "Please use the SD Card installation type instead." }
+                    div { {t!("emulator.not_found", emulator = emulator.name)} }
+                    div { {t!("emulator.use_sd")} }
                 }
             }
+            if !notice().is_empty() {
+                div { class: "note", {notice} }
+            }
         }
     }
 }
 
+// `locked` while an install is running, the folder it's writing to can't change under it.
 #[cfg(feature = "desktop")]
 #[component]
-pub fn SdCardSelector(mut selected_sdcard_path: Signal<String>) -> Element {
+pub fn SdCardSelector(mut selected_sdcard_path: Signal<String>, locked: bool) -> Element {
     rsx! {
-        div { id: "sd_select_container", class: "message_zone second",
-            div { "Select your SD Card folder, and we'll install Cobalt there." }
+        div { id: "sd_select_container", class: if locked { "message_zone second locked" } else { "message_zone second" },
+            div { class: "label_with_help",
+                {t!("sd.intro")}
+                Help { text: t!("help.sd_card") }
+            }
             div { id: "sd_select_button_container",
-                label { id: "sd_select_label", r#for: "sd_select", "Select SD Card folder" }
+                label { id: "sd_select_label", r#for: "sd_select", {t!("sd.select")} }
                 input {
                     id: "sd_select",
                     r#type: "file",
                     // Select a folder by setting the directory attribute
                     directory: true,
+                    disabled: locked,
                     onchange: move |evt| {
                         let files = evt.files();
-                        if let Some(file) = files.iter().next() {
+                        if let Some(file) = files.first() {
                             let dir = file.name().to_string();
                             tracing::info!("You chose folder: {}", dir);
                             selected_sdcard_path.set(dir);
@@ -596,16 +4334,17 @@ pub fn SdCardSelector(mut selected_sdcard_path: Signal<String>) -> Element {
                 }
                 div {
                     code {
-                        if selected_sdcard_path().len() == 0 {
-                            {"No folder selected"}
+                        if selected_sdcard_path().is_empty() {
+                            {t!("sd.none")}
                         } else {
                             {selected_sdcard_path}
                         }
                     }
                 }
-                if selected_sdcard_path().len() > 0 {
+                if !selected_sdcard_path().is_empty() {
                     button {
                         class: "close",
+                        disabled: locked,
                         onclick: move |_| {
                             selected_sdcard_path.set("".to_string());
                         },
@@ -613,6 +4352,9 @@ pub fn SdCardSelector(mut selected_sdcard_path: Signal<String>) -> Element {
                     }
                 }
             }
+            if let Some(warning) = layout::target_warning(Path::new(&selected_sdcard_path())) {
+                div { class: "layout_warning", {warning} }
+            }
         }
     }
 }
@@ -0,0 +1,91 @@
+// What a previous install wrote, and which Cobalt version it was.
+//
+// Manifests live in the installer's data directory rather than on the target, one per
+// SD card folder we installed into. That way detection works the same for emulators and
+// SD cards, and nothing extra gets left on the card.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths::data_dir;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    // The SD card folder (or the emulator's virtual one) the files were written into.
+    pub target: PathBuf,
+    // The release tag, or None if GitHub couldn't be reached to resolve it at install time.
+    pub version: Option<String>,
+    // Seconds since the Unix epoch.
+    pub installed_at: u64,
+    // Files written, relative to `target`, as they were named in the release zip.
+    pub files: Vec<String>,
+    // The CRC-32 of each file as written, for telling later whether it's been changed or
+    // damaged. Empty for installs made before these were recorded.
+    #[serde(default)]
+    pub checksums: BTreeMap<String, u32>,
+}
+
+fn manifests_dir() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("manifests"))
+}
+
+// One file per target. The name only has to be stable and filesystem safe, the real
+// target path is stored inside and checked on load.
+fn manifest_path(target: &Path) -> Option<PathBuf> {
+    let name: String = target
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    manifests_dir().map(|d| d.join(format!("{name}.json")))
+}
+
+impl Manifest {
+    pub fn new(target: PathBuf, version: Option<String>, files: Vec<String>) -> Self {
+        let installed_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self { target, version, installed_at, files, checksums: BTreeMap::new() }
+    }
+
+    pub fn load(target: &Path) -> Option<Manifest> {
+        let contents = std::fs::read_to_string(manifest_path(target)?).ok()?;
+        let manifest: Manifest = serde_json::from_str(&contents).ok()?;
+        (manifest.target == target).then_some(manifest)
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = manifest_path(&self.target)
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "No data directory"))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    // Every install we know about, on any target, oldest first. Targets that aren't
+    // reachable right now (an SD card that isn't inserted) are still listed.
+    pub fn all() -> Vec<Manifest> {
+        let Some(entries) = manifests_dir().and_then(|d| std::fs::read_dir(d).ok()) else {
+            return Vec::new();
+        };
+        let mut manifests: Vec<Manifest> = entries
+            .flatten()
+            .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+            .filter_map(|contents| serde_json::from_str(&contents).ok())
+            .collect();
+        manifests.sort_by_key(|m| m.installed_at);
+        manifests
+    }
+
+    // Forget the install on `target`, after an uninstall.
+    pub fn remove(target: &Path) -> std::io::Result<()> {
+        match manifest_path(target) {
+            Some(path) if path.exists() => std::fs::remove_file(path),
+            _ => Ok(()),
+        }
+    }
+}
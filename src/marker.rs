@@ -0,0 +1,127 @@
+// A journal left on the target while an extraction runs.
+//
+// The first line is a JSON header (version, start time), every line after it names a
+// file that was written. A finished extraction deletes the marker, so finding one means
+// the app crashed or was closed mid-install, and the lines tell us exactly what landed.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use dioxus::logger::tracing;
+use serde::{Deserialize, Serialize};
+
+use crate::uninstall::remove_empty_dirs;
+
+pub const MARKER_FILE: &str = ".cobalt_install_in_progress";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Header {
+    version: Option<String>,
+    // Seconds since the Unix epoch.
+    started_at: u64,
+}
+
+// What an interrupted install left behind.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interrupted {
+    pub version: Option<String>,
+    pub started_at: u64,
+    // Files written before the interruption, relative to the target.
+    pub files: Vec<String>,
+}
+
+pub struct Marker {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl Marker {
+    pub fn begin(target: &Path, version: Option<&str>) -> std::io::Result<Marker> {
+        std::fs::create_dir_all(target)?;
+        let path = target.join(MARKER_FILE);
+        let mut file = File::create(&path)?;
+        let header = Header {
+            version: version.map(str::to_string),
+            started_at: crate::history::now(),
+        };
+        writeln!(file, "{}", serde_json::to_string(&header)?)?;
+        file.sync_data()?;
+        Ok(Marker { path, file: Mutex::new(file) })
+    }
+
+    // Note a written file. A marker we can't append to only weakens cleanup later, so
+    // this logs instead of failing the install.
+    pub fn record(&self, name: &str) {
+        // A panic elsewhere while holding the lock doesn't hurt the file, keep appending.
+        let mut file = self.file.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Err(e) = writeln!(file, "{name}") {
+            tracing::warn!("Couldn't record {name} in the install marker: {e}");
+        }
+    }
+
+    // The extraction finished, the marker has served its purpose.
+    pub fn finish(self) -> std::io::Result<()> {
+        drop(self.file);
+        std::fs::remove_file(self.path)
+    }
+}
+
+pub fn find(target: &Path) -> Option<Interrupted> {
+    let file = File::open(target.join(MARKER_FILE)).ok()?;
+    let mut lines = BufReader::new(file).lines().map_while(Result::ok);
+    let header: Header = serde_json::from_str(&lines.next()?).ok()?;
+    Some(Interrupted {
+        version: header.version,
+        started_at: header.started_at,
+        files: lines.filter(|l| !l.is_empty()).collect(),
+    })
+}
+
+// Forget about an interrupted install without touching its files, e.g. once a repair
+// has rewritten them.
+pub fn discard(target: &Path) -> std::io::Result<()> {
+    let path = target.join(MARKER_FILE);
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+// Undo a cancelled install as far as the files allow: delete what it added, and hand back
+// what it wrote over a file `previous` (the install before it) had, since only a backup
+// can bring those back. Leaves the marker for the caller to discard once that's settled.
+pub fn roll_back(target: &Path, interrupted: &Interrupted, previous: &[String]) -> std::io::Result<(usize, Vec<String>)> {
+    let mut removed = 0;
+    let mut overwritten = Vec::new();
+    for name in &interrupted.files {
+        if previous.contains(name) {
+            overwritten.push(name.clone());
+            continue;
+        }
+        let path = target.join(name);
+        if path.is_file() {
+            std::fs::remove_file(path)?;
+            removed += 1;
+        }
+    }
+    remove_empty_dirs(target, &interrupted.files);
+    Ok((removed, overwritten))
+}
+
+// Delete everything the interrupted install wrote, then the marker. Returns how many
+// files were removed.
+pub fn clean_up(target: &Path, interrupted: &Interrupted) -> std::io::Result<usize> {
+    let mut removed = 0;
+    for name in &interrupted.files {
+        let path = target.join(name);
+        if path.is_file() {
+            std::fs::remove_file(path)?;
+            removed += 1;
+        }
+    }
+    remove_empty_dirs(target, &interrupted.files);
+    discard(target)?;
+    Ok(removed)
+}
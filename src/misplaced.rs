@@ -0,0 +1,74 @@
+// Mod content sitting where Cobalt never looks: a bare Data/ tree or fe_assets_* folder
+// dropped straight into engage/mods (or onto the SD root) instead of inside a named mod
+// folder. Found by name only, and only ever moved after the user has seen where to.
+
+use std::path::{Path, PathBuf};
+
+use crate::mods::{mods_dir, MODS_DIR};
+
+// Where the asset bundle folders go inside a mod.
+const ASSETS_PATH: &str = "Data/StreamingAssets/aa/Switch";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stray {
+    pub path: PathBuf,
+    // Where it belongs, relative to the mod folder it's moved into.
+    pub destination: String,
+    // "engage/mods" or "the SD root", for explaining what's wrong.
+    pub location: String,
+}
+
+fn scan_dir(dir: &Path, location: &str, strays: &mut Vec<Stray>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let destination = if name.eq_ignore_ascii_case("data") {
+            "Data".to_string()
+        } else if name.to_lowercase().starts_with("fe_assets_") {
+            format!("{ASSETS_PATH}/{name}")
+        } else {
+            continue;
+        };
+        strays.push(Stray { path: entry.path(), destination, location: location.to_string() });
+    }
+}
+
+pub fn scan(target: &Path) -> Vec<Stray> {
+    let mut strays = Vec::new();
+    scan_dir(&mods_dir(target), MODS_DIR, &mut strays);
+    scan_dir(target, "the SD root", &mut strays);
+    strays
+}
+
+// Each stray's current path and where it would end up in engage/mods/<mod_name>.
+pub fn preview(target: &Path, strays: &[Stray], mod_name: &str) -> Vec<(PathBuf, PathBuf)> {
+    let mod_dir = mods_dir(target).join(mod_name);
+    strays.iter().map(|s| (s.path.clone(), mod_dir.join(&s.destination))).collect()
+}
+
+// Move every stray into engage/mods/<mod_name>. Nothing moves if any destination
+// already exists.
+pub fn move_into_mod(target: &Path, strays: &[Stray], mod_name: &str) -> std::io::Result<()> {
+    if mod_name.is_empty() || mod_name.contains(['/', '\\']) || mod_name == "." || mod_name == ".." {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("\"{mod_name}\" can't be used as a folder name")));
+    }
+    let moves = preview(target, strays, mod_name);
+    if let Some((_, taken)) = moves.iter().find(|(_, to)| to.exists()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists, nothing was moved", taken.display()),
+        ));
+    }
+    for (from, to) in moves {
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::rename(from, to)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,96 @@
+// Does a zip look like an Engage mod at all?
+//
+// Only ever a warning: the mod format keeps growing, so a layout we don't recognize can
+// still be perfectly fine, and the user gets to install it anyway. The rules work on the
+// zip's file list (relative to the mod's own folder), so they can be tested without any
+// files on disk.
+
+// Fire Emblem Engage's title ID.
+pub const ENGAGE_TITLE_ID: &str = "0100a6301214e000";
+
+// Folders a mod keeps its content in, at its root.
+pub const CONTENT_FOLDERS: &[&str] = &["data", "patches", "plugins", "msbt"];
+
+// Why `files` doesn't look like an Engage mod, or None if it does.
+pub fn check(files: &[String]) -> Option<String> {
+    let lower: Vec<String> = files.iter().map(|f| f.to_lowercase()).collect();
+    if lower.is_empty() {
+        return Some("The zip is empty.".to_string());
+    }
+
+    // Something packaged for the whole SD card, e.g. another game's atmosphere mod.
+    if let Some(title) = lower.iter().find_map(|f| {
+        let rest = f.strip_prefix("atmosphere/contents/")?;
+        rest.split('/').next()
+    }) {
+        if title != ENGAGE_TITLE_ID {
+            return Some(format!("It's made for another game (title ID {}).", title.to_uppercase()));
+        }
+        return Some("It's laid out for the root of an SD card, not for engage/mods. Install it by copying it onto the SD card instead.".to_string());
+    }
+
+    if lower.iter().any(|f| f.starts_with("romfs/") || f.starts_with("exefs/")) {
+        return Some("It looks like a raw romfs/exefs dump rather than a Cobalt mod.".to_string());
+    }
+
+    let has_content = lower.iter().any(|f| {
+        f.split_once('/')
+            .is_some_and(|(folder, _)| CONTENT_FOLDERS.contains(&folder))
+    });
+    if !has_content {
+        return Some(format!(
+            "None of the folders Cobalt mods use ({}) are in it.",
+            CONTENT_FOLDERS.iter().map(|f| format!("{f}/")).collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(files: &[&str]) -> Vec<String> {
+        files.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn accepts_a_data_mod() {
+        let files = layout(&[
+            "Data/StreamingAssets/aa/Switch/fe_assets_unit/ubody.bundle",
+            "readme.txt",
+        ]);
+        assert_eq!(check(&files), None);
+    }
+
+    #[test]
+    fn accepts_patches_and_messages_in_any_case() {
+        assert_eq!(check(&layout(&["patches/xml/Person.xml"])), None);
+        assert_eq!(check(&layout(&["MSBT/message/us/usen/accessories.txt"])), None);
+    }
+
+    #[test]
+    fn rejects_a_romfs_dump() {
+        let files = layout(&["romfs/Data/StreamingAssets/aa/catalog.json", "exefs/main"]);
+        assert!(check(&files).unwrap().contains("romfs"));
+    }
+
+    #[test]
+    fn rejects_another_games_mod() {
+        let files = layout(&["atmosphere/contents/01006F8002326000/romfs/file.bin"]);
+        assert!(check(&files).unwrap().contains("01006F8002326000"));
+    }
+
+    #[test]
+    fn points_sd_card_layouts_for_engage_elsewhere() {
+        let files = layout(&["atmosphere/contents/0100A6301214E000/romfs/skyline/plugins/libfoo.nro"]);
+        assert!(check(&files).unwrap().contains("root of an SD card"));
+    }
+
+    #[test]
+    fn rejects_random_files() {
+        assert!(check(&layout(&["photo.png", "notes/todo.txt"])).is_some());
+        assert!(check(&[]).is_some());
+    }
+}
@@ -0,0 +1,508 @@
+// The user's mods: every first-level folder under engage/mods on a target.
+
+use std::path::{Path, PathBuf};
+
+// Relative to the SD root.
+pub const MODS_DIR: &str = "engage/mods";
+
+// Cobalt skips folders with this suffix, which is how a mod gets switched off without
+// deleting it.
+pub const DISABLED_SUFFIX: &str = ".disabled";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModEntry {
+    // The folder name without any `.disabled` suffix, which is what the user knows the
+    // mod by.
+    pub name: String,
+    pub path: PathBuf,
+    pub enabled: bool,
+    pub file_count: usize,
+    // The redundant wrapper folder inside this mod, if it looks packed one level too deep.
+    pub nested: Option<String>,
+}
+
+pub fn mods_dir(target: &Path) -> PathBuf {
+    target.join(MODS_DIR)
+}
+
+// Finding a mod's content folders one level down instead of at its root is the other sign
+// of a zip packed with an extra folder.
+use crate::archive_path;
+use crate::mod_validation::CONTENT_FOLDERS;
+
+// Lowercase letters and digits only, so "Cool Mod", "cool_mod" and "CoolMod-v2" can be
+// compared loosely.
+fn simplified(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric()).map(|c| c.to_ascii_lowercase()).collect()
+}
+
+fn has_content_folder(dir: &Path) -> bool {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries.flatten().any(|e| {
+                e.path().is_dir() && CONTENT_FOLDERS.contains(&e.file_name().to_string_lossy().to_lowercase().as_str())
+            })
+        })
+        .unwrap_or(false)
+}
+
+// The single folder `path` wraps, when the mod looks like `CoolMod/CoolMod/Data/...`:
+// its only entry is a folder with (roughly) the mod's name, or one that holds the content
+// folders the mod itself should.
+fn nested_folder(path: &Path, name: &str) -> Option<String> {
+    let entries: Vec<_> = std::fs::read_dir(path).ok()?.flatten().collect();
+    let [only] = entries.as_slice() else {
+        return None;
+    };
+    if !only.file_type().ok()?.is_dir() {
+        return None;
+    }
+    let inner = only.file_name().to_string_lossy().to_string();
+    let (a, b) = (simplified(&inner), simplified(name));
+    let similar_name = !a.is_empty() && !b.is_empty() && (a.contains(&b) || b.contains(&a));
+    (similar_name || has_content_folder(&only.path())).then_some(inner)
+}
+
+fn count_files(dir: &Path) -> std::io::Result<usize> {
+    let mut count = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            count += count_files(&entry.path())?;
+        } else {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+// The mods on `target`, sorted by name. None when there's no mods folder at all, so the
+// UI can tell "nothing installed" from "nowhere to install to". Walks every mod folder,
+// so keep it off the UI thread.
+pub fn list(target: &Path) -> std::io::Result<Option<Vec<ModEntry>>> {
+    let dir = mods_dir(target);
+    if !dir.is_dir() {
+        return Ok(None);
+    }
+    let mut mods = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let path = entry.path();
+        let folder = entry.file_name().to_string_lossy().to_string();
+        let (name, enabled) = match folder.strip_suffix(DISABLED_SUFFIX) {
+            Some(name) => (name.to_string(), false),
+            None => (folder, true),
+        };
+        mods.push(ModEntry {
+            nested: nested_folder(&path, &name),
+            name,
+            file_count: count_files(&path)?,
+            path,
+            enabled,
+        });
+    }
+    mods.sort_by_key(|m| m.name.to_lowercase());
+    Ok(Some(mods))
+}
+
+// Switch a mod on or off by renaming its folder. Refuses when the other spelling already
+// exists (both `Foo` and `Foo.disabled`), since renaming would have to merge or clobber
+// one of them. Returns the folder's new path.
+pub fn set_enabled(entry: &ModEntry, enabled: bool) -> std::io::Result<PathBuf> {
+    if entry.enabled == enabled {
+        return Ok(entry.path.clone());
+    }
+    let folder = if enabled {
+        entry.name.clone()
+    } else {
+        format!("{}{DISABLED_SUFFIX}", entry.name)
+    };
+    let destination = entry.path.with_file_name(&folder);
+    if destination.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("both {} and {folder} exist, remove or rename one of them first", entry.path.file_name().unwrap_or_default().to_string_lossy()),
+        ));
+    }
+    std::fs::rename(&entry.path, &destination).map_err(|e| {
+        std::io::Error::new(
+            e.kind(),
+            format!("couldn't rename {} ({e}), is a file in it open in another program?", entry.name),
+        )
+    })?;
+    Ok(destination)
+}
+
+// A mod zip, as far as where its files should go.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModArchive {
+    pub zip_path: PathBuf,
+    // The name the mod's folder gets: the wrapping folder's name when the zip has one,
+    // otherwise the zip's own name.
+    pub name: String,
+    // The single folder everything in the zip sits in, if it's packed that way.
+    wrapper: Option<String>,
+    // Every file in the zip, relative to the mod's folder (so without the wrapper).
+    pub files: Vec<String>,
+}
+
+// Whether `name` can be a mod's folder: one plain name, nothing like `..` or an empty
+// string that would point at engage/ or the mods folder itself.
+pub(crate) fn is_plain_name(name: &str) -> bool {
+    archive_path::sanitize(name).is_ok_and(|path| path.components().count() == 1 && path == Path::new(name))
+}
+
+fn not_a_mod_name(name: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{name:?} can't be a mod's folder name"))
+}
+
+// Work out whether the zip's root is the mod itself or holds one folder that is. macOS
+// metadata folders don't count towards either.
+pub fn inspect_zip(zip_path: &Path) -> std::io::Result<ModArchive> {
+    let archive = zip::ZipArchive::new(std::fs::File::open(zip_path)?)?;
+    let mut top_level = std::collections::BTreeSet::new();
+    let mut loose_files = false;
+    for name in archive.file_names() {
+        if name.starts_with("__MACOSX/") {
+            continue;
+        }
+        match name.split_once('/') {
+            Some((folder, _)) => {
+                top_level.insert(folder.to_string());
+            }
+            None => loose_files = true,
+        }
+    }
+
+    // A lone "Data" or "msbt" folder is the mod's content, not a wrapper around it. Neither
+    // is a name that isn't a folder at all, like the `..` of a zip made to escape.
+    let wrapper = match (loose_files, top_level.len()) {
+        (false, 1) => top_level
+            .into_iter()
+            .next()
+            .filter(|folder| is_plain_name(folder) && !CONTENT_FOLDERS.contains(&folder.to_lowercase().as_str())),
+        _ => None,
+    };
+    let files = archive
+        .file_names()
+        .filter(|name| !name.starts_with("__MACOSX/") && !name.ends_with('/'))
+        .map(|name| match &wrapper {
+            Some(folder) => name.strip_prefix(folder.as_str()).unwrap_or(name).trim_start_matches('/').to_string(),
+            None => name.to_string(),
+        })
+        .collect();
+    let name = match &wrapper {
+        Some(folder) => folder.clone(),
+        None => zip_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .filter(|stem| is_plain_name(stem))
+            .unwrap_or_else(|| "New mod".to_string()),
+    };
+    Ok(ModArchive { zip_path: zip_path.to_path_buf(), name, wrapper, files })
+}
+
+// Folder translation patches keep their text in, at the mod's root. Cobalt loads message
+// overrides from here.
+pub const MESSAGES_FOLDER: &str = "msbt";
+
+// A translation patch is a mod made of message files: it has to have an msbt folder, and
+// nothing outside it but the odd readme.
+pub fn check_translation_patch(archive: &ModArchive) -> Result<(), String> {
+    let prefix = format!("{MESSAGES_FOLDER}/");
+    let is_message = |f: &String| f.to_lowercase().starts_with(&prefix);
+    if !archive.files.iter().any(is_message) {
+        return Err(format!("{} has no {MESSAGES_FOLDER} folder, so it isn't a translation patch", archive.name));
+    }
+    let strays: Vec<&String> = archive
+        .files
+        .iter()
+        .filter(|f| !is_message(f) && f.contains('/'))
+        .collect();
+    if let Some(first) = strays.first() {
+        return Err(format!(
+            "{} has files outside its {MESSAGES_FOLDER} folder ({first} and {} more), it looks like a regular mod rather than a translation patch",
+            archive.name,
+            strays.len() - 1,
+        ));
+    }
+    Ok(())
+}
+
+// Whether a mod called `name` is already there, enabled or not, in any case.
+pub fn exists(target: &Path, name: &str) -> bool {
+    let dir = mods_dir(target);
+    [name.to_string(), format!("{name}{DISABLED_SUFFIX}")]
+        .iter()
+        .any(|folder| crate::disk::resolve_case_insensitive(&dir, folder).exists())
+}
+
+// Make room for a mod called `name`, deleting it whether it's enabled or not. A disabled
+// copy has to go too, or we'd end up with both spellings side by side.
+pub fn remove_existing(target: &Path, name: &str) -> std::io::Result<()> {
+    if !is_plain_name(name) {
+        return Err(not_a_mod_name(name));
+    }
+    let dir = mods_dir(target);
+    for old in [name.to_string(), format!("{name}{DISABLED_SUFFIX}")]
+        .iter()
+        .map(|folder| crate::disk::resolve_case_insensitive(&dir, folder))
+    {
+        if old.exists() {
+            std::fs::remove_dir_all(old)?;
+        }
+    }
+    Ok(())
+}
+
+// `name`, or "name (2)", "name (3)"... whichever isn't taken yet.
+pub fn unique_name(target: &Path, name: &str) -> String {
+    (2..)
+        .map(|n| format!("{name} ({n})"))
+        .find(|candidate| !exists(target, candidate))
+        .filter(|_| exists(target, name))
+        .unwrap_or_else(|| name.to_string())
+}
+
+// Extract `archive` into engage/mods/<name> through the same checks as a Cobalt
+// install. With `overwrite` an existing mod of that name (enabled or not) is replaced
+// entirely, so none of its old files linger, otherwise it's an error for one to exist.
+pub fn install_zip(
+    target: &Path,
+    archive: &ModArchive,
+    name: &str,
+    overwrite: bool,
+    progress: impl FnMut(usize, usize),
+) -> std::io::Result<crate::extract::Extracted> {
+    if !is_plain_name(name) {
+        return Err(not_a_mod_name(name));
+    }
+    let dest = mods_dir(target).join(name);
+    if exists(target, name) {
+        if !overwrite {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("a mod called {name} is already installed"),
+            ));
+        }
+        remove_existing(target, name)?;
+    }
+    std::fs::create_dir_all(&dest)?;
+    let options = crate::extract::ExtractOptions {
+        strip_prefix: archive.wrapper.as_deref().unwrap_or(""),
+        ..Default::default()
+    };
+    crate::extract::extract_archive(std::fs::File::open(&archive.zip_path)?, &dest, options, progress)
+}
+
+// Total size of the files under `dir`. Symlinks count as themselves, never followed.
+pub fn dir_size(dir: &Path) -> std::io::Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.path().symlink_metadata()?;
+        size += if metadata.is_dir() { dir_size(&entry.path())? } else { metadata.len() };
+    }
+    Ok(size)
+}
+
+// Delete everything under `path`, depth first, without following symlinks. The error
+// names the file that couldn't be removed.
+fn remove_tree(path: &Path) -> std::io::Result<()> {
+    let blocked = |e: std::io::Error, path: &Path| std::io::Error::new(e.kind(), format!("{} couldn't be removed: {e}", path.display()));
+    if path.symlink_metadata().map_err(|e| blocked(e, path))?.is_dir() {
+        for entry in std::fs::read_dir(path).map_err(|e| blocked(e, path))? {
+            remove_tree(&entry.map_err(|e| blocked(e, path))?.path())?;
+        }
+        std::fs::remove_dir(path).map_err(|e| blocked(e, path))
+    } else {
+        std::fs::remove_file(path).map_err(|e| blocked(e, path))
+    }
+}
+
+// Remove a mod's folder for good. Only ever a direct child of engage/mods on `target`:
+// anything else, including a link that resolves somewhere outside the mods folder, is
+// refused rather than deleted.
+pub fn delete(target: &Path, entry: &ModEntry) -> std::io::Result<()> {
+    let refuse = || std::io::Error::new(
+        std::io::ErrorKind::PermissionDenied,
+        format!("{} isn't inside engage/mods, refusing to delete it", entry.path.display()),
+    );
+    let mods = mods_dir(target).canonicalize()?;
+    if entry.path.parent().and_then(|p| p.canonicalize().ok()).as_deref() != Some(mods.as_path()) {
+        return Err(refuse());
+    }
+    let metadata = entry.path.symlink_metadata()?;
+    if metadata.is_symlink() {
+        if !entry.path.canonicalize()?.starts_with(&mods) {
+            return Err(refuse());
+        }
+        // Drop the link itself, whatever it points at stays.
+        return std::fs::remove_file(&entry.path);
+    }
+    remove_tree(&entry.path)
+}
+
+// A file more than one enabled mod ships. Which copy wins depends on load order, so the
+// user should know about it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+    // Relative to the mod folders, with forward slashes.
+    pub path: String,
+    pub mods: Vec<String>,
+}
+
+fn index_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            index_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let parts: Vec<_> = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+            files.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+// Every path claimed by two or more enabled mods, sorted by path.
+pub fn find_conflicts(target: &Path) -> std::io::Result<Vec<Conflict>> {
+    let mut claims: std::collections::BTreeMap<String, Vec<String>> = Default::default();
+    for m in list(target)?.unwrap_or_default().into_iter().filter(|m| m.enabled) {
+        let mut files = Vec::new();
+        index_files(&m.path, &m.path, &mut files)?;
+        for file in files {
+            claims.entry(file).or_default().push(m.name.clone());
+        }
+    }
+    Ok(claims
+        .into_iter()
+        .filter(|(_, mods)| mods.len() > 1)
+        .map(|(path, mods)| Conflict { path, mods })
+        .collect())
+}
+
+// The conflicts as plain text, for pasting into a help channel.
+pub fn conflicts_report(conflicts: &[Conflict]) -> String {
+    if conflicts.is_empty() {
+        return "No conflicts: no two enabled mods ship the same file.".to_string();
+    }
+    let mut report = format!("{} files are shipped by more than one enabled mod:\n", conflicts.len());
+    for conflict in conflicts {
+        report.push_str(&format!("\n{}\n", conflict.path));
+        for m in &conflict.mods {
+            report.push_str(&format!("  - {m}\n"));
+        }
+    }
+    report
+}
+
+// Move everything in the mod's wrapper folder up one level and remove the wrapper. Checks
+// every move first and changes nothing if any of them would overwrite something.
+pub fn flatten(entry: &ModEntry) -> std::io::Result<()> {
+    let Some(inner_name) = &entry.nested else {
+        return Ok(());
+    };
+    let inner = entry.path.join(inner_name);
+    let children: Vec<_> = std::fs::read_dir(&inner)?.collect::<Result<_, _>>()?;
+    for child in &children {
+        let name = child.file_name();
+        // A child named like the wrapper only collides with the wrapper itself, which is
+        // moved out of the way first.
+        if name != inner.file_name().unwrap_or_default() && entry.path.join(&name).exists() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("{} already has a {}, flattening would overwrite it", entry.name, name.to_string_lossy()),
+            ));
+        }
+    }
+
+    let staging = entry.path.join(".cobalt_flatten");
+    std::fs::rename(&inner, &staging)?;
+    for child in &children {
+        std::fs::rename(staging.join(child.file_name()), entry.path.join(child.file_name()))?;
+    }
+    std::fs::remove_dir(&staging)
+}
+
+// How many mods `target` has and how much space they take, for the quick summary in the
+// action zone. None without a mods folder.
+pub fn summary(target: &Path) -> std::io::Result<Option<(usize, u64)>> {
+    let Some(found) = list(target)? else {
+        return Ok(None);
+    };
+    let mut total = 0;
+    for m in &found {
+        total += dir_size(&m.path)?;
+    }
+    Ok(Some((found.len(), total)))
+}
+
+// Copy `src` to `dst` file by file. Links are skipped rather than followed out of the
+// mod. Returns how many files were copied.
+fn copy_tree(src: &Path, dst: &Path) -> std::io::Result<usize> {
+    std::fs::create_dir_all(dst)?;
+    let mut copied = 0;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let dest = dst.join(entry.file_name());
+        if file_type.is_dir() {
+            copied += copy_tree(&entry.path(), &dest)?;
+        } else if file_type.is_file() {
+            std::fs::copy(entry.path(), &dest)?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+// Copy a mod into engage/mods on another target, keeping it enabled or disabled as it
+// is. An existing mod of the same name (in any case, enabled or not) is replaced with
+// `overwrite` and left alone otherwise. Returns the files copied, or None if skipped.
+pub fn copy_to(entry: &ModEntry, dest_target: &Path, overwrite: bool) -> std::io::Result<Option<usize>> {
+    if exists(dest_target, &entry.name) {
+        if !overwrite {
+            return Ok(None);
+        }
+        remove_existing(dest_target, &entry.name)?;
+    }
+    let folder = entry.path.file_name().unwrap_or_default();
+    let dest = crate::disk::resolve_case_insensitive(dest_target, MODS_DIR).join(folder);
+    copy_tree(&entry.path, &dest).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scratch;
+
+    fn zip_of(names: &[&str]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for name in names {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn escaping_wrappers_are_not_mod_names() {
+        let scratch = Scratch::new("mods_escaping_wrapper");
+        scratch.file("engage/mods/keep/mod.txt", b"keep");
+        for (zip, names) in [("dotdot.zip", &["../x/a.txt", "../x/b.txt"][..]), ("rooted.zip", &["/a.txt", "/b.txt"])] {
+            scratch.file(zip, &zip_of(names));
+            let archive = inspect_zip(&scratch.0.join(zip)).unwrap();
+            assert_eq!(archive.wrapper, None, "{zip}");
+            assert_eq!(archive.name, zip.trim_end_matches(".zip"));
+        }
+        for name in ["..", "", ".", "a/b", "../mods"] {
+            assert!(remove_existing(&scratch.0, name).is_err(), "{name:?}");
+        }
+        assert!(scratch.0.join("engage/mods/keep/mod.txt").exists());
+    }
+}
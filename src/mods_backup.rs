@@ -0,0 +1,225 @@
+// Zipping up a target's whole engage/mods folder, and putting such a zip back.
+//
+// Unlike install backups these are for the user to keep, so they go wherever the user
+// says rather than into the data directory. Entries are named relative to engage/mods,
+// so each mod (disabled ones included) is a top-level folder in the zip.
+
+use std::fs::File;
+use std::io::{Seek, Write};
+use std::path::{Path, PathBuf};
+
+use dioxus::logger::tracing;
+use time::macros::format_description;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::mods::mods_dir;
+
+// What a mods backup ended up holding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModsBackup {
+    pub path: PathBuf,
+    pub files: usize,
+    // Size of the zip itself.
+    pub size: u64,
+    // Symlinks, which are never followed out of the mods folder, relative to it.
+    pub skipped: Vec<String>,
+}
+
+// cobalt_mods_<date>_<time>.zip, in UTC.
+pub fn default_file_name() -> String {
+    let now = time::OffsetDateTime::now_utc();
+    let stamp = now
+        .format(format_description!("[year]-[month]-[day]_[hour]-[minute]-[second]"))
+        .unwrap_or_else(|_| now.unix_timestamp().to_string());
+    format!("cobalt_mods_{stamp}.zip")
+}
+
+// Zip entry name for `path` under `root`, always with forward slashes.
+fn entry_name(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let parts: Vec<_> = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+    parts.join("/")
+}
+
+// Files and folders under `dir`, folders first so empty ones survive too. Symlinks go in
+// `skipped` instead.
+fn collect(root: &Path, dir: &Path, dirs: &mut Vec<PathBuf>, files: &mut Vec<PathBuf>, skipped: &mut Vec<String>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            tracing::warn!("Not backing up {}, it's a link", path.display());
+            skipped.push(entry_name(root, &path));
+        } else if file_type.is_dir() {
+            dirs.push(path.clone());
+            collect(root, &path, dirs, files, skipped)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+// Back up engage/mods on `target` to `dest`, calling `progress(done, total)` after each
+// file.
+pub fn create(target: &Path, dest: &Path, mut progress: impl FnMut(usize, usize)) -> std::io::Result<ModsBackup> {
+    let root = mods_dir(target);
+    let (mut dirs, mut files, mut skipped) = (Vec::new(), Vec::new(), Vec::new());
+    collect(&root, &root, &mut dirs, &mut files, &mut skipped)?;
+
+    let result = write_zip(File::create(dest)?, &root, &dirs, &files, &mut progress);
+    if result.is_err() {
+        // Don't leave a half written zip around looking like a usable backup.
+        let _ = std::fs::remove_file(dest);
+    }
+    result?;
+    Ok(ModsBackup {
+        path: dest.to_path_buf(),
+        files: files.len(),
+        size: std::fs::metadata(dest)?.len(),
+        skipped,
+    })
+}
+
+fn write_zip<W: Write + Seek>(
+    out: W,
+    root: &Path,
+    dirs: &[PathBuf],
+    files: &[PathBuf],
+    progress: &mut impl FnMut(usize, usize),
+) -> std::io::Result<()> {
+    let mut zip = ZipWriter::new(out);
+    let options = SimpleFileOptions::default();
+    for dir in dirs {
+        zip.add_directory(entry_name(root, dir), options)?;
+    }
+    for (i, file) in files.iter().enumerate() {
+        zip.start_file(entry_name(root, file), options)?;
+        std::io::copy(&mut File::open(file)?, &mut zip)?;
+        progress(i + 1, files.len());
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+// What to do with one mod from a backup.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Resolution {
+    Skip,
+    // Put it back, replacing a mod of the same name if there is one.
+    Restore,
+    // Put it back next to the existing mod, under the first free "name (n)".
+    Rename,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlannedMod {
+    // The folder as stored in the backup, `.disabled` suffix and all.
+    pub folder: String,
+    // A mod of the same name is already on the target.
+    pub conflict: bool,
+    pub resolution: Resolution,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestorePlan {
+    pub zip_path: PathBuf,
+    pub mods: Vec<PlannedMod>,
+}
+
+fn base_name(folder: &str) -> &str {
+    folder.strip_suffix(crate::mods::DISABLED_SUFFIX).unwrap_or(folder)
+}
+
+// Backups are picked by the user and could have come from anywhere, a folder called `..`
+// or nothing at all would have the restore replace engage/ or the whole mods folder.
+fn is_mod_folder(folder: &str) -> bool {
+    crate::mods::is_plain_name(folder) && crate::mods::is_plain_name(base_name(folder))
+}
+
+// Read the mod folders out of a backup and check each against `target`. Conflicting
+// mods default to being skipped, so nothing is replaced unless the user picks it.
+pub fn plan_restore(target: &Path, zip_path: &Path) -> std::io::Result<RestorePlan> {
+    let archive = zip::ZipArchive::new(File::open(zip_path)?)?;
+    let folders: std::collections::BTreeSet<String> = archive
+        .file_names()
+        .filter_map(|name| name.split_once('/').map(|(folder, _)| folder.to_string()))
+        .filter(|folder| folder != "__MACOSX" && is_mod_folder(folder))
+        .collect();
+    if folders.is_empty() {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "this zip doesn't contain any mod folders"));
+    }
+    let mods = folders
+        .into_iter()
+        .map(|folder| {
+            let conflict = crate::mods::exists(target, base_name(&folder));
+            PlannedMod {
+                folder,
+                conflict,
+                resolution: if conflict { Resolution::Skip } else { Resolution::Restore },
+            }
+        })
+        .collect();
+    Ok(RestorePlan { zip_path: zip_path.to_path_buf(), mods })
+}
+
+// Carry out `plan` on `target`, calling `progress(done, total)` after each mod. Returns
+// how many mods were put back.
+pub fn restore(target: &Path, plan: &RestorePlan, mut progress: impl FnMut(usize, usize)) -> std::io::Result<usize> {
+    let chosen: Vec<&PlannedMod> =
+        plan.mods.iter().filter(|m| m.resolution != Resolution::Skip && is_mod_folder(&m.folder)).collect();
+    for (i, planned) in chosen.iter().enumerate() {
+        let base = base_name(&planned.folder);
+        let folder = match planned.resolution {
+            Resolution::Rename if planned.conflict => {
+                let name = crate::mods::unique_name(target, base);
+                format!("{name}{}", &planned.folder[base.len()..])
+            }
+            _ => {
+                crate::mods::remove_existing(target, base)?;
+                planned.folder.clone()
+            }
+        };
+        let dest = mods_dir(target).join(&folder);
+        std::fs::create_dir_all(&dest)?;
+        let options = crate::extract::ExtractOptions {
+            strip_prefix: &planned.folder,
+            ..Default::default()
+        };
+        crate::extract::extract_archive(File::open(&plan.zip_path)?, &dest, options, |_, _| {})?;
+        progress(i + 1, chosen.len());
+    }
+    Ok(chosen.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scratch;
+
+    #[test]
+    fn only_plain_folders_are_restored() {
+        let scratch = Scratch::new("mods_backup_plain");
+        scratch.file("engage/mods/keep/mod.txt", b"keep");
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for name in ["../evil.txt", "/root.txt", ".disabled/x.txt", "fine/mod.txt"] {
+            zip.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+        }
+        scratch.file("backup.zip", &zip.finish().unwrap().into_inner());
+
+        let plan = plan_restore(&scratch.0, &scratch.0.join("backup.zip")).unwrap();
+        let folders: Vec<&str> = plan.mods.iter().map(|m| m.folder.as_str()).collect();
+        assert_eq!(folders, ["fine"]);
+        // Even when asked to directly.
+        let forged = RestorePlan {
+            mods: vec![PlannedMod { folder: "..".to_string(), conflict: false, resolution: Resolution::Restore }],
+            ..plan.clone()
+        };
+        assert_eq!(restore(&scratch.0, &forged, |_, _| {}).unwrap(), 0);
+        assert_eq!(restore(&scratch.0, &plan, |_, _| {}).unwrap(), 1);
+        assert!(scratch.0.join("engage/mods/keep/mod.txt").exists());
+        assert!(scratch.0.join("engage/mods/fine/mod.txt").exists());
+    }
+}
@@ -0,0 +1,79 @@
+// A plain text rundown of what's installed on a target, for pasting into a help channel
+// when someone asks "what mods do you have".
+//
+// Only the target's own path is shown, and even that with the home folder (and the user
+// name, wherever else it turns up, e.g. /run/media/<user>) taken out.
+
+use std::path::Path;
+
+use crate::manifest::Manifest;
+use crate::{disk, layout, misplaced, mods};
+
+// `path` with the home folder swapped for `~` and any component naming the user
+// replaced.
+pub fn redact(path: &Path) -> String {
+    let Some(home) = dirs::home_dir() else {
+        return path.display().to_string();
+    };
+    let shown = match path.strip_prefix(&home) {
+        Ok(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Ok(rest) => Path::new("~").join(rest).display().to_string(),
+        Err(_) => path.display().to_string(),
+    };
+    match home.file_name().map(|n| n.to_string_lossy().to_string()) {
+        Some(user) if !user.is_empty() => {
+            let parts: Vec<&str> =
+                shown.split(std::path::MAIN_SEPARATOR).map(|c| if c == user { "<user>" } else { c }).collect();
+            parts.join(std::path::MAIN_SEPARATOR_STR)
+        }
+        _ => shown,
+    }
+}
+
+// Walks every mod twice (sizes, then conflicts), so keep it off the UI thread.
+pub fn build(target: &Path) -> std::io::Result<String> {
+    let version = Manifest::load(target)
+        .map(|m| m.version.unwrap_or_else(|| "unknown version".to_string()))
+        .unwrap_or_else(|| "not installed by this installer".to_string());
+    let mut report = format!(
+        "Cobalt mods report\nInstaller: {}\nCobalt: {version}\nTarget: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        redact(target),
+    );
+
+    report.push_str("\nMods:\n");
+    match mods::list(target)? {
+        None => report.push_str("  (no engage/mods folder)\n"),
+        Some(found) if found.is_empty() => report.push_str("  (none)\n"),
+        Some(found) => {
+            for m in found {
+                let state = if m.enabled { "enabled" } else { "disabled" };
+                let size = mods::dir_size(&m.path).map(disk::format_bytes).unwrap_or_else(|_| "unknown size".to_string());
+                report.push_str(&format!("  - {} [{state}] {} files, {size}\n", m.name, m.file_count));
+                if let Some(inner) = &m.nested {
+                    report.push_str(&format!("    packed one folder too deep (inside {inner})\n"));
+                }
+            }
+        }
+    }
+
+    let mut problems = Vec::new();
+    if let Some(warning) = layout::target_warning(target) {
+        problems.push(warning);
+    }
+    for stray in misplaced::scan(target) {
+        let name = stray.path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        problems.push(format!("{name} is directly in {}, outside any mod folder", stray.location));
+    }
+    report.push_str("\nLayout problems:\n");
+    if problems.is_empty() {
+        report.push_str("  (none found)\n");
+    }
+    for problem in problems {
+        report.push_str(&format!("  - {problem}\n"));
+    }
+
+    report.push_str("\nConflicts:\n");
+    report.push_str(&mods::conflicts_report(&mods::find_conflicts(target)?));
+    Ok(report)
+}
@@ -0,0 +1,71 @@
+// Native "it's done" notifications, for when the user has switched away mid-install.
+//
+// There's no notification crate in the dependency tree, so this goes through each
+// platform's own tool, the same way open_dir goes through the file manager. Only
+// notify-send can tell us the notification was clicked.
+
+use std::process::Command;
+
+const TITLE: &str = "Cobalt Installer";
+
+// PowerShell's own app id, toasts from an unregistered one are silently dropped.
+#[cfg(windows)]
+const POWERSHELL_APP_ID: &str = r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe";
+
+// Show `message`, blocking until the notification is gone where the platform waits for
+// that. Returns whether the user clicked it.
+pub fn show(message: &str) -> std::io::Result<bool> {
+    match std::env::consts::OS {
+        "linux" => {
+            let output = Command::new("notify-send")
+                .args(["--app-name", TITLE, "--action=default=Show", "--wait", TITLE, message])
+                .output()?;
+            if !output.status.success() {
+                return Err(std::io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).trim() == "default")
+        }
+        "macos" => {
+            let script = format!(
+                "display notification {} with title {}",
+                serde_json::to_string(message)?,
+                serde_json::to_string(TITLE)?,
+            );
+            let status = Command::new("osascript").args(["-e", &script]).status()?;
+            if !status.success() {
+                return Err(std::io::Error::other(format!("osascript exited with {status}")));
+            }
+            Ok(false)
+        }
+        #[cfg(windows)]
+        "windows" => {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+            // The text goes in through the environment so nothing in it needs escaping.
+            let script = r#"
+                [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null
+                $template = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02)
+                $text = $template.GetElementsByTagName('text')
+                $text.Item(0).AppendChild($template.CreateTextNode($env:COBALT_TOAST_TITLE)) > $null
+                $text.Item(1).AppendChild($template.CreateTextNode($env:COBALT_TOAST_MESSAGE)) > $null
+                $toast = [Windows.UI.Notifications.ToastNotification]::new($template)
+                [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($env:COBALT_TOAST_APP).Show($toast)
+            "#;
+            let status = Command::new("powershell")
+                .args(["-NoProfile", "-NonInteractive", "-Command", script])
+                .env("COBALT_TOAST_TITLE", TITLE)
+                .env("COBALT_TOAST_MESSAGE", message)
+                .env("COBALT_TOAST_APP", POWERSHELL_APP_ID)
+                .creation_flags(CREATE_NO_WINDOW)
+                .status()?;
+            if !status.success() {
+                return Err(std::io::Error::other(format!("powershell exited with {status}")));
+            }
+            Ok(false)
+        }
+        other => Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("no notifications on {other}"),
+        )),
+    }
+}
@@ -0,0 +1,429 @@
+// Where things are on this machine: each emulator's folders, the installer's own data
+// directory, the folders Cobalt uses under an SD root, and how to show one of them in the
+// file manager.
+//
+// On Android we don't hunt for install folders, the user hands us Eden's folder through
+// the system picker instead (see the `saf` module in main.rs).
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+
+use dioxus::logger::tracing;
+use dirs::home_dir;
+
+use crate::installation_type::InstallationType;
+use crate::system::{RealSystem, System};
+use crate::t;
+
+pub struct Emulator {
+    pub name: &'static str,
+    pub linux_data_path: &'static str,
+    pub macos_data_path: &'static str,
+    pub windows_data_folder: &'static str,
+    pub sd_card_folder: &'static str,
+    // The program's name on PATH (and its .app on macOS), for the Launch button. None when
+    // it doesn't install anywhere we can count on.
+    pub executable: Option<&'static str>,
+    pub flatpak_id: Option<&'static str>,
+}
+
+// Where Linux's XDG base directories are by default, relative to home, what moves them,
+// and what a Flatpak sandbox calls them under ~/.var/app/<id>.
+const XDG_DIRS: &[(&str, &str, &str)] = &[(".config/", "XDG_CONFIG_HOME", "config"), (".local/share/", "XDG_DATA_HOME", "data")];
+
+impl Emulator {
+    // Everywhere the emulator might keep its data on `system`, most likely first. On Linux
+    // that's the XDG folder if it's been moved, the usual one, then the Flatpak sandbox's.
+    pub fn data_path_candidates(&self, system: &dyn System) -> Vec<PathBuf> {
+        let home = system.home_dir();
+        match system.os() {
+            "macos" => home.map(|h| h.join(self.macos_data_path)).into_iter().collect(),
+            "windows" => system
+                .var_os("APPDATA")
+                .filter(|a| !a.is_empty())
+                .map(|a| PathBuf::from(a).join(self.windows_data_folder))
+                .into_iter()
+                .collect(),
+            "linux" => {
+                let mut candidates = Vec::new();
+                for (default, var, sandboxed) in XDG_DIRS {
+                    let Some(rest) = self.linux_data_path.strip_prefix(default) else {
+                        continue;
+                    };
+                    // The spec says to ignore relative ones.
+                    if let Some(dir) = system.var_os(var).map(PathBuf::from).filter(|d| d.is_absolute()) {
+                        candidates.push(dir.join(rest));
+                    }
+                    if let Some(home) = &home {
+                        candidates.push(home.join(self.linux_data_path));
+                        if let Some(id) = self.flatpak_id {
+                            candidates.push(home.join(".var/app").join(id).join(sandboxed).join(rest));
+                        }
+                    }
+                }
+                if candidates.is_empty() {
+                    candidates.extend(home.map(|h| h.join(self.linux_data_path)));
+                }
+                candidates.dedup();
+                candidates
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    // The first candidate that exists, or the most likely one when none do yet.
+    pub fn data_path_in(&self, system: &dyn System) -> Option<PathBuf> {
+        let candidates = self.data_path_candidates(system);
+        if candidates.is_empty() {
+            tracing::trace!("{}: nowhere to look on {}", self.name, system.os());
+        }
+        let found = candidates.iter().find(|c| {
+            let exists = system.exists(c);
+            tracing::trace!("{}: {} {}", self.name, c.display(), if exists { "exists" } else { "doesn't exist" });
+            exists
+        });
+        found.or(candidates.first()).cloned()
+    }
+
+    pub fn sd_card_path_in(&self, system: &dyn System) -> Option<PathBuf> {
+        self.data_path_in(system).map(|p| p.join(self.sd_card_folder))
+    }
+
+    pub fn is_installed_in(&self, system: &dyn System) -> bool {
+        self.data_path_in(system).is_some_and(|p| system.exists(&p))
+    }
+
+    pub fn data_path(&self) -> Option<PathBuf> {
+        self.data_path_in(&RealSystem)
+    }
+
+    pub fn sd_card_path(&self) -> Option<PathBuf> {
+        self.sd_card_path_in(&RealSystem)
+    }
+
+    pub fn is_installed(&self) -> bool {
+        self.is_installed_in(&RealSystem)
+    }
+
+    // A command that starts the emulator, if we can find it.
+    pub fn launcher(&self) -> Option<Command> {
+        let executable = self.executable?;
+        if std::env::consts::OS == "macos" {
+            let app = ["/Applications".into(), home_dir()?.join("Applications")]
+                .into_iter()
+                .map(|dir: PathBuf| dir.join(format!("{executable}.app")))
+                .find(|app| app.exists())?;
+            let mut command = Command::new("open");
+            command.arg(app);
+            return Some(command);
+        }
+        let file_name = if cfg!(windows) { format!("{executable}.exe") } else { executable.to_string() };
+        let on_path = std::env::var_os("PATH").and_then(|paths| {
+            std::env::split_paths(&paths).map(|dir| dir.join(&file_name)).find(|p| p.is_file())
+        });
+        if let Some(program) = on_path {
+            return Some(Command::new(program));
+        }
+        let flatpak_id = self.flatpak_id?;
+        let installed = [PathBuf::from("/var/lib/flatpak/app"), home_dir()?.join(".local/share/flatpak/app")]
+            .iter()
+            .any(|dir| dir.join(flatpak_id).exists());
+        installed.then(|| {
+            let mut command = Command::new("flatpak");
+            command.args(["run", flatpak_id]);
+            command
+        })
+    }
+}
+
+pub static EMULATORS: &[Emulator] = &[
+    Emulator {
+        name: "Ryujinx",
+        linux_data_path: ".config/Ryujinx",
+        macos_data_path: "Library/Application Support/Ryujinx",
+        windows_data_folder: "Ryujinx",
+        sd_card_folder: "sdcard",
+        executable: Some("Ryujinx"),
+        flatpak_id: Some("org.ryujinx.Ryujinx"),
+    },
+    Emulator {
+        name: "Citron",
+        linux_data_path: ".local/share/citron", // I got this from the docs https://citron-emu.org/docs/installation
+        macos_data_path: ".local/share/citron",
+        windows_data_folder: "citron",
+        sd_card_folder: "sdmc",
+        executable: None,
+        flatpak_id: None,
+    },
+    Emulator {
+        name: "Eden",
+        linux_data_path: ".local/share/eden", // Assuming based on how Eden has the same structure as Citron, it's not mentioned in the docs.
+        macos_data_path: ".local/share/eden",
+        windows_data_folder: "eden",
+        sd_card_folder: "sdmc",
+        executable: None,
+        flatpak_id: None,
+    },
+];
+
+pub fn get_emulator(name: &str) -> Option<&'static Emulator> {
+    EMULATORS.iter().find(|e| e.name == name)
+}
+
+// The installation type that installs into `target`, for labelling targets we only know
+// by path.
+pub fn target_type_of(target: &Path) -> InstallationType {
+    EMULATORS
+        .iter()
+        .find(|e| e.sd_card_path().as_deref() == Some(target))
+        .and_then(|e| InstallationType::from_name(e.name))
+        .unwrap_or(InstallationType::SdCard)
+}
+
+// Where the installer keeps its own files: the webview data, LocalStorage, manifests.
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("CobaltInstaller"))
+}
+
+// Downloaded releases we may reuse. Safe to delete at any time.
+pub fn cache_dir() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("cache"))
+}
+
+// Why an installation type has nowhere to install yet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TargetError {
+    EmulatorNotFound(&'static str),
+    NoDataPath(&'static str),
+    NoSdPath,
+}
+
+// In the user's language, for showing where the path would be.
+impl std::fmt::Display for TargetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let text = match self {
+            TargetError::EmulatorNotFound(emulator) => t!("emulator.not_found", emulator = emulator),
+            TargetError::NoDataPath(emulator) => t!("target.no_data_path", emulator = emulator),
+            TargetError::NoSdPath => t!("target.no_sd_path"),
+        };
+        f.write_str(&text)
+    }
+}
+
+// Where an installation type puts Cobalt: the emulator's sdcard folder, or the SD card
+// folder the user picked.
+pub fn resolve_target(installation_type: InstallationType, sd_card_path: &str) -> Result<PathBuf, TargetError> {
+    resolve_target_in(&RealSystem, installation_type, sd_card_path)
+}
+
+pub fn resolve_target_in(system: &dyn System, installation_type: InstallationType, sd_card_path: &str) -> Result<PathBuf, TargetError> {
+    let target = match installation_type.emulator() {
+        Some(emulator) if !emulator.is_installed_in(system) => Err(TargetError::EmulatorNotFound(emulator.name)),
+        Some(emulator) => emulator.sd_card_path_in(system).ok_or(TargetError::NoDataPath(emulator.name)),
+        None if sd_card_path.is_empty() => Err(TargetError::NoSdPath),
+        None => Ok(PathBuf::from(sd_card_path)),
+    };
+    tracing::debug!("{} installs into {target:?}", installation_type.name());
+    target
+}
+
+pub fn open_engage_mods_folder(path: impl AsRef<Path>) -> std::io::Result<()> {
+    let mods_path = path.as_ref().join("engage").join("mods");
+    open_dir(mods_path)
+}
+
+pub fn does_engage_mods_folder_exist(path: impl AsRef<Path>) -> bool {
+    let mods_path = path.as_ref().join("engage").join("mods");
+    mods_path.exists()
+}
+
+// Where Cobalt picks up patch files, relative to the SD root.
+pub const PATCHES_DIR: &str = "engage/patches";
+
+// Cobalt's runtime options, relative to the SD root. If Cobalt ever moves them, this is
+// the one place to change (installs also preserve what's in here, see PROTECTED_PATHS).
+pub const CONFIG_DIR: &str = "engage/config";
+
+// Where Cobalt writes its logs and crash reports, relative to the SD root.
+pub const LOGS_DIR: &str = "engage/logs";
+
+// The most recently written file in `dir`, with its modification time in seconds since
+// the Unix epoch. None when the folder is missing or empty.
+pub fn newest_file(dir: &Path) -> Option<(String, u64)> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter_map(|e| {
+            let metadata = e.metadata().ok()?;
+            let modified = metadata.modified().ok()?.duration_since(std::time::UNIX_EPOCH).ok()?;
+            metadata.is_file().then(|| (e.file_name().to_string_lossy().to_string(), modified.as_secs()))
+        })
+        .max_by_key(|(_, modified)| *modified)
+}
+
+pub fn does_engage_folder_exist(path: impl AsRef<Path>) -> bool {
+    path.as_ref().join("engage").is_dir()
+}
+
+// Open a folder under the SD root, creating it first if Cobalt hasn't yet.
+pub fn open_engage_subfolder(path: impl AsRef<Path>, relative: &str) -> std::io::Result<()> {
+    let folder = path.as_ref().join(relative);
+    std::fs::create_dir_all(&folder)?;
+    open_dir(folder)?;
+    Ok(())
+}
+
+pub fn open_dir(path: impl AsRef<Path>) -> std::io::Result<()> {
+    let cmd = match std::env::consts::OS {
+        "macos" => "open",
+        "windows" => "explorer",
+        "linux" => "xdg-open",
+        other => return Err(std::io::Error::new(std::io::ErrorKind::Unsupported, format!("no file manager to open on {other}"))),
+    };
+    Command::new(cmd).arg(path.as_ref()).spawn().map(reap)
+}
+
+// The file manager can stay open for as long as the user likes, so it's waited on off the
+// UI thread, just so it doesn't linger as a zombie once it exits.
+fn reap(mut child: Child) {
+    std::thread::spawn(move || child.wait());
+}
+
+// Like `open_dir`, but shows the folder in its parent where the file manager can. Only
+// Explorer knows how to select an item it's asked to open, elsewhere the folder itself
+// is opened.
+pub fn reveal_dir(path: impl AsRef<Path>) -> std::io::Result<()> {
+    if std::env::consts::OS == "windows" {
+        let mut select = std::ffi::OsString::from("/select,");
+        select.push(path.as_ref());
+        Command::new("explorer").arg(select).spawn().map(reap)
+    } else {
+        open_dir(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::FakeSystem;
+    use crate::Scratch;
+
+    fn ryujinx() -> &'static Emulator {
+        get_emulator("Ryujinx").unwrap()
+    }
+
+    fn eden() -> &'static Emulator {
+        get_emulator("Eden").unwrap()
+    }
+
+    #[test]
+    fn windows_uses_appdata() {
+        let system = FakeSystem::new("windows", Some(Path::new("/home/user"))).with_var("APPDATA", "/roaming");
+        assert_eq!(ryujinx().data_path_in(&system), Some(PathBuf::from("/roaming/Ryujinx")));
+        assert_eq!(eden().sd_card_path_in(&system), Some(PathBuf::from("/roaming/eden/sdmc")));
+    }
+
+    #[test]
+    fn windows_without_appdata_has_no_data_path() {
+        let system = FakeSystem::new("windows", Some(Path::new("/home/user")));
+        assert_eq!(ryujinx().data_path_in(&system), None);
+        let system = system.with_var("APPDATA", "");
+        assert_eq!(ryujinx().data_path_in(&system), None);
+        assert_eq!(
+            resolve_target_in(&system, InstallationType::from_name("Ryujinx").unwrap(), ""),
+            Err(TargetError::EmulatorNotFound("Ryujinx"))
+        );
+    }
+
+    #[test]
+    fn macos_uses_application_support() {
+        let system = FakeSystem::new("macos", Some(Path::new("/Users/user")));
+        assert_eq!(ryujinx().data_path_in(&system), Some(PathBuf::from("/Users/user/Library/Application Support/Ryujinx")));
+        assert_eq!(ryujinx().data_path_in(&FakeSystem::new("macos", None)), None);
+    }
+
+    #[test]
+    fn linux_uses_the_home_folder() {
+        let system = FakeSystem::new("linux", Some(Path::new("/home/user")));
+        assert_eq!(ryujinx().data_path_in(&system), Some(PathBuf::from("/home/user/.config/Ryujinx")));
+        assert_eq!(eden().data_path_in(&system), Some(PathBuf::from("/home/user/.local/share/eden")));
+        assert_eq!(ryujinx().data_path_in(&FakeSystem::new("freebsd", Some(Path::new("/home/user")))), None);
+    }
+
+    #[test]
+    fn xdg_variables_move_linux_folders() {
+        let system = FakeSystem::new("linux", Some(Path::new("/home/user")))
+            .with_var("XDG_CONFIG_HOME", "/xdg/config")
+            .with_var("XDG_DATA_HOME", "/xdg/data");
+        assert_eq!(ryujinx().data_path_in(&system), Some(PathBuf::from("/xdg/config/Ryujinx")));
+        assert_eq!(eden().data_path_in(&system), Some(PathBuf::from("/xdg/data/eden")));
+        // Relative values are invalid and ignored.
+        let system = FakeSystem::new("linux", Some(Path::new("/home/user"))).with_var("XDG_CONFIG_HOME", "config");
+        assert_eq!(ryujinx().data_path_in(&system), Some(PathBuf::from("/home/user/.config/Ryujinx")));
+    }
+
+    #[test]
+    fn existing_candidates_win() {
+        let scratch = Scratch::new("data_path_candidates");
+        let system = FakeSystem::new("linux", Some(&scratch.0)).with_var("XDG_CONFIG_HOME", scratch.0.join("xdg"));
+        let [xdg, usual, flatpak] = [
+            scratch.0.join("xdg/Ryujinx"),
+            scratch.0.join(".config/Ryujinx"),
+            scratch.0.join(".var/app/org.ryujinx.Ryujinx/config/Ryujinx"),
+        ];
+        assert_eq!(ryujinx().data_path_candidates(&system), vec![xdg.clone(), usual.clone(), flatpak.clone()]);
+        assert!(!ryujinx().is_installed_in(&system));
+        assert_eq!(ryujinx().data_path_in(&system), Some(xdg.clone()));
+
+        std::fs::create_dir_all(&flatpak).unwrap();
+        assert!(ryujinx().is_installed_in(&system));
+        assert_eq!(ryujinx().data_path_in(&system), Some(flatpak.clone()));
+        std::fs::create_dir_all(&usual).unwrap();
+        assert_eq!(ryujinx().data_path_in(&system), Some(usual.clone()));
+        assert_eq!(
+            resolve_target_in(&system, InstallationType::from_name("Ryujinx").unwrap(), ""),
+            Ok(usual.join("sdcard"))
+        );
+    }
+
+    #[test]
+    fn sd_card_target_comes_from_the_picked_folder() {
+        assert_eq!(resolve_target(InstallationType::SdCard, ""), Err(TargetError::NoSdPath));
+        assert_eq!(resolve_target(InstallationType::SdCard, "/media/switch"), Ok(PathBuf::from("/media/switch")));
+    }
+
+    #[test]
+    fn emulators_are_found_by_name() {
+        for emulator in EMULATORS {
+            assert_eq!(get_emulator(emulator.name).map(|e| e.name), Some(emulator.name));
+        }
+        assert!(get_emulator("yuzu").is_none());
+    }
+
+    #[test]
+    fn sd_card_folder_is_inside_the_data_folder() {
+        for emulator in EMULATORS {
+            if let (Some(data), Some(sd)) = (emulator.data_path(), emulator.sd_card_path()) {
+                assert_eq!(sd, data.join(emulator.sd_card_folder));
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_targets_are_sd_cards() {
+        assert_eq!(target_type_of(Path::new("/media/switch")), InstallationType::SdCard);
+        if let Some(sd) = EMULATORS[0].sd_card_path() {
+            assert_eq!(target_type_of(&sd).name(), EMULATORS[0].name);
+        }
+    }
+
+    #[test]
+    fn newest_file_skips_folders() {
+        let scratch = Scratch::new("newest_file");
+        assert_eq!(newest_file(&scratch.0), None);
+        scratch.file("crash.txt", b"");
+        std::fs::create_dir(scratch.0.join("older")).unwrap();
+        assert_eq!(newest_file(&scratch.0).map(|(name, _)| name), Some("crash.txt".to_string()));
+        assert_eq!(newest_file(&scratch.0.join("missing")), None);
+    }
+}
@@ -0,0 +1,78 @@
+// Skyline plugins: the .nro files Skyline loads next to Cobalt's own.
+
+use std::path::{Path, PathBuf};
+
+// Relative to the SD root.
+pub const PLUGINS_DIR: &str = "atmosphere/contents/0100a6301214e000/romfs/skyline/plugins";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plugin {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+pub fn plugins_dir(target: &Path) -> PathBuf {
+    crate::disk::resolve_case_insensitive(target, PLUGINS_DIR)
+}
+
+pub fn is_plugin(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case("nro"))
+}
+
+// The .nro files in the plugins folder, sorted by name. Empty when there's no folder.
+pub fn list(target: &Path) -> std::io::Result<Vec<Plugin>> {
+    let dir = plugins_dir(target);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut plugins = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_file() && is_plugin(&entry.path()) {
+            plugins.push(Plugin {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path: entry.path(),
+                size: metadata.len(),
+            });
+        }
+    }
+    plugins.sort_by_key(|p| p.name.to_lowercase());
+    Ok(plugins)
+}
+
+// Which of `files` would replace a plugin that's already installed.
+pub fn duplicates(target: &Path, files: &[PathBuf]) -> Vec<String> {
+    files
+        .iter()
+        .filter_map(|f| f.file_name())
+        .filter(|name| plugins_dir(target).join(name).exists())
+        .map(|name| name.to_string_lossy().to_string())
+        .collect()
+}
+
+// Copy `files` into the plugins folder, creating it if needed. Existing plugins with the
+// same name are only replaced with `overwrite`, otherwise they're skipped. Returns the
+// names copied.
+pub fn install(target: &Path, files: &[PathBuf], overwrite: bool) -> std::io::Result<Vec<String>> {
+    let dir = plugins_dir(target);
+    std::fs::create_dir_all(&dir)?;
+    let mut copied = Vec::new();
+    for file in files {
+        let Some(name) = file.file_name() else {
+            continue;
+        };
+        let dest = dir.join(name);
+        if dest.exists() && !overwrite {
+            continue;
+        }
+        std::fs::copy(file, &dest)?;
+        copied.push(name.to_string_lossy().to_string());
+    }
+    Ok(copied)
+}
+
+pub fn remove(plugin: &Plugin) -> std::io::Result<()> {
+    std::fs::remove_file(&plugin.path)
+}
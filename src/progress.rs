@@ -0,0 +1,203 @@
+// One overall progress figure for an install, made out of its phases. Each phase gets a
+// share of the bar by weight, so a finished download doesn't send the bar back to zero
+// when extraction starts.
+//
+// Each phase is also timed, on the monotonic clock so a system clock change mid-install
+// can't make one take negative time.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::install_state::InstallState;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Phase {
+    // Finding the release and cleaning up the target.
+    Prepare,
+    Download,
+    Backup,
+    Extract,
+    Finalize,
+    // Waiting for a removable drive to finish writing.
+    Flush,
+}
+
+impl Phase {
+    // Roughly how much of a typical install's time each phase takes.
+    fn weight(self) -> f32 {
+        match self {
+            // Usually over too quickly to be worth a slice of the bar.
+            Phase::Prepare => 0.0,
+            Phase::Download => 60.0,
+            Phase::Backup => 10.0,
+            Phase::Extract => 35.0,
+            Phase::Finalize => 5.0,
+            // Only SD cards and the like, where the flush can take a while.
+            Phase::Flush => 10.0,
+        }
+    }
+
+    // For the summary text and the history, which are in English.
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Prepare => "Preparing",
+            Phase::Download => "Download",
+            Phase::Backup => "Backup",
+            Phase::Extract => "Extraction",
+            Phase::Finalize => "Verification",
+            Phase::Flush => "Finalizing",
+        }
+    }
+
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Phase::Prepare => "progress.prepare",
+            Phase::Download => "progress.download",
+            Phase::Backup => "progress.backup",
+            Phase::Extract => "progress.extract",
+            Phase::Finalize => "progress.finalize",
+            Phase::Flush => "progress.flush",
+        }
+    }
+}
+
+// "0:42", or "1:02:05" past the hour.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    match secs / 3600 {
+        0 => format!("{}:{:02}", secs / 60, secs % 60),
+        hours => format!("{hours}:{:02}:{:02}", secs / 60 % 60, secs % 60),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pipeline {
+    // Phases that haven't finished yet, the running one first.
+    remaining: Vec<Phase>,
+    running: bool,
+    // The overall fraction when the running phase started.
+    base: f32,
+    overall: f32,
+    // Whether the running phase has reported a fraction yet.
+    measured: bool,
+    phase_started: Option<Instant>,
+    // How long each finished phase took, in the order they ran.
+    timings: Vec<(Phase, Duration)>,
+}
+
+impl Pipeline {
+    pub fn new(phases: &[Phase]) -> Self {
+        Self {
+            remaining: phases.to_vec(),
+            running: false,
+            base: 0.0,
+            overall: 0.0,
+            measured: false,
+            phase_started: None,
+            timings: Vec::new(),
+        }
+    }
+
+    // The running phase's slice of the bar: its weight's share of whatever's left.
+    fn share(&self) -> f32 {
+        let total: f32 = self.remaining.iter().map(|p| p.weight()).sum();
+        match self.remaining.first() {
+            Some(phase) if total > 0.0 => phase.weight() / total * (1.0 - self.base),
+            _ => 0.0,
+        }
+    }
+
+    // Finish whatever's running and move on to `phase`. Phases listed before it that
+    // never started count as skipped.
+    pub fn start(&mut self, phase: Phase) {
+        let restarted = self.running && self.remaining.first() == Some(&phase);
+        if self.running && !restarted {
+            self.complete_running();
+        }
+        self.skip_until(phase);
+        self.running = true;
+        self.measured = false;
+        if !restarted {
+            self.phase_started = Some(Instant::now());
+        }
+    }
+
+    fn complete_running(&mut self) {
+        if let (Some(&phase), Some(started)) = (self.remaining.first(), self.phase_started.take()) {
+            self.timings.push((phase, started.elapsed()));
+        }
+        self.overall = self.overall.max(self.base + self.share()).min(1.0);
+        self.base = self.overall;
+        self.remaining.remove(0);
+        self.running = false;
+    }
+
+    // Everything's done.
+    pub fn finish(&mut self) {
+        if self.running {
+            self.complete_running();
+        }
+        self.remaining.clear();
+        self.running = false;
+        self.overall = 1.0;
+    }
+
+    fn skip_until(&mut self, phase: Phase) {
+        while self.remaining.first().is_some_and(|&p| p != phase) {
+            self.remaining.remove(0);
+        }
+    }
+
+    // Leave `phase` out, its share goes to the phases still to come.
+    pub fn skip(&mut self, phase: Phase) {
+        let running = self.running.then(|| self.remaining.first().copied()).flatten();
+        if running != Some(phase) {
+            self.remaining.retain(|&p| p != phase);
+        }
+    }
+
+    // How far the running phase is, from 0 to 1. The overall figure never goes backwards.
+    pub fn update(&mut self, fraction: f32) {
+        if !self.running {
+            return;
+        }
+        let overall = self.base + self.share() * fraction.clamp(0.0, 1.0);
+        self.overall = self.overall.max(overall.min(1.0));
+        self.measured = true;
+    }
+
+    pub fn overall(&self) -> f32 {
+        self.overall
+    }
+
+    pub fn phase(&self) -> Option<Phase> {
+        self.remaining.first().copied().filter(|_| self.running)
+    }
+
+    // When the running phase started.
+    pub fn phase_started(&self) -> Option<Instant> {
+        self.phase_started.filter(|_| self.running)
+    }
+
+    // Seconds each phase took so far, the running one up to now.
+    pub fn phase_times(&self) -> Vec<(Phase, f64)> {
+        let running = self.phase().zip(self.phase_started()).map(|(phase, started)| (phase, started.elapsed()));
+        self.timings.iter().copied().chain(running).map(|(phase, took)| (phase, took.as_secs_f64())).collect()
+    }
+
+    // The install state for the running phase, None between phases. Until the first
+    // bytes arrive a download has nothing to measure.
+    pub fn state(&self) -> Option<InstallState> {
+        let progress = self.overall;
+        Some(match self.phase()? {
+            Phase::Prepare => InstallState::Preparing,
+            Phase::Download if !self.measured => InstallState::Connecting,
+            Phase::Download => InstallState::Downloading { progress },
+            Phase::Backup => InstallState::BackingUp { progress },
+            Phase::Extract => InstallState::Extracting { progress },
+            Phase::Finalize => InstallState::Verifying,
+            Phase::Flush => InstallState::Flushing { progress },
+        })
+    }
+}
@@ -0,0 +1,50 @@
+// A small note left at the SD root saying what put Cobalt there.
+//
+// Unlike the manifest (which stays in the installer's data directory) this travels with
+// the card, so a card handed to someone else still says which installer and which Cobalt
+// version wrote it. It isn't one of the release's files: the manifest never lists it,
+// and uninstalling removes it along with them.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+pub const RECEIPT_FILE: &str = "cobalt_install.json";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Receipt {
+    // The release tag, or None if it couldn't be resolved at install time.
+    pub cobalt_version: Option<String>,
+    pub installer_version: String,
+    // Seconds since the Unix epoch.
+    pub installed_at: u64,
+    pub file_count: usize,
+}
+
+impl Receipt {
+    pub fn new(cobalt_version: Option<String>, file_count: usize) -> Self {
+        Self {
+            cobalt_version,
+            installer_version: env!("CARGO_PKG_VERSION").to_string(),
+            installed_at: crate::history::now(),
+            file_count,
+        }
+    }
+
+    pub fn load(target: &Path) -> Option<Receipt> {
+        let contents = std::fs::read_to_string(target.join(RECEIPT_FILE)).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    pub fn save(&self, target: &Path) -> std::io::Result<()> {
+        std::fs::write(target.join(RECEIPT_FILE), serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn remove(target: &Path) -> std::io::Result<()> {
+        let path = target.join(RECEIPT_FILE);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
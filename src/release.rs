@@ -0,0 +1,111 @@
+// Looking up Cobalt releases through the GitHub API.
+//
+// RELEASE_URL always serves the newest stable release.zip, but it can't tell us which
+// tag that is. The API can, which is what the update check compares the installed
+// version against, and what lets the prerelease channel pick up prereleases at all.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+const RELEASES_API: &str = "https://api.github.com/repos/Raytwo/Cobalt/releases";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+    // The release's page on GitHub.
+    #[serde(default)]
+    pub html_url: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Asset {
+    pub name: String,
+    pub size: u64,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    // The tag without the leading "v", which is how we show versions to the user.
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    pub fn zip_asset(&self) -> Option<&Asset> {
+        self.assets.iter().find(|a| a.name == "release.zip")
+    }
+}
+
+// Resolve the release we'd install right now. The stable channel asks for /latest
+// (which GitHub never points at a prerelease), the prerelease channel takes the newest
+// entry of the full list instead.
+pub async fn latest(include_prereleases: bool) -> reqwest::Result<Option<Release>> {
+    if include_prereleases {
+        let releases: Vec<Release> = crate::http::get(RELEASES_API)
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(releases.into_iter().next())
+    } else {
+        let release: Release = crate::http::get(&format!("{RELEASES_API}/latest"))
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(Some(release))
+    }
+}
+
+// Every published release, newest first, for the version picker. Prereleases are only
+// included on the prerelease channel.
+pub async fn list(include_prereleases: bool) -> reqwest::Result<Vec<Release>> {
+    let releases: Vec<Release> = crate::http::get(RELEASES_API)
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(releases.into_iter().filter(|r| include_prereleases || !r.prerelease).collect())
+}
+
+// Pull the numeric parts out of a tag like "v1.4.2", "1.4.3-beta" or a date-based one
+// like "nightly-2024-05-01". Leading text is skipped, a tag without any number isn't
+// comparable.
+fn numeric_parts(version: &str) -> Option<Vec<u64>> {
+    let start = version.find(|c: char| c.is_ascii_digit())?;
+    let parts: Vec<u64> = version[start..]
+        .split(['.', '-', '_', '+'])
+        .map_while(|part| {
+            let digits: String = part.chars().take_while(|c| c.is_ascii_digit()).collect();
+            digits.parse().ok()
+        })
+        .collect();
+    (!parts.is_empty()).then_some(parts)
+}
+
+// Order two tags by their numeric parts, missing parts counting as zero. Semver-ish tags
+// compare part by part, date tags ("2024.05.01", "2024-05-01") compare by date. None
+// when either tag can't be made sense of.
+pub fn compare(a: &str, b: &str) -> Option<Ordering> {
+    let (mut a, mut b) = (numeric_parts(a)?, numeric_parts(b)?);
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    Some(a.cmp(&b))
+}
+
+// True when `candidate` is a strictly newer version than `installed`. Versions we can't
+// make sense of never count as newer, so a weird tag can't nag the user forever.
+pub fn is_newer(candidate: &str, installed: &str) -> bool {
+    compare(candidate, installed) == Some(Ordering::Greater)
+}
+
+// True when installing `candidate` over `installed` would go backwards. Same rules as
+// `is_newer`, this is what every downgrade confirmation goes through.
+pub fn is_older(candidate: &str, installed: &str) -> bool {
+    compare(candidate, installed) == Some(Ordering::Less)
+}
@@ -0,0 +1,101 @@
+// Makes sure only one install pipeline runs at a time. Two overlapping runs would race
+// on the same destination files, so a second start is refused rather than queued.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[derive(Clone, Debug, Default)]
+pub struct RunGuard(Arc<AtomicBool>);
+
+// Held for as long as a run lasts. Dropping it, however the run ended, lets the next one
+// start.
+#[derive(Debug)]
+pub struct Running(Arc<AtomicBool>);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AlreadyRunning;
+
+impl std::fmt::Display for AlreadyRunning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "an installation is already running")
+    }
+}
+
+impl RunGuard {
+    pub fn try_start(&self) -> Result<Running, AlreadyRunning> {
+        self.0
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .map(|_| Running(self.0.clone()))
+            .map_err(|_| AlreadyRunning)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{mpsc, Mutex};
+
+    #[test]
+    fn refuses_a_second_start_while_running() {
+        let guard = RunGuard::default();
+        let running = guard.try_start().unwrap();
+        assert!(guard.is_running());
+        assert_eq!(guard.try_start().unwrap_err(), AlreadyRunning);
+        drop(running);
+        assert!(!guard.is_running());
+        assert!(guard.try_start().is_ok());
+    }
+
+    #[test]
+    fn clones_share_the_same_guard() {
+        let guard = RunGuard::default();
+        let _running = guard.try_start().unwrap();
+        assert_eq!(guard.clone().try_start().unwrap_err(), AlreadyRunning);
+    }
+
+    #[test]
+    fn a_second_run_never_interleaves_writes() {
+        let guard = RunGuard::default();
+        let writes = Arc::new(Mutex::new(Vec::new()));
+        let (started_tx, started_rx) = mpsc::channel();
+        let (finish_tx, finish_rx) = mpsc::channel::<()>();
+
+        let first = {
+            let guard = guard.clone();
+            let writes = writes.clone();
+            std::thread::spawn(move || {
+                let _running = guard.try_start().unwrap();
+                writes.lock().unwrap().push("first: start");
+                started_tx.send(()).unwrap();
+                finish_rx.recv().unwrap();
+                writes.lock().unwrap().push("first: end");
+            })
+        };
+
+        started_rx.recv().unwrap();
+        let second = {
+            let guard = guard.clone();
+            let writes = writes.clone();
+            std::thread::spawn(move || match guard.try_start() {
+                Ok(_running) => writes.lock().unwrap().push("second"),
+                Err(e) => assert_eq!(e.to_string(), "an installation is already running"),
+            })
+        };
+        second.join().unwrap();
+        finish_tx.send(()).unwrap();
+        first.join().unwrap();
+
+        assert_eq!(*writes.lock().unwrap(), vec!["first: start", "first: end"]);
+        assert!(!guard.is_running());
+    }
+}
@@ -0,0 +1,188 @@
+// Replacing the running installer with a newer release.
+//
+// Everything risky happens on a copy: the new build is downloaded, checked against the
+// checksum published with the release, and written next to the current executable
+// before anything is swapped. Any failure up to the swap leaves the running binary as it
+// was. Windows won't let us overwrite a running exe but does let us rename it, so there
+// the old one moves aside to `<exe>.old` (deleted on the next launch) and the new one
+// takes its name. Elsewhere a rename over the running file is fine.
+
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use dioxus::logger::tracing;
+use zip::ZipArchive;
+
+use crate::release::{Asset, Release};
+
+fn error(message: impl Into<String>) -> std::io::Error {
+    std::io::Error::other(message.into())
+}
+
+// The release asset built for this OS. Names are matched loosely since the build setup
+// has changed over time: a Windows .exe, or anything mentioning the platform.
+fn platform_asset(release: &Release) -> Option<&Asset> {
+    let names: &[&str] = match std::env::consts::OS {
+        "windows" => &["windows", ".exe"],
+        "macos" => &["macos", "darwin", "mac"],
+        "linux" => &["linux"],
+        _ => &[],
+    };
+    release.assets.iter().find(|a| {
+        let name = a.name.to_lowercase();
+        !name.ends_with(".sha256") && names.iter().any(|n| name.contains(n))
+    })
+}
+
+// The published SHA-256 for `asset`: either a `<asset>.sha256` file next to it, or a
+// line for it in a combined checksums file.
+async fn expected_checksum(release: &Release, asset: &Asset) -> std::io::Result<String> {
+    let own = format!("{}.sha256", asset.name);
+    let source = release
+        .assets
+        .iter()
+        .find(|a| a.name == own)
+        .or_else(|| release.assets.iter().find(|a| {
+            let name = a.name.to_lowercase();
+            name == "sha256sums" || name == "sha256sums.txt" || name == "checksums.txt"
+        }))
+        .ok_or_else(|| error("This release doesn't publish checksums, so it can't be verified"))?;
+    let text = crate::http::get(&source.browser_download_url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(std::io::Error::other)?
+        .text()
+        .await
+        .map_err(std::io::Error::other)?;
+
+    // "<hex>  <name>" per line, or just "<hex>" in a per-asset file.
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            match parts.next() {
+                Some(name) if name.trim_start_matches('*') != asset.name => None,
+                _ => Some(hash.to_lowercase()),
+            }
+        })
+        .next()
+        .ok_or_else(|| error(format!("No checksum listed for {}", asset.name)))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, bytes)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+// The executable itself. Zipped builds carry it under the same file name as the
+// running one (inside an .app bundle on macOS).
+fn unpack_executable(asset: &Asset, bytes: Vec<u8>, exe: &Path) -> std::io::Result<Vec<u8>> {
+    if !asset.name.to_lowercase().ends_with(".zip") {
+        return Ok(bytes);
+    }
+    let exe_name = exe
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| error("Can't tell the installer's own file name"))?;
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_file() && file.name().rsplit('/').next() == Some(exe_name.as_str()) {
+            let mut contents = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut contents)?;
+            return Ok(contents);
+        }
+    }
+    Err(error(format!("{} doesn't contain {exe_name}", asset.name)))
+}
+
+fn old_path(exe: &Path) -> PathBuf {
+    let mut name = exe.as_os_str().to_owned();
+    name.push(".old");
+    PathBuf::from(name)
+}
+
+// Put `contents` in place of the running executable at `exe`.
+fn swap(exe: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut staged = exe.as_os_str().to_owned();
+    staged.push(".new");
+    let staged = PathBuf::from(staged);
+    std::fs::write(&staged, contents)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    let result = if cfg!(windows) {
+        let old = old_path(exe);
+        let _ = std::fs::remove_file(&old);
+        std::fs::rename(exe, &old).and_then(|_| {
+            std::fs::rename(&staged, exe).inspect_err(|_| {
+                // Put the running build back under its own name.
+                let _ = std::fs::rename(&old, exe);
+            })
+        })
+    } else {
+        std::fs::rename(&staged, exe)
+    };
+    if result.is_err() {
+        let _ = std::fs::remove_file(&staged);
+    }
+    result
+}
+
+// Download, verify and install `release` over the running installer. Returns the path
+// to relaunch.
+pub async fn update(release: &Release) -> std::io::Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    let asset = platform_asset(release)
+        .ok_or_else(|| error(format!("Installer {} has no build for {}", release.tag_name, std::env::consts::OS)))?;
+
+    let expected = expected_checksum(release, asset).await?;
+    tracing::info!("Downloading installer {} from {}", release.tag_name, asset.browser_download_url);
+    let bytes = crate::http::get(&asset.browser_download_url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(std::io::Error::other)?
+        .bytes()
+        .await
+        .map_err(std::io::Error::other)?
+        .to_vec();
+
+    let actual = sha256_hex(&bytes);
+    if actual != expected {
+        return Err(error(format!("{} failed verification (expected {expected}, got {actual})", asset.name)));
+    }
+
+    let contents = unpack_executable(asset, bytes, &exe)?;
+    let target = exe.clone();
+    tokio::task::spawn_blocking(move || swap(&target, &contents))
+        .await
+        .map_err(std::io::Error::other)??;
+    tracing::info!("Installer updated to {}", release.tag_name);
+    Ok(exe)
+}
+
+// Start the freshly installed build and leave. The instance lock goes first, or the new
+// build would find us still running and hand over to us instead.
+pub fn relaunch(exe: &Path) -> std::io::Result<()> {
+    crate::single_instance::release();
+    std::process::Command::new(exe).spawn()?;
+    std::process::exit(0);
+}
+
+// The previous build a Windows update moved aside, once it's no longer running.
+pub fn remove_leftovers() {
+    if let Ok(exe) = std::env::current_exe() {
+        let old = old_path(&exe);
+        if old.exists() {
+            if let Err(e) = std::fs::remove_file(&old) {
+                tracing::info!("Couldn't remove the previous installer build: {e}");
+            }
+        }
+    }
+}
@@ -0,0 +1,302 @@
+// The installer's persisted settings.
+//
+// All of them are one InstallerSettings value, saved as a single LocalStorage entry (a
+// file in the data directory). Each setting is declared here once, as a field with its
+// default and a `Setting` constant, and read through that constant everywhere:
+// `use_synced` in components, `get` anywhere else. `get` goes straight to the storage
+// file, so the install pipeline sees what the settings page wrote without any component
+// being mounted.
+//
+// Saving a setting reloads the file, changes that one field and writes it back, so two
+// settings changed at the same time don't undo each other. Components follow every save
+// through the InstallerSettings signal App provides (see `use_provider`).
+
+use std::sync::{Mutex, OnceLock};
+
+use dioxus::prelude::*;
+use dioxus_sdk::storage::{LocalStorage, StorageBacking};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Language;
+use crate::installation_type::InstallationType;
+use crate::theme::Theme;
+use crate::window_state::WindowState;
+use crate::paths::{cache_dir, data_dir};
+
+// The storage entry holding all of the settings.
+pub const STORAGE_KEY: &str = "installer_settings";
+
+// Field names match the keys each setting had when they were stored one per key. A field
+// missing from the file (a setting newer than the file) gets its default.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstallerSettings {
+    pub installation_type: InstallationType,
+    pub sd_card_path: String,
+    pub sd_card_skip_confirm: Vec<String>,
+    pub selected_version: String,
+    pub window: Option<WindowState>,
+    pub setup_done: bool,
+    pub has_completed_first_run: bool,
+    pub check_installer_updates: bool,
+    pub backup_before_update: bool,
+    pub theme: Theme,
+    pub language: Language,
+    pub close_after_install: bool,
+    pub ui_scale: u32,
+    pub include_prereleases: bool,
+    pub custom_release_url: String,
+    pub preserve_config: bool,
+    pub verbose_logging: bool,
+    pub extraction_workers: u32,
+    pub write_buffer_kb: u32,
+    pub flush_removable: bool,
+}
+
+impl Default for InstallerSettings {
+    fn default() -> Self {
+        Self {
+            installation_type: InstallationType::default(),
+            sd_card_path: String::new(),
+            sd_card_skip_confirm: Vec::new(),
+            selected_version: String::new(),
+            window: None,
+            setup_done: false,
+            has_completed_first_run: false,
+            check_installer_updates: true,
+            backup_before_update: true,
+            theme: Theme::default(),
+            language: Language::default(),
+            close_after_install: false,
+            ui_scale: 100,
+            include_prereleases: false,
+            custom_release_url: String::new(),
+            preserve_config: true,
+            verbose_logging: false,
+            extraction_workers: 0,
+            write_buffer_kb: 0,
+            flush_removable: true,
+        }
+    }
+}
+
+// Held while a setting is read, changed and written back.
+static SAVING: Mutex<()> = Mutex::new(());
+
+// Bumped on every save, `use_provider` reloads when it changes.
+fn saves() -> &'static tokio::sync::watch::Sender<u64> {
+    static SAVES: OnceLock<tokio::sync::watch::Sender<u64>> = OnceLock::new();
+    SAVES.get_or_init(|| tokio::sync::watch::channel(0).0)
+}
+
+impl InstallerSettings {
+    // As saved, or the defaults if nothing is (or the file can't be read).
+    pub fn load() -> Self {
+        LocalStorage::get(&STORAGE_KEY.to_string()).unwrap_or_default()
+    }
+
+    // Change the saved settings with `change`, leaving whatever else was saved meanwhile.
+    pub fn update(change: impl FnOnce(&mut InstallerSettings)) {
+        let _saving = SAVING.lock().unwrap_or_else(|e| e.into_inner());
+        let mut settings = Self::load();
+        change(&mut settings);
+        LocalStorage::set(STORAGE_KEY.to_string(), &settings);
+        saves().send_modify(|n| *n += 1);
+    }
+}
+
+// Call once at the top of App. Provides the saved settings as a signal that follows every
+// save, from any component or thread, for `use_synced` to read from.
+pub fn use_provider() -> Signal<InstallerSettings> {
+    let mut saved = use_context_provider(|| Signal::new(InstallerSettings::load()));
+    use_future(move || async move {
+        let mut changes = saves().subscribe();
+        while changes.changed().await.is_ok() {
+            saved.set(InstallerSettings::load());
+        }
+    });
+    saved
+}
+
+// One field of InstallerSettings.
+pub struct Setting<T: 'static> {
+    // The field's name, also its key from before the settings were one entry.
+    pub key: &'static str,
+    read: fn(&InstallerSettings) -> T,
+    write: fn(&mut InstallerSettings, T),
+}
+
+impl<T: Clone + PartialEq + 'static> Setting<T> {
+    pub fn get(&self) -> T {
+        (self.read)(&InstallerSettings::load())
+    }
+
+    pub fn set(&self, value: &T) {
+        let (write, value) = (self.write, value.clone());
+        InstallerSettings::update(move |settings| write(settings, value));
+    }
+
+    pub fn default(&self) -> T {
+        (self.read)(&InstallerSettings::default())
+    }
+
+    // Write the default back. Components showing the setting pick it up right away.
+    pub fn restore_default(&self) {
+        self.set(&self.default());
+    }
+
+    // Put `value` into `settings` without saving, for migrations building them up.
+    pub(crate) fn put(&self, settings: &mut InstallerSettings, value: T) {
+        (self.write)(settings, value)
+    }
+
+    // The setting as a signal that saves on every change and follows changes made
+    // elsewhere (another component, `restore_default`).
+    pub fn use_synced(&self) -> Signal<T> {
+        let (read, write) = (self.read, self.write);
+        let saved = use_context::<Signal<InstallerSettings>>();
+        let mut value = use_signal(|| read(&saved.peek()));
+        use_effect(move || {
+            let latest = read(&saved.read());
+            if *value.peek() != latest {
+                value.set(latest);
+            }
+        });
+        use_effect(move || {
+            let changed = value();
+            if changed != read(&InstallerSettings::load()) {
+                InstallerSettings::update(move |settings| write(settings, changed));
+            }
+        });
+        value
+    }
+}
+
+macro_rules! setting {
+    ($name:ident: $type:ty = $field:ident) => {
+        pub const $name: Setting<$type> = Setting {
+            key: stringify!($field),
+            read: |settings| settings.$field.clone(),
+            write: |settings, value| settings.$field = value,
+        };
+    };
+}
+
+// Where to install, picked on the main screen rather than the settings page.
+setting!(INSTALLATION_TYPE: InstallationType = installation_type);
+setting!(SD_CARD_PATH: String = sd_card_path);
+// SD card folders the user installs to without the confirmation card.
+setting!(SD_CARD_SKIP_CONFIRM: Vec<String> = sd_card_skip_confirm);
+// The tag picked in the version dropdown, or empty for "latest".
+setting!(SELECTED_VERSION: String = selected_version);
+// Saved as the window moves, not shown on the settings page.
+setting!(WINDOW: Option<WindowState> = window);
+// Set once the user has started an install from the wizard, so later launches with a
+// usable target open straight on the install step.
+setting!(SETUP_DONE: bool = setup_done);
+// Set once the first-run hints are dismissed or an install succeeds. Only a settings
+// reset brings the hints back.
+setting!(FIRST_RUN_COMPLETED: bool = has_completed_first_run);
+
+// General
+setting!(CHECK_INSTALLER_UPDATES: bool = check_installer_updates);
+setting!(BACKUP_BEFORE_UPDATE: bool = backup_before_update);
+setting!(THEME: Theme = theme);
+setting!(LANGUAGE: Language = language);
+setting!(CLOSE_AFTER_INSTALL: bool = close_after_install);
+// In percent, see ui_scale.
+setting!(UI_SCALE: u32 = ui_scale);
+
+// Network
+setting!(INCLUDE_PRERELEASES: bool = include_prereleases);
+// A release zip to install instead of the latest official one, for testing builds.
+// Empty to use GitHub.
+setting!(CUSTOM_RELEASE_URL: String = custom_release_url);
+
+// Advanced
+// Keep the user's existing files under extract::PROTECTED_PATHS on reinstall.
+setting!(PRESERVE_CONFIG: bool = preserve_config);
+// Log everything the installer does to the log file, for chasing down detection problems.
+setting!(VERBOSE_LOGGING: bool = verbose_logging);
+// How many files an install writes at once, 0 to let install::WriteOptions pick.
+setting!(EXTRACTION_WORKERS: u32 = extraction_workers);
+// The buffer for each file written, in KB, 0 to let install::WriteOptions pick.
+setting!(WRITE_BUFFER_KB: u32 = write_buffer_kb);
+// What the settings page offers for WRITE_BUFFER_KB besides automatic.
+pub const WRITE_BUFFER_CHOICES_KB: &[u32] = &[8, 64, 256, 1024, 4096];
+// Wait for installs onto SD cards and other removable drives to be on the drive before
+// calling them done, see install::needs_flush.
+setting!(FLUSH_REMOVABLE: bool = flush_removable);
+
+// Every file the settings have been stored in, now and before they were one entry, so
+// "Reset installer settings" can clear them all.
+pub const STORAGE_KEYS: &[&str] = &[
+    STORAGE_KEY,
+    crate::settings_migration::SCHEMA_VERSION_KEY,
+    INSTALLATION_TYPE.key,
+    SD_CARD_PATH.key,
+    SD_CARD_SKIP_CONFIRM.key,
+    SELECTED_VERSION.key,
+    WINDOW.key,
+    SETUP_DONE.key,
+    FIRST_RUN_COMPLETED.key,
+    CHECK_INSTALLER_UPDATES.key,
+    BACKUP_BEFORE_UPDATE.key,
+    THEME.key,
+    LANGUAGE.key,
+    CLOSE_AFTER_INSTALL.key,
+    UI_SCALE.key,
+    INCLUDE_PRERELEASES.key,
+    CUSTOM_RELEASE_URL.key,
+    PRESERVE_CONFIG.key,
+    VERBOSE_LOGGING.key,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Section {
+    General,
+    Network,
+    Advanced,
+}
+
+pub fn restore_defaults(section: Section) {
+    match section {
+        Section::General => {
+            CHECK_INSTALLER_UPDATES.restore_default();
+            BACKUP_BEFORE_UPDATE.restore_default();
+            THEME.restore_default();
+            LANGUAGE.restore_default();
+            CLOSE_AFTER_INSTALL.restore_default();
+            UI_SCALE.restore_default();
+        }
+        Section::Network => {
+            INCLUDE_PRERELEASES.restore_default();
+            CUSTOM_RELEASE_URL.restore_default();
+        }
+        Section::Advanced => {
+            PRESERVE_CONFIG.restore_default();
+            VERBOSE_LOGGING.restore_default();
+            EXTRACTION_WORKERS.restore_default();
+            WRITE_BUFFER_KB.restore_default();
+            FLUSH_REMOVABLE.restore_default();
+        }
+    }
+}
+
+// Delete every persisted setting and the download cache. Install history, manifests and
+// backups describe what's on the user's targets rather than how the installer is set up,
+// so they stay.
+pub fn reset() -> std::io::Result<()> {
+    let dir = data_dir().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "No data directory"))?;
+    for key in STORAGE_KEYS {
+        let path = dir.join(key);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    saves().send_modify(|n| *n += 1);
+    if let Some(cache) = cache_dir().filter(|c| c.exists()) {
+        std::fs::remove_dir_all(cache)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,278 @@
+// Bringing settings saved by older installers up to date. The settings carry a schema
+// version of their own, missing before versions existed (so 0). At startup each step
+// from the stored version to SCHEMA_VERSION runs in turn, then the new version is saved.
+//
+// Up to version 1 every setting was its own storage entry, named after its field. From
+// version 2 they're all one InstallerSettings entry.
+//
+// A step that can't make sense of a value logs a warning and puts the settings it covers
+// back to their defaults, a bad value is never a reason not to start. Settings from a
+// newer installer than this one are left alone.
+
+use dioxus::logger::tracing;
+use dioxus_sdk::storage::{LocalStorage, StorageBacking};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::installation_type::{InstallationType, INSTALLATION_TYPES};
+use crate::settings::{self, InstallerSettings, Setting};
+
+// Bump this and add a step to `step` whenever a key or value format changes.
+pub const SCHEMA_VERSION: u32 = 2;
+
+// Stored on its own, next to the settings rather than in them.
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+// Where the migrations read and write, LocalStorage outside of tests.
+trait Store {
+    fn get<T: DeserializeOwned + Clone + 'static>(&self, key: &str) -> Option<T>;
+    fn set<T: Serialize + Send + Sync + Clone + 'static>(&mut self, key: &str, value: &T);
+
+    // For steps up to version 1, which wrote the settings one entry each.
+    fn restore_default<T: Serialize + Clone + Send + Sync + PartialEq + 'static>(&mut self, setting: &Setting<T>) {
+        self.set(setting.key, &setting.default());
+    }
+}
+
+struct Local;
+
+impl Store for Local {
+    fn get<T: DeserializeOwned + Clone + 'static>(&self, key: &str) -> Option<T> {
+        LocalStorage::get(&key.to_string())
+    }
+
+    fn set<T: Serialize + Send + Sync + Clone + 'static>(&mut self, key: &str, value: &T) {
+        LocalStorage::set(key.to_string(), value);
+    }
+}
+
+// An installation type however an older installer or a hand edit might have spelled it:
+// any case, with or without the space in "SD Card".
+fn installation_type_v1(raw: &str) -> Option<InstallationType> {
+    let squashed = |name: &str| -> String {
+        name.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+    };
+    let wanted = squashed(raw);
+    INSTALLATION_TYPES.into_iter().find(|t| squashed(t.name()) == wanted)
+}
+
+// An SD card folder as typed or pasted: no surrounding whitespace or trailing separator,
+// and without the \\?\ prefix Windows sometimes hands out, so the same folder is always
+// the same string.
+fn sd_card_path_v1(raw: &str) -> String {
+    let path = raw.trim();
+    let path = match path.strip_prefix(r"\\?\UNC\") {
+        Some(share) => format!(r"\\{share}"),
+        None => path.strip_prefix(r"\\?\").unwrap_or(path).to_string(),
+    };
+    let trimmed = path.trim_end_matches(['/', '\\']);
+    // "/" and "C:\" stay as they are, without the separator they'd mean something else.
+    if trimmed.is_empty() || trimmed.ends_with(':') {
+        path
+    } else {
+        trimmed.to_string()
+    }
+}
+
+// Version 0 to 1: the SD card paths are normalized and the installation type is matched
+// leniently before the strict enum reads it.
+fn to_v1(store: &mut impl Store) -> Result<(), String> {
+    if let Some(path) = store.get::<String>(settings::SD_CARD_PATH.key) {
+        store.set(settings::SD_CARD_PATH.key, &sd_card_path_v1(&path));
+    }
+    if let Some(paths) = store.get::<Vec<String>>(settings::SD_CARD_SKIP_CONFIRM.key) {
+        let mut normalized: Vec<String> = paths.iter().map(|p| sd_card_path_v1(p)).collect();
+        normalized.dedup();
+        store.set(settings::SD_CARD_SKIP_CONFIRM.key, &normalized);
+    }
+    if let Some(raw) = store.get::<String>(settings::INSTALLATION_TYPE.key) {
+        let installation_type = installation_type_v1(&raw).ok_or_else(|| format!("unknown installation type {raw:?}"))?;
+        store.set(settings::INSTALLATION_TYPE.key, &installation_type);
+    }
+    Ok(())
+}
+
+// The setting's own entry from version 1, if it's there and readable, into `settings`.
+fn take<T: DeserializeOwned + Clone + PartialEq + 'static>(store: &impl Store, setting: &Setting<T>, settings: &mut InstallerSettings) {
+    if let Some(value) = store.get::<T>(setting.key) {
+        setting.put(settings, value);
+    }
+}
+
+// Version 1 to 2: every setting from its own entry into the one InstallerSettings entry.
+// An unreadable entry keeps its default. The old entries stay behind unused, a settings
+// reset clears them.
+fn to_v2(store: &mut impl Store) -> Result<(), String> {
+    if store.get::<InstallerSettings>(settings::STORAGE_KEY).is_some() {
+        return Ok(());
+    }
+    let mut merged = InstallerSettings::default();
+    take(store, &settings::INSTALLATION_TYPE, &mut merged);
+    take(store, &settings::SD_CARD_PATH, &mut merged);
+    take(store, &settings::SD_CARD_SKIP_CONFIRM, &mut merged);
+    take(store, &settings::SELECTED_VERSION, &mut merged);
+    take(store, &settings::WINDOW, &mut merged);
+    take(store, &settings::SETUP_DONE, &mut merged);
+    take(store, &settings::FIRST_RUN_COMPLETED, &mut merged);
+    take(store, &settings::CHECK_INSTALLER_UPDATES, &mut merged);
+    take(store, &settings::BACKUP_BEFORE_UPDATE, &mut merged);
+    take(store, &settings::THEME, &mut merged);
+    take(store, &settings::LANGUAGE, &mut merged);
+    take(store, &settings::CLOSE_AFTER_INSTALL, &mut merged);
+    take(store, &settings::UI_SCALE, &mut merged);
+    take(store, &settings::INCLUDE_PRERELEASES, &mut merged);
+    take(store, &settings::CUSTOM_RELEASE_URL, &mut merged);
+    take(store, &settings::PRESERVE_CONFIG, &mut merged);
+    take(store, &settings::VERBOSE_LOGGING, &mut merged);
+    store.set(settings::STORAGE_KEY, &merged);
+    Ok(())
+}
+
+// Run the step from version `from` to the next one, falling back to the defaults of what
+// it covers if it fails.
+fn step(store: &mut impl Store, from: u32) {
+    let result = match from {
+        0 => to_v1(store),
+        1 => to_v2(store),
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        tracing::warn!("Couldn't migrate the settings from version {from} ({e}), using the defaults instead");
+        if from == 0 {
+            store.restore_default(&settings::INSTALLATION_TYPE);
+        }
+    }
+}
+
+fn migrate(store: &mut impl Store) {
+    let version = store.get::<u32>(SCHEMA_VERSION_KEY).unwrap_or(0);
+    if version > SCHEMA_VERSION {
+        tracing::warn!("The settings are from a newer installer (version {version}), leaving them as they are");
+        return;
+    }
+    for from in version..SCHEMA_VERSION {
+        tracing::info!("Migrating the settings from version {from} to {}", from + 1);
+        step(store, from);
+    }
+    store.set(SCHEMA_VERSION_KEY, &SCHEMA_VERSION);
+}
+
+// Call once at startup, after the storage directory is set and before any setting is read.
+pub fn run() {
+    migrate(&mut Local);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    // Values kept as JSON, which like LocalStorage's format only cares about the shape.
+    #[derive(Default)]
+    struct Memory(HashMap<String, serde_json::Value>);
+
+    impl Store for Memory {
+        fn get<T: DeserializeOwned + Clone + 'static>(&self, key: &str) -> Option<T> {
+            serde_json::from_value(self.0.get(key)?.clone()).ok()
+        }
+
+        fn set<T: Serialize + Send + Sync + Clone + 'static>(&mut self, key: &str, value: &T) {
+            self.0.insert(key.to_string(), serde_json::to_value(value).unwrap());
+        }
+    }
+
+    fn version_0(installation_type: &str, sd_card_path: &str) -> Memory {
+        let mut store = Memory::default();
+        store.set(settings::INSTALLATION_TYPE.key, &installation_type.to_string());
+        store.set(settings::SD_CARD_PATH.key, &sd_card_path.to_string());
+        store
+    }
+
+    #[test]
+    fn version_0_settings_are_brought_up_to_date() {
+        let mut store = version_0("sd card", " /media/SWITCH/ ");
+        store.set(settings::SD_CARD_SKIP_CONFIRM.key, &vec![r"\\?\E:\".to_string(), r"E:\".to_string()]);
+        migrate(&mut store);
+        assert_eq!(store.get::<InstallationType>(settings::INSTALLATION_TYPE.key), Some(InstallationType::SdCard));
+        assert_eq!(store.get::<String>(settings::SD_CARD_PATH.key).as_deref(), Some("/media/SWITCH"));
+        assert_eq!(store.get::<Vec<String>>(settings::SD_CARD_SKIP_CONFIRM.key), Some(vec![r"E:\".to_string()]));
+        assert_eq!(store.get::<u32>(SCHEMA_VERSION_KEY), Some(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn version_1_entries_are_merged_into_one() {
+        let mut store = version_0("citron", "/media/switch/");
+        store.set(settings::UI_SCALE.key, &125u32);
+        store.set(settings::PRESERVE_CONFIG.key, &false);
+        // Unreadable, so left at the default.
+        store.set(settings::THEME.key, &"Sepia".to_string());
+        migrate(&mut store);
+
+        let merged = store.get::<InstallerSettings>(settings::STORAGE_KEY).unwrap();
+        assert_eq!(merged.installation_type, InstallationType::Citron);
+        assert_eq!(merged.sd_card_path, "/media/switch");
+        assert_eq!(merged.ui_scale, 125);
+        assert!(!merged.preserve_config);
+        assert_eq!(merged.theme, InstallerSettings::default().theme);
+        assert!(merged.check_installer_updates);
+        assert_eq!(store.get::<u32>(SCHEMA_VERSION_KEY), Some(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn merged_settings_are_not_overwritten() {
+        let mut store = version_0("Ryujinx", "");
+        store.set(SCHEMA_VERSION_KEY, &1u32);
+        let saved = InstallerSettings { ui_scale: 150, ..Default::default() };
+        store.set(settings::STORAGE_KEY, &saved);
+        migrate(&mut store);
+        assert_eq!(store.get::<InstallerSettings>(settings::STORAGE_KEY).map(|s| s.ui_scale), Some(150));
+    }
+
+    #[test]
+    fn a_failed_migration_falls_back_to_the_default() {
+        let mut store = version_0("Yuzu", "/media/switch");
+        migrate(&mut store);
+        assert_eq!(store.get::<InstallationType>(settings::INSTALLATION_TYPE.key), Some(InstallationType::default()));
+        // What could be migrated still was.
+        assert_eq!(store.get::<String>(settings::SD_CARD_PATH.key).as_deref(), Some("/media/switch"));
+        assert_eq!(store.get::<u32>(SCHEMA_VERSION_KEY), Some(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn fresh_and_current_settings_are_left_alone() {
+        let mut store = Memory::default();
+        migrate(&mut store);
+        // Just the version and the defaults.
+        assert_eq!(store.0.len(), 2);
+        assert_eq!(store.get::<InstallerSettings>(settings::STORAGE_KEY), Some(InstallerSettings::default()));
+
+        let mut store = version_0("Eden", "sd");
+        store.set(SCHEMA_VERSION_KEY, &(SCHEMA_VERSION + 1));
+        migrate(&mut store);
+        assert_eq!(store.get::<String>(settings::INSTALLATION_TYPE.key).as_deref(), Some("Eden"));
+        assert_eq!(store.get::<u32>(SCHEMA_VERSION_KEY), Some(SCHEMA_VERSION + 1));
+    }
+
+    #[test]
+    fn paths_are_normalized() {
+        assert_eq!(sd_card_path_v1(""), "");
+        assert_eq!(sd_card_path_v1("/"), "/");
+        assert_eq!(sd_card_path_v1(r"E:\"), r"E:\");
+        assert_eq!(sd_card_path_v1(r"\\?\E:\switch\"), r"E:\switch");
+        assert_eq!(sd_card_path_v1(r"\\?\UNC\nas\sd\"), r"\\nas\sd");
+        assert_eq!(sd_card_path_v1("  /home/me/sd//"), "/home/me/sd");
+    }
+
+    #[test]
+    fn installation_types_are_matched_leniently() {
+        for installation_type in INSTALLATION_TYPES {
+            assert_eq!(installation_type_v1(installation_type.name()), Some(installation_type));
+            assert_eq!(installation_type_v1(&installation_type.name().to_uppercase()), Some(installation_type));
+        }
+        assert_eq!(installation_type_v1("SdCard"), Some(InstallationType::SdCard));
+        assert_eq!(installation_type_v1("sd_card"), Some(InstallationType::SdCard));
+        assert_eq!(installation_type_v1("Yuzu"), None);
+        assert_eq!(installation_type_v1(""), None);
+    }
+}
@@ -0,0 +1,127 @@
+// Only one installer window at a time. Two of them installing into the same emulator
+// folder would overwrite each other's files halfway through.
+//
+// The first instance writes its PID to a lock file in the data directory. A second one
+// finds that PID still alive, leaves a note asking the first to come to the front, says
+// so in a message box and exits. A lock left by a crashed instance has a dead PID and is
+// taken over.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::paths::data_dir;
+
+fn lock_path() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("installer.lock"))
+}
+
+// Created by a second instance, picked up by the running one.
+fn focus_request_path() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("installer.focus"))
+}
+
+// Holds the lock until dropped.
+#[derive(Debug)]
+pub struct Instance(PathBuf);
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn is_alive(pid: u32) -> bool {
+    // Signal 0 only checks the process is there. EPERM means it is, it's just not ours.
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    let found = unsafe { libc::kill(pid, 0) } == 0;
+    found || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(windows)]
+pub(crate) fn is_alive(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, STILL_ACTIVE};
+    use windows_sys::Win32::System::Threading::{GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+    if handle.is_null() {
+        return false;
+    }
+    let mut code = 0u32;
+    let ok = unsafe { GetExitCodeProcess(handle, &mut code) };
+    unsafe { CloseHandle(handle) };
+    ok != 0 && code == STILL_ACTIVE as u32
+}
+
+// The PID in the lock file, if it's there and that process is still running (and isn't
+// us, PIDs get reused).
+fn running_owner(path: &PathBuf) -> Option<u32> {
+    let pid: u32 = std::fs::read_to_string(path).ok()?.trim().parse().ok()?;
+    (pid != std::process::id() && is_alive(pid)).then_some(pid)
+}
+
+// Ok with the lock, or Err with the PID of the instance that has it. Anything that stops
+// the lock from being written (no data directory, a read-only disk) lets this instance
+// run rather than locking everyone out.
+pub fn acquire() -> Result<Option<Instance>, u32> {
+    let Some(path) = lock_path() else {
+        return Ok(None);
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    // Twice at most: the second go is after clearing a stale lock.
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                let _ = write!(file, "{}", std::process::id());
+                return Ok(Some(Instance(path)));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                if let Some(pid) = running_owner(&path) {
+                    return Err(pid);
+                }
+                let _ = std::fs::remove_file(&path);
+            }
+            Err(_) => return Ok(None),
+        }
+    }
+    Ok(None)
+}
+
+// Give the lock up now, for when this process is about to start its own successor and
+// exit, which skips Instance's drop. Only if it's ours.
+pub fn release() {
+    let Some(path) = lock_path() else {
+        return;
+    };
+    let owner = std::fs::read_to_string(&path).ok().and_then(|pid| pid.trim().parse::<u32>().ok());
+    if owner == Some(std::process::id()) {
+        let _ = std::fs::remove_file(&path);
+    }
+}
+
+// Ask the running instance to show its window.
+pub fn request_focus() {
+    if let Some(path) = focus_request_path() {
+        let _ = std::fs::write(path, std::process::id().to_string());
+    }
+}
+
+// Whether another instance asked for this one's window since the last call.
+pub fn take_focus_request() -> bool {
+    focus_request_path().is_some_and(|path| std::fs::remove_file(path).is_ok())
+}
+
+// Before the UI exists, so no t!.
+pub fn show_already_running() {
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Info)
+        .set_title("Cobalt Installer")
+        .set_description("The Cobalt Installer is already open. Switching to that window instead.")
+        .set_buttons(rfd::MessageButtons::Ok)
+        .show();
+}
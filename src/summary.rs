@@ -0,0 +1,78 @@
+// What the last successful install did, shown in the summary panel and copyable as
+// plain text for support chats.
+
+use std::path::PathBuf;
+
+use crate::disk::format_bytes;
+use crate::i18n::Message;
+use crate::progress::Phase;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstallSummary {
+    pub version: Option<String>,
+    pub destination: PathBuf,
+    // Relative to the destination, as named in the release.
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    // Left over from the previous install and no longer in this release.
+    pub removed: Vec<String>,
+    pub files_skipped: usize,
+    pub bytes_written: u64,
+    pub duration_secs: f64,
+    // Seconds each phase took, in the order they ran.
+    pub phases: Vec<(Phase, f64)>,
+    pub backup: Option<PathBuf>,
+    // Things worth knowing that didn't stop the install: cleanups we did, steps we had
+    // to skip.
+    pub warnings: Vec<Message>,
+}
+
+impl InstallSummary {
+    pub fn version_label(&self) -> String {
+        self.version
+            .as_deref()
+            .map(|v| v.trim_start_matches('v').to_string())
+            .unwrap_or_else(|| "(unknown version)".to_string())
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "Cobalt {} installed\n\
+             Destination: {}\n\
+             Files added: {}\n\
+             Files updated: {}\n\
+             Files removed: {}\n\
+             Files unchanged: {}\n\
+             Total written: {}\n\
+             Duration: {:.1}s\n",
+            self.version_label(),
+            self.destination.display(),
+            self.added.len(),
+            self.updated.len(),
+            self.removed.len(),
+            self.files_skipped,
+            format_bytes(self.bytes_written),
+            self.duration_secs,
+        );
+        for (phase, secs) in &self.phases {
+            text.push_str(&format!("  {}: {secs:.1}s\n", phase.name()));
+        }
+        if let Some(backup) = &self.backup {
+            text.push_str(&format!("Backup: {}\n", backup.display()));
+        }
+        for (label, files) in [("Added", &self.added), ("Updated", &self.updated), ("Removed", &self.removed)] {
+            for file in files {
+                text.push_str(&format!("{label}: {file}\n"));
+            }
+        }
+        if self.warnings.is_empty() {
+            text.push_str("Warnings: none\n");
+        } else {
+            text.push_str("Warnings:\n");
+            for warning in &self.warnings {
+                text.push_str(&format!("- {warning}\n"));
+            }
+        }
+        text
+    }
+}
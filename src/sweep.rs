@@ -0,0 +1,126 @@
+// Clearing out what crashed or killed runs leave in the data directory: downloaded
+// releases nothing will extract now, and backups that were never finished. A run that
+// ends normally deletes its own, so this only finds leftovers, and only ones older than
+// MIN_AGE so nothing another window might still be writing is touched. It runs once at
+// startup and from the "Clean up now" button in the settings.
+//
+// Finished backups are kept however old, they're the user's to delete.
+
+use std::fs::File;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use dioxus::logger::tracing;
+
+use crate::backup::MANIFEST_ENTRY;
+use crate::disk;
+use crate::paths::data_dir;
+
+const MIN_AGE: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Swept {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+// Old enough that whoever wrote it is done with it.
+fn is_old(path: &Path, now: SystemTime) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| now.duration_since(modified).is_ok_and(|age| age >= MIN_AGE))
+}
+
+// Ours while this process runs, see install::ArchiveFile.
+fn is_ours(name: &str) -> bool {
+    name.starts_with(&format!("release_{}_", std::process::id()))
+}
+
+// A backup with its manifest in it was finished, whatever else is wrong with it.
+fn is_finished_backup(path: &Path) -> bool {
+    File::open(path)
+        .ok()
+        .and_then(|file| zip::ZipArchive::new(file).ok())
+        .is_some_and(|mut zip| zip.by_name(MANIFEST_ENTRY).is_ok())
+}
+
+fn remove(path: &Path, swept: &mut Swept) {
+    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    match std::fs::remove_file(path) {
+        Ok(()) => {
+            tracing::debug!("Swept {}", path.display());
+            swept.files += 1;
+            swept.bytes += size;
+        }
+        Err(e) => tracing::warn!("Couldn't delete the leftover {}: {e}", path.display()),
+    }
+}
+
+fn files_in(dir: &Path) -> impl Iterator<Item = std::fs::DirEntry> {
+    std::fs::read_dir(dir).into_iter().flatten().flatten().filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+}
+
+fn sweep_in(data: &Path, now: SystemTime) -> Swept {
+    let mut swept = Swept::default();
+    for entry in files_in(&data.join("cache").join("downloads")) {
+        if !is_ours(&entry.file_name().to_string_lossy()) && is_old(&entry.path(), now) {
+            remove(&entry.path(), &mut swept);
+        }
+    }
+    for entry in files_in(&data.join("backups")) {
+        let path = entry.path();
+        if path.extension().is_some_and(|e| e == "zip") && is_old(&path, now) && !is_finished_backup(&path) {
+            remove(&path, &mut swept);
+        }
+    }
+    swept
+}
+
+// Sweep the data directory, and log what it came to.
+pub fn sweep() -> Swept {
+    let Some(data) = data_dir() else {
+        return Swept::default();
+    };
+    let swept = sweep_in(&data, SystemTime::now());
+    if swept.files > 0 {
+        tracing::info!("Cleaned up {} leftover files, {} reclaimed", swept.files, disk::format_bytes(swept.bytes));
+    }
+    swept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scratch;
+
+    fn finished_backup() -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        zip.start_file(MANIFEST_ENTRY, zip::write::SimpleFileOptions::default()).unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn only_old_leftovers_are_swept() {
+        let scratch = Scratch::new("sweep");
+        scratch.file("cache/downloads/release_1_0.zip", &[0; 100]);
+        scratch.file(&format!("cache/downloads/release_{}_0.zip", std::process::id()), &[0; 10]);
+        scratch.file("cache/cobalt_readme.json", b"{}");
+        scratch.file("backups/cobalt_backup_v1.0.0_2026-01-01.zip", &finished_backup());
+        scratch.file("backups/cobalt_backup_v1.1.0_2026-02-01.zip", b"PK half a zip");
+        scratch.file("history.json", b"[]");
+
+        // Everything's too new yet.
+        assert_eq!(sweep_in(&scratch.0, SystemTime::now()), Swept::default());
+
+        let later = SystemTime::now() + MIN_AGE;
+        assert_eq!(sweep_in(&scratch.0, later), Swept { files: 2, bytes: 100 + 13 });
+        assert!(!scratch.0.join("cache/downloads/release_1_0.zip").exists());
+        assert!(!scratch.0.join("backups/cobalt_backup_v1.1.0_2026-02-01.zip").exists());
+        // This process's own download, the cache index, a finished backup and the history.
+        assert!(scratch.0.join(format!("cache/downloads/release_{}_0.zip", std::process::id())).exists());
+        assert!(scratch.0.join("cache/cobalt_readme.json").exists());
+        assert!(scratch.0.join("backups/cobalt_backup_v1.0.0_2026-01-01.zip").exists());
+        assert!(scratch.0.join("history.json").exists());
+        assert_eq!(sweep_in(&scratch.0, later), Swept::default());
+    }
+}
@@ -0,0 +1,76 @@
+// The bits of the machine that emulator detection reads: which OS this is, the home
+// folder, environment variables and whether a path exists. Going through `System` rather
+// than std and dirs directly lets the tests stand in a fake machine (any OS, any home
+// folder, any APPDATA) instead of poking at the developer's own.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+pub trait System {
+    // One of std::env::consts::OS's values.
+    fn os(&self) -> &str;
+    fn home_dir(&self) -> Option<PathBuf>;
+    fn var_os(&self, name: &str) -> Option<OsString>;
+    fn exists(&self, path: &Path) -> bool;
+}
+
+// This machine.
+pub struct RealSystem;
+
+impl System for RealSystem {
+    fn os(&self) -> &str {
+        std::env::consts::OS
+    }
+
+    fn home_dir(&self) -> Option<PathBuf> {
+        dirs::home_dir()
+    }
+
+    fn var_os(&self, name: &str) -> Option<OsString> {
+        std::env::var_os(name)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+}
+
+// A made-up machine for tests. Paths still go to the real disk, so point the home folder
+// and variables into a scratch folder and create what should exist there.
+#[cfg(test)]
+pub(crate) struct FakeSystem {
+    pub os: &'static str,
+    pub home: Option<PathBuf>,
+    pub vars: std::collections::HashMap<&'static str, OsString>,
+}
+
+#[cfg(test)]
+impl FakeSystem {
+    pub fn new(os: &'static str, home: Option<&Path>) -> Self {
+        Self { os, home: home.map(Path::to_path_buf), vars: Default::default() }
+    }
+
+    pub fn with_var(mut self, name: &'static str, value: impl Into<OsString>) -> Self {
+        self.vars.insert(name, value.into());
+        self
+    }
+}
+
+#[cfg(test)]
+impl System for FakeSystem {
+    fn os(&self) -> &str {
+        self.os
+    }
+
+    fn home_dir(&self) -> Option<PathBuf> {
+        self.home.clone()
+    }
+
+    fn var_os(&self, name: &str) -> Option<OsString> {
+        self.vars.get(name).cloned()
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+}
@@ -0,0 +1,265 @@
+// Only one install into a target at a time. The single instance lock keeps two windows
+// on this machine apart, but not a second machine writing to the same Ryujinx folder on
+// a network share, or an installer started from another user account. So every install
+// also leaves a lock file at the target's root while it runs, saying who holds it.
+//
+// A lock whose owner is gone is taken over: on this machine that's a dead PID, from
+// another machine (where the PID means nothing to us) one older than STALE_AFTER.
+//
+// Two installers can go for the lock at the same moment, so it only ever appears whole:
+// the holder is written under a name of our own and linked into place, which fails if
+// there's a lock already. One that can't be read is still taken as live until it's old,
+// it could be from a filesystem without links, caught halfway through being written.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use dioxus::logger::tracing;
+use serde::{Deserialize, Serialize};
+
+use crate::history;
+use crate::single_instance::is_alive;
+
+pub const LOCK_FILE: &str = ".cobalt_installer.lock";
+
+// No install takes anywhere near this long, a lock this old was left by a crash.
+const STALE_AFTER: u64 = 6 * 60 * 60;
+
+// Who holds a lock, as written in it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Holder {
+    pub pid: u32,
+    pub host: String,
+    // Seconds since the Unix epoch.
+    pub since: u64,
+}
+
+impl Holder {
+    fn us() -> Self {
+        Self { pid: std::process::id(), host: hostname(), since: history::now() }
+    }
+
+    // For a lock file that couldn't be read, going by when it was written.
+    fn unknown(path: &Path) -> Self {
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let since = modified.and_then(|m| m.duration_since(UNIX_EPOCH).ok()).map_or_else(history::now, |d| d.as_secs());
+        Self { pid: 0, host: String::new(), since }
+    }
+
+    // Whether the holder could still be installing.
+    fn is_live(&self) -> bool {
+        if self.host != hostname() {
+            return history::now().saturating_sub(self.since) < STALE_AFTER;
+        }
+        // PIDs get reused, our own one is a lock we failed to remove.
+        self.pid != std::process::id() && is_alive(self.pid)
+    }
+}
+
+impl std::fmt::Display for Holder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let since = history::format_timestamp(self.since);
+        if self.host.is_empty() {
+            return write!(f, "another installer, since {since}");
+        }
+        write!(f, "the installer (process {}) on {}, since {since}", self.pid, self.host)
+    }
+}
+
+#[cfg(unix)]
+fn hostname() -> String {
+    let mut buffer = [0u8; 256];
+    if unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) } != 0 {
+        return "unknown".to_string();
+    }
+    let end = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..end]).into_owned()
+}
+
+#[cfg(windows)]
+fn hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_else(|_| "unknown".to_string())
+}
+
+// Holds the lock until dropped, however the install ended.
+#[derive(Debug)]
+pub struct TargetLock(PathBuf);
+
+impl Drop for TargetLock {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.0) {
+            tracing::warn!("Couldn't remove the install lock {}: {e}", self.0.display());
+        }
+    }
+}
+
+// Whoever holds the lock at `path`, if they could still be installing. None once it's gone.
+fn live_holder(path: &Path) -> Option<Holder> {
+    let holder = match std::fs::read_to_string(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Ok(text) => serde_json::from_str(&text).unwrap_or_else(|_| Holder::unknown(path)),
+        Err(_) => Holder::unknown(path),
+    };
+    holder.is_live().then_some(holder)
+}
+
+// A file next to the lock that only this process uses.
+fn own_file(path: &Path, suffix: &str) -> PathBuf {
+    path.with_file_name(format!("{LOCK_FILE}.{}.{suffix}", std::process::id()))
+}
+
+// Put the lock at `path` with `holder` in it, or fail with AlreadyExists.
+fn create(path: &Path, holder: &Holder) -> std::io::Result<()> {
+    let holder = serde_json::to_string(holder)?;
+    let temp = own_file(path, "tmp");
+    std::fs::write(&temp, &holder)?;
+    let linked = std::fs::hard_link(&temp, path);
+    let _ = std::fs::remove_file(&temp);
+    match linked {
+        Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => {
+            // FAT and exFAT cards have no links. Created empty and filled in, which is why
+            // an unreadable lock counts as live.
+            tracing::debug!("Couldn't link the install lock into place ({e}), writing it directly");
+            let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+            if let Err(e) = file.write_all(holder.as_bytes()) {
+                tracing::warn!("Couldn't write the install lock {}: {e}", path.display());
+            }
+            Ok(())
+        }
+        linked => linked,
+    }
+}
+
+// Move the stale lock aside, then check it's still the one that was found stale: another
+// installer may have cleared it and locked in the meantime, in which case theirs is put
+// back. Only one of two installers clearing the same lock gets to move it.
+fn clear_stale(path: &Path) -> Result<(), Holder> {
+    let aside = own_file(path, "stale");
+    if std::fs::rename(path, &aside).is_err() {
+        // Already cleared by someone else, the next go at creating it will tell.
+        return Ok(());
+    }
+    let result = match live_holder(&aside) {
+        Some(holder) => {
+            if std::fs::hard_link(&aside, path).is_err() && !path.exists() {
+                let _ = std::fs::rename(&aside, path);
+            }
+            Err(holder)
+        }
+        None => Ok(()),
+    };
+    let _ = std::fs::remove_file(&aside);
+    result
+}
+
+// Ok with the lock, or Err with whoever has it. Anything that stops the lock from being
+// written lets the install go ahead unlocked, it'll find out for itself if the target
+// can't be written to.
+pub fn acquire(target: &Path) -> Result<Option<TargetLock>, Holder> {
+    let path = target.join(LOCK_FILE);
+    if let Err(e) = std::fs::create_dir_all(target) {
+        tracing::warn!("Not locking {}: {e}", target.display());
+        return Ok(None);
+    }
+    // Twice at most: the second go is after clearing a stale lock.
+    for _ in 0..2 {
+        match create(&path, &Holder::us()) {
+            Ok(()) => return Ok(Some(TargetLock(path))),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                if let Some(holder) = live_holder(&path) {
+                    return Err(holder);
+                }
+                tracing::info!("Taking over a stale install lock on {}", target.display());
+                clear_stale(&path)?;
+            }
+            Err(e) => {
+                tracing::warn!("Not locking {}: {e}", target.display());
+                return Ok(None);
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scratch;
+
+    fn write_holder(scratch: &Scratch, holder: &Holder) {
+        scratch.file(LOCK_FILE, serde_json::to_string(holder).unwrap().as_bytes());
+    }
+
+    #[test]
+    fn the_lock_is_released_when_dropped() {
+        let scratch = Scratch::new("target_lock_drop");
+        let lock = acquire(&scratch.0).unwrap().unwrap();
+        let holder: Holder = serde_json::from_str(&std::fs::read_to_string(scratch.0.join(LOCK_FILE)).unwrap()).unwrap();
+        assert_eq!((holder.pid, holder.host), (std::process::id(), hostname()));
+        drop(lock);
+        assert!(!scratch.0.join(LOCK_FILE).exists());
+        assert!(acquire(&scratch.0).unwrap().is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_live_holder_keeps_the_lock() {
+        let scratch = Scratch::new("target_lock_live");
+        // Our parent is alive for as long as the test runs.
+        let parent = Holder { pid: std::os::unix::process::parent_id(), ..Holder::us() };
+        write_holder(&scratch, &parent);
+        let holder = acquire(&scratch.0).unwrap_err();
+        assert_eq!(holder, parent);
+        assert!(holder.to_string().contains(&format!("process {}", parent.pid)), "{holder}");
+
+        // Another machine can't be asked, only a recent lock counts.
+        let remote = Holder { pid: 1, host: "another-pc".to_string(), since: history::now() - 60 };
+        write_holder(&scratch, &remote);
+        assert_eq!(acquire(&scratch.0).unwrap_err(), remote);
+    }
+
+    #[test]
+    fn stale_locks_are_taken_over() {
+        let scratch = Scratch::new("target_lock_stale");
+        for stale in [
+            // Left by this very process.
+            Holder::us(),
+            Holder { pid: 1, host: "another-pc".to_string(), since: history::now() - STALE_AFTER - 1 },
+        ] {
+            write_holder(&scratch, &stale);
+            let lock = acquire(&scratch.0).unwrap();
+            assert!(lock.is_some(), "{stale:?} wasn't taken over");
+        }
+        // Unreadable counts as stale too, once it's old.
+        scratch.file(LOCK_FILE, b"not json");
+        let old = std::time::SystemTime::now() - std::time::Duration::from_secs(STALE_AFTER + 60);
+        std::fs::File::options().write(true).open(scratch.0.join(LOCK_FILE)).unwrap().set_modified(old).unwrap();
+        assert!(acquire(&scratch.0).unwrap().is_some());
+    }
+
+    // Empty is what a lock being written looks like, or one another installer has only
+    // just created.
+    #[test]
+    fn a_lock_being_written_is_live() {
+        let scratch = Scratch::new("target_lock_unreadable");
+        scratch.file(LOCK_FILE, b"");
+        let holder = acquire(&scratch.0).unwrap_err();
+        assert!(holder.to_string().starts_with("another installer"), "{holder}");
+        assert!(scratch.0.join(LOCK_FILE).exists());
+    }
+
+    // Two installers clearing the same stale lock: by the time the second moves it aside
+    // it's the first one's new lock, which goes back.
+    #[test]
+    fn a_lock_cleared_by_someone_else_is_put_back() {
+        let scratch = Scratch::new("target_lock_race");
+        let theirs = Holder { pid: 1, host: "another-pc".to_string(), since: history::now() };
+        write_holder(&scratch, &theirs);
+        assert_eq!(clear_stale(&scratch.0.join(LOCK_FILE)), Err(theirs.clone()));
+        assert_eq!(live_holder(&scratch.0.join(LOCK_FILE)), Some(theirs));
+        let left: Vec<_> = std::fs::read_dir(&scratch.0).unwrap().flatten().map(|e| e.file_name()).collect();
+        assert_eq!(left, [LOCK_FILE]);
+    }
+}
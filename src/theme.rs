@@ -0,0 +1,79 @@
+// Light and dark looks. The stylesheet keys its colors off a theme-* class on the root
+// element, with dark as the default. theme-system follows the OS through
+// prefers-color-scheme, where the webview passes it on.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+pub const THEMES: [Theme; 3] = [Theme::System, Theme::Light, Theme::Dark];
+
+// Matching the stylesheet's backgrounds, for what's shown before it has loaded.
+const DARK_BACKGROUND: &str = "#0f1116";
+const LIGHT_BACKGROUND: &str = "#f4f5f7";
+
+impl Theme {
+    pub fn key(self) -> &'static str {
+        match self {
+            Theme::System => "system",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Theme {
+        THEMES.into_iter().find(|t| t.key() == key).unwrap_or_default()
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Theme::System => crate::t!("theme.system"),
+            Theme::Light => crate::t!("theme.light"),
+            Theme::Dark => crate::t!("theme.dark"),
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Theme::System => "theme-system",
+            Theme::Light => "theme-light",
+            Theme::Dark => "theme-dark",
+        }
+    }
+
+    // The webview's own background, under the page. System can't be resolved from here,
+    // so it gets the dark one like before themes existed.
+    pub fn background_color(self) -> (u8, u8, u8, u8) {
+        match self {
+            Theme::Light => (0xf4, 0xf5, 0xf7, 0xff),
+            Theme::System | Theme::Dark => (0x0f, 0x11, 0x16, 0xff),
+        }
+    }
+}
+
+// Goes into the page's <head>, so the class and background are there before anything is
+// drawn. The stylesheet is only linked once the app renders, and without this the window
+// would flash white (or the wrong theme) until it loads.
+pub fn head(theme: Theme) -> String {
+    format!(
+        r#"<style>
+html {{ background-color: {DARK_BACKGROUND}; }}
+html.theme-light {{ background-color: {LIGHT_BACKGROUND}; }}
+@media (prefers-color-scheme: light) {{ html.theme-system {{ background-color: {LIGHT_BACKGROUND}; }} }}
+</style>
+<script>document.documentElement.className = "{}";</script>"#,
+        theme.class()
+    )
+}
+
+// Switch the running page over, when the setting changes.
+pub fn apply(theme: Theme) {
+    document::eval(&format!("document.documentElement.className = \"{}\";", theme.class()));
+}
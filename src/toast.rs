@@ -0,0 +1,59 @@
+// Small notices in the corner for things worth knowing that aren't what the installer is
+// doing right now: an update being out, a leftover file cleaned up, a path copied. The
+// status line and the error banner stay for the install itself, errors never go here.
+//
+// The queue is global so anything can push to it without a signal being passed down.
+
+use dioxus::prelude::*;
+
+use crate::activity::Severity;
+
+// Older toasts make room past this.
+const MAX_SHOWN: usize = 3;
+
+// How long a toast stays up unless dismissed sooner.
+const SHOWN_FOR: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Toast {
+    id: u64,
+    pub severity: Severity,
+    pub message: String,
+}
+
+pub static TOASTS: GlobalSignal<Vec<Toast>> = Signal::global(Vec::new);
+static NEXT_ID: GlobalSignal<u64> = Signal::global(|| 0);
+
+pub fn push(severity: Severity, message: impl Into<String>) {
+    let id = *NEXT_ID.peek();
+    *NEXT_ID.write() += 1;
+    {
+        let mut toasts = TOASTS.write();
+        toasts.push(Toast { id, severity, message: message.into() });
+        let excess = toasts.len().saturating_sub(MAX_SHOWN);
+        toasts.drain(..excess);
+    }
+    // Not tied to whatever component pushed it, which may be gone before the toast is.
+    dioxus::core::spawn_forever(async move {
+        tokio::time::sleep(SHOWN_FOR).await;
+        dismiss(id);
+    });
+}
+
+fn dismiss(id: u64) {
+    TOASTS.write().retain(|t| t.id != id);
+}
+
+#[component]
+pub fn Toasts() -> Element {
+    rsx! {
+        div { id: "toasts",
+            for toast in TOASTS() {
+                div { key: "{toast.id}", class: "toast {toast.severity.class()}",
+                    span { {toast.message.clone()} }
+                    button { class: "close", title: crate::t!("common.close"), onclick: move |_| dismiss(toast.id), "✕" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+// Zooming the whole UI, for high-DPI screens and anyone who finds the default too small.
+// It's the webview's own zoom, so everything scales and reflows together like zooming a
+// web page would.
+
+use dioxus::prelude::*;
+
+pub const MIN: u32 = 90;
+pub const MAX: u32 = 150;
+const STEP: u32 = 10;
+
+// Every size the settings page offers, in percent.
+pub fn options() -> impl Iterator<Item = u32> {
+    (MIN..=MAX).step_by(STEP as usize)
+}
+
+// One step up or down from `scale`, staying within the range.
+pub fn step(scale: u32, up: bool) -> u32 {
+    let scale = scale.clamp(MIN, MAX);
+    if up {
+        (scale + STEP).min(MAX)
+    } else {
+        scale.saturating_sub(STEP).max(MIN)
+    }
+}
+
+pub fn apply(scale: u32) {
+    dioxus::desktop::window().set_zoom_level(scale.clamp(MIN, MAX) as f64 / 100.0);
+}
+
+// Ctrl/Cmd with = (or +) and -, sent back as true for bigger and false for smaller. The
+// webview doesn't zoom on these by itself.
+pub fn listen_for_shortcuts() -> document::Eval {
+    document::eval(
+        r#"
+        window.addEventListener("keydown", (e) => {
+            if (!(e.ctrlKey || e.metaKey) || e.altKey) return;
+            if (e.key === "=" || e.key === "+") {
+                e.preventDefault();
+                dioxus.send(true);
+            } else if (e.key === "-") {
+                e.preventDefault();
+                dioxus.send(false);
+            }
+        });
+        "#,
+    )
+}
@@ -0,0 +1,93 @@
+// Removing what a previous install wrote, driven by its manifest so nothing the user
+// added themselves gets touched.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use dioxus::logger::tracing;
+
+use crate::manifest::Manifest;
+use crate::receipt::Receipt;
+
+// Folders that hold the user's own content. Never removed, even when empty, and nothing
+// inside them is ever considered for cleanup. Relative to the SD root.
+pub const USER_CONTENT_DIRS: &[&str] = &["engage/mods"];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Uninstalled {
+    pub files_removed: usize,
+    pub dirs_removed: usize,
+    pub bytes_removed: u64,
+}
+
+fn is_user_content(relative: &Path) -> bool {
+    USER_CONTENT_DIRS.iter().any(|dir| relative.starts_with(dir))
+}
+
+pub fn uninstall(manifest: &Manifest) -> std::io::Result<Uninstalled> {
+    let mut result = Uninstalled::default();
+
+    for name in &manifest.files {
+        let path = manifest.target.join(name);
+        if let Ok(metadata) = std::fs::metadata(&path) {
+            if metadata.is_file() {
+                std::fs::remove_file(&path)?;
+                result.files_removed += 1;
+                result.bytes_removed += metadata.len();
+            }
+        }
+    }
+
+    result.dirs_removed = remove_empty_dirs(&manifest.target, &manifest.files);
+    Receipt::remove(&manifest.target)?;
+    Ok(result)
+}
+
+// Delete the files an older install wrote that a newer one no longer ships, so an update
+// syncs the target to the release instead of just overlaying it. Anything under a user
+// content folder is left alone. Returns the files removed.
+pub fn remove_stale_files(target: &Path, previous: &[String], current: &[String]) -> std::io::Result<Vec<String>> {
+    let mut removed = Vec::new();
+    for stale in previous.iter().filter(|f| !current.contains(f)) {
+        if is_user_content(Path::new(stale)) {
+            continue;
+        }
+        let path = target.join(stale);
+        if path.is_file() {
+            std::fs::remove_file(&path)?;
+            removed.push(stale.clone());
+        }
+    }
+    Ok(removed)
+}
+
+// Walk up from every removed file's folder, deepest first, and drop the folders that
+// are now empty. Stops at `root` (the SD root itself is never a candidate) and skips
+// user content folders. Returns how many folders were removed.
+pub fn remove_empty_dirs(root: &Path, files: &[String]) -> usize {
+    let mut candidates: BTreeSet<PathBuf> = BTreeSet::new();
+    for name in files {
+        for ancestor in Path::new(name).ancestors().skip(1) {
+            if ancestor.as_os_str().is_empty() || is_user_content(ancestor) {
+                continue;
+            }
+            candidates.insert(ancestor.to_path_buf());
+        }
+    }
+
+    let mut candidates: Vec<PathBuf> = candidates.into_iter().collect();
+    candidates.sort_by_key(|p| std::cmp::Reverse(p.components().count()));
+
+    let mut removed = 0;
+    for relative in candidates {
+        let path = root.join(&relative);
+        let is_empty = std::fs::read_dir(&path).map(|mut d| d.next().is_none()).unwrap_or(false);
+        if is_empty {
+            match std::fs::remove_dir(&path) {
+                Ok(()) => removed += 1,
+                Err(e) => tracing::warn!("Couldn't remove empty folder {}: {e}", path.display()),
+            }
+        }
+    }
+    removed
+}
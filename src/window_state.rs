@@ -0,0 +1,72 @@
+// Where the window was and how big it was, so the next launch opens it the same way.
+//
+// Kept in physical pixels, as tao reports them. A saved position is only used if the
+// window's title bar would land on a monitor that's connected now, otherwise the window
+// opens wherever the OS puts it (at the saved size, if that fits).
+
+use dioxus::desktop::tao::dpi::{PhysicalPosition, PhysicalSize};
+use dioxus::desktop::tao::monitor::MonitorHandle;
+use dioxus::desktop::tao::window::Window;
+use serde::{Deserialize, Serialize};
+
+// How much of the window's top left corner has to be on a monitor to be grabbable.
+const VISIBLE_MARGIN: i32 = 50;
+
+// Anything smaller was probably a glitch, not a size someone picked.
+const MIN_WIDTH: u32 = 300;
+const MIN_HEIGHT: u32 = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
+
+fn contains(monitor: &MonitorHandle, x: i32, y: i32) -> bool {
+    let position = monitor.position();
+    let size = monitor.size();
+    x >= position.x && x < position.x + size.width as i32 && y >= position.y && y < position.y + size.height as i32
+}
+
+impl WindowState {
+    // The window as it is now. A maximized window keeps the size and position it had
+    // before, so un-maximizing after the next launch goes back to them. Minimized, there's
+    // nothing worth keeping.
+    pub fn capture(window: &Window, previous: Option<WindowState>) -> Option<WindowState> {
+        if window.is_minimized() {
+            return previous;
+        }
+        if window.is_maximized() {
+            return previous.map(|p| WindowState { maximized: true, ..p });
+        }
+        let position = window.outer_position().ok()?;
+        let size = window.inner_size();
+        Some(WindowState { x: position.x, y: position.y, width: size.width, height: size.height, maximized: false })
+    }
+
+    pub fn restore(self, window: &Window) {
+        let monitor = window
+            .available_monitors()
+            .find(|m| contains(m, self.x + VISIBLE_MARGIN, self.y + VISIBLE_MARGIN / 2));
+
+        // Never bigger than the monitor the window ends up on.
+        let bounds = monitor.clone().or_else(|| window.current_monitor()).map(|m| m.size());
+        let (width, height) = match bounds {
+            Some(bounds) => (self.width.min(bounds.width), self.height.min(bounds.height)),
+            None => (self.width, self.height),
+        };
+        if width >= MIN_WIDTH && height >= MIN_HEIGHT {
+            window.set_inner_size(PhysicalSize::new(width, height));
+        }
+
+        if monitor.is_some() {
+            window.set_outer_position(PhysicalPosition::new(self.x, self.y));
+        }
+        if self.maximized {
+            window.set_maximized(true);
+        }
+    }
+}
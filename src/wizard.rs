@@ -0,0 +1,31 @@
+// The main screen as three steps: pick where Cobalt goes, check what's about to happen,
+// then install. Every step is drawn from the same Controls state, so going back and
+// forth only changes what's shown, nothing is looked up again.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Target,
+    Confirm,
+    Install,
+}
+
+pub const STEPS: [Step; 3] = [Step::Target, Step::Confirm, Step::Install];
+
+impl Step {
+    pub fn number(self) -> usize {
+        STEPS.iter().position(|s| *s == self).unwrap() + 1
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Step::Target => crate::t!("wizard.target"),
+            Step::Confirm => crate::t!("wizard.confirm"),
+            Step::Install => crate::t!("wizard.install"),
+        }
+    }
+
+    // Picking a target is the only step that can be done without one.
+    pub fn needs_target(self) -> bool {
+        self != Step::Target
+    }
+}
@@ -0,0 +1,155 @@
+// How much memory an install needs on top of what the installer already uses. The
+// download and the extraction both stream, so the peak shouldn't grow with the release:
+// this serves a release much bigger than its largest file from localhost, downloads and
+// extracts it, and checks the peak stays within a small allowance of that largest file.
+//
+// A binary of its own because the allocator counts everything in the process, tests
+// running next to this one would be counted too.
+
+#![cfg(feature = "desktop")]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use cobalt_installer::cancel::CancelToken;
+use cobalt_installer::{download, extract};
+use cobalt_installer::install::{self, ArchiveFile};
+
+// Counts the bytes allocated right now and the most there have been since `reset_peak`.
+struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn allocated(size: usize) {
+    let now = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(now, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new.is_null() {
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+            allocated(new_size);
+        }
+        new
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+// Where the count stands now, with the peak started over from there.
+fn reset_peak() -> usize {
+    let now = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(now, Ordering::Relaxed);
+    now
+}
+
+const MB: usize = 1024 * 1024;
+
+// The release's biggest file. The others are smaller, and there are enough of them for
+// the whole archive to be several times this.
+const LARGEST_ENTRY: usize = 16 * MB;
+const ARCHIVE: usize = 96 * MB;
+
+// What the runtime, reqwest and the zip reader may hold on top, buffers and bookkeeping.
+const ALLOWANCE: usize = 4 * MB;
+
+// Written to disk a megabyte at a time, so making it doesn't count against the install.
+// Stored rather than deflated so the download is as big as what it unpacks to.
+fn write_release(path: &Path) {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let chunk: Vec<u8> = (0..MB).map(|i| (i % 251) as u8).collect();
+    let mut sizes = vec![LARGEST_ENTRY];
+    while sizes.iter().sum::<usize>() < ARCHIVE {
+        sizes.push(LARGEST_ENTRY / 2);
+    }
+    for (n, size) in sizes.into_iter().enumerate() {
+        zip.start_file(format!("atmosphere/contents/0100A6301214E000/romfs/Data/blob_{n}.bin"), options).unwrap();
+        for _ in 0..size / MB {
+            zip.write_all(&chunk).unwrap();
+        }
+    }
+    zip.finish().unwrap();
+}
+
+// Serves the file at `path` from disk, a piece at a time. Returns the URL.
+fn serve(path: PathBuf) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/release.zip", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut request = Vec::new();
+            let mut byte = [0];
+            while !request.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap_or(0) == 1 {
+                request.push(byte[0]);
+            }
+            let mut file = std::fs::File::open(&path).unwrap();
+            let length = file.metadata().unwrap().len();
+            let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {length}\r\nConnection: close\r\n\r\n");
+            let _ = stream.write_all(head.as_bytes()).and_then(|_| std::io::copy(&mut file, &mut stream));
+        }
+    });
+    url
+}
+
+#[test]
+fn an_install_never_holds_the_whole_release() {
+    let scratch = std::env::temp_dir().join(format!("cobalt_installer_memory_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&scratch);
+    std::fs::create_dir_all(&scratch).unwrap();
+    let release = scratch.join("served.zip");
+    write_release(&release);
+    let url = serve(release);
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+    let baseline = reset_peak();
+    let archive = ArchiveFile::in_dir(&scratch.join("downloads")).unwrap();
+    let extracted = runtime.block_on(async {
+        let response = download::download_release(&url).await.unwrap();
+        let size = download::save_with_progress(response, archive.path(), &CancelToken::new(), |_| {}).await.unwrap();
+        assert!(size as usize >= ARCHIVE);
+        let (result, _) = install::extract_release(
+            archive.path().to_path_buf(),
+            url.clone(),
+            scratch.join("sd"),
+            None,
+            CancelToken::new(),
+            install::WriteOptions { preserve_config: false, workers: extract::MAX_WORKERS, buffer_size: MB },
+            |_| {},
+        )
+        .await;
+        result.unwrap()
+    });
+    let peak = PEAK.load(Ordering::Relaxed) - baseline;
+    drop(archive);
+    let _ = std::fs::remove_dir_all(&scratch);
+
+    assert!(extracted.bytes as usize >= ARCHIVE);
+    assert!(
+        peak <= LARGEST_ENTRY + ALLOWANCE,
+        "the install peaked at {} MB over the baseline, for a {} MB release whose largest file is {} MB",
+        peak / MB,
+        ARCHIVE / MB,
+        LARGEST_ENTRY / MB
+    );
+}
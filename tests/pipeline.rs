@@ -0,0 +1,453 @@
+// The download and extract steps of an install run end to end against a server on
+// localhost, into a scratch folder. Nothing here reaches the network or the real home
+// folder: the release comes from the fixture below, is downloaded into a scratch folder
+// of its own, and the manifest is checked as built rather than saved into the data
+// directory. Installs cancelled at each step have to leave nothing behind, in the
+// downloads folder or on the target.
+
+#![cfg(feature = "desktop")]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use cobalt_installer::cancel::CancelToken;
+use cobalt_installer::error::InstallerError;
+use cobalt_installer::extract::{file_crc32, Extracted};
+use cobalt_installer::failure::Failure;
+use cobalt_installer::install::{ArchiveFile, ExtractUpdate};
+use cobalt_installer::installer::{Install, Installer, InstallerEvent, StepEvent, StepStatus};
+use cobalt_installer::manifest::Manifest;
+use cobalt_installer::{download, install, layout, marker, receipt};
+
+const NPDM: &str = "atmosphere/contents/0100A6301214E000/exefs/main.npdm";
+const SUBSDK9: &str = "atmosphere/contents/0100A6301214E000/exefs/subsdk9";
+const ROMFS_FILE: &str = "atmosphere/contents/0100A6301214E000/romfs/Data/readme.txt";
+
+// A miniature release with the layout of a real one.
+fn release_zip() -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    for (name, contents) in [(NPDM, &b"npdm"[..]), (SUBSDK9, b"cobalt loader"), (ROMFS_FILE, &[b'x'; 64 * 1024])] {
+        zip.start_file(name, options).unwrap();
+        zip.write_all(contents).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+// How the server answers.
+#[derive(Clone)]
+enum Reply {
+    Ok(Vec<u8>),
+    NotFound,
+    // Promises the whole body, sends half and hangs up.
+    Truncated(Vec<u8>),
+    // Waits before answering, then sends the body in pieces with pauses in between.
+    Slow(Vec<u8>),
+}
+
+// Serves `reply` to every request on a free port until the test ends. Returns the URL.
+fn serve(reply: Reply) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/release.zip", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let reply = reply.clone();
+            std::thread::spawn(move || answer(stream, reply));
+        }
+    });
+    url
+}
+
+fn answer(mut stream: TcpStream, reply: Reply) {
+    // The request itself doesn't matter, only that it's been sent.
+    let mut request = Vec::new();
+    let mut byte = [0];
+    while !request.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap_or(0) == 1 {
+        request.push(byte[0]);
+    }
+    let head = |status: &str, length: usize| format!("HTTP/1.1 {status}\r\nContent-Length: {length}\r\nConnection: close\r\n\r\n");
+    let _ = match reply {
+        Reply::Ok(body) => stream.write_all(head("200 OK", body.len()).as_bytes()).and_then(|_| stream.write_all(&body)),
+        Reply::NotFound => stream.write_all(head("404 Not Found", 0).as_bytes()),
+        Reply::Truncated(body) => stream
+            .write_all(head("200 OK", body.len()).as_bytes())
+            .and_then(|_| stream.write_all(&body[..body.len() / 2])),
+        Reply::Slow(body) => {
+            std::thread::sleep(Duration::from_millis(300));
+            let mut result = stream.write_all(head("200 OK", body.len()).as_bytes());
+            for piece in body.chunks(body.len().div_ceil(4)) {
+                std::thread::sleep(Duration::from_millis(100));
+                result = result.and_then(|_| stream.write_all(piece)).and_then(|_| stream.flush());
+            }
+            result
+        }
+    };
+}
+
+// A folder in the system temp directory, deleted again when dropped.
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("cobalt_installer_pipeline_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn block_on<T>(future: impl std::future::Future<Output = T>) -> T {
+    tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+}
+
+// Download into `archive`, with every progress report kept.
+async fn fetch(url: &str, archive: &ArchiveFile) -> (Result<u64, InstallerError>, Vec<f32>) {
+    fetch_until(url, archive, &CancelToken::new(), |_| {}).await
+}
+
+// The same, calling `on_progress` with `cancel` for each report so it can cancel.
+async fn fetch_until(
+    url: &str,
+    archive: &ArchiveFile,
+    cancel: &CancelToken,
+    on_progress: impl Fn(&CancelToken),
+) -> (Result<u64, InstallerError>, Vec<f32>) {
+    let mut progress = Vec::new();
+    let result = match cancel.run(download::download_release(url)).await.and_then(|response| response) {
+        Ok(response) => {
+            download::save_with_progress(response, archive.path(), cancel, |fraction| {
+                progress.push(fraction);
+                on_progress(cancel);
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    (result, progress)
+}
+
+// Every file under `dir`, as paths relative to it.
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(files_in(&path).into_iter().map(|f| Path::new(&entry.file_name()).join(f)));
+        } else {
+            files.push(PathBuf::from(entry.file_name()));
+        }
+    }
+    files
+}
+
+// The steps install_cobalt runs after the download, minus saving the manifest.
+async fn install_into(archive: &Path, url: &str, target: &Path) -> Result<(Extracted, Manifest), InstallerError> {
+    let cancel = CancelToken::new();
+    let write = install::WriteOptions { workers: 2, ..Default::default() };
+    let (result, _) =
+        install::extract_release(archive.to_path_buf(), url.to_string(), target.to_path_buf(), None, cancel, write, |_| {}).await;
+    let extracted = result?;
+    install::create_mods_directory(target.to_path_buf()).await?;
+    layout::verify_install(target, &extracted.files)
+        .map_err(|problem| InstallerError::Validation { path: target.to_path_buf(), problem })?;
+    let manifest = Manifest {
+        checksums: extracted.checksums.clone(),
+        ..Manifest::new(target.to_path_buf(), Some("v1.0.0".to_string()), extracted.files.clone())
+    };
+    Ok((extracted, manifest))
+}
+
+#[test]
+fn installs_a_release_from_the_server() {
+    let scratch = Scratch::new("happy_path");
+    let downloads = Scratch::new("happy_path_downloads");
+    let archive = ArchiveFile::in_dir(&downloads.0).unwrap();
+    let url = serve(Reply::Ok(release_zip()));
+    let (extracted, manifest) = block_on(async {
+        let (size, progress) = fetch(&url, &archive).await;
+        assert_eq!(progress.last(), Some(&1.0));
+        assert_eq!(size.unwrap(), release_zip().len() as u64);
+        install_into(archive.path(), &url, &scratch.0).await.unwrap()
+    });
+    // Only the extracted files stay, the download goes once it's done with.
+    let path = archive.path().to_path_buf();
+    drop(archive);
+    assert!(!path.exists());
+
+    assert_eq!(std::fs::read(scratch.0.join(SUBSDK9)).unwrap(), b"cobalt loader");
+    assert_eq!(std::fs::read(scratch.0.join(NPDM)).unwrap(), b"npdm");
+    assert_eq!(std::fs::read(scratch.0.join(ROMFS_FILE)).unwrap().len(), 64 * 1024);
+    assert!(scratch.0.join("engage/mods").is_dir());
+    assert!(layout::has_cobalt(&scratch.0));
+
+    let mut files = manifest.files.clone();
+    files.sort();
+    assert_eq!(files, vec![NPDM.to_string(), SUBSDK9.to_string(), ROMFS_FILE.to_string()]);
+    assert_eq!(extracted.added.len(), 3);
+    assert_eq!(manifest.target, scratch.0);
+    for file in &manifest.files {
+        assert_eq!(manifest.checksums.get(file).copied(), Some(file_crc32(&scratch.0.join(file)).unwrap()), "{file}");
+    }
+    assert_eq!(layout::damaged_files(&scratch.0, &manifest), Some(Vec::new()));
+    // What gets saved reads back the same.
+    let saved: Manifest = serde_json::from_str(&serde_json::to_string(&manifest).unwrap()).unwrap();
+    assert_eq!(saved, manifest);
+}
+
+#[test]
+fn a_missing_release_writes_nothing() {
+    let scratch = Scratch::new("not_found");
+    let archive = ArchiveFile::in_dir(&scratch.0.join("downloads")).unwrap();
+    let url = serve(Reply::NotFound);
+    let (result, _) = block_on(fetch(&url, &archive));
+    let error = result.unwrap_err();
+    match &error {
+        InstallerError::Network { url: failed, source } => {
+            assert_eq!(failed, &url);
+            assert_eq!(source.status().map(|s| s.as_u16()), Some(404));
+        }
+        other => panic!("expected a network error, got {other:?}"),
+    }
+    assert_eq!(Failure::from_error(&error), Some(Failure::Download));
+    assert!(!archive.path().exists());
+}
+
+#[test]
+fn a_truncated_download_fails() {
+    let scratch = Scratch::new("truncated");
+    let archive = ArchiveFile::in_dir(&scratch.0).unwrap();
+    let url = serve(Reply::Truncated(release_zip()));
+    let (result, _) = block_on(fetch(&url, &archive));
+    let error = result.unwrap_err();
+    assert!(matches!(error, InstallerError::Network { .. }), "{error:?}");
+    assert_eq!(Failure::from_error(&error), Some(Failure::Download));
+}
+
+#[test]
+fn a_damaged_archive_is_not_installed() {
+    let scratch = Scratch::new("damaged");
+    let downloads = Scratch::new("damaged_downloads");
+    let archive = ArchiveFile::in_dir(&downloads.0).unwrap();
+    let mut bytes = release_zip();
+    bytes.truncate(bytes.len() / 2);
+    std::fs::write(archive.path(), bytes).unwrap();
+    let error = block_on(install_into(archive.path(), "http://localhost/release.zip", &scratch.0)).unwrap_err();
+    assert!(matches!(error, InstallerError::Archive { .. }), "{error:?}");
+    assert_eq!(Failure::from_error(&error), Some(Failure::DamagedDownload));
+    assert!(!layout::has_cobalt(&scratch.0));
+}
+
+#[test]
+fn a_slow_server_still_finishes() {
+    let scratch = Scratch::new("slow");
+    let downloads = Scratch::new("slow_downloads");
+    let archive = ArchiveFile::in_dir(&downloads.0).unwrap();
+    let url = serve(Reply::Slow(release_zip()));
+    let (_, manifest) = block_on(async {
+        let (size, progress) = fetch(&url, &archive).await;
+        // Reported as the pieces arrive, not all at once at the end.
+        assert!(progress.len() >= 2, "{progress:?}");
+        assert!(progress.windows(2).all(|w| w[0] <= w[1]), "{progress:?}");
+        assert_eq!(progress.last(), Some(&1.0));
+        size.unwrap();
+        install_into(archive.path(), &url, &scratch.0).await.unwrap()
+    });
+    assert_eq!(manifest.files.len(), 3);
+    assert!(layout::has_cobalt(&scratch.0));
+}
+
+#[test]
+fn cancelling_while_connecting_stops_at_once() {
+    let downloads = Scratch::new("cancel_connecting");
+    let archive = ArchiveFile::in_dir(&downloads.0).unwrap();
+    // Takes 300ms to answer at all.
+    let url = serve(Reply::Slow(release_zip()));
+    let cancel = CancelToken::new();
+    let canceller = cancel.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        canceller.cancel();
+    });
+    let started = Instant::now();
+    let (result, progress) = block_on(fetch_until(&url, &archive, &cancel, |_| {}));
+    assert!(matches!(result, Err(InstallerError::Cancelled)), "{result:?}");
+    assert!(started.elapsed() < Duration::from_millis(250), "{:?}", started.elapsed());
+    assert!(progress.is_empty());
+    assert!(files_in(&downloads.0).is_empty());
+}
+
+#[test]
+fn a_cancelled_download_leaves_nothing() {
+    let downloads = Scratch::new("cancel_download");
+    let archive = ArchiveFile::in_dir(&downloads.0).unwrap();
+    let url = serve(Reply::Slow(release_zip()));
+    let (result, progress) = block_on(fetch_until(&url, &archive, &CancelToken::new(), CancelToken::cancel));
+    assert!(matches!(result, Err(InstallerError::Cancelled)), "{result:?}");
+    // Stopped after the first piece rather than waiting for the rest.
+    assert_eq!(progress.len(), 1, "{progress:?}");
+    assert!(files_in(&downloads.0).is_empty(), "{:?}", files_in(&downloads.0));
+}
+
+// Enough files for the extraction to still be going when the first progress report
+// comes back.
+fn big_release_zip() -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    for name in [NPDM, SUBSDK9] {
+        zip.start_file(name, options).unwrap();
+        zip.write_all(b"cobalt").unwrap();
+    }
+    for n in 0..2000 {
+        zip.start_file(format!("atmosphere/contents/0100A6301214E000/romfs/Data/file_{n}.bin"), options).unwrap();
+        zip.write_all(&[b'x'; 1024]).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+#[test]
+fn a_cancelled_extraction_is_rolled_back() {
+    let scratch = Scratch::new("cancel_extract");
+    let downloads = Scratch::new("cancel_extract_downloads");
+    let archive = ArchiveFile::in_dir(&downloads.0).unwrap();
+    std::fs::write(archive.path(), big_release_zip()).unwrap();
+    let cancel = CancelToken::new();
+    let marker = marker::Marker::begin(&scratch.0, Some("v1.0.0")).unwrap();
+    let write = install::WriteOptions { workers: 1, ..Default::default() };
+    let (result, marker) = block_on(install::extract_release(
+        archive.path().to_path_buf(),
+        "http://localhost/release.zip".to_string(),
+        scratch.0.clone(),
+        Some(marker),
+        cancel.clone(),
+        write,
+        |update| {
+            if let ExtractUpdate::Progress(..) = update {
+                cancel.cancel();
+            }
+        },
+    ));
+    assert!(matches!(result, Err(InstallerError::Cancelled)), "{result:?}");
+    drop(marker);
+    // Some of it was written, the marker knows which.
+    assert!(!marker::find(&scratch.0).unwrap().files.is_empty());
+
+    block_on(install::roll_back_install(scratch.0.clone(), None, None)).unwrap();
+    assert!(files_in(&scratch.0).is_empty(), "{:?}", files_in(&scratch.0));
+    drop(archive);
+    assert!(files_in(&downloads.0).is_empty());
+}
+
+#[test]
+fn cancelling_the_finish_rolls_the_install_back() {
+    let scratch = Scratch::new("cancel_finalize");
+    let downloads = Scratch::new("cancel_finalize_downloads");
+    let archive = ArchiveFile::in_dir(&downloads.0).unwrap();
+    std::fs::write(archive.path(), release_zip()).unwrap();
+    let marker = marker::Marker::begin(&scratch.0, Some("v1.0.0")).unwrap();
+    let cancel = CancelToken::new();
+    let extracted = block_on(async {
+        let (result, marker) = install::extract_release(
+            archive.path().to_path_buf(),
+            "http://localhost/release.zip".to_string(),
+            scratch.0.clone(),
+            Some(marker),
+            cancel.clone(),
+            install::WriteOptions::default(),
+            |_| {},
+        )
+        .await;
+        drop(marker);
+        result.unwrap()
+    });
+    assert!(layout::has_cobalt(&scratch.0));
+
+    // Cancelled while it's flushed to the card.
+    let result = block_on(install::flush_install(scratch.0.clone(), extracted.added.clone(), cancel.clone(), |done, _| {
+        if done == 1 {
+            cancel.cancel();
+        }
+    }));
+    assert!(matches!(result, Err(InstallerError::Cancelled)), "{result:?}");
+    block_on(install::roll_back_install(scratch.0.clone(), None, None)).unwrap();
+    assert!(files_in(&scratch.0).is_empty(), "{:?}", files_in(&scratch.0));
+    drop(archive);
+    assert!(files_in(&downloads.0).is_empty());
+}
+
+// The same steps the UI runs, driven through the installer.
+fn release_install(url: &str, target: &Path, downloads: &Path) -> Install {
+    Install {
+        version: Some("v1.0.0".to_string()),
+        write: install::WriteOptions { workers: 2, ..Default::default() },
+        flush: true,
+        downloads: Some(downloads.to_path_buf()),
+        ..Install::new(target.to_path_buf(), url.to_string())
+    }
+}
+
+#[test]
+fn the_installer_runs_every_step() {
+    let scratch = Scratch::new("installer");
+    let downloads = Scratch::new("installer_downloads");
+    let url = serve(Reply::Ok(release_zip()));
+    let mut install = release_install(&url, &scratch.0, &downloads.0);
+    let mut installer = Installer::release();
+    let mut progress = Vec::new();
+    let result = block_on(installer.run(&mut install, |event| {
+        if let InstallerEvent::Step(index, StepEvent::Progress(fraction)) = event {
+            progress.push((index, fraction));
+        }
+    }));
+    assert!(result.is_ok(), "{result:?}");
+
+    let status: Vec<(&str, StepStatus)> =
+        installer.steps().iter().map(|s| s.name).zip(installer.status().iter().cloned()).collect();
+    assert_eq!(status[0], ("Cleanup", StepStatus::Skipped));
+    assert_eq!(status[2], ("Backup", StepStatus::Skipped));
+    assert!(status.iter().filter(|(name, _)| *name != "Cleanup" && *name != "Backup").all(|(_, s)| *s == StepStatus::Done));
+    assert_eq!(progress.first().map(|p| p.0), Some(1));
+    assert!(layout::has_cobalt(&scratch.0));
+    assert!(scratch.0.join("engage/mods").is_dir());
+    assert_eq!(install.extracted.as_ref().map(|e| e.added.len()), Some(3));
+    // The download goes once it's been extracted, the marker stays until the install is
+    // recorded.
+    assert!(install.archive.is_none() && files_in(&downloads.0).is_empty());
+    assert!(marker::find(&scratch.0).is_some());
+}
+
+// By the flush the old release's stale files are gone, so there's nothing to roll back
+// to: the cancel stops the flush and the install stays.
+#[test]
+fn a_cancel_while_flushing_keeps_the_install() {
+    let scratch = Scratch::new("installer_cancel");
+    let downloads = Scratch::new("installer_cancel_downloads");
+    let url = serve(Reply::Ok(release_zip()));
+    let mut install = release_install(&url, &scratch.0, &downloads.0);
+    let cancel = install.cancel.clone();
+    let mut installer = Installer::release();
+    let flush = installer.steps().iter().position(|s| s.name == "Flush").unwrap();
+    let result = block_on(installer.run(&mut install, |event| {
+        assert_ne!(event, InstallerEvent::RollingBack);
+        if event == InstallerEvent::Status(flush, StepStatus::Running) {
+            cancel.cancel();
+        }
+    }));
+    assert!(result.is_ok(), "{result:?}");
+    assert_eq!(installer.status()[flush], StepStatus::Cancelled);
+    assert!(installer.status()[..flush].iter().all(|s| matches!(s, StepStatus::Done | StepStatus::Skipped)));
+    assert!(install.warnings.iter().any(|w| w.key == "warning.not_flushed"), "{:?}", install.warnings);
+    let mut expected: Vec<String> = install.extracted.as_ref().unwrap().files.clone();
+    expected.push(receipt::RECEIPT_FILE.to_string());
+    for file in &expected {
+        assert!(scratch.0.join(file).is_file(), "{file} didn't survive the cancel");
+    }
+    assert!(files_in(&downloads.0).is_empty());
+}